@@ -0,0 +1,8 @@
+-- Per-project git settings used when pushing branches and opening PRs
+CREATE TABLE IF NOT EXISTS forge_project_git_config (
+    project_id BLOB PRIMARY KEY NOT NULL,
+    default_base_branch TEXT,
+    remote_name TEXT,
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
@@ -0,0 +1,12 @@
+-- Tasks that have to be done before a task is ready to be worked on
+CREATE TABLE IF NOT EXISTS forge_task_dependencies (
+    task_id BLOB NOT NULL,
+    depends_on_task_id BLOB NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (task_id, depends_on_task_id),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (depends_on_task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_forge_task_dependencies_depends_on
+    ON forge_task_dependencies(depends_on_task_id);
@@ -0,0 +1,7 @@
+-- Who is working on a task, so cooperating agents don't pick up the same one
+CREATE TABLE IF NOT EXISTS forge_task_assignees (
+    task_id BLOB PRIMARY KEY,
+    assignee TEXT NOT NULL,
+    assigned_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
@@ -1,13 +1,62 @@
-use std::{path::PathBuf, str::FromStr, sync::Arc};
+use std::{path::PathBuf, str::FromStr, sync::Arc, time::Duration};
 
+use log::LevelFilter;
 use sqlx::{
-    Error, Pool, Sqlite, SqlitePool,
+    ConnectOptions, Error, Pool, Sqlite,
+    migrate::MigrateError,
+    pool::PoolConnection,
     sqlite::{SqliteConnectOptions, SqliteConnection, SqlitePoolOptions},
 };
 use utils::assets::asset_dir;
 
 pub mod models;
 
+/// Environment variable holding how many seconds to wait for a free pooled connection.
+pub const ACQUIRE_TIMEOUT_SECS_ENV: &str = "FORGE_DB_ACQUIRE_TIMEOUT_SECS";
+
+/// How long to wait for a free pooled connection when [`ACQUIRE_TIMEOUT_SECS_ENV`] is unset.
+const DEFAULT_ACQUIRE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Environment variable holding the slow statement threshold in milliseconds.
+pub const SLOW_QUERY_MS_ENV: &str = "FORGE_DB_SLOW_QUERY_MS";
+
+/// Environment variable that opts in to clearing a dirty migration on startup.
+pub const REPAIR_ENV: &str = "FORGE_DB_REPAIR";
+
+#[derive(Debug, thiserror::Error)]
+pub enum DBError {
+    /// A migration was interrupted and sqlx refuses to continue until it is cleared
+    #[error(
+        "Database migration {0} was interrupted and left the database in a dirty state. Back up the \
+         database file, then restart with FORGE_DB_REPAIR=1 to clear the failed migration and \
+         re-run it."
+    )]
+    DirtyMigration(i64),
+    /// Every pooled connection stayed busy for the whole acquire timeout
+    #[error("Timed out waiting for a database connection")]
+    PoolTimeout,
+    #[error(transparent)]
+    Sqlx(Error),
+}
+
+impl From<Error> for DBError {
+    fn from(err: Error) -> Self {
+        match err {
+            Error::PoolTimedOut => DBError::PoolTimeout,
+            err => DBError::Sqlx(err),
+        }
+    }
+}
+
+impl From<MigrateError> for DBError {
+    fn from(err: MigrateError) -> Self {
+        match err {
+            MigrateError::Dirty(version) => DBError::DirtyMigration(version),
+            err => DBError::from(Error::from(err)),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct DBService {
     pub pool: Pool<Sqlite>,
@@ -15,28 +64,40 @@ pub struct DBService {
 
 impl DBService {
     /// Get the database URL from environment variable or default to asset_dir
-    fn get_database_url() -> String {
+    fn get_database_url() -> Result<String, Error> {
         if let Ok(db_url) = std::env::var("DATABASE_URL") {
             // If DATABASE_URL is set, use it
-            // Handle both absolute paths and relative paths
-            if db_url.starts_with("sqlite://") {
-                let path_part = db_url.strip_prefix("sqlite://").unwrap();
-                if PathBuf::from(path_part).is_absolute() {
-                    db_url
-                } else {
-                    // Relative path - resolve from current working directory
-                    let abs_path = std::env::current_dir()
-                        .unwrap_or_else(|_| PathBuf::from("."))
-                        .join(path_part);
-                    Self::format_sqlite_url(&abs_path)
-                }
-            } else {
-                db_url
-            }
+            Ok(Self::resolve_database_url(&db_url))
         } else {
             // Default to asset_dir/db.sqlite
-            let db_path = asset_dir().join("db.sqlite");
-            Self::format_sqlite_url(&db_path)
+            let db_path = asset_dir()?.join("db.sqlite");
+            Ok(Self::format_sqlite_url(&db_path))
+        }
+    }
+
+    /// Resolve a user supplied DATABASE_URL, turning relative sqlite paths into absolute ones
+    /// against the current working directory. Any query string (e.g. `?mode=ro&cache=shared`) is
+    /// kept so SqliteConnectOptions still applies it.
+    fn resolve_database_url(db_url: &str) -> String {
+        let Some(rest) = db_url.strip_prefix("sqlite://") else {
+            return db_url.to_string();
+        };
+        let (path_part, query) = match rest.split_once('?') {
+            Some((path, query)) => (path, Some(query)),
+            None => (rest, None),
+        };
+        if PathBuf::from(path_part).is_absolute() {
+            return db_url.to_string();
+        }
+
+        // Relative path - resolve from current working directory
+        let abs_path = std::env::current_dir()
+            .unwrap_or_else(|_| PathBuf::from("."))
+            .join(path_part);
+        let url = Self::format_sqlite_url(&abs_path);
+        match query {
+            Some(query) => format!("{url}?{query}"),
+            None => url,
         }
     }
 
@@ -71,15 +132,73 @@ impl DBService {
         }
     }
 
-    pub async fn new() -> Result<DBService, Error> {
-        let database_url = Self::get_database_url();
-        let options = SqliteConnectOptions::from_str(&database_url)?.create_if_missing(true);
-        let pool = SqlitePool::connect_with(options).await?;
-        sqlx::migrate!("./migrations").run(&pool).await?;
+    /// Read the pool acquire timeout from [`ACQUIRE_TIMEOUT_SECS_ENV`], falling back to the default
+    fn acquire_timeout() -> Duration {
+        std::env::var(ACQUIRE_TIMEOUT_SECS_ENV)
+            .ok()
+            .and_then(|secs| secs.trim().parse::<u64>().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_ACQUIRE_TIMEOUT)
+    }
+
+    /// Statements slower than FORGE_DB_SLOW_QUERY_MS are logged at warn level. When unset, sqlx's
+    /// own statement logging is left untouched so nothing extra runs per query.
+    fn slow_query_threshold() -> Option<Duration> {
+        std::env::var(SLOW_QUERY_MS_ENV)
+            .ok()
+            .and_then(|ms| ms.trim().parse::<u64>().ok())
+            .map(Duration::from_millis)
+    }
+
+    fn connect_options(database_url: &str) -> Result<SqliteConnectOptions, Error> {
+        let options = SqliteConnectOptions::from_str(database_url)?.create_if_missing(true);
+        Ok(match Self::slow_query_threshold() {
+            Some(threshold) => options.log_slow_statements(LevelFilter::Warn, threshold),
+            None => options,
+        })
+    }
+
+    fn pool_options(acquire_timeout: Duration) -> SqlitePoolOptions {
+        SqlitePoolOptions::new().acquire_timeout(acquire_timeout)
+    }
+
+    /// Check out a pooled connection, reporting an exhausted pool as [`DBError::PoolTimeout`]
+    pub async fn acquire(&self) -> Result<PoolConnection<Sqlite>, DBError> {
+        Ok(self.pool.acquire().await?)
+    }
+
+    pub async fn new() -> Result<DBService, DBError> {
+        let database_url = Self::get_database_url()?;
+        let options = Self::connect_options(&database_url)?;
+        let pool = Self::pool_options(Self::acquire_timeout())
+            .connect_with(options)
+            .await?;
+        Self::run_migrations(&pool, Self::repair_enabled()).await?;
+        Ok(DBService { pool })
+    }
+
+    /// Open a fresh, fully migrated in-memory database, intended for tests.
+    ///
+    /// Each call gets its own named shared-cache database, so tests stay isolated from each other
+    /// while every connection of the pool sees the same data. The pool keeps one connection open
+    /// for its whole lifetime, because SQLite drops an in-memory database with its last connection.
+    pub async fn new_in_memory() -> Result<DBService, DBError> {
+        let database_url = format!(
+            "sqlite:file:db-{}?mode=memory&cache=shared",
+            uuid::Uuid::new_v4().simple()
+        );
+        let options = SqliteConnectOptions::from_str(&database_url)?;
+        let pool = Self::pool_options(Self::acquire_timeout())
+            .min_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect_with(options)
+            .await?;
+        Self::run_migrations(&pool, Self::repair_enabled()).await?;
         Ok(DBService { pool })
     }
 
-    pub async fn new_with_after_connect<F>(after_connect: F) -> Result<DBService, Error>
+    pub async fn new_with_after_connect<F>(after_connect: F) -> Result<DBService, DBError>
     where
         F: for<'a> Fn(
                 &'a mut SqliteConnection,
@@ -93,7 +212,37 @@ impl DBService {
         Ok(DBService { pool })
     }
 
-    async fn create_pool<F>(after_connect: Option<Arc<F>>) -> Result<Pool<Sqlite>, Error>
+    /// Whether FORGE_DB_REPAIR opts in to clearing a dirty migration on startup
+    fn repair_enabled() -> bool {
+        std::env::var(REPAIR_ENV)
+            .map(|value| matches!(value.trim().to_ascii_lowercase().as_str(), "1" | "true"))
+            .unwrap_or(false)
+    }
+
+    /// Apply pending migrations. A migration left dirty by an interrupted run is reported as
+    /// [`DBError::DirtyMigration`], unless `repair` is set: then its record is deleted and it is
+    /// re-run. SQLite migrations run in a transaction, so the failed one left no partial schema.
+    async fn run_migrations(pool: &Pool<Sqlite>, repair: bool) -> Result<(), DBError> {
+        let migrator = sqlx::migrate!("./migrations");
+        match migrator.run(pool).await {
+            Err(MigrateError::Dirty(version)) if repair => {
+                tracing::error!(
+                    "Database migration {} is dirty; FORGE_DB_REPAIR is set, clearing it and re-running",
+                    version
+                );
+                sqlx::query("DELETE FROM _sqlx_migrations WHERE version = $1 AND success = false")
+                    .bind(version)
+                    .execute(pool)
+                    .await?;
+                migrator.run(pool).await?;
+                tracing::warn!("Database migration {} repaired", version);
+                Ok(())
+            }
+            result => Ok(result?),
+        }
+    }
+
+    async fn create_pool<F>(after_connect: Option<Arc<F>>) -> Result<Pool<Sqlite>, DBError>
     where
         F: for<'a> Fn(
                 &'a mut SqliteConnection,
@@ -103,11 +252,12 @@ impl DBService {
             + Sync
             + 'static,
     {
-        let database_url = Self::get_database_url();
-        let options = SqliteConnectOptions::from_str(&database_url)?.create_if_missing(true);
+        let database_url = Self::get_database_url()?;
+        let options = Self::connect_options(&database_url)?;
 
+        let pool_options = Self::pool_options(Self::acquire_timeout());
         let pool = if let Some(hook) = after_connect {
-            SqlitePoolOptions::new()
+            pool_options
                 .after_connect(move |conn, _meta| {
                     let hook = hook.clone();
                     Box::pin(async move {
@@ -118,10 +268,139 @@ impl DBService {
                 .connect_with(options)
                 .await?
         } else {
-            SqlitePool::connect_with(options).await?
+            pool_options.connect_with(options).await?
         };
 
-        sqlx::migrate!("./migrations").run(&pool).await?;
+        Self::run_migrations(&pool, Self::repair_enabled()).await?;
         Ok(pool)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::models::project::{CreateProject, Project};
+
+    #[test]
+    fn relative_database_url_is_resolved_against_cwd() {
+        let cwd = std::env::current_dir().unwrap();
+        let expected = DBService::format_sqlite_url(&cwd.join("data/db.sqlite"));
+
+        assert_eq!(
+            DBService::resolve_database_url("sqlite://data/db.sqlite"),
+            expected
+        );
+        assert_eq!(
+            DBService::resolve_database_url("sqlite:///var/lib/db.sqlite"),
+            "sqlite:///var/lib/db.sqlite"
+        );
+    }
+
+    #[test]
+    fn database_url_query_params_are_preserved() {
+        let cwd = std::env::current_dir().unwrap();
+        let expected = DBService::format_sqlite_url(&cwd.join("data/db.sqlite"));
+
+        let resolved = DBService::resolve_database_url("sqlite://data/db.sqlite?cache=shared");
+        assert_eq!(resolved, format!("{expected}?cache=shared"));
+        assert_eq!(
+            DBService::resolve_database_url("sqlite:///var/lib/db.sqlite?mode=ro&cache=shared"),
+            "sqlite:///var/lib/db.sqlite?mode=ro&cache=shared"
+        );
+
+        // The options parser must still see the parameters after resolution
+        let options = SqliteConnectOptions::from_str(&format!("{expected}?mode=ro")).unwrap();
+        assert!(options.get_filename().ends_with("data/db.sqlite"));
+    }
+
+    #[tokio::test]
+    async fn exhausted_pool_times_out_instead_of_blocking() {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:").unwrap();
+        let pool = DBService::pool_options(Duration::from_millis(100))
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .unwrap();
+
+        let db = DBService { pool };
+
+        let _held = db.acquire().await.unwrap();
+        let result = tokio::time::timeout(Duration::from_secs(5), db.acquire())
+            .await
+            .expect("acquire should give up on its own before the test timeout");
+        assert!(matches!(result, Err(DBError::PoolTimeout)));
+    }
+
+    /// A database that has every migration but the last applied, with the last one recorded as
+    /// failed, like after a crash in the middle of it
+    async fn dirty_pool() -> (Pool<Sqlite>, i64) {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let mut migrator = sqlx::migrate!("./migrations");
+        let mut migrations = migrator.migrations.to_vec();
+        let last = migrations.pop().unwrap();
+        migrator.migrations = migrations.into();
+        migrator.run(&pool).await.unwrap();
+
+        sqlx::query(
+            "INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time)
+             VALUES ($1, $2, false, $3, -1)",
+        )
+        .bind(last.version)
+        .bind(last.description.as_ref())
+        .bind(last.checksum.as_ref())
+        .execute(&pool)
+        .await
+        .unwrap();
+        (pool, last.version)
+    }
+
+    #[tokio::test]
+    async fn dirty_migration_is_reported_without_repair() {
+        let (pool, version) = dirty_pool().await;
+
+        let err = DBService::run_migrations(&pool, false).await.unwrap_err();
+        assert!(matches!(err, DBError::DirtyMigration(v) if v == version));
+        assert!(err.to_string().contains("FORGE_DB_REPAIR=1"));
+    }
+
+    #[tokio::test]
+    async fn dirty_migration_is_rerun_with_repair() {
+        let (pool, version) = dirty_pool().await;
+
+        DBService::run_migrations(&pool, true).await.unwrap();
+        let success: bool =
+            sqlx::query_scalar("SELECT success FROM _sqlx_migrations WHERE version = $1")
+                .bind(version)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert!(success);
+    }
+
+    #[tokio::test]
+    async fn in_memory_databases_are_migrated_and_isolated() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let other = DBService::new_in_memory().await.unwrap();
+
+        let data =
+            CreateProject::from_existing_repo("memory".to_string(), "/tmp/memory".to_string());
+        let project = Project::create(&db.pool, &data, Uuid::new_v4())
+            .await
+            .unwrap();
+
+        // Connections beyond the first must see the same database
+        let (a, b) = tokio::join!(
+            Project::find_by_id(&db.pool, project.id),
+            Project::find_all(&db.pool)
+        );
+        assert_eq!(a.unwrap().unwrap().name, "memory");
+        assert_eq!(b.unwrap().len(), 1);
+        assert!(Project::find_all(&other.pool).await.unwrap().is_empty());
+    }
+}
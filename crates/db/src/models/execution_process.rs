@@ -6,6 +6,7 @@ use executors::{
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sqlx::{FromRow, SqlitePool, Type};
+use strum_macros::Display;
 use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
@@ -28,20 +29,31 @@ pub enum ExecutionProcessError {
     ValidationError(String),
 }
 
-#[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS)]
+#[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS, Display)]
 #[sqlx(type_name = "execution_process_status", rename_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
 #[ts(use_ts_enum)]
 pub enum ExecutionProcessStatus {
+    // Waiting for a free coding agent slot before it is spawned
+    Queued,
     Running,
     Completed,
     Failed,
     Killed,
 }
 
-#[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS)]
+impl ExecutionProcessStatus {
+    /// Queued or running, i.e. not finished yet
+    pub fn is_active(&self) -> bool {
+        matches!(self, Self::Queued | Self::Running)
+    }
+}
+
+#[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS, Display)]
 #[sqlx(type_name = "execution_process_run_reason", rename_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
 pub enum ExecutionProcessRunReason {
     SetupScript,
     CleanupScript,
@@ -323,6 +335,7 @@ impl ExecutionProcess {
         data: &CreateExecutionProcess,
         process_id: Uuid,
         before_head_commit: Option<&str>,
+        status: ExecutionProcessStatus,
     ) -> Result<Self, sqlx::Error> {
         let now = Utc::now();
         let executor_action_json = sqlx::types::Json(&data.executor_action);
@@ -340,7 +353,7 @@ impl ExecutionProcess {
             data.run_reason,
             executor_action_json,
             before_head_commit,
-            ExecutionProcessStatus::Running,
+            status,
             None::<i64>,
             now,
             None::<DateTime<Utc>>,
@@ -391,6 +404,61 @@ impl ExecutionProcess {
         Ok(())
     }
 
+    /// Moves a queued process to running once it is about to be spawned. Returns `false` if it is
+    /// no longer queued, e.g. because it was stopped while waiting.
+    pub async fn start_queued(pool: &SqlitePool, id: Uuid) -> Result<bool, sqlx::Error> {
+        let now = Utc::now();
+        let result = sqlx::query(
+            r#"UPDATE execution_processes
+               SET status = 'running', started_at = ?, updated_at = ?
+               WHERE id = ? AND status = 'queued'"#,
+        )
+        .bind(now)
+        .bind(now)
+        .bind(id)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Finishes a process that never left the queue with `status`. Returns `false` if it is no
+    /// longer queued.
+    pub async fn cancel_queued(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: ExecutionProcessStatus,
+    ) -> Result<bool, sqlx::Error> {
+        let now = Utc::now();
+        let result = sqlx::query(
+            r#"UPDATE execution_processes
+               SET status = ?, completed_at = ?, updated_at = ?
+               WHERE id = ? AND status = 'queued'"#,
+        )
+        .bind(status)
+        .bind(now)
+        .bind(now)
+        .bind(id)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Fails every process still waiting for a slot, e.g. when the server restarts and the queue
+    /// is gone. Returns how many were failed.
+    pub async fn fail_queued(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+        let now = Utc::now();
+        let result = sqlx::query(
+            r#"UPDATE execution_processes
+               SET status = 'failed', completed_at = ?, updated_at = ?
+               WHERE status = 'queued'"#,
+        )
+        .bind(now)
+        .bind(now)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
     /// Update the "after" commit oid for the process
     pub async fn update_after_head_commit(
         pool: &SqlitePool,
@@ -577,3 +645,123 @@ impl ExecutionProcess {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use executors::{
+        actions::script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
+        executors::BaseCodingAgent,
+    };
+
+    use super::*;
+    use crate::{
+        DBService,
+        models::{
+            project::{CreateProject, Project},
+            task::CreateTask,
+            task_attempt::CreateTaskAttempt,
+        },
+    };
+
+    async fn create_queued(db: &DBService, task_attempt_id: Uuid) -> ExecutionProcess {
+        let action = ExecutorAction::new(
+            ExecutorActionType::ScriptRequest(ScriptRequest {
+                script: "true".to_string(),
+                language: ScriptRequestLanguage::Bash,
+                context: ScriptContext::SetupScript,
+            }),
+            None,
+        );
+        ExecutionProcess::create(
+            &db.pool,
+            &CreateExecutionProcess {
+                task_attempt_id,
+                executor_action: action,
+                run_reason: ExecutionProcessRunReason::CodingAgent,
+            },
+            Uuid::new_v4(),
+            None,
+            ExecutionProcessStatus::Queued,
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn queued_process_starts_once_and_cannot_start_after_cancel() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let project = Project::create(
+            &db.pool,
+            &CreateProject::from_existing_repo("queue".to_string(), "/tmp/queue".to_string()),
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        let task = Task::create(
+            &db.pool,
+            &CreateTask::from_title_description(project.id, "Wait".to_string(), None),
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        let attempt = TaskAttempt::create(
+            &db.pool,
+            &CreateTaskAttempt {
+                executor: BaseCodingAgent::ClaudeCode,
+                base_branch: "main".to_string(),
+                branch: "forge/queue".to_string(),
+            },
+            Uuid::new_v4(),
+            task.id,
+        )
+        .await
+        .unwrap();
+
+        let started = create_queued(&db, attempt.id).await;
+        assert!(started.status.is_active());
+        assert!(
+            ExecutionProcess::start_queued(&db.pool, started.id)
+                .await
+                .unwrap()
+        );
+        assert!(
+            !ExecutionProcess::start_queued(&db.pool, started.id)
+                .await
+                .unwrap()
+        );
+
+        let cancelled = create_queued(&db, attempt.id).await;
+        assert!(
+            ExecutionProcess::cancel_queued(&db.pool, cancelled.id, ExecutionProcessStatus::Killed)
+                .await
+                .unwrap()
+        );
+        assert!(
+            !ExecutionProcess::start_queued(&db.pool, cancelled.id)
+                .await
+                .unwrap()
+        );
+
+        let orphaned = create_queued(&db, attempt.id).await;
+        assert_eq!(ExecutionProcess::fail_queued(&db.pool).await.unwrap(), 1);
+        let failed = ExecutionProcess::find_by_id(&db.pool, orphaned.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(failed.completed_at, Some(failed.updated_at));
+        assert!(failed.updated_at > orphaned.updated_at);
+        let mut statuses = Vec::new();
+        for id in [started.id, cancelled.id, orphaned.id] {
+            let process = ExecutionProcess::find_by_id(&db.pool, id).await.unwrap();
+            statuses.push(process.unwrap().status);
+        }
+        assert_eq!(
+            statuses,
+            vec![
+                ExecutionProcessStatus::Running,
+                ExecutionProcessStatus::Killed,
+                ExecutionProcessStatus::Failed,
+            ]
+        );
+    }
+}
@@ -7,4 +7,6 @@ pub mod merge;
 pub mod project;
 pub mod tag;
 pub mod task;
+pub mod task_assignee;
 pub mod task_attempt;
+pub mod task_dependency;
@@ -2,7 +2,7 @@ use std::path::PathBuf;
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::{FromRow, SqliteConnection, SqliteExecutor, SqlitePool};
 use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
@@ -48,6 +48,21 @@ pub struct CreateProject {
     pub copy_files: Option<String>,
 }
 
+impl CreateProject {
+    /// A project for an existing repository, without scripts or files to copy.
+    pub fn from_existing_repo(name: String, git_repo_path: String) -> Self {
+        Self {
+            name,
+            git_repo_path,
+            use_existing_repo: true,
+            setup_script: None,
+            dev_script: None,
+            cleanup_script: None,
+            copy_files: None,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, TS)]
 pub struct UpdateProject {
     pub name: Option<String>,
@@ -58,6 +73,29 @@ pub struct UpdateProject {
     pub copy_files: Option<String>,
 }
 
+/// Git settings used when pushing a project's branches and opening PRs. Projects without a
+/// `forge_project_git_config` row push to the repository's first remote and target the global
+/// default PR base.
+#[derive(Debug, Clone, Default, PartialEq, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectGitConfig {
+    pub default_base_branch: Option<String>,
+    pub remote_name: Option<String>,
+}
+
+/// A new name for a project, and optionally a new default PR base branch to set with it.
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct RenameProject {
+    pub name: String,
+    pub default_base_branch: Option<String>,
+}
+
+/// Omitted fields are left unchanged; an empty string clears the setting.
+#[derive(Debug, Default, Serialize, Deserialize, TS)]
+pub struct UpdateProjectGitConfig {
+    pub default_base_branch: Option<String>,
+    pub remote_name: Option<String>,
+}
+
 #[derive(Debug, Serialize, TS)]
 pub struct SearchResult {
     pub path: String,
@@ -194,6 +232,36 @@ impl Project {
         .await
     }
 
+    /// Rename a project and, if given, set its default PR base branch in the same transaction,
+    /// so an agent never leaves one change applied without the other. Returns `None` if the
+    /// project doesn't exist.
+    pub async fn rename(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &RenameProject,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let result = sqlx::query(
+            "UPDATE projects SET name = ?, updated_at = datetime('now', 'subsec') WHERE id = ?",
+        )
+        .bind(data.name.trim())
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Ok(None);
+        }
+        if let Some(default_base_branch) = &data.default_base_branch {
+            let update = UpdateProjectGitConfig {
+                default_base_branch: Some(default_base_branch.clone()),
+                remote_name: None,
+            };
+            ProjectGitConfig::update_in(&mut tx, id, &update).await?;
+        }
+        tx.commit().await?;
+        Self::find_by_id(pool, id).await
+    }
+
     pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
         let result = sqlx::query!("DELETE FROM projects WHERE id = $1", id)
             .execute(pool)
@@ -216,3 +284,97 @@ impl Project {
         Ok(result.count > 0)
     }
 }
+
+impl ProjectGitConfig {
+    pub async fn find<'e>(
+        executor: impl SqliteExecutor<'e>,
+        project_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let config = sqlx::query_as::<_, ProjectGitConfig>(
+            r#"SELECT default_base_branch, remote_name
+               FROM forge_project_git_config
+               WHERE project_id = ?"#,
+        )
+        .bind(project_id)
+        .fetch_optional(executor)
+        .await?;
+        Ok(config.unwrap_or_default())
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        update: &UpdateProjectGitConfig,
+    ) -> Result<Self, sqlx::Error> {
+        let mut conn = pool.acquire().await?;
+        Self::update_in(&mut conn, project_id, update).await
+    }
+
+    /// Like [`Self::update`], on a connection that may be inside a transaction.
+    pub async fn update_in(
+        conn: &mut SqliteConnection,
+        project_id: Uuid,
+        update: &UpdateProjectGitConfig,
+    ) -> Result<Self, sqlx::Error> {
+        let current = Self::find(&mut *conn, project_id).await?;
+        let merge = |new: &Option<String>, old: Option<String>| match new.as_deref().map(str::trim)
+        {
+            Some("") => None,
+            Some(value) => Some(value.to_string()),
+            None => old,
+        };
+        let config = Self {
+            default_base_branch: merge(&update.default_base_branch, current.default_base_branch),
+            remote_name: merge(&update.remote_name, current.remote_name),
+        };
+
+        sqlx::query(
+            r#"INSERT INTO forge_project_git_config (project_id, default_base_branch, remote_name)
+               VALUES (?, ?, ?)
+               ON CONFLICT(project_id) DO UPDATE SET
+                   default_base_branch = excluded.default_base_branch,
+                   remote_name = excluded.remote_name,
+                   updated_at = datetime('now')"#,
+        )
+        .bind(project_id)
+        .bind(&config.default_base_branch)
+        .bind(&config.remote_name)
+        .execute(&mut *conn)
+        .await?;
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DBService;
+
+    #[tokio::test]
+    async fn rename_updates_name_and_default_branch_together() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let data = CreateProject::from_existing_repo("old".to_string(), "/tmp/rename".to_string());
+        let project = Project::create(&db.pool, &data, Uuid::new_v4())
+            .await
+            .unwrap();
+
+        let rename = RenameProject {
+            name: "  new  ".to_string(),
+            default_base_branch: Some("develop".to_string()),
+        };
+        let renamed = Project::rename(&db.pool, project.id, &rename)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(renamed.name, "new");
+        let config = ProjectGitConfig::find(&db.pool, project.id).await.unwrap();
+        assert_eq!(config.default_base_branch.as_deref(), Some("develop"));
+
+        assert!(
+            Project::rename(&db.pool, Uuid::new_v4(), &rename)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
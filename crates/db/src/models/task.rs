@@ -2,10 +2,14 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{Executor, FromRow, Sqlite, SqlitePool, Type};
 use strum_macros::{Display, EnumString};
+use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
 
-use super::{project::Project, task_attempt::TaskAttempt};
+use super::{
+    project::Project, task_assignee::TaskAssignee, task_attempt::TaskAttempt,
+    task_dependency::TaskDependency,
+};
 
 #[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, TS, EnumString, Display)]
 #[sqlx(type_name = "task_status", rename_all = "lowercase")]
@@ -43,6 +47,10 @@ pub struct TaskWithAttemptStatus {
     pub has_merged_attempt: bool,
     pub last_attempt_failed: bool,
     pub executor: String,
+    /// Tasks that have to be done before this one is ready
+    pub depends_on: Vec<Uuid>,
+    /// Who is working on the task, if anyone claimed it
+    pub assignee: Option<String>,
 }
 
 impl std::ops::Deref for TaskWithAttemptStatus {
@@ -72,9 +80,57 @@ pub struct CreateTask {
     pub description: Option<String>,
     pub parent_task_attempt: Option<Uuid>,
     pub image_ids: Option<Vec<Uuid>>,
+    /// Tasks of the same project that have to be done first
+    pub depends_on: Option<Vec<Uuid>>,
+    /// Client-chosen key; retrying a create with the same key returns the first task instead of
+    /// creating a duplicate
+    pub idempotency_key: Option<String>,
+}
+
+/// Longest accepted task title, in characters. GitHub caps issue titles at 256, so imported
+/// issues always fit.
+pub const MAX_TASK_TITLE_CHARS: usize = 256;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum TaskValidationError {
+    #[error("Task title must not be empty")]
+    EmptyTitle,
+    #[error("Task title is {len} characters long; the limit is {max}")]
+    TitleTooLong { len: usize, max: usize },
+}
+
+/// Checks a task title after trimming surrounding whitespace.
+pub fn validate_task_title(title: &str) -> Result<(), TaskValidationError> {
+    let len = title.trim().chars().count();
+    if len == 0 {
+        return Err(TaskValidationError::EmptyTitle);
+    }
+    if len > MAX_TASK_TITLE_CHARS {
+        return Err(TaskValidationError::TitleTooLong {
+            len,
+            max: MAX_TASK_TITLE_CHARS,
+        });
+    }
+    Ok(())
 }
 
 impl CreateTask {
+    /// Like [`Self::from_title_description`], but trims the title and rejects invalid ones.
+    pub fn try_from_title_description(
+        project_id: Uuid,
+        title: String,
+        description: Option<String>,
+    ) -> Result<Self, TaskValidationError> {
+        Self::from_title_description(project_id, title, description).validated()
+    }
+
+    /// Trims the title and rejects the task if the title is invalid.
+    pub fn validated(mut self) -> Result<Self, TaskValidationError> {
+        validate_task_title(&self.title)?;
+        self.title = self.title.trim().to_string();
+        Ok(self)
+    }
+
     pub fn from_title_description(
         project_id: Uuid,
         title: String,
@@ -86,6 +142,8 @@ impl CreateTask {
             description,
             parent_task_attempt: None,
             image_ids: None,
+            depends_on: None,
+            idempotency_key: None,
         }
     }
 }
@@ -97,6 +155,18 @@ pub struct UpdateTask {
     pub status: Option<TaskStatus>,
     pub parent_task_attempt: Option<Uuid>,
     pub image_ids: Option<Vec<Uuid>>,
+    /// Replaces the task's dependencies; an empty list clears them
+    pub depends_on: Option<Vec<Uuid>>,
+}
+
+impl UpdateTask {
+    /// Checks the fields being changed; omitted fields are always valid.
+    pub fn validate(&self) -> Result<(), TaskValidationError> {
+        match &self.title {
+            Some(title) => validate_task_title(title),
+            None => Ok(()),
+        }
+    }
 }
 
 impl Task {
@@ -165,6 +235,8 @@ ORDER BY t.created_at DESC"#,
         )
         .fetch_all(pool)
         .await?;
+        let mut dependencies = TaskDependency::find_by_project_id(pool, project_id).await?;
+        let mut assignees = TaskAssignee::find_by_project_id(pool, project_id).await?;
 
         let tasks = records
             .into_iter()
@@ -184,6 +256,8 @@ ORDER BY t.created_at DESC"#,
                 has_merged_attempt: false, // TODO use merges table
                 last_attempt_failed: rec.last_attempt_failed != 0,
                 executor: rec.executor,
+                depends_on: dependencies.remove(&rec.id).unwrap_or_default(),
+                assignee: assignees.remove(&rec.id),
             })
             .collect();
 
@@ -278,6 +352,27 @@ ORDER BY t.created_at DESC"#,
         .await
     }
 
+    /// Reassigns the task to another project. Dependency edges from or to the task are dropped,
+    /// since they may only link tasks of the same project.
+    pub async fn move_to_project(
+        pool: &SqlitePool,
+        id: Uuid,
+        project_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query("UPDATE tasks SET project_id = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+            .bind(project_id)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        TaskDependency::delete_all_for_task(&mut *tx, id).await?;
+        tx.commit().await?;
+
+        Self::find_by_id(pool, id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
     pub async fn update_status(
         pool: &SqlitePool,
         id: Uuid,
@@ -386,3 +481,55 @@ ORDER BY t.created_at DESC"#,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_task_title_is_trimmed_and_validated() {
+        let project_id = Uuid::new_v4();
+        let task =
+            CreateTask::try_from_title_description(project_id, "  Fix login  ".to_string(), None)
+                .unwrap();
+        assert_eq!(task.title, "Fix login");
+
+        assert_eq!(
+            CreateTask::try_from_title_description(project_id, " \n\t".to_string(), None)
+                .unwrap_err(),
+            TaskValidationError::EmptyTitle
+        );
+        assert_eq!(
+            CreateTask::try_from_title_description(
+                project_id,
+                "x".repeat(MAX_TASK_TITLE_CHARS + 1),
+                None
+            )
+            .unwrap_err(),
+            TaskValidationError::TitleTooLong {
+                len: MAX_TASK_TITLE_CHARS + 1,
+                max: MAX_TASK_TITLE_CHARS,
+            }
+        );
+        // The limit counts characters, not bytes
+        assert!(validate_task_title(&"é".repeat(MAX_TASK_TITLE_CHARS)).is_ok());
+    }
+
+    #[test]
+    fn test_update_task_only_validates_changed_title() {
+        let update = |title: Option<&str>| UpdateTask {
+            title: title.map(str::to_string),
+            description: None,
+            status: Some(TaskStatus::Done),
+            parent_task_attempt: None,
+            image_ids: None,
+            depends_on: None,
+        };
+        assert!(update(None).validate().is_ok());
+        assert!(update(Some("Ship it")).validate().is_ok());
+        assert_eq!(
+            update(Some("   ")).validate().unwrap_err(),
+            TaskValidationError::EmptyTitle
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// The agent or person working on a task. Tasks have at most one assignee.
+pub struct TaskAssignee;
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct SetTaskAssignee {
+    /// `None` or a blank name clears the assignee
+    pub assignee: Option<String>,
+}
+
+impl TaskAssignee {
+    pub async fn find_for_task(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Option<String>, sqlx::Error> {
+        sqlx::query_scalar::<_, String>(
+            "SELECT assignee FROM forge_task_assignees WHERE task_id = ?",
+        )
+        .bind(task_id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Assignees of the project's assigned tasks, keyed by task.
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<HashMap<Uuid, String>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, (Uuid, String)>(
+            r#"SELECT a.task_id, a.assignee
+               FROM forge_task_assignees a
+               JOIN tasks t ON t.id = a.task_id
+               WHERE t.project_id = ?"#,
+        )
+        .bind(project_id)
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().collect())
+    }
+
+    /// Assigns the task, replacing any previous assignee. `None` or a blank name unassigns it.
+    /// Returns the stored assignee.
+    pub async fn set(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        assignee: Option<&str>,
+    ) -> Result<Option<String>, sqlx::Error> {
+        let Some(assignee) = assignee.map(str::trim).filter(|name| !name.is_empty()) else {
+            sqlx::query("DELETE FROM forge_task_assignees WHERE task_id = ?")
+                .bind(task_id)
+                .execute(pool)
+                .await?;
+            return Ok(None);
+        };
+        sqlx::query(
+            r#"INSERT INTO forge_task_assignees (task_id, assignee)
+               VALUES (?, ?)
+               ON CONFLICT(task_id) DO UPDATE SET
+                   assignee = excluded.assignee,
+                   assigned_at = datetime('now')"#,
+        )
+        .bind(task_id)
+        .bind(assignee)
+        .execute(pool)
+        .await?;
+        Ok(Some(assignee.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        DBService,
+        models::{
+            project::{CreateProject, Project},
+            task::{CreateTask, Task},
+        },
+    };
+
+    #[tokio::test]
+    async fn assignee_can_be_set_replaced_and_cleared() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let project = Project::create(
+            &db.pool,
+            &CreateProject::from_existing_repo(
+                "assignees".to_string(),
+                "/tmp/assignees".to_string(),
+            ),
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        let task = Task::create(
+            &db.pool,
+            &CreateTask::from_title_description(project.id, "Claim me".to_string(), None),
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+
+        let stored = TaskAssignee::set(&db.pool, task.id, Some("  agent-1 "))
+            .await
+            .unwrap();
+        assert_eq!(stored.as_deref(), Some("agent-1"));
+        TaskAssignee::set(&db.pool, task.id, Some("agent-2"))
+            .await
+            .unwrap();
+        assert_eq!(
+            TaskAssignee::find_by_project_id(&db.pool, project.id)
+                .await
+                .unwrap(),
+            HashMap::from([(task.id, "agent-2".to_string())])
+        );
+
+        assert!(
+            TaskAssignee::set(&db.pool, task.id, Some(" "))
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            TaskAssignee::find_for_task(&db.pool, task.id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
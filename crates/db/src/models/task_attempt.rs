@@ -222,6 +222,25 @@ impl TaskAttempt {
         Ok(())
     }
 
+    /// Record whether the attempt runs in its own worktree. Attempts without a
+    /// `forge_task_attempt_config` row use one.
+    pub async fn set_use_worktree(
+        pool: &SqlitePool,
+        attempt_id: Uuid,
+        use_worktree: bool,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"INSERT INTO forge_task_attempt_config (task_attempt_id, use_worktree)
+               VALUES (?, ?)
+               ON CONFLICT(task_attempt_id) DO UPDATE SET use_worktree = excluded.use_worktree"#,
+        )
+        .bind(attempt_id)
+        .bind(use_worktree)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     /// Update token usage metrics for telemetry
     /// Saves LLM API usage data (input, output, cache tokens) to task_attempts table
     pub async fn update_token_usage(
@@ -0,0 +1,122 @@
+use std::collections::{HashMap, HashSet};
+
+use sqlx::{Executor, Sqlite, SqlitePool};
+use uuid::Uuid;
+
+/// "Blocked by" edges between tasks of the same project. A task is ready once every task it
+/// depends on is done.
+pub struct TaskDependency;
+
+impl TaskDependency {
+    /// IDs of the tasks `task_id` depends on, oldest edge first.
+    pub async fn find_for_task(pool: &SqlitePool, task_id: Uuid) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar::<_, Uuid>(
+            r#"SELECT depends_on_task_id
+               FROM forge_task_dependencies
+               WHERE task_id = ?
+               ORDER BY created_at, rowid"#,
+        )
+        .bind(task_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Every dependency edge of the project's tasks, keyed by the dependent task.
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<HashMap<Uuid, Vec<Uuid>>, sqlx::Error> {
+        let edges = sqlx::query_as::<_, (Uuid, Uuid)>(
+            r#"SELECT d.task_id, d.depends_on_task_id
+               FROM forge_task_dependencies d
+               JOIN tasks t ON t.id = d.task_id
+               WHERE t.project_id = ?
+               ORDER BY d.created_at, d.rowid"#,
+        )
+        .bind(project_id)
+        .fetch_all(pool)
+        .await?;
+
+        let mut dependencies: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+        for (task_id, depends_on) in edges {
+            dependencies.entry(task_id).or_default().push(depends_on);
+        }
+        Ok(dependencies)
+    }
+
+    /// Replaces the dependencies of `task_id` with `depends_on`; an empty slice clears them.
+    pub async fn replace(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        depends_on: &[Uuid],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query("DELETE FROM forge_task_dependencies WHERE task_id = ?")
+            .bind(task_id)
+            .execute(&mut *tx)
+            .await?;
+        for depends_on_task_id in depends_on {
+            sqlx::query(
+                r#"INSERT OR IGNORE INTO forge_task_dependencies (task_id, depends_on_task_id)
+                   VALUES (?, ?)"#,
+            )
+            .bind(task_id)
+            .bind(depends_on_task_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+
+    /// Drops every edge from or to `task_id`.
+    pub async fn delete_all_for_task<'e, E>(executor: E, task_id: Uuid) -> Result<u64, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let result = sqlx::query(
+            "DELETE FROM forge_task_dependencies WHERE task_id = ?1 OR depends_on_task_id = ?1",
+        )
+        .bind(task_id)
+        .execute(executor)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Whether giving `task_id` the dependencies `depends_on` would close a cycle in `existing`.
+    pub fn would_create_cycle(
+        existing: &HashMap<Uuid, Vec<Uuid>>,
+        task_id: Uuid,
+        depends_on: &[Uuid],
+    ) -> bool {
+        let mut stack: Vec<Uuid> = depends_on.to_vec();
+        let mut seen = HashSet::new();
+        while let Some(current) = stack.pop() {
+            if current == task_id {
+                return true;
+            }
+            if seen.insert(current)
+                && let Some(next) = existing.get(&current)
+            {
+                stack.extend(next.iter().copied());
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycles_are_detected_through_existing_edges() {
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        // b depends on a, c depends on b
+        let existing = HashMap::from([(b, vec![a]), (c, vec![b])]);
+
+        assert!(TaskDependency::would_create_cycle(&existing, a, &[c]));
+        assert!(TaskDependency::would_create_cycle(&existing, a, &[a]));
+        assert!(!TaskDependency::would_create_cycle(&existing, c, &[a]));
+        assert!(!TaskDependency::would_create_cycle(&existing, a, &[]));
+    }
+}
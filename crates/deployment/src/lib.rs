@@ -4,7 +4,7 @@ use anyhow::Error as AnyhowError;
 use async_trait::async_trait;
 use axum::response::sse::Event;
 use db::{
-    DBService,
+    DBError, DBService,
     models::{
         execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
         project::{CreateProject, Project},
@@ -28,8 +28,10 @@ use services::services::{
     filesystem::{FilesystemError, FilesystemService},
     filesystem_watcher::FilesystemWatcherError,
     git::{GitService, GitServiceError},
+    idempotency::IdempotencyService,
     image::{ImageError, ImageService},
     pr_monitor::PrMonitorService,
+    webhook::{AttemptEvent, AttemptEventPayload, WebhookService},
     worktree_manager::WorktreeError,
 };
 use sqlx::{Error as SqlxError, types::Uuid};
@@ -44,6 +46,8 @@ pub enum DeploymentError {
     #[error(transparent)]
     Sqlx(#[from] SqlxError),
     #[error(transparent)]
+    Database(#[from] DBError),
+    #[error(transparent)]
     Git2(#[from] Git2Error),
     #[error(transparent)]
     GitServiceError(#[from] GitServiceError),
@@ -105,6 +109,8 @@ pub trait Deployment: Clone + Send + Sync + 'static {
 
     fn drafts(&self) -> &DraftsService;
 
+    fn idempotency(&self) -> &IdempotencyService;
+
     async fn update_sentry_scope(&self) -> Result<(), DeploymentError> {
         let user_id = self.user_id();
         let config = self.config().read().await;
@@ -139,8 +145,27 @@ pub trait Deployment: Clone + Send + Sync + 'static {
         }
     }
 
+    /// Notifies the configured webhook, if any, of a task attempt lifecycle event
+    async fn send_attempt_webhook(
+        &self,
+        event: AttemptEvent,
+        task: &Task,
+        task_attempt: &TaskAttempt,
+    ) {
+        let webhook = self.config().read().await.webhook.clone();
+        WebhookService::dispatch(
+            &webhook,
+            AttemptEventPayload::new(event, task, task_attempt),
+        );
+    }
+
     /// Cleanup executions marked as running in the db, call at startup
     async fn cleanup_orphan_executions(&self) -> Result<(), DeploymentError> {
+        // The in-memory queue did not survive the restart
+        let queued = ExecutionProcess::fail_queued(&self.db().pool).await?;
+        if queued > 0 {
+            tracing::info!("Failed {} execution processes left queued", queued);
+        }
         let running_processes = ExecutionProcess::find_running(&self.db().pool).await?;
         for process in running_processes {
             tracing::info!(
@@ -7,10 +7,23 @@ use ts_rs::TS;
 use crate::{
     actions::Executable,
     approvals::ExecutorApprovalService,
-    executors::{BaseCodingAgent, ExecutorError, SpawnedChild, StandardCodingAgentExecutor},
+    executors::{
+        BaseCodingAgent, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
+        codex::ReasoningEffort,
+    },
     profile::{ExecutorConfigs, ExecutorProfileId},
 };
 
+/// Settings that apply from this follow-up on, e.g. to escalate to a stronger model after a
+/// failed turn. Only Codex can switch them mid-conversation; other agents ignore them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS)]
+pub struct ResumeOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning_effort: Option<ReasoningEffort>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
 pub struct CodingAgentFollowUpRequest {
     pub prompt: String,
@@ -19,6 +32,8 @@ pub struct CodingAgentFollowUpRequest {
     #[serde(alias = "profile_variant_label")]
     // Backwards compatability with ProfileVariantIds, esp stored in DB under ExecutorAction
     pub executor_profile_id: ExecutorProfileId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resume_overrides: Option<ResumeOverrides>,
 }
 
 impl CodingAgentFollowUpRequest {
@@ -47,6 +62,9 @@ impl Executable for CodingAgentFollowUpRequest {
             ))?;
 
         agent.use_approvals(approvals.clone());
+        if let Some(overrides) = &self.resume_overrides {
+            agent.apply_resume_overrides(overrides);
+        }
 
         agent
             .spawn_follow_up(current_dir, &self.prompt, &self.session_id)
@@ -7,7 +7,7 @@ use thiserror::Error;
 use workspace_utils::approvals::ApprovalStatus;
 
 /// Errors emitted by executor approval services.
-#[derive(Debug, Error)]
+#[derive(Debug, Clone, Error)]
 pub enum ExecutorApprovalError {
     #[error("executor approval session not registered")]
     SessionNotRegistered,
@@ -33,6 +33,10 @@ pub trait ExecutorApprovalService: Send + Sync {
         tool_input: Value,
         tool_call_id: &str,
     ) -> Result<ApprovalStatus, ExecutorApprovalError>;
+
+    /// Resolves every approval still awaiting a decision as denied, so callers blocked in
+    /// [`Self::request_tool_approval`] return. Used when the execution stops mid-approval.
+    async fn cancel_pending_approvals(&self) {}
 }
 
 #[derive(Debug, Default)]
@@ -1,8 +1,12 @@
-use std::path::PathBuf;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use tokio::process::Command;
 use ts_rs::TS;
 use workspace_utils::shell::resolve_executable_path;
 
@@ -29,12 +33,41 @@ impl CommandParts {
         Self { program, args }
     }
 
+    /// Resolve the program on PATH, failing with [`ExecutorError::MissingBinary`] and an install
+    /// hint when it cannot be found.
     pub async fn into_resolved(self) -> Result<(PathBuf, Vec<String>), ExecutorError> {
-        let CommandParts { program, args } = self;
-        let executable = resolve_executable_path(&program)
-            .await
-            .ok_or(ExecutorError::ExecutableNotFound { program })?;
-        Ok((executable, args))
+        let Some(executable) = resolve_executable_path(&self.program).await else {
+            let install_hint = install_hint(&self.program, &self.args);
+            return Err(ExecutorError::MissingBinary {
+                command: self.program,
+                install_hint,
+            });
+        };
+        Ok((executable, self.args))
+    }
+}
+
+/// Suggest how to install a missing `program`. For `npx` the hint names the package it would run;
+/// a missing `npx` means `npm` is missing too, so the hint points at Node.js.
+fn install_hint(program: &str, args: &[String]) -> String {
+    let name = std::path::Path::new(program)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or(program);
+    match name {
+        "npx" | "npm" | "node" => {
+            let package = args.iter().find(|arg| !arg.starts_with('-'));
+            match (name, package) {
+                ("npx", Some(package)) => format!(
+                    "Install Node.js (https://nodejs.org), which provides `npm` and `npx`, to run `{package}`"
+                ),
+                _ => "Install Node.js (https://nodejs.org) and make sure it is on PATH".to_string(),
+            }
+        }
+        "cursor-agent" => {
+            "Install the Cursor CLI with `curl https://cursor.com/install -fsS | bash`".to_string()
+        }
+        _ => format!("Install `{name}` and make sure it is on PATH"),
     }
 }
 
@@ -52,6 +85,80 @@ pub struct CmdOverrides {
     )]
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub additional_params: Option<Vec<String>>,
+    #[schemars(
+        title = "Shell Override",
+        description = "Run the command through this shell, given as [program, argument] (e.g. [\"zsh\", \"-lc\"] for a login shell)"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shell_override: Option<(String, String)>,
+    #[schemars(
+        title = "Environment File",
+        description = "Env file loaded into the agent's environment, relative to the worktree (defaults to .forge.env)"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_file: Option<String>,
+    #[schemars(
+        title = "Environment Variables",
+        description = "Environment variables set for the agent; these take precedence over the env file"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<HashMap<String, String>>,
+}
+
+/// Project env file read from the worktree root when [`CmdOverrides::env_file`] is unset.
+pub const PROJECT_ENV_FILE: &str = ".forge.env";
+
+impl CmdOverrides {
+    /// Variables to set on the agent process, in the order they should be applied.
+    ///
+    /// Precedence, lowest first: the inherited environment and the executor's own defaults
+    /// (e.g. `NODE_NO_WARNINGS`), then the project env file, then the explicit `env` overrides.
+    pub fn resolved_env(&self, current_dir: &Path) -> Vec<(String, String)> {
+        let mut vars = match &self.env_file {
+            Some(env_file) => read_env_file(&current_dir.join(env_file), true),
+            None => read_env_file(&current_dir.join(PROJECT_ENV_FILE), false),
+        };
+        if let Some(env) = &self.env {
+            let mut explicit: Vec<_> = env.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+            explicit.sort();
+            vars.extend(explicit);
+        }
+        vars
+    }
+}
+
+/// Parses a dotenv-style file. A missing file is only worth a warning when it was configured
+/// explicitly; malformed lines are skipped so one typo doesn't drop the whole file.
+fn read_env_file(path: &Path, configured: bool) -> Vec<(String, String)> {
+    let iter = match dotenvy::from_path_iter(path) {
+        Ok(iter) => iter,
+        Err(dotenvy::Error::Io(err)) if err.kind() == std::io::ErrorKind::NotFound => {
+            if configured {
+                tracing::warn!("Env file {} not found", path.display());
+            }
+            return Vec::new();
+        }
+        Err(err) => {
+            tracing::warn!("Failed to read env file {}: {err}", path.display());
+            return Vec::new();
+        }
+    };
+    iter.filter_map(|item| {
+        item.inspect_err(|err| {
+            tracing::warn!(
+                "Skipping invalid line in env file {}: {err}",
+                path.display()
+            )
+        })
+        .ok()
+    })
+    .collect()
+}
+
+/// Applies the project env file and explicit overrides to `command`. Call this after the
+/// executor's own `env` defaults so the project settings win.
+pub fn apply_env(command: &mut Command, current_dir: &Path, overrides: &CmdOverrides) {
+    command.envs(overrides.resolved_env(current_dir));
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
@@ -60,6 +167,9 @@ pub struct CommandBuilder {
     pub base: String,
     /// Optional parameters to append to the base command
     pub params: Option<Vec<String>>,
+    /// Optional shell (program, argument) the command is run through instead of being spawned directly
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shell: Option<(String, String)>,
 }
 
 impl CommandBuilder {
@@ -67,6 +177,7 @@ impl CommandBuilder {
         Self {
             base: base.into(),
             params: None,
+            shell: None,
         }
     }
 
@@ -84,6 +195,11 @@ impl CommandBuilder {
         self
     }
 
+    pub fn shell<P: Into<String>, A: Into<String>>(mut self, program: P, arg: A) -> Self {
+        self.shell = Some((program.into(), arg.into()));
+        self
+    }
+
     pub fn extend_params<I>(mut self, more: I) -> Self
     where
         I: IntoIterator,
@@ -111,6 +227,14 @@ impl CommandBuilder {
     fn build(&self, additional_args: &[String]) -> Result<CommandParts, CommandBuildError> {
         let mut parts = split_command_line(&self.simple_join(additional_args))?;
 
+        if let Some((shell, shell_arg)) = &self.shell {
+            let command = shlex::try_join(parts.iter().map(String::as_str))?;
+            return Ok(CommandParts::new(
+                shell.clone(),
+                vec![shell_arg.clone(), command],
+            ));
+        }
+
         let program = parts.remove(0);
         Ok(CommandParts::new(program, parts))
     }
@@ -148,9 +272,116 @@ pub fn apply_overrides(builder: CommandBuilder, overrides: &CmdOverrides) -> Com
     } else {
         builder
     };
-    if let Some(ref extra) = overrides.additional_params {
+    let builder = if let Some(ref extra) = overrides.additional_params {
         builder.extend_params(extra.clone())
     } else {
         builder
+    };
+    if let Some((ref program, ref arg)) = overrides.shell_override {
+        builder.shell(program, arg)
+    } else {
+        builder
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shell_override_wraps_the_whole_command() {
+        let overrides = CmdOverrides {
+            shell_override: Some(("zsh".to_string(), "-lc".to_string())),
+            ..Default::default()
+        };
+        let builder = apply_overrides(
+            CommandBuilder::new("npx -y some-agent@1.0").params(["--model", "gpt-5"]),
+            &overrides,
+        );
+
+        let parts = builder.build_follow_up(&["--resume".to_string()]).unwrap();
+        assert_eq!(parts.program, "zsh");
+        assert_eq!(
+            parts.args,
+            vec![
+                "-lc".to_string(),
+                "npx -y some-agent@1.0 --model gpt-5 --resume".to_string()
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_program_reports_install_hint() {
+        let parts = CommandBuilder::new("definitely-not-installed-agent --flag")
+            .build_initial()
+            .unwrap();
+        let Err(ExecutorError::MissingBinary {
+            command,
+            install_hint,
+        }) = parts.into_resolved().await
+        else {
+            panic!("expected a missing binary error");
+        };
+        assert_eq!(command, "definitely-not-installed-agent");
+        assert!(install_hint.contains("`definitely-not-installed-agent`"));
+
+        let hint = super::install_hint(
+            "npx",
+            &[
+                "-y".to_string(),
+                "@anthropic-ai/claude-code@2.0.31".to_string(),
+            ],
+        );
+        assert!(hint.contains("Node.js"));
+        assert!(hint.contains("`@anthropic-ai/claude-code@2.0.31`"));
+        assert!(!hint.contains("npm i -g"));
+    }
+
+    #[test]
+    fn project_env_file_is_layered_under_explicit_env() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(PROJECT_ENV_FILE),
+            "# test database\nDATABASE_URL=postgres://localhost/test\nexport FEATURE_X=\"on\"\n",
+        )
+        .unwrap();
+
+        let overrides = CmdOverrides {
+            env: Some(HashMap::from([(
+                "FEATURE_X".to_string(),
+                "off".to_string(),
+            )])),
+            ..Default::default()
+        };
+        let vars = overrides.resolved_env(dir.path());
+        assert_eq!(
+            vars,
+            vec![
+                (
+                    "DATABASE_URL".to_string(),
+                    "postgres://localhost/test".to_string()
+                ),
+                ("FEATURE_X".to_string(), "on".to_string()),
+                ("FEATURE_X".to_string(), "off".to_string()),
+            ]
+        );
+
+        let configured = CmdOverrides {
+            env_file: Some("missing.env".to_string()),
+            ..Default::default()
+        };
+        assert!(configured.resolved_env(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn without_shell_override_the_program_is_spawned_directly() {
+        let parts = apply_overrides(
+            CommandBuilder::new("npx -y some-agent"),
+            &CmdOverrides::default(),
+        )
+        .build_initial()
+        .unwrap();
+        assert_eq!(parts.program, "npx");
+        assert_eq!(parts.args, vec!["-y".to_string(), "some-agent".to_string()]);
     }
 }
@@ -0,0 +1,117 @@
+//! Caps how many coding agents run at once.
+//!
+//! Every agent is a heavyweight process tree (usually `npx` plus a Node runtime), so starting
+//! many attempts together can exhaust CPU and memory. The container takes a permit from a
+//! process-wide limiter before spawning a coding agent, queueing the execution when none is free;
+//! the permit travels with the [`crate::executors::SpawnedChild`] and is released when the
+//! container drops it after the child exits.
+
+use std::sync::{Arc, LazyLock};
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Environment variable setting the maximum number of concurrently running coding agents.
+/// Unset or `0` means no limit.
+pub const MAX_CONCURRENT_EXECUTORS_ENV: &str = "FORGE_MAX_CONCURRENT_EXECUTORS";
+
+static EXECUTOR_LIMITER: LazyLock<ExecutorLimiter> = LazyLock::new(|| {
+    let limit = std::env::var(MAX_CONCURRENT_EXECUTORS_ENV)
+        .ok()
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .filter(|value| *value > 0);
+    ExecutorLimiter::new(limit)
+});
+
+/// Held for as long as a coding agent process is running.
+pub type ExecutorPermit = OwnedSemaphorePermit;
+
+/// Every slot of the limiter is taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExecutorLimitReached;
+
+#[derive(Debug, Clone)]
+pub struct ExecutorLimiter {
+    semaphore: Option<Arc<Semaphore>>,
+}
+
+impl ExecutorLimiter {
+    /// A limiter allowing `limit` concurrent agents, or any number when `None`.
+    pub fn new(limit: Option<usize>) -> Self {
+        Self {
+            semaphore: limit.map(|limit| Arc::new(Semaphore::new(limit))),
+        }
+    }
+
+    /// Takes a permit if one is free right now. `Ok(None)` means unlimited; `Err` means every slot
+    /// is taken.
+    pub fn try_acquire(&self) -> Result<Option<ExecutorPermit>, ExecutorLimitReached> {
+        let Some(semaphore) = self.semaphore.clone() else {
+            return Ok(None);
+        };
+        semaphore
+            .try_acquire_owned()
+            .map(Some)
+            .map_err(|_| ExecutorLimitReached)
+    }
+
+    /// Waits until fewer than the limit of agents are running. Returns `None` when unlimited.
+    pub async fn acquire(&self) -> Option<ExecutorPermit> {
+        let semaphore = self.semaphore.clone()?;
+        if semaphore.available_permits() == 0 {
+            tracing::info!("Executor concurrency limit reached, waiting for an agent to exit");
+        }
+        // The semaphore is never closed, so acquiring can't fail
+        semaphore.acquire_owned().await.ok()
+    }
+}
+
+/// Acquires a permit from the process-wide limiter configured by [`MAX_CONCURRENT_EXECUTORS_ENV`].
+pub async fn acquire_executor_permit() -> Option<ExecutorPermit> {
+    EXECUTOR_LIMITER.acquire().await
+}
+
+/// Takes a permit from the process-wide limiter without waiting, see [`ExecutorLimiter::try_acquire`].
+pub fn try_acquire_executor_permit() -> Result<Option<ExecutorPermit>, ExecutorLimitReached> {
+    EXECUTOR_LIMITER.try_acquire()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_spawn_over_the_limit_waits_for_a_permit() {
+        let limiter = ExecutorLimiter::new(Some(2));
+        let first = limiter.acquire().await.unwrap();
+        let _second = limiter.acquire().await.unwrap();
+
+        let waiting = tokio::spawn({
+            let limiter = limiter.clone();
+            async move { limiter.acquire().await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiting.is_finished());
+
+        drop(first);
+        let third = tokio::time::timeout(Duration::from_secs(1), waiting)
+            .await
+            .expect("permit is released when a running agent exits")
+            .unwrap();
+        assert!(third.is_some());
+
+        assert!(ExecutorLimiter::new(None).acquire().await.is_none());
+    }
+
+    #[test]
+    fn test_try_acquire_fails_fast_when_every_slot_is_taken() {
+        let limiter = ExecutorLimiter::new(Some(1));
+        let first = limiter.try_acquire().unwrap().unwrap();
+        assert_eq!(limiter.try_acquire().unwrap_err(), ExecutorLimitReached);
+
+        drop(first);
+        assert!(limiter.try_acquire().unwrap().is_some());
+        assert!(ExecutorLimiter::new(None).try_acquire().unwrap().is_none());
+    }
+}
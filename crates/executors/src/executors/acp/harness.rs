@@ -18,7 +18,7 @@ use workspace_utils::stream_lines::LinesStreamExt;
 
 use super::{AcpClient, SessionManager};
 use crate::{
-    command::CommandParts,
+    command::{CmdOverrides, CommandParts, apply_env},
     executors::{ExecutorError, SpawnedChild, acp::AcpEvent},
 };
 
@@ -54,6 +54,7 @@ impl AcpAgentHarness {
         current_dir: &Path,
         prompt: String,
         command_parts: CommandParts,
+        cmd: &CmdOverrides,
     ) -> Result<SpawnedChild, ExecutorError> {
         let (program_path, args) = command_parts.into_resolved().await?;
         let mut command = Command::new(program_path);
@@ -65,6 +66,7 @@ impl AcpAgentHarness {
             .current_dir(current_dir)
             .args(&args)
             .env("NODE_NO_WARNINGS", "1");
+        apply_env(&mut command, current_dir, cmd);
 
         let mut child = command.group_spawn()?;
 
@@ -82,6 +84,8 @@ impl AcpAgentHarness {
         Ok(SpawnedChild {
             child,
             exit_signal: Some(exit_rx),
+            permit: None,
+            replacements: None,
         })
     }
 
@@ -91,6 +95,7 @@ impl AcpAgentHarness {
         prompt: String,
         session_id: &str,
         command_parts: CommandParts,
+        cmd: &CmdOverrides,
     ) -> Result<SpawnedChild, ExecutorError> {
         let (program_path, args) = command_parts.into_resolved().await?;
         let mut command = Command::new(program_path);
@@ -102,6 +107,7 @@ impl AcpAgentHarness {
             .current_dir(current_dir)
             .args(&args)
             .env("NODE_NO_WARNINGS", "1");
+        apply_env(&mut command, current_dir, cmd);
 
         let mut child = command.group_spawn()?;
 
@@ -119,6 +125,8 @@ impl AcpAgentHarness {
         Ok(SpawnedChild {
             child,
             exit_signal: Some(exit_rx),
+            permit: None,
+            replacements: None,
         })
     }
 
@@ -264,6 +264,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                             .unwrap_or_default()
                             .to_string_lossy()
                             .to_string(),
+                        result: None,
                     }
                 }
                 agent_client_protocol::ToolKind::Edit => {
@@ -317,7 +318,11 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                             output: None,
                         });
                     }
-                    ActionType::CommandRun { command, result }
+                    ActionType::CommandRun {
+                        command,
+                        cwd: None,
+                        result,
+                    }
                 }
                 agent_client_protocol::ToolKind::Delete => ActionType::FileEdit {
                     path: tc
@@ -350,7 +355,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                             url = extracted;
                         }
                     }
-                    ActionType::WebFetch { url }
+                    ActionType::WebFetch { url, result: None }
                 }
                 agent_client_protocol::ToolKind::Think => {
                     let tool_name = extract_tool_name_from_id(tc.id.0.as_ref())
@@ -440,11 +445,9 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                             &rel,
                             old_text,
                             &diff.new_text,
+                            workspace_utils::diff::DiffAlgorithm::default(),
                         );
-                        changes.push(FileChange::Edit {
-                            unified_diff: unified,
-                            has_line_numbers: false,
-                        });
+                        changes.push(FileChange::edit(unified, false));
                     }
                 }
             }
@@ -6,13 +6,13 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tokio::{io::AsyncWriteExt, process::Command};
 use ts_rs::TS;
-use workspace_utils::msg_store::MsgStore;
+use workspace_utils::{diff::DiffAlgorithm, msg_store::MsgStore};
 
 use crate::{
-    command::{CmdOverrides, CommandBuilder, apply_overrides},
+    command::{CmdOverrides, CommandBuilder, apply_env, apply_overrides},
     executors::{
         AppendPrompt, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
-        claude::{ClaudeLogProcessor, HistoryStrategy},
+        claude::{ClaudeLogOptions, ClaudeLogProcessor, HistoryStrategy},
     },
     logs::{stderr_processor::normalize_stderr_logs, utils::EntryIndexProvider},
 };
@@ -27,6 +27,12 @@ pub struct Amp {
         description = "Allow all commands to be executed, even if they are not safe."
     )]
     pub dangerously_allow_all: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Diff Algorithm",
+        description = "Line diff algorithm used to render file edits (default: myers)"
+    )]
+    pub diff_algorithm: Option<DiffAlgorithm>,
     #[serde(flatten)]
     pub cmd: CmdOverrides,
 }
@@ -58,6 +64,7 @@ impl StandardCodingAgentExecutor for Amp {
             .stderr(Stdio::piped())
             .current_dir(current_dir)
             .args(&args);
+        apply_env(&mut command, current_dir, &self.cmd);
 
         let mut child = command.group_spawn()?;
 
@@ -84,14 +91,15 @@ impl StandardCodingAgentExecutor for Amp {
             session_id.to_string(),
         ])?;
         let (fork_program, fork_args) = fork_line.into_resolved().await?;
-        let fork_output = Command::new(fork_program)
+        let mut fork_command = Command::new(fork_program);
+        fork_command
             .kill_on_drop(true)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .current_dir(current_dir)
-            .args(&fork_args)
-            .output()
-            .await?;
+            .args(&fork_args);
+        apply_env(&mut fork_command, current_dir, &self.cmd);
+        let fork_output = fork_command.output().await?;
         let stdout_str = String::from_utf8_lossy(&fork_output.stdout);
         let new_thread_id = stdout_str
             .lines()
@@ -126,6 +134,7 @@ impl StandardCodingAgentExecutor for Amp {
             .stderr(Stdio::piped())
             .current_dir(current_dir)
             .args(&continue_args);
+        apply_env(&mut command, current_dir, &self.cmd);
 
         let mut child = command.group_spawn()?;
 
@@ -147,6 +156,10 @@ impl StandardCodingAgentExecutor for Amp {
             current_dir,
             entry_index_provider.clone(),
             HistoryStrategy::AmpResume,
+            ClaudeLogOptions {
+                diff_algorithm: self.diff_algorithm.unwrap_or_default(),
+                ..Default::default()
+            },
         );
 
         // Process stderr logs using the standard stderr processor
@@ -1,9 +1,15 @@
 // SDK submodules
 pub mod client;
 pub mod protocol;
+pub mod tool_registry;
 pub mod types;
 
-use std::{collections::HashMap, path::Path, process::Stdio, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    process::Stdio,
+    sync::Arc,
+};
 
 use async_trait::async_trait;
 use command_group::AsyncCommandGroup;
@@ -14,26 +20,31 @@ use tokio::process::Command;
 use ts_rs::TS;
 use workspace_utils::{
     approvals::ApprovalStatus,
-    diff::{concatenate_diff_hunks, create_unified_diff, create_unified_diff_hunk},
-    log_msg::LogMsg,
+    diff::{DiffAlgorithm, concatenate_diff_hunks, create_unified_diff, create_unified_diff_hunk},
     msg_store::MsgStore,
     path::make_path_relative,
+    stream_lines::BoundedLine,
 };
 
 use self::{client::ClaudeAgentClient, protocol::ProtocolPeer, types::PermissionMode};
 use crate::{
     approvals::ExecutorApprovalService,
-    command::{CmdOverrides, CommandBuilder, CommandParts, apply_overrides},
+    command::{CmdOverrides, CommandBuilder, CommandParts, apply_env, apply_overrides},
     executors::{
-        AppendPrompt, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
-        codex::client::LogWriter,
+        AppendPrompt, BaseCodingAgent, ExecutorError, ExecutorLogLevel, SpawnedChild,
+        StandardCodingAgentExecutor, codex::client::LogWriter,
     },
     logs::{
-        ActionType, FileChange, NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
-        TodoItem, ToolStatus,
+        ActionKind, ActionType, FileChange, NormalizedEntry, NormalizedEntryError,
+        NormalizedEntryType, NotificationLevel, TodoItem, ToolStatus,
         stderr_processor::normalize_stderr_logs,
-        utils::{EntryIndexProvider, patch::ConversationPatch},
+        utils::{
+            EntryIndexProvider, PatchCoalescer, ToolRenderFilter,
+            patch::ConversationPatch,
+            rate_limit::{is_rate_limit_message, rate_limit_entry},
+        },
     },
+    profile::ExecutorConfigs,
     stdout_dup::create_stdout_pipe_writer,
 };
 
@@ -45,13 +56,43 @@ fn base_command(claude_code_router: bool) -> &'static str {
     }
 }
 
+/// When set to "1" or "true", `dangerously_skip_permissions` is ignored for every profile.
+pub const FORBID_SKIP_PERMISSIONS_ENV: &str = "FORGE_FORBID_SKIP_PERMISSIONS";
+
+/// Claude Code has no CLI flag for the response length; it reads the cap from this variable.
+const MAX_OUTPUT_TOKENS_ENV: &str = "CLAUDE_CODE_MAX_OUTPUT_TOKENS";
+
+fn skip_permissions_forbidden() -> bool {
+    std::env::var(FORBID_SKIP_PERMISSIONS_ENV)
+        .map(|value| {
+            let value = value.trim();
+            value == "1" || value.eq_ignore_ascii_case("true")
+        })
+        .unwrap_or(false)
+}
+
 use derivative::Derivative;
 
+/// How follow-ups resume the previous Claude session
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum ClaudeResumeMode {
+    /// Branch a new session from the previous one (`--fork-session --resume`)
+    #[default]
+    Fork,
+    /// Append to the previous session in place (`--resume`)
+    Continue,
+}
+
 #[derive(Derivative, Clone, Serialize, Deserialize, TS, JsonSchema)]
 #[derivative(Debug, PartialEq)]
 pub struct ClaudeCode {
     #[serde(default)]
     pub append_prompt: AppendPrompt,
+    /// Text added to Claude's system prompt for every session (`--append-system-prompt`), e.g.
+    /// coding standards. Unlike `append_prompt`, the user message is left as is.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt_append: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub claude_code_router: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -62,6 +103,36 @@ pub struct ClaudeCode {
     pub model: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub dangerously_skip_permissions: Option<bool>,
+    /// Emit the resolved configuration (secrets redacted) as a system message at spawn
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dump_config: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resume_mode: Option<ClaudeResumeMode>,
+    /// Line diff algorithm used to render Edit and MultiEdit calls (default: myers)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diff_algorithm: Option<DiffAlgorithm>,
+    /// Don't add a warning to the conversation when ANTHROPIC_API_KEY is billed instead of the
+    /// Claude subscription
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suppress_api_key_warning: Option<bool>,
+    /// Show tool results as plain text even when they are valid JSON
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefer_raw_tool_results: Option<bool>,
+    /// Cap on tokens per model response. Claude Code has no CLI flag for this, so it is passed
+    /// as `CLAUDE_CODE_MAX_OUTPUT_TOKENS`; must be positive.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u32>,
+    /// `debug` adds Claude Code's `--debug`. `--verbose` is always passed because stream-json
+    /// output requires it, so lower levels change nothing yet.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_level: Option<ExecutorLogLevel>,
+    /// Add a "Completed in 6.0s, 3 turns" system message when Claude Code reports its final result
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completion_summary: Option<bool>,
+    /// Tool use kinds left out of the conversation, e.g. `todo_management` or `search`. Raw logs
+    /// still contain them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hidden_tool_kinds: Option<Vec<ActionKind>>,
     #[serde(flatten)]
     pub cmd: CmdOverrides,
 
@@ -73,6 +144,17 @@ pub struct ClaudeCode {
 
 impl ClaudeCode {
     async fn build_command_builder(&self) -> CommandBuilder {
+        self.command_builder(skip_permissions_forbidden(), &ExecutorConfigs::get_cached())
+            .await
+    }
+
+    /// Builds the command line, dropping `dangerously_skip_permissions` when
+    /// `skip_permissions_forbidden` is set and taking the default model from `configs`.
+    async fn command_builder(
+        &self,
+        skip_permissions_forbidden: bool,
+        configs: &ExecutorConfigs,
+    ) -> CommandBuilder {
         // If base_command_override is provided and claude_code_router is also set, log a warning
         if self.cmd.base_command_override.is_some() && self.claude_code_router.is_some() {
             tracing::warn!(
@@ -98,10 +180,28 @@ impl ClaudeCode {
             )]);
         }
         if self.dangerously_skip_permissions.unwrap_or(false) {
-            builder = builder.extend_params(["--dangerously-skip-permissions"]);
+            if skip_permissions_forbidden {
+                tracing::warn!(
+                    "{} is set, ignoring dangerously_skip_permissions",
+                    FORBID_SKIP_PERMISSIONS_ENV
+                );
+            } else {
+                builder = builder.extend_params(["--dangerously-skip-permissions"]);
+            }
+        }
+        if let Some(model) = self
+            .model
+            .clone()
+            .or_else(|| configs.default_model(BaseCodingAgent::ClaudeCode))
+        {
+            builder = builder.extend_params(["--model", &model]);
         }
-        if let Some(model) = &self.model {
-            builder = builder.extend_params(["--model", model]);
+        if let Some(system_prompt) = self
+            .system_prompt_append
+            .as_deref()
+            .filter(|text| !text.trim().is_empty())
+        {
+            builder = builder.extend_params(["--append-system-prompt", system_prompt]);
         }
         builder = builder.extend_params([
             "--verbose",
@@ -109,6 +209,9 @@ impl ClaudeCode {
             "--input-format=stream-json",
             "--include-partial-messages",
         ]);
+        if self.log_level == Some(ExecutorLogLevel::Debug) {
+            builder = builder.extend_params(["--debug"]);
+        }
 
         apply_overrides(builder, &self.cmd)
     }
@@ -123,6 +226,39 @@ impl ClaudeCode {
         }
     }
 
+    fn resume_args(&self, session_id: &str) -> Vec<String> {
+        let mut args = Vec::with_capacity(3);
+        if self.resume_mode.unwrap_or_default() == ClaudeResumeMode::Fork {
+            args.push("--fork-session".to_string());
+        }
+        args.extend(["--resume".to_string(), session_id.to_string()]);
+        args
+    }
+
+    /// Build the `executor_config` system line written to the log at spawn, if enabled.
+    fn config_dump_line(&self, program_path: &Path, args: &[String]) -> Option<String> {
+        if !self.dump_config.unwrap_or(false) {
+            return None;
+        }
+
+        let mut config = serde_json::to_value(self).unwrap_or(serde_json::Value::Null);
+        redact_secrets(&mut config);
+        let mut command = vec![program_path.to_string_lossy().to_string()];
+        command.extend(redact_args(args));
+
+        let line = serde_json::json!({
+            "type": "system",
+            "subtype": "executor_config",
+            "executor_config": {
+                "executor": "CLAUDE_CODE",
+                "permission_mode": self.permission_mode().to_string(),
+                "config": config,
+                "command": command,
+            },
+        });
+        Some(line.to_string())
+    }
+
     pub fn get_hooks(&self) -> Option<serde_json::Value> {
         if self.plan.unwrap_or(false) {
             Some(serde_json::json!({
@@ -168,17 +304,13 @@ impl StandardCodingAgentExecutor for ClaudeCode {
         session_id: &str,
     ) -> Result<SpawnedChild, ExecutorError> {
         let command_builder = self.build_command_builder().await;
-        let command_parts = command_builder.build_follow_up(&[
-            "--fork-session".to_string(),
-            "--resume".to_string(),
-            session_id.to_string(),
-        ])?;
+        let command_parts = command_builder.build_follow_up(&self.resume_args(session_id))?;
         self.spawn_internal(current_dir, prompt, command_parts)
             .await
     }
 
     fn normalize_logs(&self, msg_store: Arc<MsgStore>, current_dir: &Path) {
-        let entry_index_provider = EntryIndexProvider::start_from(&msg_store);
+        let entry_index_provider = EntryIndexProvider::resume_from(&msg_store);
 
         // Process stdout logs (Claude's JSON output)
         ClaudeLogProcessor::process_logs(
@@ -186,6 +318,16 @@ impl StandardCodingAgentExecutor for ClaudeCode {
             current_dir,
             entry_index_provider.clone(),
             HistoryStrategy::Default,
+            ClaudeLogOptions {
+                diff_algorithm: self.diff_algorithm.unwrap_or_default(),
+                suppress_api_key_warning: self.suppress_api_key_warning.unwrap_or(false),
+                prefer_raw_tool_results: self.prefer_raw_tool_results.unwrap_or(false),
+                claude_code_router: self.claude_code_router.unwrap_or(false),
+                completion_summary: self.completion_summary.unwrap_or(false),
+                tool_filter: ToolRenderFilter::hiding(
+                    self.hidden_tool_kinds.iter().flatten().copied(),
+                ),
+            },
         );
 
         // Process stderr logs using the standard stderr processor
@@ -207,16 +349,9 @@ impl ClaudeCode {
     ) -> Result<SpawnedChild, ExecutorError> {
         let (program_path, args) = command_parts.into_resolved().await?;
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
+        let config_dump = self.config_dump_line(&program_path, &args);
 
-        let mut command = Command::new(program_path);
-        command
-            .kill_on_drop(true)
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .current_dir(current_dir)
-            .args(&args);
-
+        let mut command = self.command(&program_path, &args, current_dir);
         let mut child = command.group_spawn()?;
         let child_stdout = child.inner().stdout.take().ok_or_else(|| {
             ExecutorError::Io(std::io::Error::other("Claude Code missing stdout"))
@@ -238,6 +373,10 @@ impl ClaudeCode {
             let client = ClaudeAgentClient::new(log_writer.clone(), approvals_clone);
             let protocol_peer = ProtocolPeer::spawn(child_stdin, child_stdout, client.clone());
 
+            if let Some(dump) = config_dump {
+                let _ = log_writer.log_raw(&dump).await;
+            }
+
             // Initialize control protocol
             if let Err(e) = protocol_peer.initialize(hooks).await {
                 tracing::error!("Failed to initialize control protocol: {e}");
@@ -263,8 +402,92 @@ impl ClaudeCode {
         Ok(SpawnedChild {
             child,
             exit_signal: None,
+            permit: None,
+            replacements: None,
         })
     }
+
+    fn command(&self, program_path: &Path, args: &[String], current_dir: &Path) -> Command {
+        let mut command = Command::new(program_path);
+        command
+            .kill_on_drop(true)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .current_dir(current_dir)
+            .args(args);
+        if let Some(max_output_tokens) = self.max_output_tokens {
+            command.env(MAX_OUTPUT_TOKENS_ENV, max_output_tokens.to_string());
+        }
+        apply_env(&mut command, current_dir, &self.cmd);
+        command
+    }
+}
+
+const REDACTED: &str = "<redacted>";
+
+fn is_secret_name(name: &str) -> bool {
+    let name = name.trim_start_matches('-').to_ascii_lowercase();
+    ["key", "token", "secret", "password", "credential", "auth"]
+        .iter()
+        .any(|marker| name.contains(marker))
+}
+
+/// Redact values of secret-looking `--flag value`, `--flag=value` and `NAME=value` arguments.
+fn redact_args(args: &[String]) -> Vec<String> {
+    let mut redacted = Vec::with_capacity(args.len());
+    let mut redact_next = false;
+    for arg in args {
+        if std::mem::take(&mut redact_next) && !arg.starts_with('-') {
+            redacted.push(REDACTED.to_string());
+            continue;
+        }
+        match arg.split_once('=') {
+            Some((name, _)) if is_secret_name(name) => {
+                redacted.push(format!("{name}={REDACTED}"));
+            }
+            Some(_) => redacted.push(arg.clone()),
+            None => {
+                redact_next = arg.starts_with('-') && is_secret_name(arg);
+                redacted.push(arg.clone());
+            }
+        }
+    }
+    redacted
+}
+
+/// Recursively redact secret-looking keys and command-line fragments in a config value.
+fn redact_secrets(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, v) in map.iter_mut() {
+                if is_secret_name(key) && !v.is_null() {
+                    *v = serde_json::Value::String(REDACTED.to_string());
+                } else {
+                    redact_secrets(v);
+                }
+            }
+        }
+        serde_json::Value::Array(items) if items.iter().all(|v| v.is_string()) => {
+            let args: Vec<String> = items
+                .iter()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect();
+            *items = redact_args(&args)
+                .into_iter()
+                .map(serde_json::Value::String)
+                .collect();
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(redact_secrets),
+        serde_json::Value::String(s) => {
+            let tokens: Vec<String> = s.split_whitespace().map(str::to_string).collect();
+            let redacted = redact_args(&tokens);
+            if redacted != tokens {
+                *s = redacted.join(" ");
+            }
+        }
+        _ => {}
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -275,15 +498,38 @@ pub enum HistoryStrategy {
     AmpResume,
 }
 
+/// Executor settings that affect how Claude logs are normalized
+#[derive(Debug, Clone, Default)]
+pub struct ClaudeLogOptions {
+    pub diff_algorithm: DiffAlgorithm,
+    /// Skip the conversation entry for an unmanaged ANTHROPIC_API_KEY (it is still logged)
+    pub suppress_api_key_warning: bool,
+    /// Keep text tool results as Markdown instead of parsing JSON-looking ones
+    pub prefer_raw_tool_results: bool,
+    /// Running through claude-code-router, whose providers may leave `message.model` unset
+    pub claude_code_router: bool,
+    /// Turn a successful Result message into a system message with its duration and turn count
+    pub completion_summary: bool,
+    /// Tool uses to leave out of the conversation
+    pub tool_filter: ToolRenderFilter,
+}
+
 /// Handles log processing and interpretation for Claude executor
 pub struct ClaudeLogProcessor {
     model_name: Option<String>,
+    // Model reported by the system init message, used when assistant messages carry none
+    init_model: Option<String>,
     // Map tool_use_id -> structured info for follow-up ToolResult replacement
     tool_map: HashMap<String, ClaudeToolCallInfo>,
     // Strategy controlling how to handle history and user messages
     strategy: HistoryStrategy,
     streaming_messages: HashMap<String, StreamingMessageState>,
     streaming_message_id: Option<String>,
+    // tool_use_ids whose denial was already surfaced from an approval response
+    denied_tool_use_ids: HashSet<String>,
+    // Bash tool_use_ids still waiting for their tool_result
+    pending_commands: HashSet<String>,
+    options: ClaudeLogOptions,
 }
 
 impl ClaudeLogProcessor {
@@ -295,10 +541,14 @@ impl ClaudeLogProcessor {
     fn new_with_strategy(strategy: HistoryStrategy) -> Self {
         Self {
             model_name: None,
+            init_model: None,
             tool_map: HashMap::new(),
             strategy,
             streaming_messages: HashMap::new(),
             streaming_message_id: None,
+            denied_tool_use_ids: HashSet::new(),
+            pending_commands: HashSet::new(),
+            options: ClaudeLogOptions::default(),
         }
     }
 
@@ -308,100 +558,122 @@ impl ClaudeLogProcessor {
         current_dir: &Path,
         entry_index_provider: EntryIndexProvider,
         strategy: HistoryStrategy,
+        options: ClaudeLogOptions,
     ) {
         let current_dir_clone = current_dir.to_owned();
         tokio::spawn(async move {
-            let mut stream = msg_store.history_plus_stream();
-            let mut buffer = String::new();
+            let mut lines = msg_store.stdout_bounded_lines_stream();
+            let sink = PatchCoalescer::resuming(msg_store.clone(), &entry_index_provider);
             let worktree_path = current_dir_clone.to_string_lossy().to_string();
             let mut session_id_extracted = false;
             let mut processor = Self::new_with_strategy(strategy);
-
-            while let Some(Ok(msg)) = stream.next().await {
-                let chunk = match msg {
-                    LogMsg::Stdout(x) => x,
-                    LogMsg::JsonPatch(_) | LogMsg::SessionId(_) | LogMsg::Stderr(_) => continue,
-                    LogMsg::Finished => break,
-                };
-
-                buffer.push_str(&chunk);
-
-                // Process complete JSON lines
-                for line in buffer
-                    .split_inclusive('\n')
-                    .filter(|l| l.ends_with('\n'))
-                    .map(str::to_owned)
-                    .collect::<Vec<_>>()
-                {
-                    let trimmed = line.trim();
-                    if trimmed.is_empty() {
+            processor.options = options;
+
+            while let Some(Ok(line)) = lines.next().await {
+                let line = match line {
+                    BoundedLine::Line(line) => line,
+                    BoundedLine::Oversized { limit, .. } => {
+                        let patch = ConversationPatch::add_normalized_entry(
+                            entry_index_provider.next(),
+                            NormalizedEntry::oversized_line(limit),
+                        );
+                        sink.push_patch(patch);
                         continue;
                     }
+                };
 
-                    // Filter out claude-code-router service messages
-                    if trimmed.starts_with("Service not running, starting service")
-                        || trimmed
-                            .contains("claude code router service has been successfully stopped")
-                    {
-                        continue;
-                    }
+                let trimmed = line.trim();
+                if trimmed.is_empty() {
+                    continue;
+                }
 
-                    match serde_json::from_str::<ClaudeJson>(trimmed) {
-                        Ok(claude_json) => {
-                            // Extract session ID if present
-                            if !session_id_extracted
-                                && let Some(session_id) = Self::extract_session_id(&claude_json)
-                            {
-                                msg_store.push_session_id(session_id);
-                                session_id_extracted = true;
-                            }
+                // Filter out claude-code-router service messages
+                if trimmed.starts_with("Service not running, starting service")
+                    || trimmed.contains("claude code router service has been successfully stopped")
+                {
+                    continue;
+                }
 
-                            let patches = processor.normalize_entries(
-                                &claude_json,
-                                &worktree_path,
-                                &entry_index_provider,
-                            );
-                            for patch in patches {
-                                msg_store.push_patch(patch);
-                            }
+                match serde_json::from_str::<ClaudeJson>(trimmed) {
+                    Ok(claude_json) => {
+                        // Extract session ID if present
+                        if !session_id_extracted
+                            && let Some(session_id) = Self::extract_session_id(&claude_json)
+                        {
+                            sink.push_session_id(session_id);
+                            session_id_extracted = true;
                         }
-                        Err(_) => {
-                            // Handle non-JSON output as raw system message
-                            if !trimmed.is_empty() {
-                                let entry = NormalizedEntry {
-                                    timestamp: None,
-                                    entry_type: NormalizedEntryType::SystemMessage,
-                                    content: trimmed.to_string(),
-                                    metadata: None,
-                                };
 
-                                let patch_id = entry_index_provider.next();
-                                let patch =
-                                    ConversationPatch::add_normalized_entry(patch_id, entry);
-                                msg_store.push_patch(patch);
-                            }
+                        let patches = processor.normalize_entries(
+                            &claude_json,
+                            &worktree_path,
+                            &entry_index_provider,
+                        );
+                        for patch in patches {
+                            sink.push_patch(patch);
                         }
                     }
-                }
+                    Err(_) => {
+                        // Handle non-JSON output as raw system message
+                        let entry = NormalizedEntry {
+                            timestamp: None,
+                            entry_type: NormalizedEntryType::SystemMessage,
+                            content: trimmed.to_string(),
+                            metadata: None,
+                        };
 
-                // Keep the partial line in the buffer
-                buffer = buffer.rsplit('\n').next().unwrap_or("").to_owned();
+                        let patch_id = entry_index_provider.next();
+                        let patch = ConversationPatch::add_normalized_entry(patch_id, entry);
+                        sink.push_patch(patch);
+                    }
+                }
             }
+            for patch in processor.finalize_pending_commands(&worktree_path) {
+                sink.push_patch(patch);
+            }
+            sink.flush();
+        });
+    }
 
-            // Handle any remaining content in buffer
-            if !buffer.trim().is_empty() {
+    /// Marks Bash calls that never received a tool_result as having an unknown outcome, so they
+    /// don't keep showing as running once the session has ended.
+    fn finalize_pending_commands(&mut self, worktree_path: &str) -> Vec<json_patch::Patch> {
+        let mut pending: Vec<ClaudeToolCallInfo> = self
+            .pending_commands
+            .drain()
+            .filter_map(|id| self.tool_map.get(&id).cloned())
+            .collect();
+        pending.sort_by_key(|info| info.entry_index);
+
+        pending
+            .into_iter()
+            .map(|info| {
                 let entry = NormalizedEntry {
                     timestamp: None,
-                    entry_type: NormalizedEntryType::SystemMessage,
-                    content: buffer.trim().to_string(),
+                    entry_type: NormalizedEntryType::ToolUse {
+                        tool_name: info.tool_name.clone(),
+                        action_type: ActionType::CommandRun {
+                            command: info.content.clone(),
+                            cwd: Self::command_cwd(&info.tool_data, worktree_path),
+                            result: None,
+                        },
+                        status: ToolStatus::Unknown,
+                    },
+                    content: info.content,
                     metadata: None,
                 };
+                ConversationPatch::replace(info.entry_index, entry)
+            })
+            .collect()
+    }
 
-                let patch_id = entry_index_provider.next();
-                let patch = ConversationPatch::add_normalized_entry(patch_id, entry);
-                msg_store.push_patch(patch);
-            }
-        });
+    fn command_cwd(tool_data: &ClaudeToolData, worktree_path: &str) -> Option<String> {
+        match tool_data {
+            ClaudeToolData::Bash { cwd, .. } => cwd
+                .as_ref()
+                .map(|cwd| make_path_relative(cwd, worktree_path)),
+            _ => None,
+        }
     }
 
     /// Extract session ID from Claude JSON
@@ -428,7 +700,8 @@ impl ClaudeLogProcessor {
                 );
                 Some(NormalizedEntry {
                     timestamp: None,
-                    entry_type: NormalizedEntryType::ErrorMessage { error_type: NormalizedEntryError::Other,
+                    entry_type: NormalizedEntryType::Notification {
+                        level: NotificationLevel::Warn,
                     },
                     content: "Claude Code + ANTHROPIC_API_KEY detected. Usage will be billed via Anthropic pay-as-you-go instead of your Claude subscription.".to_string(),
                     metadata: None,
@@ -438,38 +711,85 @@ impl ClaudeLogProcessor {
         }
     }
 
+    /// Explains a `compact_boundary` system message, after which Claude only sees a summary of
+    /// the earlier conversation.
+    fn compaction_notice(metadata: Option<&ClaudeCompactMetadata>) -> String {
+        let manual = metadata.and_then(|m| m.trigger.as_deref()) == Some("manual");
+        let mut notice = if manual {
+            "Conversation compacted with /compact".to_string()
+        } else {
+            "Conversation compacted to free context".to_string()
+        };
+        if let Some(pre_tokens) = metadata.and_then(|m| m.pre_tokens) {
+            notice.push_str(&format!(" ({pre_tokens} tokens summarized)"));
+        }
+        notice
+    }
+
+    /// Plain text of a tool_result content, whether it is a string or a list of text items
+    fn tool_result_text(content: &serde_json::Value) -> Option<String> {
+        if let Some(s) = content.as_str() {
+            return Some(s.to_string());
+        }
+        serde_json::from_value::<Vec<ClaudeToolResultTextItem>>(content.clone())
+            .ok()
+            .filter(|items| !items.is_empty())
+            .map(|items| {
+                items
+                    .into_iter()
+                    .map(|i| i.text)
+                    .collect::<Vec<_>>()
+                    .join("\n\n")
+            })
+    }
+
+    /// The feedback a user gave when rejecting a plan, without Claude's boilerplate around it.
+    fn plan_rejection_feedback(content: &serde_json::Value) -> Option<String> {
+        const USER_SAID_MARKER: &str = "the user said:";
+
+        let text = Self::tool_result_text(content)?;
+        let feedback = match text.find(USER_SAID_MARKER) {
+            Some(pos) => &text[pos + USER_SAID_MARKER.len()..],
+            // Claude's canned rejection text carries no feedback of its own
+            None if text.starts_with("The user doesn't want to") => "",
+            None => text.as_str(),
+        };
+        let feedback = feedback.trim();
+        (!feedback.is_empty() && feedback != "Denied by user").then(|| feedback.to_string())
+    }
+
+    /// Whether an errored tool_result is Claude refusing to run the tool, rather than the tool
+    /// itself failing.
+    fn is_permission_denial(text: &str) -> bool {
+        const DENIAL_PREFIXES: [&str; 3] = [
+            "Claude requested permissions to",
+            "Permission to use",
+            "The user doesn't want to proceed with this tool use",
+        ];
+
+        let text = text.trim_start();
+        DENIAL_PREFIXES
+            .iter()
+            .any(|prefix| text.starts_with(prefix))
+    }
+
     /// Normalize Claude tool_result content to either Markdown string or parsed JSON.
-    /// - If content is a string that parses as JSON, return Json with parsed value.
+    /// - If content is a string that parses as JSON, return Json with parsed value, unless
+    ///   `prefer_raw` is set.
     /// - If content is a string (non-JSON), return Markdown with the raw string.
     /// - If content is an array of { text: string }, join texts as Markdown.
     /// - Otherwise return Json with the original value.
     fn normalize_claude_tool_result_value(
         content: &serde_json::Value,
+        prefer_raw: bool,
     ) -> (crate::logs::ToolResultValueType, serde_json::Value) {
-        if let Some(s) = content.as_str() {
-            if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(s) {
-                return (crate::logs::ToolResultValueType::Json, parsed);
-            }
-            return (
-                crate::logs::ToolResultValueType::Markdown,
-                serde_json::Value::String(s.to_string()),
-            );
-        }
-
-        if let Ok(items) = serde_json::from_value::<Vec<ClaudeToolResultTextItem>>(content.clone())
-            && !items.is_empty()
-        {
-            let joined = items
-                .into_iter()
-                .map(|i| i.text)
-                .collect::<Vec<_>>()
-                .join("\n\n");
-            if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(&joined) {
+        if let Some(text) = Self::tool_result_text(content) {
+            if !prefer_raw && let Ok(parsed) = serde_json::from_str::<serde_json::Value>(&text) {
                 return (crate::logs::ToolResultValueType::Json, parsed);
             }
             return (
                 crate::logs::ToolResultValueType::Markdown,
-                serde_json::Value::String(joined),
+                serde_json::Value::String(text),
             );
         }
 
@@ -481,6 +801,7 @@ impl ClaudeLogProcessor {
         content_item: &ClaudeContentItem,
         role: &str,
         worktree_path: &str,
+        diff_algorithm: DiffAlgorithm,
     ) -> Option<NormalizedEntry> {
         match content_item {
             ClaudeContentItem::Text { text } => {
@@ -507,7 +828,8 @@ impl ClaudeLogProcessor {
             }),
             ClaudeContentItem::ToolUse { tool_data, id } => {
                 let name = tool_data.get_name();
-                let action_type = Self::extract_action_type(tool_data, worktree_path);
+                let action_type =
+                    Self::extract_action_type(tool_data, worktree_path, diff_algorithm);
                 let content =
                     Self::generate_concise_content(tool_data, &action_type, worktree_path);
 
@@ -536,14 +858,57 @@ impl ClaudeLogProcessor {
                 // TODO: Add proper ToolResult support to NormalizedEntry when the type system supports it
                 None
             }
+            ClaudeContentItem::Image { source } => {
+                let path = source
+                    .path
+                    .as_deref()
+                    .map(|path| make_path_relative(path, worktree_path));
+                let content = match (&path, &source.media_type) {
+                    (Some(path), _) => format!("Image shared: {path}"),
+                    (None, Some(media_type)) => format!("Image shared ({media_type})"),
+                    (None, None) => "Image shared".to_string(),
+                };
+
+                let mut metadata =
+                    serde_json::to_value(content_item).unwrap_or(serde_json::Value::Null);
+                if let Some(path) = path
+                    && let Some(obj) = metadata.as_object_mut()
+                {
+                    obj.insert(
+                        "thumbnail_path".to_string(),
+                        serde_json::Value::String(path),
+                    );
+                }
+
+                Some(NormalizedEntry {
+                    timestamp: None,
+                    entry_type: NormalizedEntryType::SystemMessage,
+                    content,
+                    metadata: Some(metadata),
+                })
+            }
         }
     }
 
+    /// Extract `input` if present by serializing then deserializing to a tiny struct
+    fn tool_input(tool_data: &ClaudeToolData) -> serde_json::Value {
+        serde_json::to_value(tool_data)
+            .ok()
+            .and_then(|v| serde_json::from_value::<ClaudeToolWithInput>(v).ok())
+            .map(|w| w.input)
+            .unwrap_or(serde_json::Value::Null)
+    }
+
     /// Extract action type from structured tool data
-    fn extract_action_type(tool_data: &ClaudeToolData, worktree_path: &str) -> ActionType {
+    fn extract_action_type(
+        tool_data: &ClaudeToolData,
+        worktree_path: &str,
+        diff_algorithm: DiffAlgorithm,
+    ) -> ActionType {
         match tool_data {
             ClaudeToolData::Read { file_path } => ActionType::FileRead {
                 path: make_path_relative(file_path, worktree_path),
+                result: None,
             },
             ClaudeToolData::Edit {
                 file_path,
@@ -551,14 +916,15 @@ impl ClaudeLogProcessor {
                 new_string,
             } => {
                 let changes = if old_string.is_some() || new_string.is_some() {
-                    vec![FileChange::Edit {
-                        unified_diff: create_unified_diff(
+                    vec![FileChange::edit(
+                        create_unified_diff(
                             file_path,
                             &old_string.clone().unwrap_or_default(),
                             &new_string.clone().unwrap_or_default(),
+                            diff_algorithm,
                         ),
-                        has_line_numbers: false,
-                    }]
+                        false,
+                    )]
                 } else {
                     vec![]
                 };
@@ -575,6 +941,7 @@ impl ClaudeLogProcessor {
                             Some(create_unified_diff_hunk(
                                 &edit.old_string.clone().unwrap_or_default(),
                                 &edit.new_string.clone().unwrap_or_default(),
+                                diff_algorithm,
                             ))
                         } else {
                             None
@@ -583,10 +950,10 @@ impl ClaudeLogProcessor {
                     .collect();
                 ActionType::FileEdit {
                     path: make_path_relative(file_path, worktree_path),
-                    changes: vec![FileChange::Edit {
-                        unified_diff: concatenate_diff_hunks(file_path, &hunks),
-                        has_line_numbers: false,
-                    }],
+                    changes: vec![FileChange::edit(
+                        concatenate_diff_hunks(file_path, &hunks),
+                        false,
+                    )],
                 }
             }
             ClaudeToolData::Write { file_path, content } => {
@@ -598,15 +965,24 @@ impl ClaudeLogProcessor {
                     changes: diffs,
                 }
             }
-            ClaudeToolData::Bash { command, .. } => ActionType::CommandRun {
+            ClaudeToolData::Bash { command, cwd, .. } => ActionType::CommandRun {
                 command: command.clone(),
+                cwd: cwd
+                    .as_ref()
+                    .map(|cwd| make_path_relative(cwd, worktree_path)),
                 result: None,
             },
             ClaudeToolData::Grep { pattern, .. } => ActionType::Search {
                 query: pattern.clone(),
             },
-            ClaudeToolData::WebFetch { url, .. } => ActionType::WebFetch { url: url.clone() },
-            ClaudeToolData::WebSearch { query, .. } => ActionType::WebFetch { url: query.clone() },
+            ClaudeToolData::WebFetch { url, .. } => ActionType::WebFetch {
+                url: url.clone(),
+                result: None,
+            },
+            ClaudeToolData::WebSearch { query, .. } => ActionType::WebFetch {
+                url: query.clone(),
+                result: None,
+            },
             ClaudeToolData::Task {
                 description,
                 prompt,
@@ -672,17 +1048,13 @@ impl ClaudeLogProcessor {
                     } else {
                         name.to_string()
                     };
-                    // Extract `input` if present by serializing then deserializing to a tiny struct
-                    let args = serde_json::to_value(tool_data)
-                        .ok()
-                        .and_then(|v| serde_json::from_value::<ClaudeToolWithInput>(v).ok())
-                        .map(|w| w.input)
-                        .unwrap_or(serde_json::Value::Null);
                     ActionType::Tool {
                         tool_name: label,
-                        arguments: Some(args),
+                        arguments: Some(Self::tool_input(tool_data)),
                         result: None,
                     }
+                } else if let Some(alias) = tool_registry::tool_alias(name) {
+                    alias.action_type(&Self::tool_input(tool_data), worktree_path)
                 } else {
                     ActionType::Other {
                         description: format!("Tool: {}", tool_data.get_name()),
@@ -703,11 +1075,16 @@ impl ClaudeLogProcessor {
         match claude_json {
             ClaudeJson::System {
                 subtype,
+                model,
                 api_key_source,
+                executor_config,
+                compact_metadata,
                 ..
             } => {
                 // emit billing warning if required
-                if let Some(warning) = Self::warn_if_unmanaged_key(api_key_source) {
+                if let Some(warning) = Self::warn_if_unmanaged_key(api_key_source)
+                    && !self.options.suppress_api_key_warning
+                {
                     let idx = entry_index_provider.next();
                     patches.push(ConversationPatch::add_normalized_entry(idx, warning));
                 }
@@ -717,6 +1094,32 @@ impl ClaudeLogProcessor {
                     Some("init") => {
                         // Skip system init messages because it doesn't contain the actual model that will be used in assistant messages in case of claude-code-router.
                         // We'll send system initialized message with first assistant message that has a model field.
+                        self.init_model = model.clone();
+                    }
+                    Some("executor_config") => {
+                        let entry = NormalizedEntry {
+                            timestamp: None,
+                            entry_type: NormalizedEntryType::SystemMessage,
+                            content: "Effective executor configuration".to_string(),
+                            metadata: executor_config.clone(),
+                        };
+                        let idx = entry_index_provider.next();
+                        patches.push(ConversationPatch::add_normalized_entry(idx, entry));
+                    }
+                    Some("compact_boundary") => {
+                        let entry = NormalizedEntry {
+                            timestamp: None,
+                            entry_type: NormalizedEntryType::Notification {
+                                level: NotificationLevel::Info,
+                            },
+                            content: Self::compaction_notice(compact_metadata.as_ref()),
+                            metadata: Some(
+                                serde_json::to_value(claude_json)
+                                    .unwrap_or(serde_json::Value::Null),
+                            ),
+                        };
+                        let idx = entry_index_provider.next();
+                        patches.push(ConversationPatch::add_normalized_entry(idx, entry));
                     }
                     Some(subtype) => {
                         let entry = NormalizedEntry {
@@ -747,7 +1150,9 @@ impl ClaudeLogProcessor {
                 }
             }
             ClaudeJson::Assistant { message, .. } => {
-                if let Some(patch) = extract_model_name(self, message, entry_index_provider) {
+                if let Some(patch) = extract_model_name(self, message, entry_index_provider)
+                    .or_else(|| router_model_fallback(self, entry_index_provider))
+                {
                     patches.push(patch);
                 }
 
@@ -764,7 +1169,16 @@ impl ClaudeLogProcessor {
                     match item {
                         ClaudeContentItem::ToolUse { id, tool_data } => {
                             let tool_name = tool_data.get_name().to_string();
-                            let action_type = Self::extract_action_type(tool_data, worktree_path);
+                            let action_type = Self::extract_action_type(
+                                tool_data,
+                                worktree_path,
+                                self.options.diff_algorithm,
+                            );
+                            // Hidden tool uses get no entry, so their results are dropped too
+                            if entry_index.is_none() && self.options.tool_filter.hides(&action_type)
+                            {
+                                continue;
+                            }
                             let content_text = Self::generate_concise_content(
                                 tool_data,
                                 &action_type,
@@ -793,6 +1207,9 @@ impl ClaudeLogProcessor {
                             };
                             let is_new = entry_index.is_none();
                             let id_num = entry_index.unwrap_or_else(|| entry_index_provider.next());
+                            if matches!(tool_data, ClaudeToolData::Bash { .. }) {
+                                self.pending_commands.insert(id.clone());
+                            }
                             self.tool_map.insert(
                                 id.clone(),
                                 ClaudeToolCallInfo {
@@ -809,11 +1226,14 @@ impl ClaudeLogProcessor {
                             };
                             patches.push(patch);
                         }
-                        ClaudeContentItem::Text { .. } | ClaudeContentItem::Thinking { .. } => {
+                        ClaudeContentItem::Text { .. }
+                        | ClaudeContentItem::Thinking { .. }
+                        | ClaudeContentItem::Image { .. } => {
                             if let Some(entry) = Self::content_item_to_normalized_entry(
                                 item,
                                 &message.role,
                                 worktree_path,
+                                self.options.diff_algorithm,
                             ) {
                                 let is_new = entry_index.is_none();
                                 let idx =
@@ -837,13 +1257,12 @@ impl ClaudeLogProcessor {
                         .iter()
                         .any(|c| matches!(c, ClaudeContentItem::Text { .. }))
                 {
-                    let cur = entry_index_provider.current();
-                    if cur > 0 {
-                        for _ in 0..cur {
-                            patches.push(ConversationPatch::remove_diff(0.to_string()));
-                        }
+                    let mut reset = Vec::new();
+                    if entry_index_provider.current() > 0 {
+                        reset.push(ConversationPatch::clear_entries());
                         entry_index_provider.reset();
                         self.tool_map.clear();
+                        self.pending_commands.clear();
                     }
 
                     for item in &message.content {
@@ -857,12 +1276,26 @@ impl ClaudeLogProcessor {
                                 ),
                             };
                             let id = entry_index_provider.next();
-                            patches.push(ConversationPatch::add_normalized_entry(id, entry));
+                            reset.push(ConversationPatch::add_normalized_entry(id, entry));
                         }
                     }
+                    // Clear and re-seed the conversation in one message
+                    patches.push(ConversationPatch::batch(reset));
                 }
 
                 for item in &message.content {
+                    if let ClaudeContentItem::Image { .. } = item
+                        && let Some(entry) = Self::content_item_to_normalized_entry(
+                            item,
+                            &message.role,
+                            worktree_path,
+                            self.options.diff_algorithm,
+                        )
+                    {
+                        let id = entry_index_provider.next();
+                        patches.push(ConversationPatch::add_normalized_entry(id, entry));
+                    }
+
                     if let ClaudeContentItem::ToolResult {
                         tool_use_id,
                         content,
@@ -870,6 +1303,7 @@ impl ClaudeLogProcessor {
                     } = item
                         && let Some(info) = self.tool_map.get(tool_use_id).cloned()
                     {
+                        self.pending_commands.remove(tool_use_id);
                         let is_command = matches!(info.tool_data, ClaudeToolData::Bash { .. });
 
                         let _display_tool_name = if is_command {
@@ -927,6 +1361,7 @@ impl ClaudeLogProcessor {
                                     tool_name: info.tool_name.clone(),
                                     action_type: ActionType::CommandRun {
                                         command: info.content.clone(),
+                                        cwd: Self::command_cwd(&info.tool_data, worktree_path),
                                         result,
                                     },
                                     status,
@@ -935,36 +1370,34 @@ impl ClaudeLogProcessor {
                                 metadata: None,
                             };
                             patches.push(ConversationPatch::replace(info.entry_index, entry));
-                        } else if matches!(
-                            info.tool_data,
-                            ClaudeToolData::Unknown { .. }
-                                | ClaudeToolData::Oracle { .. }
-                                | ClaudeToolData::Mermaid { .. }
-                                | ClaudeToolData::CodebaseSearchAgent { .. }
-                                | ClaudeToolData::NotebookEdit { .. }
-                        ) {
-                            let (res_type, res_value) =
-                                Self::normalize_claude_tool_result_value(content);
-
-                            let args_to_show = serde_json::to_value(&info.tool_data)
-                                .ok()
-                                .and_then(|v| serde_json::from_value::<ClaudeToolWithInput>(v).ok())
-                                .map(|w| w.input)
-                                .unwrap_or(serde_json::Value::Null);
-
-                            let tool_name = info.tool_data.get_name().to_string();
-                            let is_mcp = tool_name.starts_with("mcp__");
-                            let label = if is_mcp {
-                                let parts: Vec<&str> = tool_name.split("__").collect();
-                                if parts.len() >= 3 {
-                                    format!("mcp:{}:{}", parts[1], parts[2])
-                                } else {
-                                    tool_name.clone()
-                                }
+                        } else if matches!(info.tool_data, ClaudeToolData::Write { .. }) {
+                            let status = if is_error.unwrap_or(false) {
+                                ToolStatus::Failed
                             } else {
-                                tool_name.clone()
+                                ToolStatus::Success
                             };
+                            let write_mode = WriteMode::from_tool_result(content);
 
+                            let entry = NormalizedEntry {
+                                timestamp: None,
+                                entry_type: NormalizedEntryType::ToolUse {
+                                    tool_name: info.tool_name.clone(),
+                                    action_type: Self::extract_action_type(
+                                        &info.tool_data,
+                                        worktree_path,
+                                        self.options.diff_algorithm,
+                                    ),
+                                    status,
+                                },
+                                content: match write_mode {
+                                    Some(mode) => format!("{} {}", mode.label(), info.content),
+                                    None => info.content.clone(),
+                                },
+                                metadata: write_mode
+                                    .map(|mode| serde_json::json!({ "write_mode": mode })),
+                            };
+                            patches.push(ConversationPatch::replace(info.entry_index, entry));
+                        } else if let ClaudeToolData::WebFetch { url, .. } = &info.tool_data {
                             let status = if is_error.unwrap_or(false) {
                                 ToolStatus::Failed
                             } else {
@@ -974,13 +1407,11 @@ impl ClaudeLogProcessor {
                             let entry = NormalizedEntry {
                                 timestamp: None,
                                 entry_type: NormalizedEntryType::ToolUse {
-                                    tool_name: label.clone(),
-                                    action_type: ActionType::Tool {
-                                        tool_name: label,
-                                        arguments: Some(args_to_show),
-                                        result: Some(crate::logs::ToolResult {
-                                            r#type: res_type,
-                                            value: res_value,
+                                    tool_name: info.tool_name.clone(),
+                                    action_type: ActionType::WebFetch {
+                                        url: url.clone(),
+                                        result: Self::tool_result_text(content).map(|text| {
+                                            crate::logs::ToolResult::markdown(text).capped()
                                         }),
                                     },
                                     status,
@@ -989,15 +1420,156 @@ impl ClaudeLogProcessor {
                                 metadata: None,
                             };
                             patches.push(ConversationPatch::replace(info.entry_index, entry));
-                        }
-                        // Note: With control protocol, denials are handled via protocol messages
-                        // rather than error content parsing
-                    }
-                }
-            }
-            ClaudeJson::ToolUse { tool_data, .. } => {
+                        } else if let ClaudeToolData::Read { file_path } = &info.tool_data {
+                            let status = if is_error.unwrap_or(false) {
+                                ToolStatus::Failed
+                            } else {
+                                ToolStatus::Success
+                            };
+
+                            let entry = NormalizedEntry {
+                                timestamp: None,
+                                entry_type: NormalizedEntryType::ToolUse {
+                                    tool_name: info.tool_name.clone(),
+                                    action_type: ActionType::FileRead {
+                                        path: make_path_relative(file_path, worktree_path),
+                                        result: Self::tool_result_text(content).map(|text| {
+                                            crate::logs::ToolResult::markdown(text).capped()
+                                        }),
+                                    },
+                                    status,
+                                },
+                                content: info.content.clone(),
+                                metadata: None,
+                            };
+                            patches.push(ConversationPatch::replace(info.entry_index, entry));
+                        } else if matches!(info.tool_data, ClaudeToolData::ExitPlanMode { .. })
+                            && is_error.unwrap_or(false)
+                        {
+                            // A rejected plan comes back as an errored tool_result carrying the
+                            // user's feedback, if they gave any
+                            let feedback = Self::plan_rejection_feedback(content);
+                            let entry = NormalizedEntry {
+                                timestamp: None,
+                                entry_type: NormalizedEntryType::ToolUse {
+                                    tool_name: info.tool_name.clone(),
+                                    action_type: Self::extract_action_type(
+                                        &info.tool_data,
+                                        worktree_path,
+                                        self.options.diff_algorithm,
+                                    ),
+                                    status: ToolStatus::Denied {
+                                        reason: feedback.clone(),
+                                    },
+                                },
+                                content: info.content.clone(),
+                                metadata: None,
+                            };
+                            patches.push(ConversationPatch::replace(info.entry_index, entry));
+
+                            if !self.denied_tool_use_ids.contains(tool_use_id) {
+                                let entry = NormalizedEntry {
+                                    timestamp: None,
+                                    entry_type: NormalizedEntryType::UserFeedback {
+                                        denied_tool: info.tool_data.get_name().to_string(),
+                                    },
+                                    content: feedback
+                                        .unwrap_or_else(|| "User rejected the plan".to_string()),
+                                    metadata: None,
+                                };
+                                let idx = entry_index_provider.next();
+                                patches.push(ConversationPatch::add_normalized_entry(idx, entry));
+                            }
+                        } else if matches!(
+                            info.tool_data,
+                            ClaudeToolData::Unknown { .. }
+                                | ClaudeToolData::Oracle { .. }
+                                | ClaudeToolData::Mermaid { .. }
+                                | ClaudeToolData::CodebaseSearchAgent { .. }
+                                | ClaudeToolData::NotebookEdit { .. }
+                        ) {
+                            let (res_type, res_value) = Self::normalize_claude_tool_result_value(
+                                content,
+                                self.options.prefer_raw_tool_results,
+                            );
+
+                            let args_to_show = Self::tool_input(&info.tool_data);
+
+                            let tool_name = info.tool_data.get_name().to_string();
+                            let is_mcp = tool_name.starts_with("mcp__");
+                            let label = if is_mcp {
+                                let parts: Vec<&str> = tool_name.split("__").collect();
+                                if parts.len() >= 3 {
+                                    format!("mcp:{}:{}", parts[1], parts[2])
+                                } else {
+                                    tool_name.clone()
+                                }
+                            } else if let Some(alias) = tool_registry::tool_alias(&tool_name) {
+                                alias.display_name
+                            } else {
+                                tool_name.clone()
+                            };
+
+                            let status = if is_error.unwrap_or(false) {
+                                ToolStatus::Failed
+                            } else {
+                                ToolStatus::Success
+                            };
+
+                            let entry = NormalizedEntry {
+                                timestamp: None,
+                                entry_type: NormalizedEntryType::ToolUse {
+                                    tool_name: label.clone(),
+                                    action_type: ActionType::Tool {
+                                        tool_name: label,
+                                        arguments: Some(args_to_show),
+                                        result: Some(
+                                            crate::logs::ToolResult {
+                                                r#type: res_type,
+                                                value: res_value,
+                                            }
+                                            .capped(),
+                                        ),
+                                    },
+                                    status,
+                                },
+                                content: info.content.clone(),
+                                metadata: None,
+                            };
+                            patches.push(ConversationPatch::replace(info.entry_index, entry));
+                        }
+
+                        // Denials through the approval service are reported by their
+                        // ApprovalResponse. Claude's own permission rules block a tool without
+                        // one, so surface those from the errored result instead.
+                        if is_error.unwrap_or(false)
+                            && !matches!(info.tool_data, ClaudeToolData::ExitPlanMode { .. })
+                            && !self.denied_tool_use_ids.contains(tool_use_id)
+                            && Self::tool_result_text(content)
+                                .is_some_and(|text| Self::is_permission_denial(&text))
+                        {
+                            let denied_tool = info.tool_data.get_name().to_string();
+                            let entry = NormalizedEntry {
+                                timestamp: None,
+                                entry_type: NormalizedEntryType::UserFeedback {
+                                    denied_tool: denied_tool.clone(),
+                                },
+                                content: format!("Tool {denied_tool} was not permitted"),
+                                metadata: None,
+                            };
+                            let idx = entry_index_provider.next();
+                            patches.push(ConversationPatch::add_normalized_entry(idx, entry));
+                        }
+                    }
+                }
+            }
+            ClaudeJson::ToolUse { tool_data, .. } => {
                 let tool_name = tool_data.get_name();
-                let action_type = Self::extract_action_type(tool_data, worktree_path);
+                let action_type = Self::extract_action_type(
+                    tool_data,
+                    worktree_path,
+                    self.options.diff_algorithm,
+                );
                 let content =
                     Self::generate_concise_content(tool_data, &action_type, worktree_path);
 
@@ -1061,6 +1633,8 @@ impl ClaudeLogProcessor {
                             *index,
                             delta,
                             worktree_path,
+                            self.options.diff_algorithm,
+                            &self.options.tool_filter,
                             entry_index_provider,
                         )
                     {
@@ -1070,14 +1644,40 @@ impl ClaudeLogProcessor {
                 ClaudeStreamEvent::ContentBlockStop { .. } => {}
                 ClaudeStreamEvent::MessageDelta { .. } => {}
                 ClaudeStreamEvent::MessageStop => {
-                    if let Some(message_id) = self.streaming_message_id.take() {
-                        let _ = self.streaming_messages.remove(&message_id);
-                    }
+                    // Keep the streamed state around: the final assistant message can arrive
+                    // after message_stop and must replace the streamed entries, not duplicate them.
+                    self.streaming_message_id = None;
                 }
                 ClaudeStreamEvent::Unknown => {}
             },
-            ClaudeJson::Result { is_error, .. } => {
-                if matches!(self.strategy, HistoryStrategy::AmpResume) && is_error.unwrap_or(false)
+            ClaudeJson::Result {
+                subtype,
+                is_error,
+                result,
+                error,
+                duration_ms,
+                num_turns,
+                ..
+            } => {
+                let message = error
+                    .clone()
+                    .or_else(|| result.as_ref().and_then(|r| r.as_str()).map(str::to_string))
+                    .unwrap_or_default();
+                let rate_limited = subtype.as_deref() == Some("rate_limit")
+                    || (is_error.unwrap_or(false) && is_rate_limit_message(&message));
+                if rate_limited {
+                    let message = if message.trim().is_empty() {
+                        "Claude API rate limit reached"
+                    } else {
+                        message.as_str()
+                    };
+                    let idx = entry_index_provider.next();
+                    patches.push(ConversationPatch::add_normalized_entry(
+                        idx,
+                        rate_limit_entry(message),
+                    ));
+                } else if matches!(self.strategy, HistoryStrategy::AmpResume)
+                    && is_error.unwrap_or(false)
                 {
                     let entry = NormalizedEntry {
                         timestamp: None,
@@ -1092,13 +1692,36 @@ impl ClaudeLogProcessor {
                     };
                     let idx = entry_index_provider.next();
                     patches.push(ConversationPatch::add_normalized_entry(idx, entry));
+                } else if self.options.completion_summary
+                    && matches!(self.strategy, HistoryStrategy::Default)
+                    && !is_error.unwrap_or(false)
+                    && let Some(content) = completion_summary(*duration_ms, *num_turns)
+                {
+                    let entry = NormalizedEntry {
+                        timestamp: None,
+                        entry_type: NormalizedEntryType::SystemMessage,
+                        content,
+                        metadata: None,
+                    };
+                    let idx = entry_index_provider.next();
+                    patches.push(ConversationPatch::add_normalized_entry(idx, entry));
                 }
             }
             ClaudeJson::ApprovalResponse {
-                call_id: _,
+                call_id,
                 tool_name,
                 approval_status,
             } => {
+                if matches!(approval_status, ApprovalStatus::Denied { .. }) {
+                    self.denied_tool_use_ids.insert(call_id.clone());
+                }
+                // Denied and timed-out calls already carry a final status
+                if !matches!(
+                    approval_status,
+                    ApprovalStatus::Pending | ApprovalStatus::Approved
+                ) {
+                    self.pending_commands.remove(call_id);
+                }
                 // Convert denials and timeouts to visible entries (matching Codex behavior)
                 let entry_opt = match approval_status {
                     ApprovalStatus::Pending => None,
@@ -1153,11 +1776,11 @@ impl ClaudeLogProcessor {
         worktree_path: &str,
     ) -> String {
         match action_type {
-            ActionType::FileRead { path } => format!("`{path}`"),
+            ActionType::FileRead { path, .. } => format!("`{path}`"),
             ActionType::FileEdit { path, .. } => format!("`{path}`"),
             ActionType::CommandRun { command, .. } => format!("`{command}`"),
             ActionType::Search { query } => format!("`{query}`"),
-            ActionType::WebFetch { url } => format!("`{url}`"),
+            ActionType::WebFetch { url, .. } => format!("`{url}`"),
             ActionType::TaskCreate { description } => {
                 if description.is_empty() {
                     "Task".to_string()
@@ -1183,7 +1806,7 @@ impl ClaudeLogProcessor {
             },
             ActionType::PlanPresentation { plan } => plan.clone(),
             ActionType::TodoManagement { .. } => "TODO list updated".to_string(),
-            ActionType::Other { description: _ } => match tool_data {
+            ActionType::Other { description } => match tool_data {
                 ClaudeToolData::LS { path } => {
                     let relative_path = make_path_relative(path, worktree_path);
                     if relative_path.is_empty() {
@@ -1234,6 +1857,11 @@ impl ClaudeLogProcessor {
                         "Undo edit".to_string()
                     }
                 }
+                ClaudeToolData::Unknown { .. }
+                    if tool_registry::tool_alias(tool_data.get_name()).is_some() =>
+                {
+                    description.clone()
+                }
                 _ => tool_data.get_name().to_string(),
             },
         }
@@ -1262,6 +1890,43 @@ fn extract_model_name(
     }
 }
 
+/// Some claude-code-router providers never set `message.model`. Once a complete assistant
+/// message arrives without one, report the init model, or that the model is unknown.
+fn router_model_fallback(
+    processor: &mut ClaudeLogProcessor,
+    entry_index_provider: &EntryIndexProvider,
+) -> Option<json_patch::Patch> {
+    if !processor.options.claude_code_router || processor.model_name.is_some() {
+        return None;
+    }
+    let model = processor
+        .init_model
+        .clone()
+        .unwrap_or_else(|| "unknown".to_string());
+    processor.model_name = Some(model.clone());
+    let entry = NormalizedEntry {
+        timestamp: None,
+        entry_type: NormalizedEntryType::SystemMessage,
+        content: format!("System initialized with model: {model}"),
+        metadata: None,
+    };
+    let id = entry_index_provider.next();
+    Some(ConversationPatch::add_normalized_entry(id, entry))
+}
+
+/// "Completed in 6.1s, 3 turns" from a Result message, or `None` when it reports neither.
+fn completion_summary(duration_ms: Option<u64>, num_turns: Option<u32>) -> Option<String> {
+    let mut parts = Vec::new();
+    if let Some(ms) = duration_ms {
+        parts.push(format!("in {:.1}s", ms as f64 / 1000.0));
+    }
+    if let Some(turns) = num_turns {
+        let unit = if turns == 1 { "turn" } else { "turns" };
+        parts.push(format!("{turns} {unit}"));
+    }
+    (!parts.is_empty()).then(|| format!("Completed {}", parts.join(", ")))
+}
+
 struct StreamingMessageState {
     role: String,
     contents: HashMap<usize, StreamingContentState>,
@@ -1286,6 +1951,8 @@ impl StreamingMessageState {
         index: usize,
         delta: &ClaudeContentBlockDelta,
         worktree_path: &str,
+        diff_algorithm: DiffAlgorithm,
+        tool_filter: &ToolRenderFilter,
         entry_index_provider: &EntryIndexProvider,
     ) -> Option<json_patch::Patch> {
         if let std::collections::hash_map::Entry::Vacant(e) = self.contents.entry(index) {
@@ -1296,15 +1963,18 @@ impl StreamingMessageState {
         let entry_state = self.contents.get_mut(&index)?;
         entry_state.apply_content_delta(delta);
 
-        let content_item = entry_state.to_content_item();
+        let content_item = entry_state.to_content_item()?;
         let entry = ClaudeLogProcessor::content_item_to_normalized_entry(
             &content_item,
             &self.role,
             worktree_path,
+            diff_algorithm,
         )?;
 
         if let Some(existing_index) = entry_state.entry_index {
-            Some(ConversationPatch::replace(existing_index, entry))
+            Some(ConversationPatch::streaming_replace(existing_index, entry))
+        } else if tool_filter.hides_entry(&entry) {
+            None
         } else {
             let entry_index = entry_index_provider.next();
             entry_state.entry_index = Some(entry_index);
@@ -1319,10 +1989,15 @@ impl StreamingMessageState {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 enum StreamingContentKind {
     Text,
     Thinking,
+    /// Tool call whose input arrives as `input_json_delta` fragments in `buffer`
+    ToolUse {
+        id: String,
+        name: String,
+    },
 }
 
 struct StreamingContentState {
@@ -1344,6 +2019,15 @@ impl StreamingContentState {
                 buffer: thinking,
                 entry_index: None,
             }),
+            // The start block carries an empty input; it is streamed in the deltas.
+            ClaudeContentItem::ToolUse { id, tool_data } => Some(Self {
+                kind: StreamingContentKind::ToolUse {
+                    id,
+                    name: tool_data.get_name().to_string(),
+                },
+                buffer: String::new(),
+                entry_index: None,
+            }),
             _ => None,
         }
     }
@@ -1365,7 +2049,7 @@ impl StreamingContentState {
     }
 
     fn apply_content_delta(&mut self, delta: &ClaudeContentBlockDelta) {
-        match (self.kind, delta) {
+        match (&self.kind, delta) {
             (StreamingContentKind::Text, ClaudeContentBlockDelta::TextDelta { text }) => {
                 self.buffer.push_str(text);
             }
@@ -1375,6 +2059,12 @@ impl StreamingContentState {
             ) => {
                 self.buffer.push_str(thinking);
             }
+            (
+                StreamingContentKind::ToolUse { .. },
+                ClaudeContentBlockDelta::InputJsonDelta { partial_json },
+            ) => {
+                self.buffer.push_str(partial_json);
+            }
             _ => {
                 tracing::warn!(
                     "Mismatched content types: delta {:?}, kind {:?}",
@@ -1385,16 +2075,79 @@ impl StreamingContentState {
         }
     }
 
-    fn to_content_item(&self) -> ClaudeContentItem {
-        match self.kind {
-            StreamingContentKind::Text => ClaudeContentItem::Text {
+    /// `None` while a streamed tool input cannot be parsed yet, e.g. when it stops inside a key.
+    fn to_content_item(&self) -> Option<ClaudeContentItem> {
+        match &self.kind {
+            StreamingContentKind::Text => Some(ClaudeContentItem::Text {
                 text: self.buffer.clone(),
-            },
-            StreamingContentKind::Thinking => ClaudeContentItem::Thinking {
+            }),
+            StreamingContentKind::Thinking => Some(ClaudeContentItem::Thinking {
                 thinking: self.buffer.clone(),
-            },
+            }),
+            StreamingContentKind::ToolUse { id, name } => {
+                let input = if self.buffer.trim().is_empty() {
+                    serde_json::json!({})
+                } else {
+                    parse_partial_json(&self.buffer)?
+                };
+                let tool_data =
+                    serde_json::from_value(serde_json::json!({ "name": name, "input": input }))
+                        .ok()?;
+                Some(ClaudeContentItem::ToolUse {
+                    id: id.clone(),
+                    tool_data,
+                })
+            }
+        }
+    }
+}
+
+/// Best-effort parse of JSON that is still streaming in: closes an unterminated string and any
+/// open arrays or objects. Returns `None` when that still isn't valid JSON.
+fn parse_partial_json(partial: &str) -> Option<serde_json::Value> {
+    if let Ok(value) = serde_json::from_str(partial) {
+        return Some(value);
+    }
+
+    let mut closers = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in partial.chars() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' => closers.push('}'),
+            '[' => closers.push(']'),
+            '}' | ']' => {
+                closers.pop();
+            }
+            _ => {}
+        }
+    }
+
+    let mut repaired = partial.to_string();
+    if in_string {
+        if escaped {
+            repaired.pop();
         }
+        repaired.push('"');
+    }
+    let kept = repaired.trim_end().trim_end_matches(',').len();
+    repaired.truncate(kept);
+    if repaired.ends_with(':') {
+        repaired.push_str("null");
     }
+    repaired.extend(closers.into_iter().rev());
+    serde_json::from_str(&repaired).ok()
 }
 
 // Data structures for parsing Claude's JSON output format
@@ -1410,6 +2163,11 @@ pub enum ClaudeJson {
         model: Option<String>,
         #[serde(default, rename = "apiKeySource")]
         api_key_source: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        executor_config: Option<serde_json::Value>,
+        /// Present on `compact_boundary` messages
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        compact_metadata: Option<ClaudeCompactMetadata>,
     },
     #[serde(rename = "assistant")]
     Assistant {
@@ -1505,6 +2263,27 @@ pub enum ClaudeContentItem {
         content: serde_json::Value,
         is_error: Option<bool>,
     },
+    #[serde(rename = "image")]
+    Image { source: ClaudeImageSource },
+}
+
+/// Where an image content block comes from. Inline base64 data is accepted but never
+/// re-serialized, so it does not end up in entry metadata.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ClaudeImageSource {
+    /// "base64", "url" or "file"
+    #[serde(rename = "type")]
+    pub source_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub media_type: Option<String>,
+    #[serde(default, skip_serializing)]
+    pub data: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_id: Option<String>,
+    #[serde(default, alias = "file_path", skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
@@ -1544,6 +2323,8 @@ pub enum ClaudeContentBlockDelta {
     TextDelta { text: String },
     #[serde(rename = "thinking_delta")]
     ThinkingDelta { thinking: String },
+    #[serde(rename = "input_json_delta")]
+    InputJsonDelta { partial_json: String },
     #[serde(other)]
     Unknown,
 }
@@ -1608,6 +2389,8 @@ pub enum ClaudeToolData {
         command: String,
         #[serde(default)]
         description: Option<String>,
+        #[serde(default, alias = "workdir")]
+        cwd: Option<String>,
     },
     #[serde(rename = "Grep", alias = "grep")]
     Grep {
@@ -1704,6 +2487,15 @@ pub enum ClaudeToolData {
     },
 }
 
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ClaudeCompactMetadata {
+    /// `auto` when Claude compacted on its own, `manual` for `/compact`
+    #[serde(default)]
+    pub trigger: Option<String>,
+    #[serde(default)]
+    pub pre_tokens: Option<u64>,
+}
+
 // Helper structs for parsing tool_result content and generic tool input
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 struct ClaudeToolResultTextItem {
@@ -1727,6 +2519,45 @@ struct AmpBashResult {
     exit_code: i32,
 }
 
+/// Whether a Write tool call created a new file or replaced an existing one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum WriteMode {
+    Create,
+    Overwrite,
+}
+
+impl WriteMode {
+    /// Infer the mode from the Write tool_result text, e.g.
+    /// "File created successfully at: ..." or "The file ... has been updated."
+    fn from_tool_result(content: &serde_json::Value) -> Option<Self> {
+        let text = match content {
+            serde_json::Value::String(s) => s.clone(),
+            other => serde_json::from_value::<Vec<ClaudeToolResultTextItem>>(other.clone())
+                .ok()?
+                .into_iter()
+                .map(|item| item.text)
+                .collect::<Vec<_>>()
+                .join("\n"),
+        };
+
+        if text.starts_with("File created successfully") {
+            Some(Self::Create)
+        } else if text.contains("has been updated") || text.contains("has been overwritten") {
+            Some(Self::Overwrite)
+        } else {
+            None
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Create => "Create",
+            Self::Overwrite => "Overwrite",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 struct ClaudeToolCallInfo {
     entry_index: usize,
@@ -1838,6 +2669,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_router_falls_back_to_init_model() {
+        let router_processor = || {
+            let mut processor = ClaudeLogProcessor::new();
+            processor.options.claude_code_router = true;
+            processor
+        };
+        let init =
+            r#"{"type":"system","subtype":"init","session_id":"abc123","model":"deepseek-chat"}"#;
+        let assistant = r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Hello"}]},"session_id":"abc123"}"#;
+
+        let mut processor = router_processor();
+        assert!(
+            normalize_helper(&mut processor, &serde_json::from_str(init).unwrap(), "").is_empty()
+        );
+        let entries = normalize_helper(
+            &mut processor,
+            &serde_json::from_str(assistant).unwrap(),
+            "",
+        );
+        assert_eq!(
+            entries[0].content,
+            "System initialized with model: deepseek-chat"
+        );
+        // Reported once, not again for later messages
+        let entries = normalize_helper(
+            &mut processor,
+            &serde_json::from_str(assistant).unwrap(),
+            "",
+        );
+        assert!(
+            !entries
+                .iter()
+                .any(|e| e.content.starts_with("System initialized"))
+        );
+
+        let mut processor = router_processor();
+        let entries = normalize_helper(
+            &mut processor,
+            &serde_json::from_str(assistant).unwrap(),
+            "",
+        );
+        assert_eq!(entries[0].content, "System initialized with model: unknown");
+    }
+
     #[test]
     fn test_assistant_message_parsing() {
         let assistant_json = r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Hello world"}]},"session_id":"abc123"}"#;
@@ -1861,6 +2737,22 @@ mod tests {
         assert_eq!(entries.len(), 0); // Should be ignored like in old implementation
     }
 
+    #[test]
+    fn test_success_result_emits_completion_summary() {
+        let result_json = r#"{"type":"result","subtype":"success","is_error":false,"duration_ms":6059,"num_turns":3,"result":"Final result"}"#;
+        let parsed: ClaudeJson = serde_json::from_str(result_json).unwrap();
+
+        let mut processor = ClaudeLogProcessor::new();
+        processor.options.completion_summary = true;
+        let entries = normalize_helper(&mut processor, &parsed, "");
+        assert_eq!(entries.len(), 1);
+        assert!(matches!(
+            entries[0].entry_type,
+            NormalizedEntryType::SystemMessage
+        ));
+        assert_eq!(entries[0].content, "Completed in 6.1s, 3 turns");
+    }
+
     #[test]
     fn test_thinking_content() {
         let thinking_json = r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"thinking","thinking":"Let me think about this..."}]}}"#;
@@ -1876,38 +2768,665 @@ mod tests {
     }
 
     #[test]
-    fn test_todo_tool_empty_list() {
-        // Test TodoWrite with empty todo list
-        let empty_data = ClaudeToolData::TodoWrite { todos: vec![] };
-
-        let action_type =
-            ClaudeLogProcessor::extract_action_type(&empty_data, "/tmp/test-worktree");
-        let result = ClaudeLogProcessor::generate_concise_content(
-            &empty_data,
-            &action_type,
-            "/tmp/test-worktree",
-        );
+    fn test_final_assistant_message_replaces_streamed_text() {
+        let mut processor = ClaudeLogProcessor::new();
+        let provider = EntryIndexProvider::test_new();
+        let lines = [
+            r#"{"type":"stream_event","event":{"type":"message_start","message":{"id":"msg_1","role":"assistant","content":[]}}}"#,
+            r#"{"type":"stream_event","event":{"type":"content_block_start","index":0,"content_block":{"type":"text","text":""}}}"#,
+            r#"{"type":"stream_event","event":{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Hello "}}}"#,
+            r#"{"type":"stream_event","event":{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"wor"}}}"#,
+            r#"{"type":"stream_event","event":{"type":"content_block_stop","index":0}}"#,
+            r#"{"type":"stream_event","event":{"type":"message_stop"}}"#,
+            r#"{"type":"assistant","message":{"id":"msg_1","role":"assistant","content":[{"type":"text","text":"Hello world"}]}}"#,
+        ];
+
+        let mut conversation = serde_json::json!({ "entries": [] });
+        for line in lines {
+            let parsed: ClaudeJson = serde_json::from_str(line).unwrap();
+            for patch in processor.normalize_entries(&parsed, "", &provider) {
+                json_patch::patch(&mut conversation, &patch).unwrap();
+            }
+        }
 
-        assert_eq!(result, "TODO list updated");
+        let entries = conversation["entries"].as_array().unwrap();
+        assert_eq!(entries.len(), 1);
+        let entry: NormalizedEntry = serde_json::from_value(entries[0]["content"].clone()).unwrap();
+        assert!(matches!(
+            entry.entry_type,
+            NormalizedEntryType::AssistantMessage
+        ));
+        assert_eq!(entry.content, "Hello world");
     }
 
     #[test]
-    fn test_glob_tool_content_extraction() {
-        // Test Glob with pattern and path
-        let glob_data = ClaudeToolData::Glob {
-            pattern: "**/*.ts".to_string(),
-            path: Some("/tmp/test-worktree/src".to_string()),
-            limit: None,
-        };
-
-        let action_type = ClaudeLogProcessor::extract_action_type(&glob_data, "/tmp/test-worktree");
-        let result = ClaudeLogProcessor::generate_concise_content(
-            &glob_data,
-            &action_type,
-            "/tmp/test-worktree",
-        );
+    fn test_hidden_tool_kinds_produce_no_entries() {
+        let mut processor = ClaudeLogProcessor::new();
+        processor.options.tool_filter =
+            ToolRenderFilter::hiding([ActionKind::TodoManagement, ActionKind::Search]);
+        let provider = EntryIndexProvider::test_new();
+        let lines = [
+            r#"{"type":"stream_event","event":{"type":"message_start","message":{"id":"msg_1","role":"assistant","content":[]}}}"#,
+            r#"{"type":"stream_event","event":{"type":"content_block_start","index":0,"content_block":{"type":"tool_use","id":"toolu_1","name":"Grep","input":{}}}}"#,
+            r#"{"type":"stream_event","event":{"type":"content_block_delta","index":0,"delta":{"type":"input_json_delta","partial_json":"{\"pattern\": \"fn main\"}"}}}"#,
+            r#"{"type":"stream_event","event":{"type":"message_stop"}}"#,
+            r#"{"type":"assistant","message":{"id":"msg_1","role":"assistant","content":[{"type":"tool_use","id":"toolu_1","name":"Grep","input":{"pattern":"fn main"}},{"type":"tool_use","id":"toolu_2","name":"TodoWrite","input":{"todos":[{"content":"Ship it","status":"pending"}]}},{"type":"tool_use","id":"toolu_3","name":"Bash","input":{"command":"ls"}},{"type":"text","text":"Done"}]}}"#,
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"toolu_1","content":"src/main.rs","is_error":false}]}}"#,
+        ];
+
+        // Applying the patches fails if hidden entries left a gap in the indexes
+        let mut conversation = serde_json::json!({ "entries": [] });
+        for line in lines {
+            let parsed: ClaudeJson = serde_json::from_str(line).unwrap();
+            for patch in processor.normalize_entries(&parsed, "", &provider) {
+                json_patch::patch(&mut conversation, &patch).unwrap();
+            }
+        }
 
-        assert_eq!(result, "`**/*.ts`");
+        let entries: Vec<NormalizedEntry> = conversation["entries"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| serde_json::from_value(e["content"].clone()).unwrap())
+            .collect();
+        assert_eq!(entries.len(), 2);
+        assert!(matches!(
+            &entries[0].entry_type,
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::CommandRun { .. },
+                ..
+            }
+        ));
+        assert!(matches!(
+            entries[1].entry_type,
+            NormalizedEntryType::AssistantMessage
+        ));
+    }
+
+    #[test]
+    fn test_streamed_tool_use_blocks_render_incrementally() {
+        let mut processor = ClaudeLogProcessor::new();
+        let provider = EntryIndexProvider::test_new();
+        let stream_lines = [
+            r#"{"type":"stream_event","event":{"type":"message_start","message":{"id":"msg_1","role":"assistant","content":[]}}}"#,
+            r#"{"type":"stream_event","event":{"type":"content_block_start","index":0,"content_block":{"type":"tool_use","id":"toolu_1","name":"Bash","input":{}}}}"#,
+            r#"{"type":"stream_event","event":{"type":"content_block_start","index":1,"content_block":{"type":"tool_use","id":"toolu_2","name":"Read","input":{}}}}"#,
+            r#"{"type":"stream_event","event":{"type":"content_block_delta","index":0,"delta":{"type":"input_json_delta","partial_json":"{\"command\": \"cargo"}}}"#,
+            r#"{"type":"stream_event","event":{"type":"content_block_delta","index":1,"delta":{"type":"input_json_delta","partial_json":"{\"file_path\": \"/tmp/work/src/lib.rs\"}"}}}"#,
+        ];
+
+        let mut conversation = serde_json::json!({ "entries": [] });
+        let mut apply = |processor: &mut ClaudeLogProcessor, line: &str| {
+            let parsed: ClaudeJson = serde_json::from_str(line).unwrap();
+            for patch in processor.normalize_entries(&parsed, "/tmp/work", &provider) {
+                json_patch::patch(&mut conversation, &patch).unwrap();
+            }
+            conversation["entries"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|e| serde_json::from_value::<NormalizedEntry>(e["content"].clone()).unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        let mut entries = Vec::new();
+        for line in stream_lines {
+            entries = apply(&mut processor, line);
+        }
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].content, "`cargo`");
+        assert_eq!(entries[1].content, "`src/lib.rs`");
+
+        let entries = apply(
+            &mut processor,
+            r#"{"type":"stream_event","event":{"type":"content_block_delta","index":0,"delta":{"type":"input_json_delta","partial_json":" test\"}"}}}"#,
+        );
+        assert_eq!(entries[0].content, "`cargo test`");
+
+        let final_message = r#"{"type":"assistant","message":{"id":"msg_1","role":"assistant","content":[{"type":"tool_use","id":"toolu_1","name":"Bash","input":{"command":"cargo test"}},{"type":"tool_use","id":"toolu_2","name":"Read","input":{"file_path":"/tmp/work/src/lib.rs"}}]}}"#;
+        let entries = apply(&mut processor, final_message);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].content, "`cargo test`");
+        assert!(matches!(
+            &entries[1].entry_type,
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::FileRead { path, .. },
+                ..
+            } if path == "src/lib.rs"
+        ));
+        assert!(processor.tool_map.contains_key("toolu_1"));
+    }
+
+    #[test]
+    fn test_bash_without_result_is_finalized_as_unknown() {
+        let mut processor = ClaudeLogProcessor::new();
+        let provider = EntryIndexProvider::test_new();
+        let lines = [
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"toolu_1","name":"Bash","input":{"command":"ls"}},{"type":"tool_use","id":"toolu_2","name":"Bash","input":{"command":"sleep 600"}}]}}"#,
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"toolu_1","content":"Cargo.toml","is_error":false}]}}"#,
+        ];
+        for line in lines {
+            let parsed: ClaudeJson = serde_json::from_str(line).unwrap();
+            processor.normalize_entries(&parsed, "/tmp/work", &provider);
+        }
+
+        let patches = processor.finalize_pending_commands("/tmp/work");
+        assert_eq!(patches.len(), 1);
+        let (index, entry) = extract_normalized_entry_from_patch(&patches[0]).unwrap();
+        assert_eq!(index, 1);
+        assert_eq!(entry.content, "`sleep 600`");
+        assert!(matches!(
+            entry.entry_type,
+            NormalizedEntryType::ToolUse {
+                status: ToolStatus::Unknown,
+                action_type: ActionType::CommandRun { result: None, .. },
+                ..
+            }
+        ));
+        assert!(processor.finalize_pending_commands("/tmp/work").is_empty());
+    }
+
+    #[test]
+    fn test_amp_resume_clears_history_with_single_patch() {
+        let mut processor = ClaudeLogProcessor::new_with_strategy(HistoryStrategy::AmpResume);
+        let provider = EntryIndexProvider::test_new();
+        let mut conversation = serde_json::json!({ "entries": [] });
+        let mut apply = |processor: &mut ClaudeLogProcessor, line: &str| {
+            let parsed: ClaudeJson = serde_json::from_str(line).unwrap();
+            let patches = processor.normalize_entries(&parsed, "", &provider);
+            for patch in &patches {
+                json_patch::patch(&mut conversation, patch).unwrap();
+            }
+            (patches, conversation["entries"].as_array().unwrap().len())
+        };
+
+        apply(
+            &mut processor,
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"text","text":"first"}]}}"#,
+        );
+        let (_, len) = apply(
+            &mut processor,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"one"},{"type":"text","text":"two"}]}}"#,
+        );
+        assert!(len >= 3);
+
+        let (patches, len) = apply(
+            &mut processor,
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"text","text":"second"}]}}"#,
+        );
+        assert_eq!(patches.len(), 1);
+        assert_eq!(patches[0].0[0], ConversationPatch::clear_entries().0[0]);
+        let removals = patches
+            .iter()
+            .flat_map(|patch| patch.iter())
+            .filter(|op| matches!(op, json_patch::PatchOperation::Remove(_)))
+            .count();
+        assert_eq!(removals, 0);
+        assert_eq!(len, 1);
+        assert_eq!(provider.current(), 1);
+    }
+
+    #[test]
+    fn test_denied_plan_emits_user_feedback() {
+        let plan_tool_use = r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"plan_1","name":"ExitPlanMode","input":{"plan":"1. Refactor\n2. Test"}}]}}"#;
+        let rejection = r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"plan_1","content":"The user doesn't want to proceed with this tool use. The tool use was rejected. To tell you how to proceed, the user said:\nSplit the refactor into two steps.","is_error":true}]}}"#;
+
+        let mut processor = ClaudeLogProcessor::new();
+        let provider = EntryIndexProvider::test_new();
+        let mut conversation = serde_json::json!({ "entries": [] });
+        for line in [plan_tool_use, rejection] {
+            let parsed: ClaudeJson = serde_json::from_str(line).unwrap();
+            for patch in processor.normalize_entries(&parsed, "", &provider) {
+                json_patch::patch(&mut conversation, &patch).unwrap();
+            }
+        }
+        let entries: Vec<NormalizedEntry> = conversation["entries"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| serde_json::from_value(e["content"].clone()).unwrap())
+            .collect();
+
+        let plan = entries
+            .iter()
+            .find(|e| matches!(e.entry_type, NormalizedEntryType::ToolUse { .. }))
+            .unwrap();
+        assert!(matches!(
+            &plan.entry_type,
+            NormalizedEntryType::ToolUse {
+                status: ToolStatus::Denied { reason: Some(reason) },
+                ..
+            } if reason == "Split the refactor into two steps."
+        ));
+        let feedback = entries.last().unwrap();
+        assert!(matches!(
+            &feedback.entry_type,
+            NormalizedEntryType::UserFeedback { denied_tool } if denied_tool == "ExitPlanMode"
+        ));
+        assert_eq!(feedback.content, "Split the refactor into two steps.");
+
+        // Feedback already surfaced from the approval response is not repeated
+        let mut processor = ClaudeLogProcessor::new();
+        let approval = ClaudeJson::ApprovalResponse {
+            call_id: "plan_1".to_string(),
+            tool_name: "ExitPlanMode".to_string(),
+            approval_status: ApprovalStatus::Denied {
+                reason: Some("Split the refactor into two steps.".to_string()),
+            },
+        };
+        let mut feedback_count = 0;
+        for parsed in [
+            serde_json::from_str(plan_tool_use).unwrap(),
+            approval,
+            serde_json::from_str(rejection).unwrap(),
+        ] {
+            feedback_count += normalize_helper(&mut processor, &parsed, "")
+                .iter()
+                .filter(|e| matches!(e.entry_type, NormalizedEntryType::UserFeedback { .. }))
+                .count();
+        }
+        assert_eq!(feedback_count, 1);
+    }
+
+    #[test]
+    fn test_permission_denial_without_feedback_emits_user_feedback() {
+        let tool_use = r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"bash_1","name":"Bash","input":{"command":"rm -rf build"}}]}}"#;
+        let denied = r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"bash_1","content":"Permission to use Bash with command rm -rf build has been denied.","is_error":true}]}}"#;
+
+        let mut processor = ClaudeLogProcessor::new();
+        normalize_helper(&mut processor, &serde_json::from_str(tool_use).unwrap(), "");
+        let entries = normalize_helper(&mut processor, &serde_json::from_str(denied).unwrap(), "");
+        let feedback: Vec<_> = entries
+            .iter()
+            .filter(|e| {
+                matches!(
+                    &e.entry_type,
+                    NormalizedEntryType::UserFeedback { denied_tool } if denied_tool == "Bash"
+                )
+            })
+            .collect();
+        assert_eq!(feedback.len(), 1);
+        assert_eq!(feedback[0].content, "Tool Bash was not permitted");
+
+        // A tool that ran and failed is not a denial
+        let failed = r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"bash_2","content":"Exit code 1","is_error":true}]}}"#;
+        let mut processor = ClaudeLogProcessor::new();
+        normalize_helper(
+            &mut processor,
+            &serde_json::from_str(&tool_use.replace("bash_1", "bash_2")).unwrap(),
+            "",
+        );
+        let entries = normalize_helper(&mut processor, &serde_json::from_str(failed).unwrap(), "");
+        assert!(
+            !entries
+                .iter()
+                .any(|e| matches!(e.entry_type, NormalizedEntryType::UserFeedback { .. }))
+        );
+    }
+
+    #[test]
+    fn test_partial_json_is_closed_best_effort() {
+        assert_eq!(
+            parse_partial_json(r#"{"command": "ls -"#),
+            Some(serde_json::json!({ "command": "ls -" }))
+        );
+        assert_eq!(
+            parse_partial_json(r#"{"edits": [{"old": "a"}, "#),
+            Some(serde_json::json!({ "edits": [{ "old": "a" }] }))
+        );
+        assert_eq!(
+            parse_partial_json(r#"{"path": "#),
+            Some(serde_json::json!({ "path": null }))
+        );
+        assert_eq!(parse_partial_json(r#"{"pa"#), None);
+    }
+
+    #[test]
+    fn test_write_tool_result_distinguishes_create_and_overwrite() {
+        let mut processor = ClaudeLogProcessor::new();
+        let worktree = "/tmp/work";
+
+        let mut write_entry = |tool_id: &str, result_text: &str| {
+            let tool_use = format!(
+                r#"{{"type":"assistant","message":{{"role":"assistant","content":[{{"type":"tool_use","id":"{tool_id}","name":"Write","input":{{"file_path":"/tmp/work/src/new.rs","content":"fn main() {{}}"}}}}]}}}}"#
+            );
+            let tool_use: ClaudeJson = serde_json::from_str(&tool_use).unwrap();
+            normalize_helper(&mut processor, &tool_use, worktree);
+
+            let tool_result = serde_json::json!({
+                "type": "user",
+                "message": {
+                    "role": "user",
+                    "content": [{
+                        "type": "tool_result",
+                        "tool_use_id": tool_id,
+                        "content": result_text,
+                        "is_error": false
+                    }]
+                }
+            });
+            let tool_result: ClaudeJson = serde_json::from_value(tool_result).unwrap();
+            let entries = normalize_helper(&mut processor, &tool_result, worktree);
+            assert_eq!(entries.len(), 1);
+            entries.into_iter().next().unwrap()
+        };
+
+        let created = write_entry(
+            "write_1",
+            "File created successfully at: /tmp/work/src/new.rs",
+        );
+        assert_eq!(created.content, "Create `src/new.rs`");
+        assert_eq!(
+            created.metadata,
+            Some(serde_json::json!({ "write_mode": "create" }))
+        );
+        assert!(matches!(
+            created.entry_type,
+            NormalizedEntryType::ToolUse {
+                status: ToolStatus::Success,
+                ..
+            }
+        ));
+
+        let overwritten = write_entry(
+            "write_2",
+            "The file /tmp/work/src/new.rs has been updated. Here's the result of running `cat -n` on a snippet of the edited file:",
+        );
+        assert_eq!(overwritten.content, "Overwrite `src/new.rs`");
+        assert_eq!(
+            overwritten.metadata,
+            Some(serde_json::json!({ "write_mode": "overwrite" }))
+        );
+
+        let unknown = write_entry("write_3", "Done");
+        assert_eq!(unknown.content, "`src/new.rs`");
+        assert!(unknown.metadata.is_none());
+    }
+
+    #[test]
+    fn test_web_fetch_result_attached_as_markdown() {
+        let mut processor = ClaudeLogProcessor::new();
+        let tool_use = r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"fetch_1","name":"WebFetch","input":{"url":"https://example.com/docs","prompt":"Summarize the page"}}]}}"#;
+        let tool_use: ClaudeJson = serde_json::from_str(tool_use).unwrap();
+        normalize_helper(&mut processor, &tool_use, "");
+
+        let tool_result = r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"fetch_1","content":[{"type":"text","text":"The page documents the API."}],"is_error":false}]}}"#;
+        let tool_result: ClaudeJson = serde_json::from_str(tool_result).unwrap();
+        let entries = normalize_helper(&mut processor, &tool_result, "");
+
+        assert_eq!(entries.len(), 1);
+        let NormalizedEntryType::ToolUse {
+            action_type: ActionType::WebFetch { url, result },
+            status: ToolStatus::Success,
+            ..
+        } = &entries[0].entry_type
+        else {
+            panic!("expected a web fetch, got {:?}", entries[0].entry_type);
+        };
+        assert_eq!(url, "https://example.com/docs");
+        let result = result.as_ref().expect("fetched content attached");
+        assert!(matches!(
+            result.r#type,
+            crate::logs::ToolResultValueType::Markdown
+        ));
+        assert_eq!(result.value, "The page documents the API.");
+    }
+
+    #[test]
+    fn test_read_result_attaches_file_content() {
+        let mut processor = ClaudeLogProcessor::new();
+        let tool_use = r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"read_1","name":"Read","input":{"file_path":"src/lib.rs"}}]}}"#;
+        let tool_use: ClaudeJson = serde_json::from_str(tool_use).unwrap();
+        normalize_helper(&mut processor, &tool_use, "");
+
+        let tool_result = r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"read_1","content":"     1\tpub fn answer() -> u32 {\n     2\t    42\n     3\t}","is_error":false}]}}"#;
+        let tool_result: ClaudeJson = serde_json::from_str(tool_result).unwrap();
+        let entries = normalize_helper(&mut processor, &tool_result, "");
+
+        assert_eq!(entries.len(), 1);
+        let NormalizedEntryType::ToolUse {
+            action_type: ActionType::FileRead { path, result },
+            status: ToolStatus::Success,
+            ..
+        } = &entries[0].entry_type
+        else {
+            panic!("expected a file read, got {:?}", entries[0].entry_type);
+        };
+        assert_eq!(path, "src/lib.rs");
+        let result = result.as_ref().expect("read content attached");
+        assert_eq!(
+            result.value,
+            "     1\tpub fn answer() -> u32 {\n     2\t    42\n     3\t}"
+        );
+    }
+
+    #[test]
+    fn test_prefer_raw_tool_results_keeps_json_text_as_markdown() {
+        let content = serde_json::json!("{\"count\": 3}");
+
+        let (parsed_type, parsed) =
+            ClaudeLogProcessor::normalize_claude_tool_result_value(&content, false);
+        assert!(matches!(
+            parsed_type,
+            crate::logs::ToolResultValueType::Json
+        ));
+        assert_eq!(parsed, serde_json::json!({ "count": 3 }));
+
+        let (raw_type, raw) =
+            ClaudeLogProcessor::normalize_claude_tool_result_value(&content, true);
+        assert!(matches!(
+            raw_type,
+            crate::logs::ToolResultValueType::Markdown
+        ));
+        assert_eq!(raw, "{\"count\": 3}");
+    }
+
+    #[test]
+    fn test_image_blocks_are_noted_without_inline_data() {
+        let mut processor = ClaudeLogProcessor::new();
+        let message = r#"{"type":"user","message":{"role":"user","content":[{"type":"image","source":{"type":"base64","media_type":"image/png","data":"iVBORw0KGgo="}},{"type":"image","source":{"type":"file","path":"/tmp/work/screens/login.png"}}]}}"#;
+        let message: ClaudeJson = serde_json::from_str(message).unwrap();
+        let entries = normalize_helper(&mut processor, &message, "/tmp/work");
+
+        assert_eq!(entries.len(), 2);
+        assert!(matches!(
+            entries[0].entry_type,
+            NormalizedEntryType::SystemMessage
+        ));
+        assert_eq!(entries[0].content, "Image shared (image/png)");
+        let metadata = entries[0].metadata.as_ref().unwrap();
+        assert!(metadata["source"].get("data").is_none());
+
+        assert_eq!(entries[1].content, "Image shared: screens/login.png");
+        assert_eq!(
+            entries[1].metadata.as_ref().unwrap()["thumbnail_path"],
+            "screens/login.png"
+        );
+    }
+
+    #[test]
+    fn test_resume_mode_controls_fork_flag() {
+        let default: ClaudeCode = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert_eq!(
+            default.resume_args("sess-1"),
+            vec!["--fork-session", "--resume", "sess-1"]
+        );
+
+        let continue_mode: ClaudeCode =
+            serde_json::from_value(serde_json::json!({ "resume_mode": "continue" })).unwrap();
+        assert_eq!(
+            continue_mode.resume_args("sess-1"),
+            vec!["--resume", "sess-1"]
+        );
+    }
+
+    #[test]
+    fn test_config_dump_is_opt_in_and_redacts_secrets() {
+        let args = vec![
+            "-p".to_string(),
+            "--api-key".to_string(),
+            "sk-live-123".to_string(),
+            "--verbose".to_string(),
+        ];
+        let program = Path::new("/usr/bin/npx");
+
+        let disabled: ClaudeCode =
+            serde_json::from_value(serde_json::json!({ "model": "opus" })).unwrap();
+        assert!(disabled.config_dump_line(program, &args).is_none());
+
+        let enabled: ClaudeCode = serde_json::from_value(serde_json::json!({
+            "model": "opus",
+            "dump_config": true,
+            "base_command_override": "ANTHROPIC_AUTH_TOKEN=abc npx -y @anthropic-ai/claude-code",
+            "additional_params": ["--auth-token=xyz", "--max-turns", "5"]
+        }))
+        .unwrap();
+        let line = enabled.config_dump_line(program, &args).unwrap();
+        assert!(!line.contains("sk-live-123"));
+        assert!(!line.contains("abc npx"));
+        assert!(!line.contains("xyz"));
+
+        let parsed: ClaudeJson = serde_json::from_str(&line).unwrap();
+        let entries = normalize(&parsed, "");
+        assert_eq!(entries.len(), 1);
+        assert!(matches!(
+            entries[0].entry_type,
+            NormalizedEntryType::SystemMessage
+        ));
+        let dump = entries[0].metadata.as_ref().unwrap();
+        assert_eq!(dump["config"]["model"], "opus");
+        assert_eq!(
+            dump["config"]["additional_params"],
+            serde_json::json!(["--auth-token=<redacted>", "--max-turns", "5"])
+        );
+        assert_eq!(
+            dump["command"],
+            serde_json::json!(["/usr/bin/npx", "-p", "--api-key", "<redacted>", "--verbose"])
+        );
+    }
+
+    #[test]
+    fn test_todo_tool_empty_list() {
+        // Test TodoWrite with empty todo list
+        let empty_data = ClaudeToolData::TodoWrite { todos: vec![] };
+
+        let action_type = ClaudeLogProcessor::extract_action_type(
+            &empty_data,
+            "/tmp/test-worktree",
+            DiffAlgorithm::default(),
+        );
+        let result = ClaudeLogProcessor::generate_concise_content(
+            &empty_data,
+            &action_type,
+            "/tmp/test-worktree",
+        );
+
+        assert_eq!(result, "TODO list updated");
+    }
+
+    #[test]
+    fn test_multi_edit_carries_diff_stats() {
+        let multi_edit = ClaudeToolData::MultiEdit {
+            file_path: "/tmp/test-worktree/src/main.rs".to_string(),
+            edits: vec![
+                ClaudeEditItem {
+                    old_string: Some("a\n".to_string()),
+                    new_string: Some("b\nc\n".to_string()),
+                },
+                ClaudeEditItem {
+                    old_string: Some("old\n".to_string()),
+                    new_string: Some("new\n".to_string()),
+                },
+            ],
+        };
+
+        let action_type = ClaudeLogProcessor::extract_action_type(
+            &multi_edit,
+            "/tmp/test-worktree",
+            DiffAlgorithm::default(),
+        );
+        let ActionType::FileEdit { path, changes } = action_type else {
+            panic!("Expected FileEdit, got {action_type:?}");
+        };
+        assert_eq!(path, "src/main.rs");
+        let [FileChange::Edit { stats, .. }] = changes.as_slice() else {
+            panic!("Expected a single edit, got {changes:?}");
+        };
+        assert_eq!((stats.added, stats.removed, stats.files_changed), (3, 2, 1));
+    }
+
+    #[test]
+    fn test_glob_tool_content_extraction() {
+        // Test Glob with pattern and path
+        let glob_data = ClaudeToolData::Glob {
+            pattern: "**/*.ts".to_string(),
+            path: Some("/tmp/test-worktree/src".to_string()),
+            limit: None,
+        };
+
+        let action_type = ClaudeLogProcessor::extract_action_type(
+            &glob_data,
+            "/tmp/test-worktree",
+            DiffAlgorithm::default(),
+        );
+        let result = ClaudeLogProcessor::generate_concise_content(
+            &glob_data,
+            &action_type,
+            "/tmp/test-worktree",
+        );
+
+        assert_eq!(result, "`**/*.ts`");
+    }
+
+    #[test]
+    fn test_registered_tool_alias_improves_unknown_tool_rendering() {
+        use super::tool_registry::{
+            ToolAlias, ToolAliasKind, register_tool_alias, unregister_tool_alias,
+        };
+
+        let tool_data: ClaudeToolData = serde_json::from_value(serde_json::json!({
+            "name": "ReadSymbolForTest",
+            "input": { "file": "/tmp/test-worktree/src/lib.rs", "symbol": "main" },
+        }))
+        .unwrap();
+        assert!(matches!(tool_data, ClaudeToolData::Unknown { .. }));
+
+        let render = |tool_data: &ClaudeToolData| {
+            let action_type = ClaudeLogProcessor::extract_action_type(
+                tool_data,
+                "/tmp/test-worktree",
+                DiffAlgorithm::default(),
+            );
+            let content = ClaudeLogProcessor::generate_concise_content(
+                tool_data,
+                &action_type,
+                "/tmp/test-worktree",
+            );
+            (action_type, content)
+        };
+
+        let (action_type, content) = render(&tool_data);
+        assert!(matches!(action_type, ActionType::Other { .. }));
+        assert_eq!(content, "ReadSymbolForTest");
+
+        register_tool_alias(
+            "ReadSymbolForTest",
+            ToolAlias::new("Read symbol", ToolAliasKind::FileRead).summary_field("file"),
+        );
+        let (action_type, content) = render(&tool_data);
+        assert!(
+            matches!(action_type, ActionType::FileRead { ref path, .. } if path == "src/lib.rs")
+        );
+        assert_eq!(content, "`src/lib.rs`");
+
+        register_tool_alias(
+            "ReadSymbolForTest",
+            ToolAlias::new("Read symbol", ToolAliasKind::Other).summary_field("symbol"),
+        );
+        let (_, content) = render(&tool_data);
+        assert_eq!(content, "Read symbol: `main`");
+
+        unregister_tool_alias("ReadSymbolForTest");
     }
 
     #[test]
@@ -1919,7 +3438,11 @@ mod tests {
             limit: None,
         };
 
-        let action_type = ClaudeLogProcessor::extract_action_type(&glob_data, "/tmp/test-worktree");
+        let action_type = ClaudeLogProcessor::extract_action_type(
+            &glob_data,
+            "/tmp/test-worktree",
+            DiffAlgorithm::default(),
+        );
         let result = ClaudeLogProcessor::generate_concise_content(
             &glob_data,
             &action_type,
@@ -1936,7 +3459,11 @@ mod tests {
             path: "/tmp/test-worktree/components".to_string(),
         };
 
-        let action_type = ClaudeLogProcessor::extract_action_type(&ls_data, "/tmp/test-worktree");
+        let action_type = ClaudeLogProcessor::extract_action_type(
+            &ls_data,
+            "/tmp/test-worktree",
+            DiffAlgorithm::default(),
+        );
         let result = ClaudeLogProcessor::generate_concise_content(
             &ls_data,
             &action_type,
@@ -1959,6 +3486,109 @@ mod tests {
         assert_eq!(absolute_result, "src/main.rs");
     }
 
+    #[tokio::test]
+    async fn test_skip_permissions_dropped_when_forbidden() {
+        let executor: ClaudeCode =
+            serde_json::from_value(serde_json::json!({ "dangerously_skip_permissions": true }))
+                .unwrap();
+        let has_flag = |builder: CommandBuilder| {
+            builder
+                .params
+                .unwrap_or_default()
+                .iter()
+                .any(|param| param == "--dangerously-skip-permissions")
+        };
+
+        let configs = ExecutorConfigs::get_cached();
+        let forbidden = executor.command_builder(true, &configs).await;
+        let allowed = executor.command_builder(false, &configs).await;
+
+        assert!(!has_flag(forbidden));
+        assert!(has_flag(allowed));
+    }
+
+    #[tokio::test]
+    async fn test_unset_model_falls_back_to_configured_default() {
+        let mut configs = ExecutorConfigs::load();
+        configs
+            .default_models
+            .insert(BaseCodingAgent::ClaudeCode, "opus".to_string());
+
+        let unset: ClaudeCode = serde_json::from_value(serde_json::json!({})).unwrap();
+        let pinned: ClaudeCode =
+            serde_json::from_value(serde_json::json!({ "model": "sonnet" })).unwrap();
+        let unset_params = unset.command_builder(false, &configs).await.params.unwrap();
+        let pinned_params = pinned
+            .command_builder(false, &configs)
+            .await
+            .params
+            .unwrap();
+
+        assert!(unset_params.windows(2).any(|w| w == ["--model", "opus"]));
+        assert!(pinned_params.windows(2).any(|w| w == ["--model", "sonnet"]));
+        assert!(!pinned_params.contains(&"opus".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_system_prompt_append_sets_flag() {
+        let executor: ClaudeCode = serde_json::from_value(serde_json::json!({
+            "append_prompt": " Be brief.",
+            "system_prompt_append": "Follow the repo's coding standards.",
+        }))
+        .unwrap();
+        let params = executor.build_command_builder().await.params.unwrap();
+        assert!(params.windows(2).any(|w| {
+            w == [
+                "--append-system-prompt",
+                "Follow the repo's coding standards.",
+            ]
+        }));
+        assert!(!params.iter().any(|param| param.contains("Be brief.")));
+
+        let unset: ClaudeCode = serde_json::from_value(serde_json::json!({})).unwrap();
+        let params = unset.build_command_builder().await.params.unwrap();
+        assert!(!params.contains(&"--append-system-prompt".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_log_level_controls_debug_flag() {
+        let params = |log_level: serde_json::Value| async move {
+            let executor: ClaudeCode =
+                serde_json::from_value(serde_json::json!({ "log_level": log_level })).unwrap();
+            executor.build_command_builder().await.params.unwrap()
+        };
+
+        let debug = params(serde_json::json!("debug")).await;
+        assert!(debug.contains(&"--debug".to_string()));
+        for quiet in [
+            params(serde_json::json!("error")).await,
+            params(serde_json::Value::Null).await,
+        ] {
+            assert!(!quiet.contains(&"--debug".to_string()));
+            // stream-json output is rejected without it
+            assert!(quiet.contains(&"--verbose".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_max_output_tokens_sets_env() {
+        let env_value = |executor: &ClaudeCode| {
+            let command = executor.command(Path::new("claude"), &[], Path::new("/tmp"));
+            command
+                .as_std()
+                .get_envs()
+                .find(|(name, _)| *name == MAX_OUTPUT_TOKENS_ENV)
+                .and_then(|(_, value)| value.map(|value| value.to_string_lossy().into_owned()))
+        };
+
+        let capped: ClaudeCode =
+            serde_json::from_value(serde_json::json!({ "max_output_tokens": 4096 })).unwrap();
+        assert_eq!(env_value(&capped).as_deref(), Some("4096"));
+
+        let unset: ClaudeCode = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert_eq!(env_value(&unset), None);
+    }
+
     #[tokio::test]
     async fn test_streaming_patch_generation() {
         use std::sync::Arc;
@@ -1971,10 +3601,23 @@ mod tests {
             approvals: None,
             model: None,
             append_prompt: AppendPrompt::default(),
+            system_prompt_append: None,
             dangerously_skip_permissions: None,
+            dump_config: None,
+            resume_mode: None,
+            diff_algorithm: None,
+            suppress_api_key_warning: None,
+            prefer_raw_tool_results: None,
+            max_output_tokens: None,
+            log_level: None,
+            completion_summary: None,
+            hidden_tool_kinds: None,
             cmd: crate::command::CmdOverrides {
                 base_command_override: None,
                 additional_params: None,
+                shell_override: None,
+                env_file: None,
+                env: None,
             },
             approvals_service: None,
         };
@@ -2006,6 +3649,41 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_restored_store_does_not_reemit_persisted_entries() {
+        use std::sync::Arc;
+
+        use workspace_utils::{log_msg::LogMsg, msg_store::MsgStore};
+
+        use crate::logs::utils::patch::extract_normalized_entries_from_patch;
+
+        let executor: ClaudeCode = serde_json::from_value(serde_json::json!({})).unwrap();
+        let msg_store = Arc::new(MsgStore::new());
+        for text in ["First", "Second"] {
+            msg_store.push_stdout(format!(
+                r#"{{"type":"assistant","message":{{"role":"assistant","content":[{{"type":"text","text":"{text}"}}]}}}}"#
+            ));
+        }
+        msg_store.mark_persisted_entries(1);
+        msg_store.push_finished();
+
+        executor.normalize_logs(msg_store.clone(), Path::new("/tmp/test-worktree"));
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+        let emitted: Vec<_> = msg_store
+            .get_history()
+            .into_iter()
+            .filter_map(|msg| match msg {
+                LogMsg::JsonPatch(patch) => Some(patch),
+                _ => None,
+            })
+            .flat_map(|patch| extract_normalized_entries_from_patch(&patch))
+            .map(|(index, entry)| (index, entry.content))
+            .collect();
+        assert!(emitted.iter().all(|(index, _)| *index >= 1));
+        assert_eq!(emitted.last(), Some(&(1, "Second".to_string())));
+    }
+
     #[test]
     fn test_session_id_extraction() {
         let system_json = r#"{"type":"system","session_id":"test-session-123"}"#;
@@ -2234,6 +3912,32 @@ mod tests {
         assert_eq!(entries.len(), 0);
     }
 
+    #[test]
+    fn test_compact_boundary_is_a_compaction_notice() {
+        let compacted = r#"{"type":"system","subtype":"compact_boundary","session_id":"test123","compact_metadata":{"trigger":"auto","pre_tokens":155000}}"#;
+        let parsed: ClaudeJson = serde_json::from_str(compacted).unwrap();
+        let entries = normalize(&parsed, "");
+
+        assert_eq!(entries.len(), 1);
+        assert!(matches!(
+            entries[0].entry_type,
+            NormalizedEntryType::Notification {
+                level: NotificationLevel::Info,
+            },
+        ));
+        assert_eq!(
+            entries[0].content,
+            "Conversation compacted to free context (155000 tokens summarized)"
+        );
+
+        let manual = r#"{"type":"system","subtype":"compact_boundary","compact_metadata":{"trigger":"manual"}}"#;
+        let parsed: ClaudeJson = serde_json::from_str(manual).unwrap();
+        assert_eq!(
+            normalize(&parsed, "")[0].content,
+            "Conversation compacted with /compact"
+        );
+    }
+
     #[test]
     fn test_api_key_source_warning() {
         // Test with ANTHROPIC_API_KEY - should generate warning
@@ -2244,8 +3948,8 @@ mod tests {
         assert_eq!(entries.len(), 1);
         assert!(matches!(
             entries[0].entry_type,
-            NormalizedEntryType::ErrorMessage {
-                error_type: NormalizedEntryError::Other,
+            NormalizedEntryType::Notification {
+                level: NotificationLevel::Warn,
             },
         ));
         assert_eq!(
@@ -2253,6 +3957,11 @@ mod tests {
             "Claude Code + ANTHROPIC_API_KEY detected. Usage will be billed via Anthropic pay-as-you-go instead of your Claude subscription."
         );
 
+        // Suppressed - no entry even for ANTHROPIC_API_KEY
+        let mut processor = ClaudeLogProcessor::new();
+        processor.options.suppress_api_key_warning = true;
+        assert!(normalize_helper(&mut processor, &parsed, "").is_empty());
+
         // Test with managed API key source - should not generate warning
         let system_with_managed_key = r#"{"type":"system","subtype":"init","apiKeySource":"/login managed key","session_id":"test123"}"#;
         let parsed_managed: ClaudeJson = serde_json::from_str(system_with_managed_key).unwrap();
@@ -2275,6 +3984,42 @@ mod tests {
         assert_eq!(entries_no_key.len(), 0); // No warning when field is missing
     }
 
+    #[test]
+    fn test_rate_limit_result_is_a_warning() {
+        let rate_limited: ClaudeJson = serde_json::from_str(
+            r#"{"type":"result","subtype":"rate_limit","is_error":true,"error":"Rate limit exceeded. Retry after 45 seconds","session_id":"test123"}"#,
+        )
+        .unwrap();
+        let entries = normalize(&rate_limited, "");
+        assert_eq!(entries.len(), 1);
+        assert!(matches!(
+            entries[0].entry_type,
+            NormalizedEntryType::Notification {
+                level: NotificationLevel::Warn,
+            },
+        ));
+        assert_eq!(
+            entries[0].content,
+            "Rate limited, retry after 45s: Rate limit exceeded. Retry after 45 seconds"
+        );
+        assert_eq!(
+            entries[0].metadata.as_ref().unwrap()["retry_after_secs"],
+            45
+        );
+
+        // An error result mentioning a 429 is treated the same way
+        let api_error: ClaudeJson = serde_json::from_str(
+            r#"{"type":"result","subtype":"error_during_execution","is_error":true,"result":"API Error: 429 Too Many Requests","session_id":"test123"}"#,
+        )
+        .unwrap();
+        let entries = normalize(&api_error, "");
+        assert_eq!(entries.len(), 1);
+        assert_eq!(
+            entries[0].content,
+            "Rate limited: API Error: 429 Too Many Requests"
+        );
+    }
+
     #[test]
     fn test_mixed_content_with_thinking_ignores_tool_result() {
         let complex_assistant_json = r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"thinking","thinking":"I need to read the file first"},{"type":"text","text":"I'll help you with that"},{"type":"tool_result","tool_use_id":"tool_789","content":"Success","is_error":false}]}}"#;
@@ -0,0 +1,106 @@
+//! Runtime aliases for Claude/Amp tools that have no dedicated [`super::ClaudeToolData`] variant.
+//!
+//! Tools that land in `ClaudeToolData::Unknown` are rendered as a bare tool name. Integrators can
+//! register an alias for such a tool so it gets a readable name and, optionally, a structured
+//! action (file read, command, search, ...) built from one of its input fields.
+//!
+//! ```rust,ignore
+//! register_tool_alias(
+//!     "ReadSymbol",
+//!     ToolAlias::new("Read symbol", ToolAliasKind::FileRead).summary_field("file"),
+//! );
+//! ```
+
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, RwLock},
+};
+
+use serde_json::Value;
+use workspace_utils::path::make_path_relative;
+
+use crate::logs::ActionType;
+
+static TOOL_ALIASES: LazyLock<RwLock<HashMap<String, ToolAlias>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// How a registered tool should be normalized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolAliasKind {
+    FileRead,
+    CommandRun,
+    Search,
+    WebFetch,
+    Other,
+}
+
+/// Rendering hints for a tool the log processor does not know about.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolAlias {
+    pub display_name: String,
+    pub kind: ToolAliasKind,
+    /// Input field holding the path, command, query or URL that summarises a call.
+    pub summary_field: Option<String>,
+}
+
+impl ToolAlias {
+    pub fn new(display_name: impl Into<String>, kind: ToolAliasKind) -> Self {
+        Self {
+            display_name: display_name.into(),
+            kind,
+            summary_field: None,
+        }
+    }
+
+    pub fn summary_field(mut self, field: impl Into<String>) -> Self {
+        self.summary_field = Some(field.into());
+        self
+    }
+
+    /// Builds the action for a call with the given `input`. Falls back to `Other` when the
+    /// summary field is missing, since the structured actions need it.
+    pub(super) fn action_type(&self, input: &Value, worktree_path: &str) -> ActionType {
+        let summary = self
+            .summary_field
+            .as_deref()
+            .and_then(|field| input.get(field))
+            .and_then(Value::as_str)
+            .map(str::to_string);
+
+        match (self.kind, summary) {
+            (ToolAliasKind::FileRead, Some(path)) => ActionType::FileRead {
+                path: make_path_relative(&path, worktree_path),
+                result: None,
+            },
+            (ToolAliasKind::CommandRun, Some(command)) => ActionType::CommandRun {
+                command,
+                cwd: None,
+                result: None,
+            },
+            (ToolAliasKind::Search, Some(query)) => ActionType::Search { query },
+            (ToolAliasKind::WebFetch, Some(url)) => ActionType::WebFetch { url, result: None },
+            (_, Some(summary)) => ActionType::Other {
+                description: format!("{}: `{summary}`", self.display_name),
+            },
+            (_, None) => ActionType::Other {
+                description: self.display_name.clone(),
+            },
+        }
+    }
+}
+
+/// Registers (or replaces) the alias used for tool calls named `tool_name`.
+pub fn register_tool_alias(tool_name: impl Into<String>, alias: ToolAlias) {
+    TOOL_ALIASES
+        .write()
+        .unwrap()
+        .insert(tool_name.into(), alias);
+}
+
+pub fn unregister_tool_alias(tool_name: &str) -> Option<ToolAlias> {
+    TOOL_ALIASES.write().unwrap().remove(tool_name)
+}
+
+pub fn tool_alias(tool_name: &str) -> Option<ToolAlias> {
+    TOOL_ALIASES.read().unwrap().get(tool_name).cloned()
+}
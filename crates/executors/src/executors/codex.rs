@@ -3,39 +3,43 @@ pub mod jsonrpc;
 pub mod normalize_logs;
 pub mod session;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     path::{Path, PathBuf},
     sync::Arc,
 };
 
 use async_trait::async_trait;
-use codex_app_server_protocol::NewConversationParams;
+use codex_app_server_protocol::{InputItem, NewConversationParams};
 use codex_protocol::{
     config_types::SandboxMode as CodexSandboxMode, protocol::AskForApproval as CodexAskForApproval,
 };
-use command_group::AsyncCommandGroup;
+use command_group::{AsyncCommandGroup, AsyncGroupChild};
 use derivative::Derivative;
+use lazy_static::lazy_static;
+use regex::Regex;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use strum_macros::AsRefStr;
 use tokio::process::Command;
 use ts_rs::TS;
-use workspace_utils::msg_store::MsgStore;
+use workspace_utils::{msg_store::MsgStore, path::FORGE_IMAGES_DIR};
 
 use self::{
-    client::{AppServerClient, LogWriter},
-    jsonrpc::JsonRpcPeer,
-    normalize_logs::normalize_logs,
+    client::{AppServerClient, LogWriter, Reconnect},
+    jsonrpc::{JsonRpcPeer, PeerConnector, PeerReader, PeerWriter, ReconnectPolicy},
+    normalize_logs::{CodexLogOptions, HistoryStrategy, normalize_logs},
     session::SessionHandler,
 };
 use crate::{
+    actions::coding_agent_follow_up::ResumeOverrides,
     approvals::ExecutorApprovalService,
-    command::{CmdOverrides, CommandBuilder, CommandParts, apply_overrides},
+    command::{CmdOverrides, CommandBuilder, CommandParts, apply_env, apply_overrides},
     executors::{
-        AppendPrompt, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
+        AppendPrompt, BaseCodingAgent, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
         codex::{jsonrpc::ExitSignalSender, normalize_logs::Error},
     },
+    profile::ExecutorConfigs,
     stdout_dup::create_stdout_pipe_writer,
 };
 
@@ -99,6 +103,20 @@ pub enum ReasoningSummaryFormat {
     Experimental,
 }
 
+/// An MCP server added to Codex conversations, in the shape of a Codex `[mcp_servers.<name>]`
+/// table. Set either `command` for a stdio server or `url` for a streamable HTTP one.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+pub struct CodexMcpServer {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub args: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<HashMap<String, String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
 #[derive(Derivative, Clone, Serialize, Deserialize, TS, JsonSchema)]
 #[derivative(Debug, PartialEq)]
 pub struct Codex {
@@ -122,10 +140,29 @@ pub struct Codex {
     pub profile: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub base_instructions: Option<String>,
+    /// Text added after `base_instructions` for every session, e.g. coding standards. Codex can
+    /// only replace its built-in prompt, so this is ignored unless `base_instructions` is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt_append: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub include_plan_tool: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub include_apply_patch_tool: Option<bool>,
+    /// How many times to restart the app server and resume the conversation if it disconnects
+    /// mid-task, backing off exponentially between attempts. Disabled when unset or 0.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reconnect_attempts: Option<u32>,
+    /// Show tool results as plain text even when they are valid JSON
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefer_raw_tool_results: Option<bool>,
+    /// Cap on tokens per model response, passed as Codex's `model_max_output_tokens` config;
+    /// must be positive.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u32>,
+    /// MCP servers added to every conversation, keyed by name, on top of those in
+    /// `~/.codex/config.toml`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mcp_servers: Option<HashMap<String, CodexMcpServer>>,
     #[serde(flatten)]
     pub cmd: CmdOverrides,
 
@@ -141,6 +178,17 @@ impl StandardCodingAgentExecutor for Codex {
         self.approvals = Some(approvals);
     }
 
+    /// The resumed conversation is started with this executor's conversation params, so the
+    /// overrides reach Codex as `resumeConversation` overrides.
+    fn apply_resume_overrides(&mut self, overrides: &ResumeOverrides) {
+        if let Some(model) = &overrides.model {
+            self.model = Some(model.clone());
+        }
+        if let Some(effort) = &overrides.reasoning_effort {
+            self.model_reasoning_effort = Some(effort.clone());
+        }
+    }
+
     async fn spawn(&self, current_dir: &Path, prompt: &str) -> Result<SpawnedChild, ExecutorError> {
         let command_parts = self.build_command_builder().build_initial()?;
         self.spawn(current_dir, prompt, command_parts, None).await
@@ -158,7 +206,17 @@ impl StandardCodingAgentExecutor for Codex {
     }
 
     fn normalize_logs(&self, msg_store: Arc<MsgStore>, worktree_path: &Path) {
-        normalize_logs(msg_store, worktree_path);
+        // Follow-ups and reconnects resume the conversation, and Codex replays its earlier turns
+        // as `initial_messages`; rebuilding from them renders each turn once. New conversations
+        // carry no history, so this is a no-op for them.
+        normalize_logs(
+            msg_store,
+            worktree_path,
+            HistoryStrategy::Resume,
+            CodexLogOptions {
+                prefer_raw_tool_results: self.prefer_raw_tool_results.unwrap_or(false),
+            },
+        );
     }
 
     fn default_mcp_config_path(&self) -> Option<PathBuf> {
@@ -198,21 +256,92 @@ impl Codex {
         };
 
         NewConversationParams {
-            model: self.model.clone(),
+            model: self
+                .model
+                .clone()
+                .or_else(|| ExecutorConfigs::cached_default_model(BaseCodingAgent::Codex)),
             profile: self.profile.clone(),
             cwd: Some(cwd.to_string_lossy().to_string()),
             approval_policy,
             sandbox,
             config: self.build_config_overrides(),
-            base_instructions: self.base_instructions.clone(),
+            base_instructions: self.base_instructions(),
             include_plan_tool: self.include_plan_tool,
             include_apply_patch_tool: self.include_apply_patch_tool,
         }
     }
 
+    fn base_instructions(&self) -> Option<String> {
+        let append = self
+            .system_prompt_append
+            .as_deref()
+            .filter(|text| !text.trim().is_empty());
+        match (self.base_instructions.as_deref(), append) {
+            (Some(base), Some(append)) => Some(format!("{base}\n\n{append}")),
+            (None, Some(_)) => {
+                tracing::warn!(
+                    "system_prompt_append is ignored for Codex unless base_instructions is set"
+                );
+                None
+            }
+            (base, None) => base.map(str::to_string),
+        }
+    }
+
+    /// Checks that every entry of `mcp_servers` can be passed to Codex as a config override.
+    pub fn validate_mcp_servers(&self) -> Result<(), String> {
+        for (name, server) in self.mcp_servers.iter().flatten() {
+            // The name becomes part of a dotted config key
+            if name.is_empty()
+                || !name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+            {
+                return Err(format!(
+                    "MCP server name '{name}' may only contain letters, digits, '_' and '-'"
+                ));
+            }
+            match (&server.command, &server.url) {
+                (Some(command), None) if !command.trim().is_empty() => {}
+                (None, Some(url)) if url.starts_with("http://") || url.starts_with("https://") => {}
+                (Some(_), Some(_)) => {
+                    return Err(format!(
+                        "MCP server '{name}' must set either `command` or `url`, not both"
+                    ));
+                }
+                (None, Some(url)) => {
+                    return Err(format!("MCP server '{name}' has an invalid url '{url}'"));
+                }
+                _ => {
+                    return Err(format!(
+                        "MCP server '{name}' needs a non-empty `command` or a `url`"
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn build_config_overrides(&self) -> Option<HashMap<String, Value>> {
         let mut overrides = HashMap::new();
 
+        // One override per server, so servers from the user's own config are kept
+        for (name, server) in self.mcp_servers.iter().flatten() {
+            match serde_json::to_value(server) {
+                Ok(value) => {
+                    overrides.insert(format!("mcp_servers.{name}"), value);
+                }
+                Err(e) => tracing::warn!("Skipping MCP server '{name}': {e}"),
+            }
+        }
+
+        if let Some(max_output_tokens) = self.max_output_tokens {
+            overrides.insert(
+                "model_max_output_tokens".to_string(),
+                Value::from(max_output_tokens),
+            );
+        }
+
         if let Some(effort) = &self.model_reasoning_effort {
             overrides.insert(
                 "model_reasoning_effort".to_string(),
@@ -253,17 +382,8 @@ impl Codex {
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
         let (program_path, args) = command_parts.into_resolved().await?;
 
-        let mut process = Command::new(program_path);
-        process
-            .kill_on_drop(true)
-            .stdin(std::process::Stdio::piped())
-            .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::piped())
-            .current_dir(current_dir)
-            .args(&args)
-            .env("NODE_NO_WARNINGS", "1")
-            .env("NO_COLOR", "1")
-            .env("RUST_LOG", "error");
+        let mut process = app_server_command(&program_path, &args, current_dir, &self.cmd);
+        process.stderr(std::process::Stdio::piped());
 
         let mut child = process.group_spawn()?;
 
@@ -277,6 +397,7 @@ impl Codex {
         let new_stdout = create_stdout_pipe_writer(&mut child)?;
         let (exit_signal_tx, exit_signal_rx) = tokio::sync::oneshot::channel();
 
+        let input_items = user_input_items(&combined_prompt, current_dir);
         let params = self.build_new_conversation_params(current_dir);
         let resume_session = resume_session.map(|s| s.to_string());
         let auto_approve = matches!(
@@ -284,19 +405,39 @@ impl Codex {
             (Some(SandboxMode::DangerFullAccess), None)
         );
         let approvals = self.approvals.clone();
+        let mut replacements = None;
+        let reconnect = self
+            .reconnect_attempts
+            .filter(|attempts| *attempts > 0)
+            .map(|attempts| {
+                let (children, rx) = tokio::sync::mpsc::unbounded_channel();
+                replacements = Some(rx);
+                Reconnect {
+                    policy: ReconnectPolicy::new(attempts),
+                    connector: Arc::new(AppServerConnector {
+                        program_path,
+                        args,
+                        current_dir: current_dir.to_path_buf(),
+                        cmd: self.cmd.clone(),
+                        children,
+                    }),
+                    overrides: params.clone(),
+                }
+            });
         tokio::spawn(async move {
             let exit_signal_tx = ExitSignalSender::new(exit_signal_tx);
             let log_writer = LogWriter::new(new_stdout);
             if let Err(err) = Self::launch_codex_app_server(
                 params,
                 resume_session,
-                combined_prompt,
+                input_items,
                 child_stdout,
                 child_stdin,
                 log_writer.clone(),
                 exit_signal_tx.clone(),
                 approvals,
                 auto_approve,
+                reconnect,
             )
             .await
             {
@@ -317,6 +458,8 @@ impl Codex {
         Ok(SpawnedChild {
             child,
             exit_signal: Some(exit_signal_rx),
+            permit: None,
+            replacements,
         })
     }
 
@@ -324,17 +467,17 @@ impl Codex {
     async fn launch_codex_app_server(
         conversation_params: NewConversationParams,
         resume_session: Option<String>,
-        combined_prompt: String,
+        input_items: Vec<InputItem>,
         child_stdout: tokio::process::ChildStdout,
         child_stdin: tokio::process::ChildStdin,
         log_writer: LogWriter,
         exit_signal_tx: ExitSignalSender,
         approvals: Option<Arc<dyn ExecutorApprovalService>>,
         auto_approve: bool,
+        reconnect: Option<Reconnect>,
     ) -> Result<(), ExecutorError> {
         let client = AppServerClient::new(log_writer, approvals, auto_approve);
-        let rpc_peer =
-            JsonRpcPeer::spawn(child_stdin, child_stdout, client.clone(), exit_signal_tx);
+        let (rpc_peer, peer_exit) = JsonRpcPeer::spawn(child_stdin, child_stdout, client.clone());
         client.connect(rpc_peer);
         client.initialize().await?;
         match resume_session {
@@ -342,10 +485,12 @@ impl Codex {
                 let params = conversation_params;
                 let response = client.new_conversation(params).await?;
                 let conversation_id = response.conversation_id;
-                client.register_session(&conversation_id).await?;
+                client
+                    .register_session(&conversation_id, response.rollout_path)
+                    .await?;
                 client.add_conversation_listener(conversation_id).await?;
                 client
-                    .send_user_message(conversation_id, combined_prompt)
+                    .send_user_message(conversation_id, input_items)
                     .await?;
             }
             Some(session_id) => {
@@ -362,13 +507,343 @@ impl Codex {
                     response
                 );
                 let conversation_id = response.conversation_id;
-                client.register_session(&conversation_id).await?;
+                client
+                    .register_session(&conversation_id, rollout_path)
+                    .await?;
                 client.add_conversation_listener(conversation_id).await?;
                 client
-                    .send_user_message(conversation_id, combined_prompt)
+                    .send_user_message(conversation_id, input_items)
                     .await?;
             }
         }
+        let result = client.run_until_exit(peer_exit, reconnect).await;
+        // The app server is gone, so nobody is left to receive a late approval decision
+        client.cancel_pending_approvals().await;
+        result?;
+        exit_signal_tx.send_exit_signal().await;
         Ok(())
     }
 }
+
+fn app_server_command(
+    program_path: &Path,
+    args: &[String],
+    current_dir: &Path,
+    cmd: &CmdOverrides,
+) -> Command {
+    let mut process = Command::new(program_path);
+    process
+        .kill_on_drop(true)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .current_dir(current_dir)
+        .args(args)
+        .env("NODE_NO_WARNINGS", "1")
+        .env("NO_COLOR", "1")
+        .env("RUST_LOG", "error");
+    apply_env(&mut process, current_dir, cmd);
+    process
+}
+
+/// Starts a replacement app server when the original one disconnects. Like the original it runs
+/// in its own process group, and it is handed to the container through `children` so that
+/// stopping the execution kills it and its stderr reaches the execution logs.
+struct AppServerConnector {
+    program_path: PathBuf,
+    args: Vec<String>,
+    current_dir: PathBuf,
+    cmd: CmdOverrides,
+    children: tokio::sync::mpsc::UnboundedSender<AsyncGroupChild>,
+}
+
+#[async_trait]
+impl PeerConnector for AppServerConnector {
+    async fn connect(&self) -> Result<(PeerWriter, PeerReader), ExecutorError> {
+        // The container stopped tracking the execution, so it could never stop a new server
+        if self.children.is_closed() {
+            return Err(ExecutorError::Io(std::io::Error::other(
+                "Codex execution is no longer running",
+            )));
+        }
+        let mut process =
+            app_server_command(&self.program_path, &self.args, &self.current_dir, &self.cmd);
+        process.stderr(std::process::Stdio::piped());
+        let mut child = process.group_spawn()?;
+        let stdout = child.inner().stdout.take().ok_or_else(|| {
+            ExecutorError::Io(std::io::Error::other("Codex app server missing stdout"))
+        })?;
+        let stdin = child.inner().stdin.take().ok_or_else(|| {
+            ExecutorError::Io(std::io::Error::other("Codex app server missing stdin"))
+        })?;
+        self.children.send(child).map_err(|_| {
+            ExecutorError::Io(std::io::Error::other(
+                "Codex execution is no longer running",
+            ))
+        })?;
+        Ok((Box::new(stdin), Box::new(stdout)))
+    }
+}
+
+lazy_static! {
+    static ref FORGE_IMAGE_RE: Regex = Regex::new(&format!(
+        r"!\[[^\]]*\]\(([^)\s]*{}/[^)\s]+)\)",
+        regex::escape(FORGE_IMAGES_DIR)
+    ))
+    .unwrap();
+}
+
+/// The prompt as a text item, followed by a `LocalImage` item for every attached forge image it
+/// references (`![alt](<worktree>/.forge-images/<file>)`), so Codex sees the images themselves
+/// rather than only their paths. Relative paths resolve against `current_dir`; references to
+/// missing files are left as text only.
+fn user_input_items(prompt: &str, current_dir: &Path) -> Vec<InputItem> {
+    let mut items = vec![InputItem::Text {
+        text: prompt.to_string(),
+    }];
+    let mut seen = HashSet::new();
+    for caps in FORGE_IMAGE_RE.captures_iter(prompt) {
+        let path = current_dir.join(&caps[1]);
+        if path.is_file() && seen.insert(path.clone()) {
+            items.push(InputItem::LocalImage { path });
+        }
+    }
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use workspace_utils::log_msg::LogMsg;
+
+    use super::*;
+    use crate::logs::utils::patch::extract_normalized_entries_from_patch;
+
+    #[tokio::test]
+    async fn test_reconnected_app_server_is_handed_to_the_container() {
+        let (children, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let connector = AppServerConnector {
+            program_path: PathBuf::from("cat"),
+            args: Vec::new(),
+            current_dir: std::env::temp_dir(),
+            cmd: CmdOverrides::default(),
+            children,
+        };
+
+        let _peer = connector.connect().await.unwrap();
+        let mut child = rx.recv().await.unwrap();
+        assert!(child.inner().stderr.is_some());
+        child.kill().await.unwrap();
+
+        // Once the container lets go of the execution no new server is started
+        drop(rx);
+        assert!(connector.connect().await.is_err());
+    }
+
+    #[test]
+    fn test_user_message_includes_referenced_images() {
+        let worktree = tempfile::tempdir().unwrap();
+        let images_dir = worktree.path().join(FORGE_IMAGES_DIR);
+        std::fs::create_dir(&images_dir).unwrap();
+        let screenshot = images_dir.join("screenshot.png");
+        std::fs::write(&screenshot, b"png").unwrap();
+
+        let prompt = format!(
+            "Fix the layout shown here: ![screenshot]({}) and ![again]({FORGE_IMAGES_DIR}/screenshot.png), ignoring ![gone]({FORGE_IMAGES_DIR}/missing.png)",
+            screenshot.display()
+        );
+        let items = user_input_items(&prompt, worktree.path());
+
+        assert_eq!(items.len(), 2);
+        assert!(matches!(&items[0], InputItem::Text { text } if *text == prompt));
+        assert!(matches!(&items[1], InputItem::LocalImage { path } if *path == screenshot));
+
+        let text_only = user_input_items("No images here", worktree.path());
+        assert!(matches!(text_only.as_slice(), [InputItem::Text { .. }]));
+    }
+
+    #[test]
+    fn test_system_prompt_append_extends_base_instructions() {
+        let executor: Codex = serde_json::from_value(serde_json::json!({
+            "base_instructions": "You are a careful engineer.",
+            "system_prompt_append": "Follow the repo's coding standards.",
+        }))
+        .unwrap();
+        let params = executor.build_new_conversation_params(Path::new("/tmp"));
+        assert_eq!(
+            params.base_instructions.as_deref(),
+            Some("You are a careful engineer.\n\nFollow the repo's coding standards.")
+        );
+
+        let without_base: Codex = serde_json::from_value(serde_json::json!({
+            "system_prompt_append": "Follow the repo's coding standards.",
+        }))
+        .unwrap();
+        let params = without_base.build_new_conversation_params(Path::new("/tmp"));
+        assert!(params.base_instructions.is_none());
+    }
+
+    #[test]
+    fn test_mcp_servers_are_added_to_conversation_params() {
+        let executor: Codex = serde_json::from_value(serde_json::json!({
+            "mcp_servers": {
+                "docs": {
+                    "command": "npx",
+                    "args": ["-y", "@acme/docs-mcp"],
+                    "env": { "DOCS_TOKEN": "secret" },
+                },
+                "tracker": { "url": "https://tracker.example.com/mcp" },
+            },
+        }))
+        .unwrap();
+        assert!(executor.validate_mcp_servers().is_ok());
+
+        let config = executor
+            .build_new_conversation_params(Path::new("/tmp"))
+            .config
+            .unwrap();
+        assert_eq!(
+            config.get("mcp_servers.docs"),
+            Some(&serde_json::json!({
+                "command": "npx",
+                "args": ["-y", "@acme/docs-mcp"],
+                "env": { "DOCS_TOKEN": "secret" },
+            }))
+        );
+        assert_eq!(
+            config.get("mcp_servers.tracker"),
+            Some(&serde_json::json!({ "url": "https://tracker.example.com/mcp" }))
+        );
+    }
+
+    #[test]
+    fn test_mcp_servers_are_validated() {
+        let invalid = |servers: serde_json::Value| {
+            let executor: Codex =
+                serde_json::from_value(serde_json::json!({ "mcp_servers": servers })).unwrap();
+            executor.validate_mcp_servers().unwrap_err()
+        };
+
+        assert!(invalid(serde_json::json!({ "a.b": { "command": "x" } })).contains("a.b"));
+        assert!(invalid(serde_json::json!({ "empty": {} })).contains("`command` or a `url`"));
+        assert!(
+            invalid(serde_json::json!({ "both": { "command": "x", "url": "https://x" } }))
+                .contains("not both")
+        );
+        assert!(
+            invalid(serde_json::json!({ "ftp": { "url": "ftp://x" } })).contains("invalid url")
+        );
+    }
+
+    #[test]
+    fn test_max_output_tokens_sets_config_override() {
+        let executor: Codex =
+            serde_json::from_value(serde_json::json!({ "max_output_tokens": 4096 })).unwrap();
+        let params = executor.build_new_conversation_params(Path::new("/tmp"));
+        assert_eq!(
+            params.config.unwrap().get("model_max_output_tokens"),
+            Some(&serde_json::json!(4096))
+        );
+
+        let unset: Codex = serde_json::from_value(serde_json::json!({})).unwrap();
+        let params = unset.build_new_conversation_params(Path::new("/tmp"));
+        assert!(params.config.is_none());
+    }
+
+    fn event_line(msg: Value) -> String {
+        let line = serde_json::json!({
+            "method": format!("codex/event/{}", msg["type"].as_str().unwrap()),
+            "params": { "msg": msg },
+        });
+        format!("{line}\n")
+    }
+
+    #[tokio::test]
+    async fn test_resumed_history_is_rendered_once() {
+        let msg_store = Arc::new(MsgStore::new());
+        for msg in [
+            serde_json::json!({ "type": "agent_message", "message": "Stale render" }),
+            serde_json::json!({
+                "type": "session_configured",
+                "session_id": "67e55044-10b1-426f-9247-bb680e5fe0c8",
+                "model": "gpt-5",
+                "history_log_id": 0,
+                "history_entry_count": 1,
+                "rollout_path": "/tmp/rollout.jsonl",
+                "initial_messages": [{ "type": "agent_message", "message": "Stale render" }],
+            }),
+        ] {
+            msg_store.push_stdout(event_line(msg));
+        }
+        msg_store.push_finished();
+
+        let executor: Codex = serde_json::from_value(serde_json::json!({})).unwrap();
+        executor.normalize_logs(msg_store.clone(), Path::new("/tmp"));
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        let mut conversation = serde_json::json!({ "entries": [] });
+        for msg in msg_store.get_history() {
+            if let LogMsg::JsonPatch(patch) = msg {
+                json_patch::patch(&mut conversation, &patch).unwrap();
+            }
+        }
+        let contents: Vec<_> = conversation["entries"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| entry["content"]["content"].as_str().unwrap_or_default())
+            .collect();
+        assert_eq!(contents, vec!["Stale render", "model: gpt-5"]);
+    }
+
+    #[tokio::test]
+    async fn test_restored_store_does_not_reemit_persisted_entries() {
+        let msg_store = Arc::new(MsgStore::new());
+        for message in ["First", "Second", "Third"] {
+            msg_store.push_stdout(event_line(
+                serde_json::json!({ "type": "agent_message", "message": message }),
+            ));
+        }
+        msg_store.mark_persisted_entries(2);
+        msg_store.push_finished();
+
+        let executor: Codex = serde_json::from_value(serde_json::json!({})).unwrap();
+        executor.normalize_logs(msg_store.clone(), Path::new("/tmp"));
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        let emitted: Vec<_> = msg_store
+            .get_history()
+            .into_iter()
+            .filter_map(|msg| match msg {
+                LogMsg::JsonPatch(patch) => Some(patch),
+                _ => None,
+            })
+            .flat_map(|patch| extract_normalized_entries_from_patch(&patch))
+            .map(|(index, entry)| (index, entry.content))
+            .collect();
+        assert_eq!(emitted, [(2, "Third".to_string())]);
+    }
+
+    #[test]
+    fn test_resume_overrides_reach_the_resume_request() {
+        let mut executor: Codex = serde_json::from_value(serde_json::json!({
+            "model": "gpt-5-mini",
+            "model_reasoning_effort": "low",
+        }))
+        .unwrap();
+        executor.apply_resume_overrides(&ResumeOverrides {
+            model: Some("gpt-5".to_string()),
+            reasoning_effort: Some(ReasoningEffort::High),
+        });
+
+        // The same params are sent as the `resumeConversation` overrides
+        let params = executor.build_new_conversation_params(Path::new("/tmp"));
+        assert_eq!(params.model.as_deref(), Some("gpt-5"));
+        assert_eq!(
+            params.config.unwrap().get("model_reasoning_effort"),
+            Some(&serde_json::json!("high"))
+        );
+
+        executor.apply_resume_overrides(&ResumeOverrides::default());
+        let params = executor.build_new_conversation_params(Path::new("/tmp"));
+        assert_eq!(params.model.as_deref(), Some("gpt-5"));
+    }
+}
@@ -1,8 +1,10 @@
 use std::{
     borrow::Cow,
-    collections::VecDeque,
+    collections::{HashMap, VecDeque},
     io,
-    sync::{Arc, OnceLock},
+    path::PathBuf,
+    sync::{Arc, LazyLock, Mutex as StdMutex, RwLock},
+    time::Duration,
 };
 
 use async_trait::async_trait;
@@ -15,6 +17,10 @@ use codex_app_server_protocol::{
     SendUserMessageResponse, ServerNotification, ServerRequest,
 };
 use codex_protocol::{ConversationId, protocol::ReviewDecision};
+use futures::{
+    FutureExt,
+    future::{BoxFuture, Shared},
+};
 use serde::{Serialize, de::DeserializeOwned};
 use serde_json::{self, Value};
 use tokio::{
@@ -23,18 +29,71 @@ use tokio::{
 };
 use workspace_utils::approvals::ApprovalStatus;
 
-use super::jsonrpc::{JsonRpcCallbacks, JsonRpcPeer};
+use super::jsonrpc::{
+    JsonRpcCallbacks, JsonRpcPeer, PeerConnector, PeerExit, PeerExitReceiver, ReconnectPolicy,
+};
 use crate::{
     approvals::{ExecutorApprovalError, ExecutorApprovalService},
     executors::{ExecutorError, codex::normalize_logs::Approval},
 };
 
+/// Message sent after resuming a conversation on a re-established peer.
+const RECONNECT_CONTINUE_MESSAGE: &str =
+    "The connection to Codex was interrupted. Continue the task from where you left off.";
+
+/// Environment variable overriding [`DEFAULT_APPROVAL_RENDER_DELAY`] in milliseconds. `0` disables
+/// the delay; values above [`MAX_APPROVAL_RENDER_DELAY`] are capped.
+pub const APPROVAL_RENDER_DELAY_ENV: &str = "FORGE_CODEX_APPROVAL_DELAY_MS";
+
+/// Codex emits the `exec_approval_request`/`apply_patch_approval_request` event and the matching
+/// approval server request back to back. The event only becomes a tool entry once the log
+/// normalizer has processed it, and the approval service links the approval to that entry by
+/// call id; when the approval arrives first the link is missed and the UI never shows the tool
+/// as awaiting approval. Waiting briefly before asking gives the normalizer time to catch up.
+pub const DEFAULT_APPROVAL_RENDER_DELAY: Duration = Duration::from_millis(20);
+
+pub const MAX_APPROVAL_RENDER_DELAY: Duration = Duration::from_millis(500);
+
+static APPROVAL_RENDER_DELAY: LazyLock<Duration> = LazyLock::new(|| {
+    std::env::var(APPROVAL_RENDER_DELAY_ENV)
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_APPROVAL_RENDER_DELAY)
+        .min(MAX_APPROVAL_RENDER_DELAY)
+});
+
+/// An approval being asked for, shared by every request for the same call id while it's pending.
+type PendingApproval = Shared<BoxFuture<'static, Result<ApprovalStatus, ExecutorApprovalError>>>;
+
+/// Removes a pending approval once the request that started it finishes or is dropped, so the
+/// entry never outlives it.
+struct PendingApprovalEntry<'a> {
+    pending: &'a StdMutex<HashMap<String, PendingApproval>>,
+    tool_call_id: &'a str,
+}
+
+impl Drop for PendingApprovalEntry<'_> {
+    fn drop(&mut self) {
+        self.pending.lock().unwrap().remove(self.tool_call_id);
+    }
+}
+
+/// Re-establishes a dropped peer and resumes the conversation from its rollout file.
+pub struct Reconnect {
+    pub policy: ReconnectPolicy,
+    pub connector: Arc<dyn PeerConnector>,
+    pub overrides: NewConversationParams,
+}
+
 pub struct AppServerClient {
-    rpc: OnceLock<JsonRpcPeer>,
+    rpc: RwLock<Option<JsonRpcPeer>>,
     log_writer: LogWriter,
     approvals: Option<Arc<dyn ExecutorApprovalService>>,
     conversation_id: Mutex<Option<ConversationId>>,
+    rollout_path: Mutex<Option<PathBuf>>,
     pending_feedback: Mutex<VecDeque<String>>,
+    pending_approvals: StdMutex<HashMap<String, PendingApproval>>,
     auto_approve: bool,
 }
 
@@ -45,21 +104,28 @@ impl AppServerClient {
         auto_approve: bool,
     ) -> Arc<Self> {
         Arc::new(Self {
-            rpc: OnceLock::new(),
+            rpc: RwLock::new(None),
             log_writer,
             approvals,
             auto_approve,
             conversation_id: Mutex::new(None),
+            rollout_path: Mutex::new(None),
             pending_feedback: Mutex::new(VecDeque::new()),
+            pending_approvals: StdMutex::new(HashMap::new()),
         })
     }
 
+    /// Attaches `peer`, replacing any previously attached (dropped) peer.
     pub fn connect(&self, peer: JsonRpcPeer) {
-        let _ = self.rpc.set(peer);
+        self.rpc.write().unwrap().replace(peer);
     }
 
-    fn rpc(&self) -> &JsonRpcPeer {
-        self.rpc.get().expect("Codex RPC peer not attached")
+    fn rpc(&self) -> JsonRpcPeer {
+        self.rpc
+            .read()
+            .unwrap()
+            .clone()
+            .expect("Codex RPC peer not attached")
     }
 
     pub async fn initialize(&self) -> Result<(), ExecutorError> {
@@ -119,13 +185,13 @@ impl AppServerClient {
     pub async fn send_user_message(
         &self,
         conversation_id: codex_protocol::ConversationId,
-        message: String,
+        items: Vec<InputItem>,
     ) -> Result<SendUserMessageResponse, ExecutorError> {
         let request = ClientRequest::SendUserMessage {
             request_id: self.next_request_id(),
             params: SendUserMessageParams {
                 conversation_id,
-                items: vec![InputItem::Text { text: message }],
+                items,
             },
         };
         self.send_request(request, "sendUserMessage").await
@@ -209,36 +275,146 @@ impl AppServerClient {
         }
     }
 
+    /// Asks the approval service about a tool call once [`DEFAULT_APPROVAL_RENDER_DELAY`] (or its
+    /// [`APPROVAL_RENDER_DELAY_ENV`] override) has passed. A repeated
+    /// request for a call id that is still pending waits for the same decision instead of
+    /// prompting the user a second time.
     async fn request_tool_approval(
         &self,
         tool_name: &str,
         tool_input: Value,
         tool_call_id: &str,
     ) -> Result<ApprovalStatus, ExecutorError> {
-        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
         if self.auto_approve {
             return Ok(ApprovalStatus::Approved);
         }
-        Ok(self
+        let approvals = self
             .approvals
-            .as_ref()
-            .ok_or(ExecutorApprovalError::ServiceUnavailable)?
-            .request_tool_approval(tool_name, tool_input, tool_call_id)
-            .await?)
+            .clone()
+            .ok_or(ExecutorApprovalError::ServiceUnavailable)?;
+
+        let (approval, _entry) = {
+            let mut pending = self.pending_approvals.lock().unwrap();
+            match pending.get(tool_call_id) {
+                Some(approval) => {
+                    tracing::debug!("coalescing duplicate approval request for {tool_call_id}");
+                    (approval.clone(), None)
+                }
+                None => {
+                    let tool_name = tool_name.to_string();
+                    let call_id = tool_call_id.to_string();
+                    let approval = async move {
+                        let delay = *APPROVAL_RENDER_DELAY;
+                        if !delay.is_zero() {
+                            tokio::time::sleep(delay).await;
+                        }
+                        approvals
+                            .request_tool_approval(&tool_name, tool_input, &call_id)
+                            .await
+                    }
+                    .boxed()
+                    .shared();
+                    pending.insert(tool_call_id.to_string(), approval.clone());
+                    let entry = PendingApprovalEntry {
+                        pending: &self.pending_approvals,
+                        tool_call_id,
+                    };
+                    (approval, Some(entry))
+                }
+            }
+        };
+
+        Ok(approval.await?)
+    }
+
+    /// Denies any approval that is still waiting on a human, unblocking its server request.
+    pub async fn cancel_pending_approvals(&self) {
+        self.pending_approvals.lock().unwrap().clear();
+        if let Some(approvals) = self.approvals.as_ref() {
+            approvals.cancel_pending_approvals().await;
+        }
     }
 
     pub async fn register_session(
         &self,
         conversation_id: &ConversationId,
+        rollout_path: PathBuf,
     ) -> Result<(), ExecutorError> {
         {
             let mut guard = self.conversation_id.lock().await;
             guard.replace(*conversation_id);
         }
+        self.rollout_path.lock().await.replace(rollout_path);
         self.flush_pending_feedback().await;
         Ok(())
     }
 
+    /// Waits until the peer stops. When it disconnects mid-conversation and `reconnect` is set,
+    /// a new peer is opened and the conversation resumed, backing off exponentially between
+    /// attempts; once `reconnect.policy.max_attempts` are used up the disconnect is an error.
+    /// Without `reconnect` a disconnect simply ends the run.
+    pub async fn run_until_exit(
+        self: &Arc<Self>,
+        mut exit: PeerExitReceiver,
+        reconnect: Option<Reconnect>,
+    ) -> Result<(), ExecutorError> {
+        let mut attempt = 0;
+        loop {
+            if exit.await.unwrap_or(PeerExit::Disconnected) == PeerExit::Closed {
+                return Ok(());
+            }
+            let Some(reconnect) = reconnect.as_ref() else {
+                return Ok(());
+            };
+            exit = loop {
+                if attempt >= reconnect.policy.max_attempts {
+                    return Err(ExecutorError::Io(io::Error::other(format!(
+                        "Codex app server disconnected; gave up after {attempt} reconnect attempts"
+                    ))));
+                }
+                attempt += 1;
+                let delay = reconnect.policy.backoff(attempt);
+                tracing::warn!(
+                    "Codex app server disconnected; reconnecting in {delay:?} (attempt {attempt}/{})",
+                    reconnect.policy.max_attempts
+                );
+                tokio::time::sleep(delay).await;
+                match self.reconnect(reconnect).await {
+                    Ok(exit) => break exit,
+                    Err(err) => tracing::warn!("Codex reconnect attempt {attempt} failed: {err}"),
+                }
+            };
+        }
+    }
+
+    async fn reconnect(
+        self: &Arc<Self>,
+        reconnect: &Reconnect,
+    ) -> Result<PeerExitReceiver, ExecutorError> {
+        let rollout_path = self.rollout_path.lock().await.clone().ok_or_else(|| {
+            ExecutorError::Io(io::Error::other("no Codex rollout file to resume from"))
+        })?;
+        let (stdin, stdout) = reconnect.connector.connect().await?;
+        let (peer, exit) = JsonRpcPeer::spawn(stdin, stdout, self.clone());
+        self.connect(peer);
+        self.initialize().await?;
+        let response = self
+            .resume_conversation(rollout_path.clone(), reconnect.overrides.clone())
+            .await?;
+        let conversation_id = response.conversation_id;
+        self.register_session(&conversation_id, rollout_path)
+            .await?;
+        self.add_conversation_listener(conversation_id).await?;
+        self.send_user_message(
+            conversation_id,
+            vec![InputItem::Text {
+                text: RECONNECT_CONTINUE_MESSAGE.to_string(),
+            }],
+        )
+        .await?;
+        Ok(exit)
+    }
+
     async fn send_message<M>(&self, message: &M) -> Result<(), ExecutorError>
     where
         M: Serialize + Sync,
@@ -322,7 +498,7 @@ impl AppServerClient {
     }
 
     fn spawn_feedback_message(&self, conversation_id: ConversationId, feedback: String) {
-        let peer = self.rpc().clone();
+        let peer = self.rpc();
         let request = ClientRequest::SendUserMessage {
             request_id: peer.next_request_id(),
             params: SendUserMessageParams {
@@ -461,26 +637,372 @@ fn request_id(request: &ClientRequest) -> RequestId {
     }
 }
 
+type LogSink = Arc<Mutex<BufWriter<Box<dyn AsyncWrite + Send + Unpin>>>>;
+
+/// Writes raw agent output, one line per message, to every sink, e.g. the stdout pipe the
+/// MsgStore reads from plus a debug file.
 #[derive(Clone)]
 pub struct LogWriter {
-    writer: Arc<Mutex<BufWriter<Box<dyn AsyncWrite + Send + Unpin>>>>,
+    sinks: Vec<LogSink>,
 }
 
 impl LogWriter {
     pub fn new(writer: impl AsyncWrite + Send + Unpin + 'static) -> Self {
-        Self {
-            writer: Arc::new(Mutex::new(BufWriter::new(Box::new(writer)))),
-        }
+        Self { sinks: Vec::new() }.with_sink(writer)
+    }
+
+    /// Also write every line to `writer`.
+    pub fn with_sink(mut self, writer: impl AsyncWrite + Send + Unpin + 'static) -> Self {
+        let writer: Box<dyn AsyncWrite + Send + Unpin> = Box::new(writer);
+        self.sinks
+            .push(Arc::new(Mutex::new(BufWriter::new(writer))));
+        self
     }
 
+    /// Write `raw` to every sink. A failing sink is logged and skipped so the others still get
+    /// the line; an error is only returned when no sink could be written.
     pub async fn log_raw(&self, raw: &str) -> Result<(), ExecutorError> {
-        let mut guard = self.writer.lock().await;
-        guard
-            .write_all(raw.as_bytes())
+        let mut last_err = None;
+        let mut written = 0;
+        for sink in &self.sinks {
+            match Self::write_line(sink, raw).await {
+                Ok(()) => written += 1,
+                Err(err) => {
+                    tracing::warn!("Failed to write raw log line to a sink: {}", err);
+                    last_err = Some(err);
+                }
+            }
+        }
+        match last_err {
+            Some(err) if written == 0 => Err(ExecutorError::Io(err)),
+            _ => Ok(()),
+        }
+    }
+
+    async fn write_line(sink: &LogSink, raw: &str) -> io::Result<()> {
+        let mut guard = sink.lock().await;
+        guard.write_all(raw.as_bytes()).await?;
+        guard.write_all(b"\n").await?;
+        guard.flush().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::json;
+    use tokio::io::{AsyncBufReadExt, BufReader, DuplexStream};
+
+    use super::*;
+    use crate::executors::codex::jsonrpc::{PeerReader, PeerWriter};
+
+    const CONVERSATION_ID: &str = "0199a213-81c0-7800-8aa1-bbab2a035a53";
+
+    type Methods = Arc<StdMutex<Vec<String>>>;
+
+    /// Answers client requests; after the user message it either completes the task or drops the
+    /// connection.
+    async fn fake_app_server(io: DuplexStream, complete: bool, methods: Methods) {
+        let (reader, mut writer) = tokio::io::split(io);
+        let mut lines = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let message: Value = serde_json::from_str(&line).unwrap();
+            let method = message["method"].as_str().unwrap_or_default().to_string();
+            methods.lock().unwrap().push(method.clone());
+            let Some(id) = message.get("id") else {
+                continue;
+            };
+            let result = match method.as_str() {
+                "initialize" => json!({ "userAgent": "fake" }),
+                "newConversation" => json!({
+                    "conversationId": CONVERSATION_ID,
+                    "model": "gpt-5",
+                    "rolloutPath": "/tmp/rollout.jsonl",
+                }),
+                "resumeConversation" => {
+                    json!({ "conversationId": CONVERSATION_ID, "model": "gpt-5" })
+                }
+                "addConversationListener" => {
+                    json!({ "subscriptionId": "0199a213-81c0-7800-8aa1-bbab2a035a54" })
+                }
+                _ => json!({}),
+            };
+            let response = json!({ "id": id, "result": result }).to_string() + "\n";
+            writer.write_all(response.as_bytes()).await.unwrap();
+            if method == "sendUserMessage" {
+                if !complete {
+                    return;
+                }
+                let done = json!({ "method": "codex/event/task_complete", "params": {} });
+                writer
+                    .write_all(format!("{done}\n").as_bytes())
+                    .await
+                    .unwrap();
+            }
+        }
+    }
+
+    /// Hands out connections to fake servers that complete the task, or fails every connect when
+    /// `servers` is zero.
+    struct FakeConnector {
+        servers: StdMutex<u32>,
+        methods: Methods,
+    }
+
+    #[async_trait]
+    impl PeerConnector for FakeConnector {
+        async fn connect(&self) -> Result<(PeerWriter, PeerReader), ExecutorError> {
+            {
+                let mut servers = self.servers.lock().unwrap();
+                if *servers == 0 {
+                    return Err(ExecutorError::Io(io::Error::other(
+                        "app server unavailable",
+                    )));
+                }
+                *servers -= 1;
+            }
+            let (client_io, server_io) = tokio::io::duplex(64 * 1024);
+            tokio::spawn(fake_app_server(server_io, true, self.methods.clone()));
+            let (reader, writer) = tokio::io::split(client_io);
+            Ok((Box::new(writer), Box::new(reader)))
+        }
+    }
+
+    /// Starts a conversation against a server that drops the connection after the first message.
+    async fn start_dropping_conversation() -> (Arc<AppServerClient>, PeerExitReceiver) {
+        let client = AppServerClient::new(LogWriter::new(tokio::io::sink()), None, true);
+        let (client_io, server_io) = tokio::io::duplex(64 * 1024);
+        tokio::spawn(fake_app_server(server_io, false, Methods::default()));
+        let (reader, writer) = tokio::io::split(client_io);
+        let (peer, exit) = JsonRpcPeer::spawn(writer, reader, client.clone());
+        client.connect(peer);
+        client.initialize().await.unwrap();
+        let response = client
+            .new_conversation(NewConversationParams::default())
             .await
-            .map_err(ExecutorError::Io)?;
-        guard.write_all(b"\n").await.map_err(ExecutorError::Io)?;
-        guard.flush().await.map_err(ExecutorError::Io)?;
-        Ok(())
+            .unwrap();
+        client
+            .register_session(&response.conversation_id, response.rollout_path)
+            .await
+            .unwrap();
+        client
+            .add_conversation_listener(response.conversation_id)
+            .await
+            .unwrap();
+        client
+            .send_user_message(response.conversation_id, vec![])
+            .await
+            .unwrap();
+        (client, exit)
+    }
+
+    /// Rejects every write, like a file on a full disk.
+    struct FailingSink;
+
+    impl AsyncWrite for FailingSink {
+        fn poll_write(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+            _buf: &[u8],
+        ) -> std::task::Poll<io::Result<usize>> {
+            std::task::Poll::Ready(Err(io::Error::other("disk full")))
+        }
+
+        fn poll_flush(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<io::Result<()>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<io::Result<()>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn log_writer_fans_out_past_a_failing_sink() {
+        let (first, first_rx) = tokio::io::duplex(1024);
+        let (second, second_rx) = tokio::io::duplex(1024);
+        let writer = LogWriter::new(first)
+            .with_sink(FailingSink)
+            .with_sink(second);
+
+        writer.log_raw(r#"{"id":1}"#).await.unwrap();
+        drop(writer);
+
+        for rx in [first_rx, second_rx] {
+            let mut lines = BufReader::new(rx).lines();
+            assert_eq!(lines.next_line().await.unwrap().unwrap(), r#"{"id":1}"#);
+            assert!(lines.next_line().await.unwrap().is_none());
+        }
+        assert!(LogWriter::new(FailingSink).log_raw("lost").await.is_err());
+    }
+
+    fn reconnect(servers: u32, methods: Methods) -> Reconnect {
+        Reconnect {
+            policy: ReconnectPolicy {
+                max_attempts: 2,
+                initial_backoff: Duration::from_millis(1),
+                max_backoff: Duration::from_millis(5),
+            },
+            connector: Arc::new(FakeConnector {
+                servers: StdMutex::new(servers),
+                methods,
+            }),
+            overrides: NewConversationParams::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dropped_peer_reconnects_and_resumes_the_conversation() {
+        let (client, exit) = start_dropping_conversation().await;
+        let methods = Methods::default();
+        tokio::time::timeout(
+            Duration::from_secs(5),
+            client.run_until_exit(exit, Some(reconnect(1, methods.clone()))),
+        )
+        .await
+        .expect("reconnected run finishes")
+        .unwrap();
+        assert_eq!(
+            *methods.lock().unwrap(),
+            [
+                "initialize",
+                "initialized",
+                "resumeConversation",
+                "addConversationListener",
+                "sendUserMessage",
+            ]
+        );
+
+        let (client, exit) = start_dropping_conversation().await;
+        let err = client
+            .run_until_exit(exit, Some(reconnect(0, Methods::default())))
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("gave up after 2 reconnect attempts")
+        );
+
+        let (client, exit) = start_dropping_conversation().await;
+        client.run_until_exit(exit, None).await.unwrap();
+    }
+
+    #[test]
+    fn test_reconnect_backoff_doubles_up_to_the_cap() {
+        let policy = ReconnectPolicy::new(5);
+        assert_eq!(policy.backoff(1), Duration::from_millis(500));
+        assert_eq!(policy.backoff(2), Duration::from_secs(1));
+        assert_eq!(policy.backoff(4), Duration::from_secs(4));
+        assert_eq!(policy.backoff(10), Duration::from_secs(8));
+    }
+
+    /// Approves every request once the test releases it, counting how often it was asked.
+    struct CountingApprovals {
+        requests: StdMutex<Vec<String>>,
+        asked: tokio::sync::Notify,
+        release: tokio::sync::Semaphore,
+    }
+
+    impl CountingApprovals {
+        fn new() -> Arc<Self> {
+            Arc::new(Self {
+                requests: StdMutex::new(Vec::new()),
+                asked: tokio::sync::Notify::new(),
+                release: tokio::sync::Semaphore::new(0),
+            })
+        }
+
+        async fn wait_for_requests(&self, count: usize) {
+            while self.requests.lock().unwrap().len() < count {
+                self.asked.notified().await;
+            }
+        }
+    }
+
+    #[async_trait]
+    impl ExecutorApprovalService for CountingApprovals {
+        async fn request_tool_approval(
+            &self,
+            _tool_name: &str,
+            _tool_input: Value,
+            tool_call_id: &str,
+        ) -> Result<ApprovalStatus, ExecutorApprovalError> {
+            self.requests.lock().unwrap().push(tool_call_id.to_string());
+            self.asked.notify_one();
+            let _permit = self.release.acquire().await.unwrap();
+            Ok(ApprovalStatus::Approved)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_approval_requests_prompt_once() {
+        let approvals = CountingApprovals::new();
+        let client = AppServerClient::new(
+            LogWriter::new(tokio::io::sink()),
+            Some(approvals.clone()),
+            false,
+        );
+
+        // Both call ids reach the service only after every request was made, so the duplicate
+        // has joined the first one by the time they are released
+        let release = async {
+            approvals.wait_for_requests(2).await;
+            approvals.release.add_permits(2);
+        };
+        let ((first, duplicate, other), ()) = tokio::join!(
+            async {
+                tokio::join!(
+                    client.request_tool_approval("bash", json!({}), "call-1"),
+                    client.request_tool_approval("bash", json!({}), "call-1"),
+                    client.request_tool_approval("bash", json!({}), "call-2"),
+                )
+            },
+            release,
+        );
+        for status in [first, duplicate, other] {
+            assert!(matches!(status.unwrap(), ApprovalStatus::Approved));
+        }
+        let mut requests = approvals.requests.lock().unwrap().clone();
+        requests.sort();
+        assert_eq!(requests, vec!["call-1", "call-2"]);
+        assert!(client.pending_approvals.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_approval_request_is_forgotten() {
+        let approvals = CountingApprovals::new();
+        let client = AppServerClient::new(
+            LogWriter::new(tokio::io::sink()),
+            Some(approvals.clone()),
+            false,
+        );
+
+        let request = tokio::spawn({
+            let client = client.clone();
+            async move {
+                client
+                    .request_tool_approval("bash", json!({}), "call-1")
+                    .await
+            }
+        });
+        approvals.wait_for_requests(1).await;
+        request.abort();
+        assert!(request.await.unwrap_err().is_cancelled());
+        assert!(client.pending_approvals.lock().unwrap().is_empty());
+
+        // Asking again prompts anew instead of waiting on the abandoned request
+        approvals.release.add_permits(1);
+        let status = client
+            .request_tool_approval("bash", json!({}), "call-1")
+            .await;
+        assert!(matches!(status.unwrap(), ApprovalStatus::Approved));
+        assert_eq!(approvals.requests.lock().unwrap().len(), 2);
     }
 }
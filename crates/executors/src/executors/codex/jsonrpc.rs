@@ -13,6 +13,7 @@ use std::{
         Arc,
         atomic::{AtomicI64, Ordering},
     },
+    time::Duration,
 };
 
 use async_trait::async_trait;
@@ -22,8 +23,7 @@ use codex_app_server_protocol::{
 use serde::{Serialize, de::DeserializeOwned};
 use serde_json::Value;
 use tokio::{
-    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
-    process::{ChildStdin, ChildStdout},
+    io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader},
     sync::{Mutex, oneshot},
 };
 
@@ -54,37 +54,89 @@ impl ExitSignalSender {
     }
 }
 
+/// Why a peer stopped reading from the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeerExit {
+    /// The conversation finished, or a callback failed and reading was stopped on our side.
+    Closed,
+    /// The server closed its output (or it became unreadable) before the conversation finished.
+    Disconnected,
+}
+
+pub type PeerExitReceiver = oneshot::Receiver<PeerExit>;
+
+pub type PeerWriter = Box<dyn AsyncWrite + Send + Unpin>;
+pub type PeerReader = Box<dyn AsyncRead + Send + Unpin>;
+
+/// Opens a fresh connection to an app server, used to re-establish a dropped peer.
+#[async_trait]
+pub trait PeerConnector: Send + Sync {
+    async fn connect(&self) -> Result<(PeerWriter, PeerReader), ExecutorError>;
+}
+
+/// How often, and how patiently, a dropped peer is re-established.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReconnectPolicy {
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl ReconnectPolicy {
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(8),
+        }
+    }
+
+    /// Delay before the 1-based `attempt`, doubling from `initial_backoff` up to `max_backoff`.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
 #[derive(Clone)]
 pub struct JsonRpcPeer {
-    stdin: Arc<Mutex<ChildStdin>>,
+    stdin: Arc<Mutex<PeerWriter>>,
     pending: Arc<Mutex<HashMap<RequestId, oneshot::Sender<PendingResponse>>>>,
     id_counter: Arc<AtomicI64>,
 }
 
 impl JsonRpcPeer {
+    /// Starts reading server messages from `stdout`. The returned receiver resolves once reading
+    /// stops, telling a finished conversation apart from a dropped connection.
     pub fn spawn(
-        stdin: ChildStdin,
-        stdout: ChildStdout,
+        stdin: impl AsyncWrite + Send + Unpin + 'static,
+        stdout: impl AsyncRead + Send + Unpin + 'static,
         callbacks: Arc<dyn JsonRpcCallbacks>,
-        exit_tx: ExitSignalSender,
-    ) -> Self {
+    ) -> (Self, PeerExitReceiver) {
         let peer = Self {
-            stdin: Arc::new(Mutex::new(stdin)),
+            stdin: Arc::new(Mutex::new(Box::new(stdin))),
             pending: Arc::new(Mutex::new(HashMap::new())),
             id_counter: Arc::new(AtomicI64::new(1)),
         };
 
         let reader_peer = peer.clone();
         let callbacks = callbacks.clone();
+        let (exit_tx, exit_rx) = oneshot::channel();
 
         tokio::spawn(async move {
             let mut reader = BufReader::new(stdout);
             let mut buffer = String::new();
+            let mut exit = PeerExit::Closed;
 
             loop {
                 buffer.clear();
                 match reader.read_line(&mut buffer).await {
-                    Ok(0) => break,
+                    Ok(0) => {
+                        exit = PeerExit::Disconnected;
+                        break;
+                    }
                     Ok(_) => {
                         let line = buffer.trim_end_matches(['\n', '\r']);
                         if line.is_empty() {
@@ -150,16 +202,17 @@ impl JsonRpcPeer {
                     }
                     Err(err) => {
                         tracing::warn!("Error reading Codex output: {err}");
+                        exit = PeerExit::Disconnected;
                         break;
                     }
                 }
             }
 
-            exit_tx.send_exit_signal().await;
             let _ = reader_peer.shutdown().await;
+            let _ = exit_tx.send(exit);
         });
 
-        peer
+        (peer, exit_rx)
     }
 
     pub fn next_request_id(&self) -> RequestId {
@@ -7,9 +7,9 @@ use std::{
 use codex_app_server_protocol::{
     JSONRPCNotification, JSONRPCResponse, NewConversationResponse, ServerNotification,
 };
-use mcp_types::ContentBlock;
 use codex_protocol::{
     config_types::ReasoningEffort,
+    parse_command::ParsedCommand,
     plan_tool::{StepStatus, UpdatePlanArgs},
     protocol::{
         AgentMessageDeltaEvent, AgentMessageEvent, AgentReasoningDeltaEvent, AgentReasoningEvent,
@@ -17,20 +17,21 @@ use codex_protocol::{
         ErrorEvent, EventMsg, ExecApprovalRequestEvent, ExecCommandBeginEvent, ExecCommandEndEvent,
         ExecCommandOutputDeltaEvent, ExecOutputStream, FileChange as CodexProtoFileChange,
         McpInvocation, McpToolCallBeginEvent, McpToolCallEndEvent, PatchApplyBeginEvent,
-        PatchApplyEndEvent, StreamErrorEvent, TokenUsageInfo, ViewImageToolCallEvent,
-        WebSearchBeginEvent, WebSearchEndEvent,
+        PatchApplyEndEvent, StreamErrorEvent, TokenUsageInfo, UserMessageEvent,
+        ViewImageToolCallEvent, WebSearchBeginEvent, WebSearchEndEvent,
     },
 };
 use futures::StreamExt;
 use lazy_static::lazy_static;
+use mcp_types::{ContentBlock, EmbeddedResourceResource};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 use workspace_utils::{
     approvals::ApprovalStatus,
     diff::{concatenate_diff_hunks, extract_unified_diff_hunks},
     msg_store::MsgStore,
     path::make_path_relative,
+    stream_lines::BoundedLine,
 };
 
 use crate::{
@@ -38,10 +39,13 @@ use crate::{
     executors::codex::session::SessionHandler,
     logs::{
         ActionType, CommandExitStatus, CommandRunResult, FileChange, NormalizedEntry,
-        NormalizedEntryError, NormalizedEntryType, TodoItem, ToolResult, ToolResultValueType,
+        NormalizedEntryError, NormalizedEntryType, NotificationLevel, TodoItem, ToolResult,
         ToolStatus,
         stderr_processor::normalize_stderr_logs,
-        utils::{ConversationPatch, EntryIndexProvider},
+        utils::{
+            ConversationPatch, EntryIndexProvider, PatchCoalescer,
+            rate_limit::{is_rate_limit_message, rate_limit_entry},
+        },
     },
 };
 
@@ -63,12 +67,14 @@ struct CodexNotificationParams {
 struct StreamingText {
     index: usize,
     content: String,
+    section_break: bool,
 }
 
 #[derive(Default)]
 struct CommandState {
     index: Option<usize>,
     command: String,
+    cwd: Option<String>,
     stdout: String,
     stderr: String,
     formatted_output: Option<String>,
@@ -76,11 +82,12 @@ struct CommandState {
     exit_code: Option<i32>,
     awaiting_approval: bool,
     call_id: String,
+    parsed_cmd: Vec<ParsedCommand>,
 }
 
 impl ToNormalizedEntry for CommandState {
     fn to_normalized_entry(&self) -> NormalizedEntry {
-        let content = format!("`{}`", self.command);
+        let content = command_content(&self.command, &self.parsed_cmd);
 
         NormalizedEntry {
             timestamp: None,
@@ -88,10 +95,9 @@ impl ToNormalizedEntry for CommandState {
                 tool_name: "bash".to_string(),
                 action_type: ActionType::CommandRun {
                     command: self.command.clone(),
+                    cwd: self.cwd.clone(),
                     result: Some(CommandRunResult {
-                        exit_status: self
-                            .exit_code
-                            .map(|code| CommandExitStatus::ExitCode { code }),
+                        exit_status: self.exit_code.map(command_exit_status),
                         output: if self.formatted_output.is_some() {
                             self.formatted_output.clone()
                         } else {
@@ -99,7 +105,7 @@ impl ToNormalizedEntry for CommandState {
                         },
                     }),
                 },
-                status: self.status.clone(),
+                status: tool_status(&self.status, self.awaiting_approval),
             },
             content,
             metadata: serde_json::to_value(ToolCallMetadata {
@@ -110,6 +116,57 @@ impl ToNormalizedEntry for CommandState {
     }
 }
 
+/// Describes a command from Codex's parsed form when it has one, e.g. "Read `src/lib.rs`" or
+/// "Search `todo` in `src`", rather than showing the raw (often `bash -lc` wrapped) command.
+fn command_content(command: &str, parsed_cmd: &[ParsedCommand]) -> String {
+    if parsed_cmd.is_empty() {
+        return format!("`{command}`");
+    }
+    parsed_cmd
+        .iter()
+        .map(|parsed| match parsed {
+            ParsedCommand::Read { name, .. } => format!("Read `{name}`"),
+            ParsedCommand::ListFiles {
+                path: Some(path), ..
+            } => format!("List `{path}`"),
+            ParsedCommand::ListFiles { path: None, .. } => "List files".to_string(),
+            ParsedCommand::Search { query, path, .. } => match (query, path) {
+                (Some(query), Some(path)) => format!("Search `{query}` in `{path}`"),
+                (Some(query), None) => format!("Search `{query}`"),
+                (None, Some(path)) => format!("Search in `{path}`"),
+                (None, None) => "Search".to_string(),
+            },
+            ParsedCommand::Unknown { cmd } => format!("`{cmd}`"),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// An entry awaiting approval reports that instead of its own status until the call begins.
+fn tool_status(status: &ToolStatus, awaiting_approval: bool) -> ToolStatus {
+    if awaiting_approval {
+        ToolStatus::AwaitingApproval
+    } else {
+        status.clone()
+    }
+}
+
+/// Codex reports a command killed by a signal as `128 + signal`, or as the negated signal.
+fn command_exit_status(code: i32) -> CommandExitStatus {
+    const SIGNAL_EXIT_BASE: i32 = 128;
+    const MAX_SIGNAL: i32 = 64;
+
+    match code {
+        code if (-MAX_SIGNAL..0).contains(&code) => CommandExitStatus::Signal { signal: -code },
+        code if (SIGNAL_EXIT_BASE + 1..=SIGNAL_EXIT_BASE + MAX_SIGNAL).contains(&code) => {
+            CommandExitStatus::Signal {
+                signal: code - SIGNAL_EXIT_BASE,
+            }
+        }
+        code => CommandExitStatus::ExitCode { code },
+    }
+}
+
 struct McpToolState {
     index: Option<usize>,
     invocation: McpInvocation,
@@ -158,6 +215,7 @@ impl ToNormalizedEntry for WebSearchState {
                 tool_name: "web_search".to_string(),
                 action_type: ActionType::WebFetch {
                     url: self.query.clone().unwrap_or_else(|| "...".to_string()),
+                    result: None,
                 },
                 status: self.status.clone(),
             },
@@ -196,7 +254,7 @@ impl ToNormalizedEntry for PatchEntry {
                     path: self.path.clone(),
                     changes: self.changes.clone(),
                 },
-                status: self.status.clone(),
+                status: tool_status(&self.status, self.awaiting_approval),
             },
             content,
             metadata: serde_json::to_value(ToolCallMetadata {
@@ -207,8 +265,28 @@ impl ToNormalizedEntry for PatchEntry {
     }
 }
 
+/// Controls how history replayed by a resumed session is rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HistoryStrategy {
+    /// Ignore replayed history and only render events from the current turn.
+    #[default]
+    Default,
+    /// Clear previously emitted entries and rebuild them from the session's
+    /// `initial_messages`, so resumed turns are rendered exactly once.
+    Resume,
+}
+
+/// Executor settings that affect how Codex logs are normalized
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CodexLogOptions {
+    /// Render MCP tool results as their text content even when structured JSON is available
+    pub prefer_raw_tool_results: bool,
+}
+
 struct LogState {
     entry_index: EntryIndexProvider,
+    strategy: HistoryStrategy,
+    options: CodexLogOptions,
     assistant: Option<StreamingText>,
     thinking: Option<StreamingText>,
     commands: HashMap<String, CommandState>,
@@ -216,6 +294,18 @@ struct LogState {
     patches: HashMap<String, PatchState>,
     web_searches: HashMap<String, WebSearchState>,
     token_usage_info: Option<TokenUsageInfo>,
+    /// Set by a reasoning section break; consumed by the next thinking entry.
+    pending_section_break: bool,
+    last_error: Option<RepeatedError>,
+    /// Tasks started so far, i.e. the current turn of a multi-turn session.
+    turns: usize,
+}
+
+/// The most recent error entry, so retry loops that repeat it collapse into one counted entry.
+struct RepeatedError {
+    index: usize,
+    entry: NormalizedEntry,
+    count: usize,
 }
 
 enum StreamingTextKind {
@@ -224,9 +314,15 @@ enum StreamingTextKind {
 }
 
 impl LogState {
-    fn new(entry_index: EntryIndexProvider) -> Self {
+    fn new(
+        entry_index: EntryIndexProvider,
+        strategy: HistoryStrategy,
+        options: CodexLogOptions,
+    ) -> Self {
         Self {
             entry_index,
+            strategy,
+            options,
             assistant: None,
             thinking: None,
             commands: HashMap::new(),
@@ -234,9 +330,34 @@ impl LogState {
             patches: HashMap::new(),
             web_searches: HashMap::new(),
             token_usage_info: None,
+            pending_section_break: false,
+            last_error: None,
+            turns: 0,
         }
     }
 
+    /// Adds an error entry, or bumps the "(×N)" count of the previous one when it is identical
+    /// and nothing else has been rendered since.
+    fn push_error(&mut self, sink: &PatchCoalescer, entry: NormalizedEntry) {
+        if let Some(last) = &mut self.last_error
+            && last.entry.content == entry.content
+            && self.entry_index.current() == last.index + 1
+        {
+            last.count += 1;
+            let mut counted = last.entry.clone();
+            counted.content = format!("{} (×{})", counted.content, last.count);
+            replace_normalized_entry(sink, last.index, counted);
+            return;
+        }
+
+        let index = add_normalized_entry(sink, &self.entry_index, entry.clone());
+        self.last_error = Some(RepeatedError {
+            index,
+            entry,
+            count: 1,
+        });
+    }
+
     fn streaming_text_update(
         &mut self,
         content: String,
@@ -244,22 +365,33 @@ impl LogState {
         mode: UpdateMode,
     ) -> (NormalizedEntry, usize, bool) {
         let index_provider = &self.entry_index;
-        let entry = match type_ {
-            StreamingTextKind::Assistant => &mut self.assistant,
-            StreamingTextKind::Thinking => &mut self.thinking,
+        let (entry, section_break) = match type_ {
+            StreamingTextKind::Assistant => (&mut self.assistant, false),
+            StreamingTextKind::Thinking => (
+                &mut self.thinking,
+                std::mem::take(&mut self.pending_section_break),
+            ),
         };
         let is_new = entry.is_none();
-        let (content, index) = if entry.is_none() {
+        let (content, index, section_break) = if entry.is_none() {
             let index = index_provider.next();
-            *entry = Some(StreamingText { index, content });
-            (&entry.as_ref().unwrap().content, index)
+            *entry = Some(StreamingText {
+                index,
+                content,
+                section_break,
+            });
+            (&entry.as_ref().unwrap().content, index, section_break)
         } else {
             let streaming_state = entry.as_mut().unwrap();
             match mode {
                 UpdateMode::Append => streaming_state.content.push_str(&content),
                 UpdateMode::Set => streaming_state.content = content,
             }
-            (&streaming_state.content, streaming_state.index)
+            (
+                &streaming_state.content,
+                streaming_state.index,
+                streaming_state.section_break,
+            )
         };
         let normalized_entry = NormalizedEntry {
             timestamp: None,
@@ -268,7 +400,8 @@ impl LogState {
                 StreamingTextKind::Thinking => NormalizedEntryType::Thinking,
             },
             content: content.clone(),
-            metadata: None,
+            // Lets the UI render a separator before reasoning that follows a section break
+            metadata: section_break.then(|| serde_json::json!({ "section_break": true })),
         };
         (normalized_entry, index, is_new)
     }
@@ -312,37 +445,40 @@ enum UpdateMode {
 }
 
 fn upsert_normalized_entry(
-    msg_store: &Arc<MsgStore>,
+    sink: &PatchCoalescer,
     index: usize,
     normalized_entry: NormalizedEntry,
     is_new: bool,
 ) {
     if is_new {
-        msg_store.push_patch(ConversationPatch::add_normalized_entry(
+        sink.push_patch(ConversationPatch::add_normalized_entry(
             index,
             normalized_entry,
         ));
     } else {
-        msg_store.push_patch(ConversationPatch::replace(index, normalized_entry));
+        sink.push_patch(ConversationPatch::streaming_replace(
+            index,
+            normalized_entry,
+        ));
     }
 }
 
 fn add_normalized_entry(
-    msg_store: &Arc<MsgStore>,
+    sink: &PatchCoalescer,
     index_provider: &EntryIndexProvider,
     normalized_entry: NormalizedEntry,
 ) -> usize {
     let index = index_provider.next();
-    upsert_normalized_entry(msg_store, index, normalized_entry, true);
+    upsert_normalized_entry(sink, index, normalized_entry, true);
     index
 }
 
 fn replace_normalized_entry(
-    msg_store: &Arc<MsgStore>,
+    sink: &PatchCoalescer,
     index: usize,
     normalized_entry: NormalizedEntry,
 ) {
-    upsert_normalized_entry(msg_store, index, normalized_entry, false);
+    upsert_normalized_entry(sink, index, normalized_entry, false);
 }
 
 fn normalize_file_changes(
@@ -371,10 +507,7 @@ fn normalize_file_changes(
                     }
                     let hunks = extract_unified_diff_hunks(unified_diff);
                     let diff = concatenate_diff_hunks(&relative, &hunks);
-                    edits.push(FileChange::Edit {
-                        unified_diff: diff,
-                        has_line_numbers: true,
-                    });
+                    edits.push(FileChange::edit(diff, true));
                     edits
                 }
             };
@@ -392,30 +525,61 @@ fn format_todo_status(status: &StepStatus) -> String {
     .to_string()
 }
 
-pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
-    let entry_index = EntryIndexProvider::start_from(&msg_store);
+pub fn normalize_logs(
+    msg_store: Arc<MsgStore>,
+    worktree_path: &Path,
+    strategy: HistoryStrategy,
+    options: CodexLogOptions,
+) {
+    let entry_index = EntryIndexProvider::resume_from(&msg_store);
+    normalize_logs_with_index(msg_store, worktree_path, strategy, entry_index, options);
+}
+
+fn normalize_logs_with_index(
+    msg_store: Arc<MsgStore>,
+    worktree_path: &Path,
+    strategy: HistoryStrategy,
+    entry_index: EntryIndexProvider,
+    options: CodexLogOptions,
+) {
     normalize_stderr_logs(msg_store.clone(), entry_index.clone());
 
     let worktree_path_str = worktree_path.to_string_lossy().to_string();
     tokio::spawn(async move {
-        let mut state = LogState::new(entry_index.clone());
-        let mut stdout_lines = msg_store.stdout_lines_stream();
+        let mut state = LogState::new(entry_index.clone(), strategy, options);
+        let mut stdout_lines = msg_store.stdout_bounded_lines_stream();
+        let sink = PatchCoalescer::resuming(msg_store.clone(), &entry_index);
 
         while let Some(Ok(line)) = stdout_lines.next().await {
+            let line = match line {
+                BoundedLine::Line(line) => line,
+                BoundedLine::Oversized { limit, prefix } => {
+                    if let Some(session_id) = extract_session_id_prefix(&prefix) {
+                        sink.push_session_id(session_id);
+                    }
+                    add_normalized_entry(
+                        &sink,
+                        &entry_index,
+                        NormalizedEntry::oversized_line(limit),
+                    );
+                    continue;
+                }
+            };
+
             if let Ok(error) = serde_json::from_str::<Error>(&line) {
-                add_normalized_entry(&msg_store, &entry_index, error.to_normalized_entry());
+                add_normalized_entry(&sink, &entry_index, error.to_normalized_entry());
                 continue;
             }
 
             if let Ok(approval) = serde_json::from_str::<Approval>(&line) {
                 if let Some(entry) = approval.to_normalized_entry_opt() {
-                    add_normalized_entry(&msg_store, &entry_index, entry);
+                    add_normalized_entry(&sink, &entry_index, entry);
                 }
                 continue;
             }
 
             if let Ok(response) = serde_json::from_str::<JSONRPCResponse>(&line) {
-                handle_jsonrpc_response(response, &msg_store, &entry_index);
+                handle_jsonrpc_response(response, &sink, &entry_index);
                 continue;
             }
 
@@ -423,22 +587,25 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                 if let ServerNotification::SessionConfigured(session_configured) =
                     server_notification
                 {
-                    msg_store.push_session_id(session_configured.session_id.to_string());
+                    sink.push_session_id(session_configured.session_id.to_string());
+                    replay_history(
+                        &mut state,
+                        session_configured.initial_messages,
+                        &sink,
+                        &worktree_path_str,
+                    );
                     handle_model_params(
                         session_configured.model,
                         session_configured.reasoning_effort,
-                        &msg_store,
-                        &entry_index,
+                        &sink,
+                        &state.entry_index,
                     );
                 };
                 continue;
-            } else if let Some(session_id) = line
-                .strip_prefix(r#"{"method":"sessionConfigured","params":{"sessionId":""#)
-                .and_then(|suffix| SESSION_ID.captures(suffix).and_then(|caps| caps.get(1)))
-            {
+            } else if let Some(session_id) = extract_session_id_prefix(&line) {
                 // Best-effort extraction of session ID from logs in case the JSON parsing fails.
                 // This could happen if the line is truncated due to size limits because it includes the full session history.
-                msg_store.push_session_id(session_id.as_str().to_string());
+                sink.push_session_id(session_id);
                 continue;
             }
 
@@ -458,538 +625,570 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                 continue;
             };
 
-            let event = params.msg;
-            match event {
-                EventMsg::SessionConfigured(payload) => {
-                    msg_store.push_session_id(payload.session_id.to_string());
-                    handle_model_params(
-                        payload.model,
-                        payload.reasoning_effort,
-                        &msg_store,
-                        &entry_index,
-                    );
-                }
-                EventMsg::AgentMessageDelta(AgentMessageDeltaEvent { delta }) => {
-                    state.thinking = None;
-                    let (entry, index, is_new) = state.assistant_message_append(delta);
-                    upsert_normalized_entry(&msg_store, index, entry, is_new);
-                }
-                EventMsg::AgentReasoningDelta(AgentReasoningDeltaEvent { delta }) => {
-                    state.assistant = None;
-                    let (entry, index, is_new) = state.thinking_append(delta);
-                    upsert_normalized_entry(&msg_store, index, entry, is_new);
-                }
-                EventMsg::AgentMessage(AgentMessageEvent { message }) => {
-                    state.thinking = None;
-                    let (entry, index, is_new) = state.assistant_message(message);
-                    upsert_normalized_entry(&msg_store, index, entry, is_new);
-                    state.assistant = None;
-                }
-                EventMsg::AgentReasoning(AgentReasoningEvent { text }) => {
-                    state.assistant = None;
-                    let (entry, index, is_new) = state.thinking(text);
-                    upsert_normalized_entry(&msg_store, index, entry, is_new);
-                    state.thinking = None;
-                }
-                EventMsg::AgentReasoningSectionBreak(AgentReasoningSectionBreakEvent {}) => {
-                    state.assistant = None;
-                    state.thinking = None;
-                }
-                EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent {
-                    call_id,
-                    command,
-                    cwd: _,
-                    reason,
-                }) => {
-                    state.assistant = None;
-                    state.thinking = None;
-
-                    let command_text = if command.is_empty() {
-                        reason
-                            .filter(|r| !r.is_empty())
-                            .unwrap_or_else(|| "command execution".to_string())
-                    } else {
-                        command.join(" ")
-                    };
+            handle_event(&mut state, params.msg, &sink, &worktree_path_str);
+        }
+        sink.flush();
+    });
+}
+
+/// Under [`HistoryStrategy::Resume`], drop everything rendered so far and rebuild the
+/// conversation from the history a resumed session carries.
+fn replay_history(
+    state: &mut LogState,
+    initial_messages: Option<Vec<EventMsg>>,
+    sink: &PatchCoalescer,
+    worktree_path: &str,
+) {
+    if state.strategy != HistoryStrategy::Resume {
+        return;
+    }
+    let Some(history) = initial_messages.filter(|messages| !messages.is_empty()) else {
+        return;
+    };
 
-                    let command_state = state.commands.entry(call_id.clone()).or_default();
+    sink.push_patch(ConversationPatch::clear_entries());
+    state.entry_index.reset();
+    *state = LogState::new(state.entry_index.clone(), state.strategy, state.options);
+
+    for event in history {
+        match event {
+            // Live user messages are rendered by the caller, but replayed ones are the
+            // only record of earlier prompts.
+            EventMsg::UserMessage(UserMessageEvent { message, .. }) => {
+                add_normalized_entry(
+                    sink,
+                    &state.entry_index,
+                    NormalizedEntry {
+                        timestamp: None,
+                        entry_type: NormalizedEntryType::UserMessage,
+                        content: message,
+                        metadata: None,
+                    },
+                );
+            }
+            event => handle_event(state, event, sink, worktree_path),
+        }
+    }
+}
 
-                    if command_state.command.is_empty() {
-                        command_state.command = command_text;
-                    }
-                    command_state.awaiting_approval = true;
-                    if let Some(index) = command_state.index {
-                        replace_normalized_entry(
-                            &msg_store,
-                            index,
-                            command_state.to_normalized_entry(),
-                        );
-                    } else {
-                        let index = add_normalized_entry(
-                            &msg_store,
-                            &entry_index,
-                            command_state.to_normalized_entry(),
-                        );
-                        command_state.index = Some(index);
-                    }
+fn handle_event(state: &mut LogState, event: EventMsg, sink: &PatchCoalescer, worktree_path: &str) {
+    let entry_index = state.entry_index.clone();
+    match event {
+        EventMsg::SessionConfigured(payload) => {
+            sink.push_session_id(payload.session_id.to_string());
+            replay_history(state, payload.initial_messages, sink, worktree_path);
+            handle_model_params(
+                payload.model,
+                payload.reasoning_effort,
+                sink,
+                &state.entry_index,
+            );
+        }
+        EventMsg::AgentMessageDelta(AgentMessageDeltaEvent { delta }) => {
+            state.thinking = None;
+            let (entry, index, is_new) = state.assistant_message_append(delta);
+            upsert_normalized_entry(sink, index, entry, is_new);
+        }
+        EventMsg::AgentReasoningDelta(AgentReasoningDeltaEvent { delta }) => {
+            state.assistant = None;
+            let (entry, index, is_new) = state.thinking_append(delta);
+            upsert_normalized_entry(sink, index, entry, is_new);
+        }
+        EventMsg::AgentMessage(AgentMessageEvent { message }) => {
+            state.thinking = None;
+            let (entry, index, is_new) = state.assistant_message(message);
+            upsert_normalized_entry(sink, index, entry, is_new);
+            state.assistant = None;
+        }
+        EventMsg::AgentReasoning(AgentReasoningEvent { text }) => {
+            state.assistant = None;
+            let (entry, index, is_new) = state.thinking(text);
+            upsert_normalized_entry(sink, index, entry, is_new);
+            state.thinking = None;
+        }
+        EventMsg::AgentReasoningSectionBreak(AgentReasoningSectionBreakEvent {}) => {
+            state.assistant = None;
+            state.thinking = None;
+            state.pending_section_break = true;
+        }
+        EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent {
+            call_id,
+            command,
+            cwd: _,
+            reason,
+        }) => {
+            state.assistant = None;
+            state.thinking = None;
+
+            let command_text = if command.is_empty() {
+                reason
+                    .filter(|r| !r.is_empty())
+                    .unwrap_or_else(|| "command execution".to_string())
+            } else {
+                command.join(" ")
+            };
+
+            let command_state = state.commands.entry(call_id.clone()).or_default();
+
+            if command_state.command.is_empty() {
+                command_state.command = command_text;
+            }
+            command_state.awaiting_approval = true;
+            if let Some(index) = command_state.index {
+                replace_normalized_entry(sink, index, command_state.to_normalized_entry());
+            } else {
+                let index =
+                    add_normalized_entry(sink, &entry_index, command_state.to_normalized_entry());
+                command_state.index = Some(index);
+            }
+        }
+        EventMsg::ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent {
+            call_id,
+            changes,
+            reason: _,
+            grant_root: _,
+        }) => {
+            state.assistant = None;
+            state.thinking = None;
+
+            let normalized = normalize_file_changes(worktree_path, &changes);
+            let patch_state = state.patches.entry(call_id.clone()).or_default();
+
+            for entry in patch_state.entries.drain(..) {
+                if let Some(index) = entry.index {
+                    sink.push_patch(ConversationPatch::remove(index));
                 }
-                EventMsg::ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent {
-                    call_id,
-                    changes,
-                    reason: _,
-                    grant_root: _,
-                }) => {
-                    state.assistant = None;
-                    state.thinking = None;
-
-                    let normalized = normalize_file_changes(&worktree_path_str, &changes);
-                    let patch_state = state.patches.entry(call_id.clone()).or_default();
-
-                    for entry in patch_state.entries.drain(..) {
-                        if let Some(index) = entry.index {
-                            msg_store.push_patch(ConversationPatch::remove(index));
-                        }
-                    }
+            }
 
-                    for (path, file_changes) in normalized {
-                        let mut entry = PatchEntry {
-                            index: None,
-                            path,
-                            changes: file_changes,
-                            status: ToolStatus::Created,
-                            awaiting_approval: true,
-                            call_id: call_id.clone(),
-                        };
-                        let index = add_normalized_entry(
-                            &msg_store,
-                            &entry_index,
-                            entry.to_normalized_entry(),
-                        );
-                        entry.index = Some(index);
-                        patch_state.entries.push(entry);
-                    }
+            for (path, file_changes) in normalized {
+                let mut entry = PatchEntry {
+                    index: None,
+                    path,
+                    changes: file_changes,
+                    status: ToolStatus::Created,
+                    awaiting_approval: true,
+                    call_id: call_id.clone(),
+                };
+                let index = add_normalized_entry(sink, &entry_index, entry.to_normalized_entry());
+                entry.index = Some(index);
+                patch_state.entries.push(entry);
+            }
+        }
+        EventMsg::ExecCommandBegin(ExecCommandBeginEvent {
+            call_id,
+            command,
+            cwd,
+            parsed_cmd,
+        }) => {
+            state.assistant = None;
+            state.thinking = None;
+            let command_text = command.join(" ");
+            if command_text.is_empty() {
+                return;
+            }
+            // Reuse the entry created by an approval request for this call
+            let approval_index = state.commands.get(&call_id).and_then(|s| s.index);
+            state.commands.insert(
+                call_id.clone(),
+                CommandState {
+                    index: approval_index,
+                    command: command_text,
+                    cwd: Some(make_path_relative(&cwd.to_string_lossy(), worktree_path)),
+                    stdout: String::new(),
+                    stderr: String::new(),
+                    formatted_output: None,
+                    status: ToolStatus::Created,
+                    exit_code: None,
+                    awaiting_approval: false,
+                    call_id: call_id.clone(),
+                    parsed_cmd,
+                },
+            );
+            let command_state = state.commands.get_mut(&call_id).unwrap();
+            if let Some(index) = approval_index {
+                replace_normalized_entry(sink, index, command_state.to_normalized_entry());
+            } else {
+                let index =
+                    add_normalized_entry(sink, &entry_index, command_state.to_normalized_entry());
+                command_state.index = Some(index)
+            }
+        }
+        EventMsg::ExecCommandOutputDelta(ExecCommandOutputDeltaEvent {
+            call_id,
+            stream,
+            chunk,
+        }) => {
+            if let Some(command_state) = state.commands.get_mut(&call_id) {
+                let chunk = String::from_utf8_lossy(&chunk);
+                if chunk.is_empty() {
+                    return;
                 }
-                EventMsg::ExecCommandBegin(ExecCommandBeginEvent {
-                    call_id, command, ..
-                }) => {
-                    state.assistant = None;
-                    state.thinking = None;
-                    let command_text = command.join(" ");
-                    if command_text.is_empty() {
-                        continue;
-                    }
-                    state.commands.insert(
-                        call_id.clone(),
-                        CommandState {
-                            index: None,
-                            command: command_text,
-                            stdout: String::new(),
-                            stderr: String::new(),
-                            formatted_output: None,
-                            status: ToolStatus::Created,
-                            exit_code: None,
-                            awaiting_approval: false,
-                            call_id: call_id.clone(),
-                        },
-                    );
-                    let command_state = state.commands.get_mut(&call_id).unwrap();
-                    let index = add_normalized_entry(
-                        &msg_store,
-                        &entry_index,
-                        command_state.to_normalized_entry(),
-                    );
-                    command_state.index = Some(index)
+                match stream {
+                    ExecOutputStream::Stdout => command_state.stdout.push_str(&chunk),
+                    ExecOutputStream::Stderr => command_state.stderr.push_str(&chunk),
                 }
-                EventMsg::ExecCommandOutputDelta(ExecCommandOutputDeltaEvent {
-                    call_id,
-                    stream,
-                    chunk,
-                }) => {
-                    if let Some(command_state) = state.commands.get_mut(&call_id) {
-                        let chunk = String::from_utf8_lossy(&chunk);
-                        if chunk.is_empty() {
-                            continue;
-                        }
-                        match stream {
-                            ExecOutputStream::Stdout => command_state.stdout.push_str(&chunk),
-                            ExecOutputStream::Stderr => command_state.stderr.push_str(&chunk),
-                        }
-                        let Some(index) = command_state.index else {
-                            tracing::error!("missing entry index for existing command state");
-                            continue;
-                        };
-                        replace_normalized_entry(
-                            &msg_store,
-                            index,
-                            command_state.to_normalized_entry(),
-                        );
-                    }
+                let Some(index) = command_state.index else {
+                    tracing::error!("missing entry index for existing command state");
+                    return;
+                };
+                replace_normalized_entry(sink, index, command_state.to_normalized_entry());
+            }
+        }
+        EventMsg::ExecCommandEnd(ExecCommandEndEvent {
+            call_id,
+            stdout: _,
+            stderr: _,
+            aggregated_output: _,
+            exit_code,
+            duration: _,
+            formatted_output,
+        }) => {
+            if let Some(mut command_state) = state.commands.remove(&call_id) {
+                command_state.formatted_output = Some(formatted_output);
+                command_state.exit_code = Some(exit_code);
+                command_state.awaiting_approval = false;
+                command_state.status = if exit_code == 0 {
+                    ToolStatus::Success
+                } else {
+                    ToolStatus::Failed
+                };
+                let Some(index) = command_state.index else {
+                    tracing::error!("missing entry index for existing command state");
+                    return;
+                };
+                replace_normalized_entry(sink, index, command_state.to_normalized_entry());
+            }
+        }
+        EventMsg::BackgroundEvent(BackgroundEventEvent { message }) => {
+            add_normalized_entry(
+                sink,
+                &entry_index,
+                NormalizedEntry {
+                    timestamp: None,
+                    entry_type: NormalizedEntryType::Notification {
+                        level: NotificationLevel::Info,
+                    },
+                    content: format!("Background event: {message}"),
+                    metadata: None,
+                },
+            );
+        }
+        EventMsg::StreamError(StreamErrorEvent { message }) => {
+            let entry = if is_rate_limit_message(&message) {
+                rate_limit_entry(&message)
+            } else {
+                NormalizedEntry {
+                    timestamp: None,
+                    entry_type: NormalizedEntryType::ErrorMessage {
+                        error_type: NormalizedEntryError::Other,
+                    },
+                    content: format!("Stream error: {message}"),
+                    metadata: None,
                 }
-                EventMsg::ExecCommandEnd(ExecCommandEndEvent {
-                    call_id,
-                    stdout: _,
-                    stderr: _,
-                    aggregated_output: _,
-                    exit_code,
-                    duration: _,
-                    formatted_output,
-                }) => {
-                    if let Some(mut command_state) = state.commands.remove(&call_id) {
-                        command_state.formatted_output = Some(formatted_output);
-                        command_state.exit_code = Some(exit_code);
-                        command_state.awaiting_approval = false;
-                        command_state.status = if exit_code == 0 {
-                            ToolStatus::Success
-                        } else {
+            };
+            state.push_error(sink, entry);
+        }
+        EventMsg::McpToolCallBegin(McpToolCallBeginEvent {
+            call_id,
+            invocation,
+        }) => {
+            state.assistant = None;
+            state.thinking = None;
+            state.mcp_tools.insert(
+                call_id.clone(),
+                McpToolState {
+                    index: None,
+                    invocation,
+                    result: None,
+                    status: ToolStatus::Created,
+                },
+            );
+            let mcp_tool_state = state.mcp_tools.get_mut(&call_id).unwrap();
+            let index =
+                add_normalized_entry(sink, &entry_index, mcp_tool_state.to_normalized_entry());
+            mcp_tool_state.index = Some(index);
+        }
+        EventMsg::McpToolCallEnd(McpToolCallEndEvent {
+            call_id, result, ..
+        }) => {
+            if let Some(mut mcp_tool_state) = state.mcp_tools.remove(&call_id) {
+                match result {
+                    Ok(value) => {
+                        mcp_tool_state.status = if value.is_error.unwrap_or(false) {
                             ToolStatus::Failed
+                        } else {
+                            ToolStatus::Success
                         };
-                        let Some(index) = command_state.index else {
-                            tracing::error!("missing entry index for existing command state");
-                            continue;
-                        };
-                        replace_normalized_entry(
-                            &msg_store,
-                            index,
-                            command_state.to_normalized_entry(),
-                        );
-                    }
-                }
-                EventMsg::BackgroundEvent(BackgroundEventEvent { message }) => {
-                    add_normalized_entry(
-                        &msg_store,
-                        &entry_index,
-                        NormalizedEntry {
-                            timestamp: None,
-                            entry_type: NormalizedEntryType::SystemMessage,
-                            content: format!("Background event: {message}"),
-                            metadata: None,
-                        },
-                    );
-                }
-                EventMsg::StreamError(StreamErrorEvent { message }) => {
-                    add_normalized_entry(
-                        &msg_store,
-                        &entry_index,
-                        NormalizedEntry {
-                            timestamp: None,
-                            entry_type: NormalizedEntryType::ErrorMessage {
-                                error_type: NormalizedEntryError::Other,
-                            },
-                            content: format!("Stream error: {message}"),
-                            metadata: None,
-                        },
-                    );
-                }
-                EventMsg::McpToolCallBegin(McpToolCallBeginEvent {
-                    call_id,
-                    invocation,
-                }) => {
-                    state.assistant = None;
-                    state.thinking = None;
-                    state.mcp_tools.insert(
-                        call_id.clone(),
-                        McpToolState {
-                            index: None,
-                            invocation,
-                            result: None,
-                            status: ToolStatus::Created,
-                        },
-                    );
-                    let mcp_tool_state = state.mcp_tools.get_mut(&call_id).unwrap();
-                    let index = add_normalized_entry(
-                        &msg_store,
-                        &entry_index,
-                        mcp_tool_state.to_normalized_entry(),
-                    );
-                    mcp_tool_state.index = Some(index);
-                }
-                EventMsg::McpToolCallEnd(McpToolCallEndEvent {
-                    call_id, result, ..
-                }) => {
-                    if let Some(mut mcp_tool_state) = state.mcp_tools.remove(&call_id) {
-                        match result {
-                            Ok(value) => {
-                                mcp_tool_state.status = if value.is_error.unwrap_or(false) {
-                                    ToolStatus::Failed
-                                } else {
-                                    ToolStatus::Success
-                                };
-                                if value
+                        let all_text = value
+                            .content
+                            .iter()
+                            .all(|block| matches!(block, ContentBlock::TextContent(_)));
+                        let result = match value.structured_content {
+                            Some(structured)
+                                if !all_text && !state.options.prefer_raw_tool_results =>
+                            {
+                                ToolResult::json(structured)
+                            }
+                            _ => ToolResult::markdown(
+                                value
                                     .content
                                     .iter()
-                                    .all(|block| matches!(block, ContentBlock::TextContent(_)))
-                                {
-                                    mcp_tool_state.result = Some(ToolResult {
-                                        r#type: ToolResultValueType::Markdown,
-                                        value: Value::String(
-                                            value
-                                                .content
-                                                .iter()
-                                                .map(|block| {
-                                                    if let ContentBlock::TextContent(content) =
-                                                        block
-                                                    {
-                                                        content.text.clone()
-                                                    } else {
-                                                        unreachable!()
-                                                    }
-                                                })
-                                                .collect::<Vec<String>>()
-                                                .join("\n"),
-                                        ),
-                                    });
-                                } else {
-                                    mcp_tool_state.result = Some(ToolResult {
-                                        r#type: ToolResultValueType::Json,
-                                        value: value.structured_content.unwrap_or_else(|| {
-                                            serde_json::to_value(value.content).unwrap_or_default()
-                                        }),
-                                    });
-                                }
-                            }
-                            Err(err) => {
-                                mcp_tool_state.status = ToolStatus::Failed;
-                                mcp_tool_state.result = Some(ToolResult {
-                                    r#type: ToolResultValueType::Markdown,
-                                    value: Value::String(err),
-                                });
-                            }
-                        };
-                        let Some(index) = mcp_tool_state.index else {
-                            tracing::error!("missing entry index for existing mcp tool state");
-                            continue;
+                                    .map(mcp_content_block_markdown)
+                                    .collect::<Vec<String>>()
+                                    .join("\n"),
+                            ),
                         };
-                        replace_normalized_entry(
-                            &msg_store,
-                            index,
-                            mcp_tool_state.to_normalized_entry(),
-                        );
+                        mcp_tool_state.result = Some(result.capped());
                     }
-                }
-                EventMsg::PatchApplyBegin(PatchApplyBeginEvent {
-                    call_id, changes, ..
-                }) => {
-                    state.assistant = None;
-                    state.thinking = None;
-                    let normalized = normalize_file_changes(&worktree_path_str, &changes);
-                    if let Some(patch_state) = state.patches.get_mut(&call_id) {
-                        let mut iter = normalized.into_iter();
-                        for entry in &mut patch_state.entries {
-                            if let Some((path, file_changes)) = iter.next() {
-                                entry.path = path;
-                                entry.changes = file_changes;
-                            }
-                            entry.status = ToolStatus::Created;
-                            entry.awaiting_approval = false;
-                            if let Some(index) = entry.index {
-                                replace_normalized_entry(
-                                    &msg_store,
-                                    index,
-                                    entry.to_normalized_entry(),
-                                );
-                            } else {
-                                let index = add_normalized_entry(
-                                    &msg_store,
-                                    &entry_index,
-                                    entry.to_normalized_entry(),
-                                );
-                                entry.index = Some(index);
-                            }
-                        }
-                        for (path, file_changes) in iter {
-                            let mut entry = PatchEntry {
-                                index: None,
-                                path,
-                                changes: file_changes,
-                                status: ToolStatus::Created,
-                                awaiting_approval: false,
-                                call_id: call_id.clone(),
-                            };
-                            let index = add_normalized_entry(
-                                &msg_store,
-                                &entry_index,
-                                entry.to_normalized_entry(),
-                            );
-                            entry.index = Some(index);
-                            patch_state.entries.push(entry);
-                        }
-                    } else {
-                        let mut patch_state = PatchState::default();
-                        for (path, file_changes) in normalized {
-                            patch_state.entries.push(PatchEntry {
-                                index: None,
-                                path,
-                                changes: file_changes,
-                                status: ToolStatus::Created,
-                                awaiting_approval: false,
-                                call_id: call_id.clone(),
-                            });
-                            let patch_entry = patch_state.entries.last_mut().unwrap();
-                            let index = add_normalized_entry(
-                                &msg_store,
-                                &entry_index,
-                                patch_entry.to_normalized_entry(),
-                            );
-                            patch_entry.index = Some(index);
-                        }
-                        state.patches.insert(call_id, patch_state);
+                    Err(err) => {
+                        mcp_tool_state.status = ToolStatus::Failed;
+                        mcp_tool_state.result = Some(ToolResult::markdown(err).capped());
                     }
+                };
+                let Some(index) = mcp_tool_state.index else {
+                    tracing::error!("missing entry index for existing mcp tool state");
+                    return;
+                };
+                replace_normalized_entry(sink, index, mcp_tool_state.to_normalized_entry());
+            }
+        }
+        EventMsg::PatchApplyBegin(PatchApplyBeginEvent {
+            call_id, changes, ..
+        }) => {
+            state.assistant = None;
+            state.thinking = None;
+            let normalized = normalize_file_changes(worktree_path, &changes);
+            // One entry per file, sent as a single batched patch
+            let mut batch = Vec::new();
+            let patch_state = state.patches.entry(call_id.clone()).or_default();
+            let mut iter = normalized.into_iter();
+            for entry in &mut patch_state.entries {
+                if let Some((path, file_changes)) = iter.next() {
+                    entry.path = path;
+                    entry.changes = file_changes;
                 }
-                EventMsg::PatchApplyEnd(PatchApplyEndEvent {
-                    call_id,
-                    stdout: _,
-                    stderr: _,
-                    success,
-                    ..
-                }) => {
-                    if let Some(patch_state) = state.patches.remove(&call_id) {
-                        let status = if success {
-                            ToolStatus::Success
-                        } else {
-                            ToolStatus::Failed
-                        };
-                        for mut entry in patch_state.entries {
-                            entry.status = status.clone();
-                            let Some(index) = entry.index else {
-                                tracing::error!("missing entry index for existing patch entry");
-                                continue;
-                            };
-                            replace_normalized_entry(
-                                &msg_store,
-                                index,
-                                entry.to_normalized_entry(),
-                            );
-                        }
-                    }
-                }
-                EventMsg::WebSearchBegin(WebSearchBeginEvent { call_id }) => {
-                    state.assistant = None;
-                    state.thinking = None;
-                    state
-                        .web_searches
-                        .insert(call_id.clone(), WebSearchState::new());
-                    let web_search_state = state.web_searches.get_mut(&call_id).unwrap();
-                    let normalized_entry = web_search_state.to_normalized_entry();
-                    let index = add_normalized_entry(&msg_store, &entry_index, normalized_entry);
-                    web_search_state.index = Some(index);
-                }
-                EventMsg::WebSearchEnd(WebSearchEndEvent { call_id, query }) => {
-                    state.assistant = None;
-                    state.thinking = None;
-                    if let Some(mut entry) = state.web_searches.remove(&call_id) {
-                        entry.status = ToolStatus::Success;
-                        entry.query = Some(query.clone());
-                        let normalized_entry = entry.to_normalized_entry();
-                        let Some(index) = entry.index else {
-                            tracing::error!("missing entry index for existing websearch entry");
-                            continue;
-                        };
-                        replace_normalized_entry(&msg_store, index, normalized_entry);
-                    }
+                entry.status = ToolStatus::Created;
+                entry.awaiting_approval = false;
+                if let Some(index) = entry.index {
+                    batch.push(ConversationPatch::replace(
+                        index,
+                        entry.to_normalized_entry(),
+                    ));
+                } else {
+                    let index = entry_index.next();
+                    batch.push(ConversationPatch::add_normalized_entry(
+                        index,
+                        entry.to_normalized_entry(),
+                    ));
+                    entry.index = Some(index);
                 }
-                EventMsg::ViewImageToolCall(ViewImageToolCallEvent { call_id: _, path }) => {
-                    state.assistant = None;
-                    state.thinking = None;
-                    let path_str = path.to_string_lossy().to_string();
-                    let relative_path = make_path_relative(&path_str, &worktree_path_str);
-                    add_normalized_entry(
-                        &msg_store,
-                        &entry_index,
-                        NormalizedEntry {
-                            timestamp: None,
-                            entry_type: NormalizedEntryType::ToolUse {
-                                tool_name: "view_image".to_string(),
-                                action_type: ActionType::FileRead {
-                                    path: relative_path.clone(),
-                                },
-                                status: ToolStatus::Success,
-                            },
-                            content: format!("`{relative_path}`"),
-                            metadata: None,
-                        },
-                    );
+            }
+            for (path, file_changes) in iter {
+                let index = entry_index.next();
+                let entry = PatchEntry {
+                    index: Some(index),
+                    path,
+                    changes: file_changes,
+                    status: ToolStatus::Created,
+                    awaiting_approval: false,
+                    call_id: call_id.clone(),
+                };
+                batch.push(ConversationPatch::add_normalized_entry(
+                    index,
+                    entry.to_normalized_entry(),
+                ));
+                patch_state.entries.push(entry);
+            }
+            if !batch.is_empty() {
+                sink.push_patch(ConversationPatch::batch(batch));
+            }
+        }
+        EventMsg::PatchApplyEnd(PatchApplyEndEvent {
+            call_id,
+            stdout: _,
+            stderr: _,
+            success,
+            ..
+        }) => {
+            if let Some(patch_state) = state.patches.remove(&call_id) {
+                let status = if success {
+                    ToolStatus::Success
+                } else {
+                    ToolStatus::Failed
+                };
+                for mut entry in patch_state.entries {
+                    entry.status = status.clone();
+                    let Some(index) = entry.index else {
+                        tracing::error!("missing entry index for existing patch entry");
+                        continue;
+                    };
+                    replace_normalized_entry(sink, index, entry.to_normalized_entry());
                 }
-                EventMsg::PlanUpdate(UpdatePlanArgs { plan, explanation }) => {
-                    let todos: Vec<TodoItem> = plan
-                        .iter()
-                        .map(|item| TodoItem {
-                            content: item.step.clone(),
-                            status: format_todo_status(&item.status),
-                            priority: None,
-                        })
-                        .collect();
-                    let explanation = explanation
-                        .as_ref()
-                        .map(|text| text.trim())
-                        .filter(|text| !text.is_empty())
-                        .map(|text| text.to_string());
-                    let content = explanation.clone().unwrap_or_else(|| {
-                        if todos.is_empty() {
-                            "Plan updated".to_string()
-                        } else {
-                            format!("Plan updated ({} steps)", todos.len())
-                        }
-                    });
-
-                    add_normalized_entry(
-                        &msg_store,
-                        &entry_index,
-                        NormalizedEntry {
-                            timestamp: None,
-                            entry_type: NormalizedEntryType::ToolUse {
-                                tool_name: "plan".to_string(),
-                                action_type: ActionType::TodoManagement {
-                                    todos,
-                                    operation: "update".to_string(),
-                                },
-                                status: ToolStatus::Success,
-                            },
-                            content,
-                            metadata: None,
+            }
+        }
+        EventMsg::WebSearchBegin(WebSearchBeginEvent { call_id }) => {
+            state.assistant = None;
+            state.thinking = None;
+            state
+                .web_searches
+                .insert(call_id.clone(), WebSearchState::new());
+            let web_search_state = state.web_searches.get_mut(&call_id).unwrap();
+            let normalized_entry = web_search_state.to_normalized_entry();
+            let index = add_normalized_entry(sink, &entry_index, normalized_entry);
+            web_search_state.index = Some(index);
+        }
+        EventMsg::WebSearchEnd(WebSearchEndEvent { call_id, query }) => {
+            state.assistant = None;
+            state.thinking = None;
+            if let Some(mut entry) = state.web_searches.remove(&call_id) {
+                entry.status = ToolStatus::Success;
+                entry.query = Some(query.clone());
+                let normalized_entry = entry.to_normalized_entry();
+                let Some(index) = entry.index else {
+                    tracing::error!("missing entry index for existing websearch entry");
+                    return;
+                };
+                replace_normalized_entry(sink, index, normalized_entry);
+            }
+        }
+        EventMsg::ViewImageToolCall(ViewImageToolCallEvent { call_id: _, path }) => {
+            state.assistant = None;
+            state.thinking = None;
+            let path_str = path.to_string_lossy().to_string();
+            let relative_path = make_path_relative(&path_str, worktree_path);
+            add_normalized_entry(
+                sink,
+                &entry_index,
+                NormalizedEntry {
+                    timestamp: None,
+                    entry_type: NormalizedEntryType::ToolUse {
+                        tool_name: "view_image".to_string(),
+                        action_type: ActionType::FileRead {
+                            path: relative_path.clone(),
+                            result: None,
                         },
-                    );
+                        status: ToolStatus::Success,
+                    },
+                    content: format!("`{relative_path}`"),
+                    metadata: None,
+                },
+            );
+        }
+        EventMsg::PlanUpdate(UpdatePlanArgs { plan, explanation }) => {
+            let todos: Vec<TodoItem> = plan
+                .iter()
+                .map(|item| TodoItem {
+                    content: item.step.clone(),
+                    status: format_todo_status(&item.status),
+                    priority: None,
+                })
+                .collect();
+            let explanation = explanation
+                .as_ref()
+                .map(|text| text.trim())
+                .filter(|text| !text.is_empty())
+                .map(|text| text.to_string());
+            let content = explanation.clone().unwrap_or_else(|| {
+                if todos.is_empty() {
+                    "Plan updated".to_string()
+                } else {
+                    format!("Plan updated ({} steps)", todos.len())
                 }
-                EventMsg::Error(ErrorEvent { message }) => {
-                    add_normalized_entry(
-                        &msg_store,
-                        &entry_index,
-                        NormalizedEntry {
-                            timestamp: None,
-                            entry_type: NormalizedEntryType::ErrorMessage {
-                                error_type: NormalizedEntryError::Other,
-                            },
-                            content: message,
-                            metadata: None,
+            });
+
+            add_normalized_entry(
+                sink,
+                &entry_index,
+                NormalizedEntry {
+                    timestamp: None,
+                    entry_type: NormalizedEntryType::ToolUse {
+                        tool_name: "plan".to_string(),
+                        action_type: ActionType::TodoManagement {
+                            todos,
+                            operation: "update".to_string(),
                         },
-                    );
-                }
-                EventMsg::TokenCount(payload) => {
-                    if let Some(info) = payload.info {
-                        state.token_usage_info = Some(info);
-                    }
+                        status: ToolStatus::Success,
+                    },
+                    content,
+                    metadata: None,
+                },
+            );
+        }
+        EventMsg::Error(ErrorEvent { message }) => {
+            let entry = if is_rate_limit_message(&message) {
+                rate_limit_entry(&message)
+            } else {
+                NormalizedEntry {
+                    timestamp: None,
+                    entry_type: NormalizedEntryType::ErrorMessage {
+                        error_type: NormalizedEntryError::Other,
+                    },
+                    content: message,
+                    metadata: None,
                 }
-                EventMsg::AgentReasoningRawContent(..)
-                | EventMsg::AgentReasoningRawContentDelta(..)
-                | EventMsg::TaskStarted(..)
-                | EventMsg::UserMessage(..)
-                | EventMsg::TurnDiff(..)
-                | EventMsg::GetHistoryEntryResponse(..)
-                | EventMsg::McpListToolsResponse(..)
-                | EventMsg::ListCustomPromptsResponse(..)
-                | EventMsg::TurnAborted(..)
-                | EventMsg::ShutdownComplete
-                | EventMsg::ConversationPath(..)
-                | EventMsg::EnteredReviewMode(..)
-                | EventMsg::ExitedReviewMode(..)
-                | EventMsg::TaskComplete(..) => {}
+            };
+            state.push_error(sink, entry);
+        }
+        EventMsg::TokenCount(payload) => {
+            if let Some(info) = payload.info {
+                state.token_usage_info = Some(info);
             }
         }
-    });
+        EventMsg::TaskStarted(..) => {
+            state.assistant = None;
+            state.thinking = None;
+            state.turns += 1;
+            add_normalized_entry(
+                sink,
+                &state.entry_index,
+                turn_marker("Task started", state.turns),
+            );
+        }
+        EventMsg::TaskComplete(..) => {
+            state.assistant = None;
+            state.thinking = None;
+            add_normalized_entry(
+                sink,
+                &state.entry_index,
+                turn_marker("Task complete", state.turns),
+            );
+        }
+        EventMsg::AgentReasoningRawContent(..)
+        | EventMsg::AgentReasoningRawContentDelta(..)
+        | EventMsg::UserMessage(..)
+        | EventMsg::TurnDiff(..)
+        | EventMsg::GetHistoryEntryResponse(..)
+        | EventMsg::McpListToolsResponse(..)
+        | EventMsg::ListCustomPromptsResponse(..)
+        | EventMsg::TurnAborted(..)
+        | EventMsg::ShutdownComplete
+        | EventMsg::ConversationPath(..)
+        | EventMsg::EnteredReviewMode(..)
+        | EventMsg::ExitedReviewMode(..) => {}
+    }
+}
+
+/// Info notification marking where a task begins or ends. Later turns of a multi-turn session
+/// carry their number so the boundaries can be told apart.
+fn turn_marker(label: &str, turn: usize) -> NormalizedEntry {
+    let content = if turn > 1 {
+        format!("{label} (turn {turn})")
+    } else {
+        label.to_string()
+    };
+    NormalizedEntry {
+        timestamp: None,
+        entry_type: NormalizedEntryType::Notification {
+            level: NotificationLevel::Info,
+        },
+        content,
+        metadata: None,
+    }
 }
 
 fn handle_jsonrpc_response(
     response: JSONRPCResponse,
-    msg_store: &Arc<MsgStore>,
+    sink: &PatchCoalescer,
     entry_index: &EntryIndexProvider,
 ) {
     let Ok(response) = serde_json::from_value::<NewConversationResponse>(response.result.clone())
@@ -998,22 +1197,17 @@ fn handle_jsonrpc_response(
     };
 
     match SessionHandler::extract_session_id_from_rollout_path(response.rollout_path) {
-        Ok(session_id) => msg_store.push_session_id(session_id),
+        Ok(session_id) => sink.push_session_id(session_id),
         Err(err) => tracing::error!("failed to extract session id: {err}"),
     }
 
-    handle_model_params(
-        response.model,
-        response.reasoning_effort,
-        msg_store,
-        entry_index,
-    );
+    handle_model_params(response.model, response.reasoning_effort, sink, entry_index);
 }
 
 fn handle_model_params(
     model: String,
     reasoning_effort: Option<ReasoningEffort>,
-    msg_store: &Arc<MsgStore>,
+    sink: &PatchCoalescer,
     entry_index: &EntryIndexProvider,
 ) {
     let mut params = vec![];
@@ -1023,7 +1217,7 @@ fn handle_model_params(
     }
 
     add_normalized_entry(
-        msg_store,
+        sink,
         entry_index,
         NormalizedEntry {
             timestamp: None,
@@ -1034,6 +1228,47 @@ fn handle_model_params(
     );
 }
 
+/// Renders one MCP result block as Markdown. Binary payloads (images, audio, blobs) are
+/// described by their MIME type and size rather than inlined.
+fn mcp_content_block_markdown(block: &ContentBlock) -> String {
+    match block {
+        ContentBlock::TextContent(content) => content.text.clone(),
+        ContentBlock::ImageContent(image) => {
+            format!("[image: {}, {}]", image.mime_type, base64_size(&image.data))
+        }
+        ContentBlock::AudioContent(audio) => {
+            format!("[audio: {}, {}]", audio.mime_type, base64_size(&audio.data))
+        }
+        ContentBlock::ResourceLink(link) => {
+            let label = link.title.as_deref().unwrap_or(&link.name);
+            match &link.mime_type {
+                Some(mime_type) => format!("[{label}]({}) ({mime_type})", link.uri),
+                None => format!("[{label}]({})", link.uri),
+            }
+        }
+        ContentBlock::EmbeddedResource(embedded) => match &embedded.resource {
+            EmbeddedResourceResource::TextResourceContents(resource) => {
+                format!("Resource `{}`:\n\n{}", resource.uri, resource.text)
+            }
+            EmbeddedResourceResource::BlobResourceContents(resource) => format!(
+                "[resource: {}, {}, {}]",
+                resource.uri,
+                resource
+                    .mime_type
+                    .as_deref()
+                    .unwrap_or("application/octet-stream"),
+                base64_size(&resource.blob)
+            ),
+        },
+    }
+}
+
+/// Approximate decoded size of a base64 payload, for display.
+fn base64_size(data: &str) -> String {
+    let data = data.trim_end_matches('=');
+    format!("{} bytes", data.len() * 3 / 4)
+}
+
 fn build_command_output(stdout: Option<&str>, stderr: Option<&str>) -> Option<String> {
     let mut sections = Vec::new();
     if let Some(out) = stdout {
@@ -1063,6 +1298,13 @@ lazy_static! {
     .expect("valid regex");
 }
 
+/// Pull the session ID out of a `sessionConfigured` line without parsing the whole line.
+fn extract_session_id_prefix(line: &str) -> Option<String> {
+    line.strip_prefix(r#"{"method":"sessionConfigured","params":{"sessionId":""#)
+        .and_then(|suffix| SESSION_ID.captures(suffix).and_then(|caps| caps.get(1)))
+        .map(|session_id| session_id.as_str().to_string())
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub enum Error {
     LaunchError { error: String },
@@ -1164,3 +1406,655 @@ impl ToNormalizedEntryOpt for Approval {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+    use workspace_utils::log_msg::LogMsg;
+
+    use super::*;
+    use crate::logs::{
+        ToolResultValueType,
+        utils::patch::{
+            extract_normalized_entries_from_patch, extract_normalized_entry_from_patch,
+        },
+    };
+
+    fn event_line(msg: Value) -> String {
+        let line = serde_json::json!({
+            "method": format!("codex/event/{}", msg["type"].as_str().unwrap()),
+            "params": { "msg": msg },
+        });
+        format!("{line}\n")
+    }
+
+    async fn normalize_events(events: Vec<Value>) -> Vec<NormalizedEntry> {
+        normalize_events_with_strategy(events, HistoryStrategy::Default).await
+    }
+
+    async fn normalize_events_with_strategy(
+        events: Vec<Value>,
+        strategy: HistoryStrategy,
+    ) -> Vec<NormalizedEntry> {
+        normalize_events_with(events, strategy, CodexLogOptions::default()).await
+    }
+
+    /// Applies the emitted patches to an empty conversation and returns the final entries.
+    async fn normalize_events_with(
+        events: Vec<Value>,
+        strategy: HistoryStrategy,
+        options: CodexLogOptions,
+    ) -> Vec<NormalizedEntry> {
+        let msg_store = Arc::new(MsgStore::new());
+        for event in events {
+            msg_store.push_stdout(event_line(event));
+        }
+        msg_store.push_finished();
+
+        let entry_index = EntryIndexProvider::start_from(&msg_store);
+        normalize_logs_with_index(
+            msg_store.clone(),
+            Path::new("/tmp/test-worktree"),
+            strategy,
+            entry_index,
+            options,
+        );
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+        let mut conversation = serde_json::json!({ "entries": [] });
+        for msg in msg_store.get_history() {
+            if let LogMsg::JsonPatch(patch) = msg {
+                json_patch::patch(&mut conversation, &patch).unwrap();
+            }
+        }
+        conversation["entries"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| serde_json::from_value(entry["content"].clone()).unwrap())
+            .collect()
+    }
+
+    fn resumed_conversation() -> Vec<Value> {
+        vec![
+            serde_json::json!({ "type": "agent_message", "message": "Stale render" }),
+            serde_json::json!({
+                "type": "session_configured",
+                "session_id": "67e55044-10b1-426f-9247-bb680e5fe0c8",
+                "model": "gpt-5",
+                "history_log_id": 0,
+                "history_entry_count": 2,
+                "rollout_path": "/tmp/rollout.jsonl",
+                "initial_messages": [
+                    { "type": "user_message", "message": "Old prompt" },
+                    { "type": "agent_message", "message": "Old answer" },
+                ],
+            }),
+            serde_json::json!({ "type": "agent_message", "message": "New answer" }),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_resume_strategy_rebuilds_from_initial_messages() {
+        let entries =
+            normalize_events_with_strategy(resumed_conversation(), HistoryStrategy::Resume).await;
+
+        let rendered: Vec<(String, &str)> = entries
+            .iter()
+            .map(|entry| {
+                let entry_type = serde_json::to_value(&entry.entry_type).unwrap();
+                (
+                    entry_type["type"].as_str().unwrap().to_string(),
+                    entry.content.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            rendered,
+            vec![
+                ("user_message".to_string(), "Old prompt"),
+                ("assistant_message".to_string(), "Old answer"),
+                ("system_message".to_string(), "model: gpt-5"),
+                ("assistant_message".to_string(), "New answer"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_errors_become_warnings() {
+        let entries = normalize_events(vec![
+            serde_json::json!({
+                "type": "stream_error",
+                "message": "stream error: exceeded retry limit, last status: 429 Too Many Requests; retrying 1/5 in 2s…",
+            }),
+            serde_json::json!({ "type": "error", "message": "sandbox denied the command" }),
+        ])
+        .await;
+
+        assert_eq!(entries.len(), 2);
+        assert!(matches!(
+            entries[0].entry_type,
+            NormalizedEntryType::Notification {
+                level: NotificationLevel::Warn
+            }
+        ));
+        assert!(
+            entries[0]
+                .content
+                .starts_with("Rate limited, retry after 2s: ")
+        );
+        assert_eq!(entries[0].metadata.as_ref().unwrap()["retry_after_secs"], 2);
+        assert!(matches!(
+            entries[1].entry_type,
+            NormalizedEntryType::ErrorMessage { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_repeated_errors_collapse_into_one_counted_entry() {
+        let repeated = serde_json::json!({ "type": "error", "message": "upstream unavailable" });
+        let entries = normalize_events(vec![
+            repeated.clone(),
+            repeated.clone(),
+            repeated,
+            serde_json::json!({ "type": "error", "message": "giving up" }),
+        ])
+        .await;
+
+        let contents: Vec<&str> = entries.iter().map(|e| e.content.as_str()).collect();
+        assert_eq!(contents, vec!["upstream unavailable (×3)", "giving up"]);
+    }
+
+    #[tokio::test]
+    async fn test_task_started_and_complete_mark_turn_boundaries() {
+        let entries = normalize_events(vec![
+            serde_json::json!({ "type": "task_started", "model_context_window": 272000 }),
+            serde_json::json!({ "type": "agent_message", "message": "Done" }),
+            serde_json::json!({ "type": "task_complete", "last_agent_message": "Done" }),
+            serde_json::json!({ "type": "task_started", "model_context_window": 272000 }),
+            serde_json::json!({ "type": "task_complete", "last_agent_message": null }),
+        ])
+        .await;
+
+        let contents: Vec<&str> = entries.iter().map(|e| e.content.as_str()).collect();
+        assert_eq!(
+            contents,
+            vec![
+                "Task started",
+                "Done",
+                "Task complete",
+                "Task started (turn 2)",
+                "Task complete (turn 2)",
+            ]
+        );
+        assert!(matches!(
+            entries[0].entry_type,
+            NormalizedEntryType::Notification {
+                level: NotificationLevel::Info
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_default_strategy_ignores_initial_messages() {
+        let entries = normalize_events(resumed_conversation()).await;
+
+        let contents: Vec<&str> = entries.iter().map(|e| e.content.as_str()).collect();
+        assert_eq!(contents, vec!["Stale render", "model: gpt-5", "New answer"]);
+    }
+
+    #[tokio::test]
+    async fn test_reasoning_section_break_marks_next_thinking_entry() {
+        let entries = normalize_events(vec![
+            serde_json::json!({ "type": "agent_reasoning_delta", "delta": "First section" }),
+            serde_json::json!({ "type": "agent_reasoning_section_break" }),
+            serde_json::json!({ "type": "agent_reasoning_delta", "delta": "Second " }),
+            serde_json::json!({ "type": "agent_reasoning_delta", "delta": "section" }),
+        ])
+        .await;
+
+        assert_eq!(entries.len(), 2);
+        assert!(matches!(
+            entries[0].entry_type,
+            NormalizedEntryType::Thinking
+        ));
+        assert_eq!(entries[0].content, "First section");
+        assert!(entries[0].metadata.is_none());
+
+        assert!(matches!(
+            entries[1].entry_type,
+            NormalizedEntryType::Thinking
+        ));
+        assert_eq!(entries[1].content, "Second section");
+        assert_eq!(
+            entries[1].metadata,
+            Some(serde_json::json!({ "section_break": true }))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_exec_command_captures_cwd() {
+        let entries = normalize_events(vec![serde_json::json!({
+            "type": "exec_command_begin",
+            "call_id": "call-1",
+            "command": ["cargo", "test"],
+            "cwd": "/tmp/test-worktree/crates/utils",
+            "parsed_cmd": [],
+        })])
+        .await;
+
+        assert_eq!(entries.len(), 1);
+        let NormalizedEntryType::ToolUse {
+            action_type: ActionType::CommandRun { command, cwd, .. },
+            ..
+        } = &entries[0].entry_type
+        else {
+            panic!("expected a command run, got {:?}", entries[0].entry_type);
+        };
+        assert_eq!(command, "cargo test");
+        assert_eq!(cwd.as_deref(), Some("crates/utils"));
+    }
+
+    #[tokio::test]
+    async fn test_approval_requests_mark_entries_awaiting_approval() {
+        let exec_request = serde_json::json!({
+            "type": "exec_approval_request",
+            "call_id": "call-1",
+            "command": ["rm", "-rf", "target"],
+            "cwd": "/tmp/test-worktree",
+        });
+        let patch_request = serde_json::json!({
+            "type": "apply_patch_approval_request",
+            "call_id": "call-2",
+            "changes": {
+                "/tmp/test-worktree/src/a.rs": { "add": { "content": "fn a() {}\n" } },
+            },
+        });
+        let status = |entry: &NormalizedEntry| match &entry.entry_type {
+            NormalizedEntryType::ToolUse { status, .. } => status.clone(),
+            other => panic!("expected a tool use, got {other:?}"),
+        };
+
+        let entries = normalize_events(vec![exec_request.clone(), patch_request.clone()]).await;
+        assert_eq!(entries.len(), 2);
+        assert!(matches!(status(&entries[0]), ToolStatus::AwaitingApproval));
+        assert!(matches!(status(&entries[1]), ToolStatus::AwaitingApproval));
+
+        // Once approved, the calls begin and reuse their entries
+        let entries = normalize_events(vec![
+            exec_request,
+            patch_request,
+            serde_json::json!({
+                "type": "exec_command_begin",
+                "call_id": "call-1",
+                "command": ["rm", "-rf", "target"],
+                "cwd": "/tmp/test-worktree",
+                "parsed_cmd": [],
+            }),
+            serde_json::json!({
+                "type": "patch_apply_begin",
+                "call_id": "call-2",
+                "auto_approved": false,
+                "changes": {
+                    "/tmp/test-worktree/src/a.rs": { "add": { "content": "fn a() {}\n" } },
+                },
+            }),
+        ])
+        .await;
+        assert_eq!(entries.len(), 2);
+        assert!(matches!(status(&entries[0]), ToolStatus::Created));
+        assert!(matches!(status(&entries[1]), ToolStatus::Created));
+    }
+
+    #[tokio::test]
+    async fn test_parsed_cmd_describes_command_entry() {
+        let entries = normalize_events(vec![serde_json::json!({
+            "type": "exec_command_begin",
+            "call_id": "call-1",
+            "command": ["bash", "-lc", "cat src/lib.rs && rg -n todo src | head -n 5"],
+            "cwd": "/tmp/test-worktree",
+            "parsed_cmd": [
+                { "type": "read", "cmd": "cat src/lib.rs", "name": "lib.rs" },
+                { "type": "search", "cmd": "rg -n todo src", "query": "todo", "path": "src" },
+                { "type": "unknown", "cmd": "head -n 5" },
+            ],
+        })])
+        .await;
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(
+            entries[0].content,
+            "Read `lib.rs`, Search `todo` in `src`, `head -n 5`"
+        );
+        let NormalizedEntryType::ToolUse {
+            action_type: ActionType::CommandRun { command, .. },
+            ..
+        } = &entries[0].entry_type
+        else {
+            panic!("expected a command run, got {:?}", entries[0].entry_type);
+        };
+        assert_eq!(
+            command,
+            "bash -lc cat src/lib.rs && rg -n todo src | head -n 5"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_command_killed_by_signal_reports_signal() {
+        let exec_end = |call_id: &str, exit_code: i32| {
+            serde_json::json!({
+                "type": "exec_command_end",
+                "call_id": call_id,
+                "stdout": "",
+                "stderr": "",
+                "exit_code": exit_code,
+                "duration": { "secs": 1, "nanos": 0 },
+                "formatted_output": "",
+            })
+        };
+        let exec_begin = |call_id: &str| {
+            serde_json::json!({
+                "type": "exec_command_begin",
+                "call_id": call_id,
+                "command": ["sleep", "60"],
+                "cwd": "/tmp/test-worktree",
+                "parsed_cmd": [],
+            })
+        };
+        let entries = normalize_events(vec![
+            exec_begin("killed"),
+            exec_end("killed", 137),
+            exec_begin("failed"),
+            exec_end("failed", 2),
+        ])
+        .await;
+
+        let exit_statuses: Vec<_> = entries
+            .iter()
+            .map(|entry| match &entry.entry_type {
+                NormalizedEntryType::ToolUse {
+                    action_type:
+                        ActionType::CommandRun {
+                            result: Some(result),
+                            ..
+                        },
+                    status,
+                    ..
+                } => {
+                    assert!(matches!(status, ToolStatus::Failed));
+                    result.exit_status.clone()
+                }
+                other => panic!("expected a command run, got {other:?}"),
+            })
+            .collect();
+        assert!(matches!(
+            exit_statuses[0],
+            Some(CommandExitStatus::Signal { signal: 9 })
+        ));
+        assert!(matches!(
+            exit_statuses[1],
+            Some(CommandExitStatus::ExitCode { code: 2 })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_background_event_is_info_notification() {
+        let entries = normalize_events(vec![
+            serde_json::json!({ "type": "background_event", "message": "Indexing workspace" }),
+            serde_json::json!({ "type": "stream_error", "message": "connection reset" }),
+        ])
+        .await;
+
+        assert_eq!(entries.len(), 2);
+        assert!(matches!(
+            entries[0].entry_type,
+            NormalizedEntryType::Notification {
+                level: NotificationLevel::Info
+            }
+        ));
+        assert_eq!(entries[0].content, "Background event: Indexing workspace");
+        // Stream errors remain errors
+        assert!(matches!(
+            entries[1].entry_type,
+            NormalizedEntryType::ErrorMessage {
+                error_type: NormalizedEntryError::Other
+            }
+        ));
+        assert_eq!(entries[1].content, "Stream error: connection reset");
+    }
+
+    #[tokio::test]
+    async fn test_mcp_image_result_is_described_not_dumped() {
+        let invocation = serde_json::json!({
+            "server": "browser",
+            "tool": "screenshot",
+            "arguments": { "url": "http://localhost:3000" },
+        });
+        let entries = normalize_events(vec![
+            serde_json::json!({
+                "type": "mcp_tool_call_begin",
+                "call_id": "mcp-1",
+                "invocation": invocation,
+            }),
+            serde_json::json!({
+                "type": "mcp_tool_call_end",
+                "call_id": "mcp-1",
+                "invocation": invocation,
+                "duration": { "secs": 1, "nanos": 0 },
+                "result": { "Ok": { "content": [
+                    { "type": "text", "text": "Captured the page." },
+                    { "type": "image", "mimeType": "image/png", "data": "iVBORw0KGgoAAAANSUhEUg==" },
+                    { "type": "resource_link", "name": "page.html", "uri": "file:///tmp/page.html" },
+                ] } },
+            }),
+        ])
+        .await;
+
+        assert_eq!(entries.len(), 1);
+        let NormalizedEntryType::ToolUse {
+            action_type: ActionType::Tool { result, .. },
+            status,
+            ..
+        } = &entries[0].entry_type
+        else {
+            panic!(
+                "expected an MCP tool entry, got {:?}",
+                entries[0].entry_type
+            );
+        };
+        assert!(matches!(status, ToolStatus::Success));
+        let result = result.as_ref().unwrap();
+        assert!(matches!(result.r#type, ToolResultValueType::Markdown));
+        assert_eq!(
+            result.value,
+            "Captured the page.\n[image: image/png, 16 bytes]\n[page.html](file:///tmp/page.html)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_prefer_raw_tool_results_keeps_structured_mcp_results_as_text() {
+        let invocation =
+            serde_json::json!({ "server": "github", "tool": "get_issue", "arguments": {} });
+        let events = vec![
+            serde_json::json!({
+                "type": "mcp_tool_call_begin",
+                "call_id": "mcp-1",
+                "invocation": invocation,
+            }),
+            serde_json::json!({
+                "type": "mcp_tool_call_end",
+                "call_id": "mcp-1",
+                "invocation": invocation,
+                "duration": { "secs": 1, "nanos": 0 },
+                "result": { "Ok": {
+                    "content": [
+                        { "type": "text", "text": "{\"number\":42}" },
+                        { "type": "image", "mimeType": "image/png", "data": "iVBORw0KGgoAAAANSUhEUg==" },
+                    ],
+                    "structuredContent": { "number": 42 },
+                } },
+            }),
+        ];
+        let result_of = |entries: Vec<NormalizedEntry>| match entries[0].entry_type.clone() {
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::Tool { result, .. },
+                ..
+            } => result.unwrap(),
+            other => panic!("expected an MCP tool entry, got {other:?}"),
+        };
+
+        let structured = result_of(
+            normalize_events_with(
+                events.clone(),
+                HistoryStrategy::Default,
+                CodexLogOptions::default(),
+            )
+            .await,
+        );
+        assert!(matches!(structured.r#type, ToolResultValueType::Json));
+        assert_eq!(structured.value, serde_json::json!({ "number": 42 }));
+
+        let raw = result_of(
+            normalize_events_with(
+                events,
+                HistoryStrategy::Default,
+                CodexLogOptions {
+                    prefer_raw_tool_results: true,
+                },
+            )
+            .await,
+        );
+        assert!(matches!(raw.r#type, ToolResultValueType::Markdown));
+        assert_eq!(raw.value, "{\"number\":42}\n[image: image/png, 16 bytes]");
+    }
+
+    #[test]
+    fn test_update_change_carries_diff_stats() {
+        let changes = HashMap::from([(
+            PathBuf::from("/tmp/test-worktree/src/lib.rs"),
+            CodexProtoFileChange::Update {
+                unified_diff: "@@ -1,3 +1,2 @@\n-use a;\n-use b;\n+use c;\n pub fn f() {}\n"
+                    .to_string(),
+                move_path: None,
+            },
+        )]);
+
+        let normalized = normalize_file_changes("/tmp/test-worktree", &changes);
+        assert_eq!(normalized.len(), 1);
+        let (path, file_changes) = &normalized[0];
+        assert_eq!(path, "src/lib.rs");
+        let [FileChange::Edit { stats, .. }] = file_changes.as_slice() else {
+            panic!("Expected a single edit, got {file_changes:?}");
+        };
+        assert_eq!((stats.added, stats.removed, stats.files_changed), (1, 2, 1));
+    }
+
+    #[tokio::test]
+    async fn test_multi_file_patch_apply_is_one_batched_message() {
+        let msg_store = Arc::new(MsgStore::new());
+        msg_store.push_stdout(event_line(serde_json::json!({
+            "type": "patch_apply_begin",
+            "call_id": "call-1",
+            "auto_approved": true,
+            "changes": {
+                "/tmp/test-worktree/src/a.rs": { "add": { "content": "fn a() {}\n" } },
+                "/tmp/test-worktree/src/b.rs": { "add": { "content": "fn b() {}\n" } },
+                "/tmp/test-worktree/src/c.rs": { "delete": { "content": "fn c() {}\n" } },
+            },
+        })));
+        msg_store.push_finished();
+
+        normalize_logs(
+            msg_store.clone(),
+            Path::new("/tmp/test-worktree"),
+            HistoryStrategy::Default,
+            CodexLogOptions::default(),
+        );
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+        let patches: Vec<_> = msg_store
+            .get_history()
+            .into_iter()
+            .filter_map(|msg| match msg {
+                LogMsg::JsonPatch(patch) => Some(patch),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(patches.len(), 1);
+        let mut paths: Vec<_> = extract_normalized_entries_from_patch(&patches[0])
+            .into_iter()
+            .map(|(_, entry)| match entry.entry_type {
+                NormalizedEntryType::ToolUse {
+                    action_type: ActionType::FileEdit { path, .. },
+                    ..
+                } => path,
+                other => panic!("expected a file edit, got {other:?}"),
+            })
+            .collect();
+        paths.sort();
+        assert_eq!(paths, ["src/a.rs", "src/b.rs", "src/c.rs"]);
+        assert_eq!(EntryIndexProvider::start_from(&msg_store).current(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_streamed_command_output_is_coalesced() {
+        let msg_store = Arc::new(MsgStore::new());
+        msg_store.push_stdout(event_line(serde_json::json!({
+            "type": "exec_command_begin",
+            "call_id": "call-1",
+            "command": ["cargo", "build"],
+            "cwd": "/tmp/test-worktree",
+            "parsed_cmd": [],
+        })));
+        for _ in 0..200 {
+            // "ok\n", base64-encoded
+            msg_store.push_stdout(event_line(serde_json::json!({
+                "type": "exec_command_output_delta",
+                "call_id": "call-1",
+                "stream": "stdout",
+                "chunk": "b2sK",
+            })));
+        }
+        msg_store.push_finished();
+
+        normalize_logs(
+            msg_store.clone(),
+            Path::new("/tmp/test-worktree"),
+            HistoryStrategy::Default,
+            CodexLogOptions::default(),
+        );
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+        let patches: Vec<_> = msg_store
+            .get_history()
+            .into_iter()
+            .filter_map(|msg| match msg {
+                LogMsg::JsonPatch(patch) => Some(patch),
+                _ => None,
+            })
+            .collect();
+        // Typically one add plus a single replace carrying all 200 chunks; allow a few extra
+        // flushes in case the 50ms window elapses mid-stream on a slow machine.
+        assert!(
+            patches.len() < 10,
+            "expected coalesced patches, got {}",
+            patches.len()
+        );
+        let (_, entry) = extract_normalized_entry_from_patch(patches.last().unwrap()).unwrap();
+        let NormalizedEntryType::ToolUse {
+            action_type:
+                ActionType::CommandRun {
+                    result: Some(result),
+                    ..
+                },
+            ..
+        } = entry.entry_type
+        else {
+            panic!(
+                "expected a command run with output, got {:?}",
+                entry.entry_type
+            );
+        };
+        assert_eq!(result.output.unwrap().matches("ok").count(), 200);
+    }
+}
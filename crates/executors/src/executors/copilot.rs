@@ -14,22 +14,31 @@ use tokio::{
     fs,
     io::AsyncWriteExt,
     process::Command,
-    time::{interval, timeout},
+    time::{sleep, timeout},
 };
 use ts_rs::TS;
 use uuid::Uuid;
 use workspace_utils::{msg_store::MsgStore, path::get_automagik_forge_temp_dir};
 
 use crate::{
-    command::{CmdOverrides, CommandBuilder, apply_overrides},
-    executors::{AppendPrompt, ExecutorError, SpawnedChild, StandardCodingAgentExecutor},
+    command::{CmdOverrides, CommandBuilder, apply_env, apply_overrides},
+    executors::{
+        AppendPrompt, BaseCodingAgent, ExecutorError, ExecutorLogLevel, SpawnedChild,
+        StandardCodingAgentExecutor,
+    },
     logs::{
         NormalizedEntry, NormalizedEntryType, plain_text_processor::PlainTextLogProcessor,
         stderr_processor::normalize_stderr_logs, utils::EntryIndexProvider,
     },
+    profile::ExecutorConfigs,
     stdout_dup::{self, StdoutAppender},
 };
 
+/// First delay between scans for the session log; doubles up to [`SESSION_POLL_MAX`]
+const SESSION_POLL_INITIAL: Duration = Duration::from_millis(50);
+const SESSION_POLL_MAX: Duration = Duration::from_millis(500);
+const DEFAULT_SESSION_WATCH_TIMEOUT: Duration = Duration::from_secs(600);
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
 pub struct Copilot {
     #[serde(default)]
@@ -46,16 +55,24 @@ pub struct Copilot {
     pub add_dir: Option<Vec<String>>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub disable_mcp_server: Option<Vec<String>>,
+    /// Seconds to wait for Copilot to create its session log before giving up on the session id
+    /// (default: 600)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_watch_timeout_secs: Option<u32>,
+    /// Value of Copilot's `--log-level` (default: debug)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_level: Option<ExecutorLogLevel>,
     #[serde(flatten)]
     pub cmd: CmdOverrides,
 }
 
 impl Copilot {
     fn build_command_builder(&self, log_dir: &str) -> CommandBuilder {
+        let log_level = self.log_level.unwrap_or(ExecutorLogLevel::Debug);
         let mut builder = CommandBuilder::new("npx -y @github/copilot@0.0.337").params([
             "--no-color",
             "--log-level",
-            "debug",
+            log_level.as_str(),
             "--log-dir",
             log_dir,
         ]);
@@ -64,8 +81,12 @@ impl Copilot {
             builder = builder.extend_params(["--allow-all-tools"]);
         }
 
-        if let Some(model) = &self.model {
-            builder = builder.extend_params(["--model", model]);
+        if let Some(model) = self
+            .model
+            .clone()
+            .or_else(|| ExecutorConfigs::cached_default_model(BaseCodingAgent::Copilot))
+        {
+            builder = builder.extend_params(["--model", &model]);
         }
 
         if let Some(tool) = &self.allow_tool {
@@ -90,6 +111,12 @@ impl Copilot {
 
         apply_overrides(builder, &self.cmd)
     }
+
+    fn session_watch_timeout(&self) -> Duration {
+        self.session_watch_timeout_secs
+            .map(|secs| Duration::from_secs(secs.into()))
+            .unwrap_or(DEFAULT_SESSION_WATCH_TIMEOUT)
+    }
 }
 
 #[async_trait]
@@ -112,6 +139,7 @@ impl StandardCodingAgentExecutor for Copilot {
             .current_dir(current_dir)
             .args(&args)
             .env("NODE_NO_WARNINGS", "1");
+        apply_env(&mut command, current_dir, &self.cmd);
 
         let mut child = command.group_spawn()?;
 
@@ -122,7 +150,12 @@ impl StandardCodingAgentExecutor for Copilot {
         }
 
         let (_, appender) = stdout_dup::tee_stdout_with_appender(&mut child)?;
-        Self::send_session_id(log_dir, appender);
+        Self::send_session_id(
+            log_dir,
+            current_dir.to_path_buf(),
+            appender,
+            self.session_watch_timeout(),
+        );
 
         Ok(child.into())
     }
@@ -151,6 +184,7 @@ impl StandardCodingAgentExecutor for Copilot {
             .current_dir(current_dir)
             .args(&args)
             .env("NODE_NO_WARNINGS", "1");
+        apply_env(&mut command, current_dir, &self.cmd);
 
         let mut child = command.group_spawn()?;
 
@@ -161,7 +195,12 @@ impl StandardCodingAgentExecutor for Copilot {
         }
 
         let (_, appender) = stdout_dup::tee_stdout_with_appender(&mut child)?;
-        Self::send_session_id(log_dir, appender);
+        Self::send_session_id(
+            log_dir,
+            current_dir.to_path_buf(),
+            appender,
+            self.session_watch_timeout(),
+        );
 
         Ok(child.into())
     }
@@ -193,6 +232,10 @@ impl StandardCodingAgentExecutor for Copilot {
     }
 
     // MCP configuration methods
+    async fn recorded_session_id(&self, current_dir: &Path) -> Option<String> {
+        Self::read_session_sidecar(&Self::sessions_dir(), current_dir).await
+    }
+
     fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
         dirs::home_dir().map(|home| home.join(".copilot").join("mcp-config.json"))
     }
@@ -214,6 +257,7 @@ impl Copilot {
                     *line = strip_ansi_escapes::strip_str(&line);
                 })
             }))
+            .in_place_updates(true)
             .index_provider(index_provider)
             .build()
     }
@@ -235,10 +279,15 @@ impl Copilot {
     }
 
     // Scan the log directory for a file named `<UUID>.log` and extract the UUID as session ID.
-    async fn watch_session_id(log_dir_path: PathBuf) -> Result<String, String> {
-        let mut ticker = interval(Duration::from_millis(200));
-
-        timeout(Duration::from_secs(600), async {
+    // Polls quickly at first, since the log usually appears within moments, then backs off so a
+    // slow start doesn't keep the scan busy.
+    async fn watch_session_id(
+        log_dir_path: PathBuf,
+        watch_timeout: Duration,
+    ) -> Result<String, String> {
+        let mut delay = SESSION_POLL_INITIAL;
+
+        timeout(watch_timeout, async {
             loop {
                 if let Ok(mut rd) = fs::read_dir(&log_dir_path).await {
                     while let Ok(Some(e)) = rd.next_entry().await {
@@ -250,7 +299,8 @@ impl Copilot {
                         }
                     }
                 }
-                ticker.tick().await;
+                sleep(delay).await;
+                delay = (delay * 2).min(SESSION_POLL_MAX);
             }
         })
         .await
@@ -259,11 +309,57 @@ impl Copilot {
 
     const SESSION_PREFIX: &'static str = "[copilot-session] ";
 
-    // Find session id and write it to stdout prefixed
-    fn send_session_id(log_dir_path: PathBuf, stdout_appender: StdoutAppender) {
+    /// Where session sidecars are kept: the forge temp dir, beside the worktrees, so nothing is
+    /// written into the project or the worktree's parent.
+    fn sessions_dir() -> PathBuf {
+        get_automagik_forge_temp_dir().join("copilot_sessions")
+    }
+
+    /// Sidecar holding the last discovered session id, keyed by the worktree's directory name,
+    /// which embeds the attempt id. It outlives both the server process and the temp log dir.
+    fn session_sidecar_path(sessions_dir: &Path, current_dir: &Path) -> Option<PathBuf> {
+        let name = current_dir.file_name()?.to_string_lossy();
+        Some(sessions_dir.join(format!("{name}.copilot-session")))
+    }
+
+    async fn write_session_sidecar(sessions_dir: &Path, current_dir: &Path, session_id: &str) {
+        let Some(path) = Self::session_sidecar_path(sessions_dir, current_dir) else {
+            return;
+        };
+        if let Err(e) = fs::create_dir_all(sessions_dir).await {
+            tracing::warn!(
+                "Failed to create Copilot sessions dir {:?}: {}",
+                sessions_dir,
+                e
+            );
+            return;
+        }
+        if let Err(e) = fs::write(&path, session_id).await {
+            tracing::warn!("Failed to write Copilot session sidecar {:?}: {}", path, e);
+        }
+    }
+
+    async fn read_session_sidecar(sessions_dir: &Path, current_dir: &Path) -> Option<String> {
+        let path = Self::session_sidecar_path(sessions_dir, current_dir)?;
+        let contents = fs::read_to_string(&path).await.ok()?;
+        let session_id = contents.trim();
+        Uuid::parse_str(session_id)
+            .is_ok()
+            .then(|| session_id.to_string())
+    }
+
+    // Find session id, persist it next to the worktree and write it to stdout prefixed
+    fn send_session_id(
+        log_dir_path: PathBuf,
+        current_dir: PathBuf,
+        stdout_appender: StdoutAppender,
+        watch_timeout: Duration,
+    ) {
         tokio::spawn(async move {
-            match Self::watch_session_id(log_dir_path).await {
+            match Self::watch_session_id(log_dir_path, watch_timeout).await {
                 Ok(session_id) => {
+                    Self::write_session_sidecar(&Self::sessions_dir(), &current_dir, &session_id)
+                        .await;
                     let session_line = format!("{}{}\n", Self::SESSION_PREFIX, session_id);
                     stdout_appender.append_line(&session_line);
                 }
@@ -274,3 +370,93 @@ impl Copilot {
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_session_sidecar_round_trip() {
+        let root = tempfile::tempdir().unwrap();
+        let worktree = root.path().join("vk-1234-fix-bug");
+        std::fs::create_dir(&worktree).unwrap();
+        let sessions = tempfile::tempdir().unwrap();
+        let sessions_dir = sessions.path().join("copilot_sessions");
+        assert!(
+            Copilot::read_session_sidecar(&sessions_dir, &worktree)
+                .await
+                .is_none()
+        );
+
+        let session_id = Uuid::new_v4().to_string();
+        Copilot::write_session_sidecar(&sessions_dir, &worktree, &session_id).await;
+        assert!(
+            sessions_dir
+                .join("vk-1234-fix-bug.copilot-session")
+                .exists()
+        );
+        assert_eq!(
+            Copilot::read_session_sidecar(&sessions_dir, &worktree).await,
+            Some(session_id)
+        );
+
+        // Neither the worktree nor the directory holding it gets a file
+        assert!(std::fs::read_dir(&worktree).unwrap().next().is_none());
+        let entries: Vec<_> = std::fs::read_dir(root.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(entries, ["vk-1234-fix-bug"]);
+
+        Copilot::write_session_sidecar(&sessions_dir, &worktree, "not-a-session").await;
+        assert!(
+            Copilot::read_session_sidecar(&sessions_dir, &worktree)
+                .await
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_session_log_is_detected_quickly() {
+        let log_dir = tempfile::tempdir().unwrap();
+        let session_id = Uuid::new_v4().to_string();
+        let log_path = log_dir.path().join(format!("{session_id}.log"));
+        let started = std::time::Instant::now();
+        tokio::spawn(async move {
+            sleep(Duration::from_millis(10)).await;
+            fs::write(log_path, "").await.unwrap();
+        });
+
+        let found = Copilot::watch_session_id(log_dir.path().to_path_buf(), Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(found, session_id);
+        // Well under the 500ms the backoff grows to
+        assert!(started.elapsed() < Duration::from_millis(250));
+    }
+
+    #[tokio::test]
+    async fn test_session_watch_gives_up_after_timeout() {
+        let log_dir = tempfile::tempdir().unwrap();
+        assert!(
+            Copilot::watch_session_id(log_dir.path().to_path_buf(), Duration::from_millis(100))
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_log_level_sets_flag() {
+        let log_level = |executor: &Copilot| {
+            let params = executor.build_command_builder("/tmp/logs").params.unwrap();
+            let position = params.iter().position(|param| param == "--log-level")?;
+            params.get(position + 1).cloned()
+        };
+
+        let unset: Copilot = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert_eq!(log_level(&unset).as_deref(), Some("debug"));
+        let quiet: Copilot =
+            serde_json::from_value(serde_json::json!({ "log_level": "warning" })).unwrap();
+        assert_eq!(log_level(&quiet).as_deref(), Some("warning"));
+    }
+}
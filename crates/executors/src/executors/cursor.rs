@@ -10,7 +10,7 @@ use tokio::{io::AsyncWriteExt, process::Command};
 use ts_rs::TS;
 use workspace_utils::{
     diff::{
-        concatenate_diff_hunks, create_unified_diff, create_unified_diff_hunk,
+        DiffAlgorithm, concatenate_diff_hunks, create_unified_diff, create_unified_diff_hunk,
         extract_unified_diff_hunks,
     },
     msg_store::MsgStore,
@@ -19,7 +19,7 @@ use workspace_utils::{
 };
 
 use crate::{
-    command::{CmdOverrides, CommandBuilder, apply_overrides},
+    command::{CmdOverrides, CommandBuilder, apply_env, apply_overrides},
     executors::{AppendPrompt, ExecutorError, SpawnedChild, StandardCodingAgentExecutor},
     logs::{
         ActionType, FileChange, NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
@@ -42,6 +42,9 @@ pub struct CursorAgent {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[schemars(description = "auto, sonnet-4.5, sonnet-4.5-thinking, gpt-5, opus-4.1, grok")]
     pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(description = "Line diff algorithm used to render file edits (default: myers)")]
+    pub diff_algorithm: Option<DiffAlgorithm>,
     #[serde(flatten)]
     pub cmd: CmdOverrides,
 }
@@ -86,6 +89,7 @@ impl StandardCodingAgentExecutor for CursorAgent {
             .stderr(Stdio::piped())
             .current_dir(current_dir)
             .args(&args);
+        apply_env(&mut command, current_dir, &self.cmd);
 
         let mut child = command.group_spawn()?;
 
@@ -120,6 +124,7 @@ impl StandardCodingAgentExecutor for CursorAgent {
             .stderr(Stdio::piped())
             .current_dir(current_dir)
             .args(&args);
+        apply_env(&mut command, current_dir, &self.cmd);
 
         let mut child = command.group_spawn()?;
 
@@ -181,6 +186,7 @@ impl StandardCodingAgentExecutor for CursorAgent {
 
         // Process Cursor stdout JSONL with typed serde models
         let current_dir = worktree_path.to_path_buf();
+        let diff_algorithm = self.diff_algorithm.unwrap_or_default();
         tokio::spawn(async move {
             let mut lines = msg_store.stdout_lines_stream();
 
@@ -314,7 +320,7 @@ impl StandardCodingAgentExecutor for CursorAgent {
                         {
                             let tool_name = tool_call.get_name().to_string();
                             let (action_type, content) =
-                                tool_call.to_action_and_content(&worktree_str);
+                                tool_call.to_action_and_content(&worktree_str, diff_algorithm);
 
                             let entry = NormalizedEntry {
                                 timestamp: None,
@@ -341,7 +347,7 @@ impl StandardCodingAgentExecutor for CursorAgent {
                         {
                             // Compute base content and action again
                             let (mut new_action, content_str) =
-                                tool_call.to_action_and_content(&worktree_str);
+                                tool_call.to_action_and_content(&worktree_str, diff_algorithm);
                             if let CursorToolCall::Shell { args, result } = &tool_call {
                                 // Merge stdout/stderr and derive exit status when available using typed deserialization
                                 let (stdout_val, stderr_val, exit_code) = if let Some(res) = result
@@ -393,6 +399,7 @@ impl StandardCodingAgentExecutor for CursorAgent {
                                     .map(|code| crate::logs::CommandExitStatus::ExitCode { code });
                                 new_action = ActionType::CommandRun {
                                     command: args.command.clone(),
+                                    cwd: args.working_directory.clone(),
                                     result: Some(crate::logs::CommandRunResult {
                                         exit_status,
                                         output,
@@ -684,12 +691,19 @@ impl CursorToolCall {
         }
     }
 
-    pub fn to_action_and_content(&self, worktree_path: &str) -> (ActionType, String) {
+    pub fn to_action_and_content(
+        &self,
+        worktree_path: &str,
+        diff_algorithm: DiffAlgorithm,
+    ) -> (ActionType, String) {
         match self {
             CursorToolCall::Read { args, .. } => {
                 let path = make_path_relative(&args.path, worktree_path);
                 (
-                    ActionType::FileRead { path: path.clone() },
+                    ActionType::FileRead {
+                        path: path.clone(),
+                        result: None,
+                    },
                     format!("`{path}`"),
                 )
             }
@@ -709,33 +723,36 @@ impl CursorToolCall {
 
                 if let Some(apply_patch) = &args.apply_patch {
                     let hunks = extract_unified_diff_hunks(&apply_patch.patch_content);
-                    changes.push(FileChange::Edit {
-                        unified_diff: concatenate_diff_hunks(&path, &hunks),
-                        has_line_numbers: false,
-                    });
+                    changes.push(FileChange::edit(
+                        concatenate_diff_hunks(&path, &hunks),
+                        false,
+                    ));
                 }
 
                 if let Some(str_replace) = &args.str_replace {
-                    changes.push(FileChange::Edit {
-                        unified_diff: create_unified_diff(
+                    changes.push(FileChange::edit(
+                        create_unified_diff(
                             &path,
                             &str_replace.old_text,
                             &str_replace.new_text,
+                            diff_algorithm,
                         ),
-                        has_line_numbers: false,
-                    });
+                        false,
+                    ));
                 }
 
                 if let Some(multi_str_replace) = &args.multi_str_replace {
                     let hunks: Vec<String> = multi_str_replace
                         .edits
                         .iter()
-                        .map(|edit| create_unified_diff_hunk(&edit.old_text, &edit.new_text))
+                        .map(|edit| {
+                            create_unified_diff_hunk(&edit.old_text, &edit.new_text, diff_algorithm)
+                        })
                         .collect();
-                    changes.push(FileChange::Edit {
-                        unified_diff: concatenate_diff_hunks(&path, &hunks),
-                        has_line_numbers: false,
-                    });
+                    changes.push(FileChange::edit(
+                        concatenate_diff_hunks(&path, &hunks),
+                        false,
+                    ));
                 }
 
                 if changes.is_empty()
@@ -745,10 +762,10 @@ impl CursorToolCall {
                     })) = &result
                 {
                     let hunks = extract_unified_diff_hunks(diff_string);
-                    changes.push(FileChange::Edit {
-                        unified_diff: concatenate_diff_hunks(&path, &hunks),
-                        has_line_numbers: false,
-                    });
+                    changes.push(FileChange::edit(
+                        concatenate_diff_hunks(&path, &hunks),
+                        false,
+                    ));
                 }
 
                 (
@@ -774,6 +791,7 @@ impl CursorToolCall {
                 (
                     ActionType::CommandRun {
                         command: cmd.clone(),
+                        cwd: args.working_directory.clone(),
                         result: None,
                     },
                     format!("`{cmd}`"),
@@ -1186,6 +1204,7 @@ mod tests {
             append_prompt: AppendPrompt::default(),
             force: None,
             model: None,
+            diff_algorithm: None,
             cmd: Default::default(),
         };
         let msg_store = Arc::new(MsgStore::new());
@@ -13,9 +13,10 @@ use ts_rs::TS;
 use workspace_utils::msg_store::MsgStore;
 
 use crate::{
-    actions::ExecutorAction,
+    actions::{ExecutorAction, coding_agent_follow_up::ResumeOverrides},
     approvals::ExecutorApprovalService,
     command::CommandBuildError,
+    concurrency::ExecutorPermit,
     executors::{
         amp::Amp, claude::ClaudeCode, codex::Codex, copilot::Copilot, cursor::CursorAgent,
         gemini::Gemini, opencode::Opencode, qwen::QwenCode,
@@ -62,8 +63,11 @@ pub enum ExecutorError {
     ExecutorApprovalError(#[from] crate::approvals::ExecutorApprovalError),
     #[error(transparent)]
     CommandBuild(#[from] CommandBuildError),
-    #[error("Executable `{program}` not found in PATH")]
-    ExecutableNotFound { program: String },
+    #[error("Executable `{command}` not found in PATH. {install_hint}")]
+    MissingBinary {
+        command: String,
+        install_hint: String,
+    },
     #[error("Setup helper not supported")]
     SetupHelperNotSupported,
 }
@@ -77,7 +81,7 @@ pub enum ExecutorError {
 #[strum_discriminants(
     name(BaseCodingAgent),
     // Only add Hash; Eq/PartialEq are already provided by EnumDiscriminants.
-    derive(EnumString, Hash, strum_macros::Display, Serialize, Deserialize, TS, Type),
+    derive(EnumString, Hash, strum_macros::Display, Serialize, Deserialize, TS, JsonSchema, Type),
     strum(serialize_all = "SCREAMING_SNAKE_CASE"),
     ts(use_ts_enum),
     serde(rename_all = "SCREAMING_SNAKE_CASE"),
@@ -140,6 +144,15 @@ impl CodingAgent {
         self.default_mcp_config_path().is_some()
     }
 
+    /// Configured cap on tokens per model response, for executors that support one
+    pub fn max_output_tokens(&self) -> Option<u32> {
+        match self {
+            Self::ClaudeCode(claude) => claude.max_output_tokens,
+            Self::Codex(codex) => codex.max_output_tokens,
+            _ => None,
+        }
+    }
+
     pub fn capabilities(&self) -> Vec<BaseAgentCapability> {
         match self {
             Self::ClaudeCode(_) => vec![BaseAgentCapability::SessionFork],
@@ -158,6 +171,9 @@ impl CodingAgent {
 pub trait StandardCodingAgentExecutor {
     fn use_approvals(&mut self, _approvals: Arc<dyn ExecutorApprovalService>) {}
 
+    /// Applies settings a follow-up changes for the resumed conversation.
+    fn apply_resume_overrides(&mut self, _overrides: &ResumeOverrides) {}
+
     async fn spawn(&self, current_dir: &Path, prompt: &str) -> Result<SpawnedChild, ExecutorError>;
     async fn spawn_follow_up(
         &self,
@@ -167,6 +183,12 @@ pub trait StandardCodingAgentExecutor {
     ) -> Result<SpawnedChild, ExecutorError>;
     fn normalize_logs(&self, _raw_logs_event_store: Arc<MsgStore>, _worktree_path: &Path);
 
+    /// Session id the executor kept for `current_dir` outside the logs, for follow-ups whose
+    /// previous run never got its session line stored (e.g. the server restarted mid-run).
+    async fn recorded_session_id(&self, _current_dir: &Path) -> Option<String> {
+        None
+    }
+
     // MCP configuration methods
     fn default_mcp_config_path(&self) -> Option<std::path::PathBuf>;
 
@@ -186,10 +208,19 @@ pub trait StandardCodingAgentExecutor {
 /// and mark it as successful (exit code 0).
 pub type ExecutorExitSignal = tokio::sync::oneshot::Receiver<()>;
 
+/// Processes an executor starts mid-run to replace its child, e.g. a restarted app server.
+/// While the executor holds the sender, the original child exiting does not end the execution:
+/// each replacement takes the child's place in the container and the exit signal ends the run.
+/// Once the sender is dropped without an exit signal, the current child exiting ends it instead.
+pub type ReplacementChildren = tokio::sync::mpsc::UnboundedReceiver<AsyncGroupChild>;
+
 #[derive(Debug)]
 pub struct SpawnedChild {
     pub child: AsyncGroupChild,
     pub exit_signal: Option<ExecutorExitSignal>,
+    /// Slot in the concurrent executor limit; hold it until the child exits.
+    pub permit: Option<ExecutorPermit>,
+    pub replacements: Option<ReplacementChildren>,
 }
 
 impl From<AsyncGroupChild> for SpawnedChild {
@@ -197,29 +228,102 @@ impl From<AsyncGroupChild> for SpawnedChild {
         Self {
             child,
             exit_signal: None,
+            permit: None,
+            replacements: None,
         }
     }
 }
 
+/// Verbosity of an agent CLI's own logging.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum ExecutorLogLevel {
+    /// Errors only
+    Error,
+    /// Errors and warnings
+    Warning,
+    /// General progress messages
+    Info,
+    /// Everything, including protocol traffic; logs can get very large
+    Debug,
+}
+
+impl ExecutorLogLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+            Self::Info => "info",
+            Self::Debug => "debug",
+        }
+    }
+}
+
+/// Where the appended text goes relative to the task prompt.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum AppendPromptMode {
+    /// Standing instructions come before the task prompt
+    Prefix,
+    #[default]
+    Suffix,
+}
+
+/// Extra text combined with every prompt sent to the agent.
+///
+/// A plain string (or null) appends the text directly after the prompt. The object form picks
+/// the position and the separator placed between the two.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
-#[serde(transparent)]
+#[serde(untagged)]
 #[schemars(
     title = "Append Prompt",
-    description = "Extra text appended to the prompt",
-    extend("format" = "textarea")
+    description = "Extra text appended to the prompt"
 )]
-#[derive(Default)]
-pub struct AppendPrompt(pub Option<String>);
+pub enum AppendPrompt {
+    #[schemars(extend("format" = "textarea"))]
+    Text(Option<String>),
+    Configured {
+        #[schemars(extend("format" = "textarea"))]
+        text: Option<String>,
+        #[serde(default)]
+        mode: AppendPromptMode,
+        /// Placed between the prompt and the text; nothing by default
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        separator: Option<String>,
+    },
+}
+
+impl Default for AppendPrompt {
+    fn default() -> Self {
+        AppendPrompt::Text(None)
+    }
+}
 
 impl AppendPrompt {
     pub fn get(&self) -> Option<String> {
-        self.0.clone()
+        match self {
+            AppendPrompt::Text(text) | AppendPrompt::Configured { text, .. } => text.clone(),
+        }
     }
 
     pub fn combine_prompt(&self, prompt: &str) -> String {
-        match self {
-            AppendPrompt(Some(value)) => format!("{prompt}{value}"),
-            AppendPrompt(None) => prompt.to_string(),
+        let (text, mode, separator) = match self {
+            AppendPrompt::Text(text) => (text, AppendPromptMode::Suffix, None),
+            AppendPrompt::Configured {
+                text,
+                mode,
+                separator,
+            } => (text, *mode, separator.as_deref()),
+        };
+        let Some(text) = text.as_deref().filter(|text| !text.is_empty()) else {
+            return prompt.to_string();
+        };
+        let separator = separator.unwrap_or_default();
+        match mode {
+            AppendPromptMode::Prefix => format!("{text}{separator}{prompt}"),
+            AppendPromptMode::Suffix => format!("{prompt}{separator}{text}"),
         }
     }
 }
@@ -255,4 +359,57 @@ mod tests {
         assert!(result.is_ok(), "CURSOR should deserialize via serde");
         assert_eq!(result.unwrap(), BaseCodingAgent::CursorAgent);
     }
+
+    #[test]
+    fn test_append_prompt_suffix_keeps_legacy_behavior() {
+        let legacy: AppendPrompt = serde_json::from_value(serde_json::json!(" Be brief.")).unwrap();
+        assert_eq!(
+            legacy.combine_prompt("Fix the bug."),
+            "Fix the bug. Be brief."
+        );
+        assert_eq!(
+            serde_json::to_value(&legacy).unwrap(),
+            serde_json::json!(" Be brief.")
+        );
+
+        let suffix: AppendPrompt = serde_json::from_value(serde_json::json!({
+            "text": "Be brief.",
+            "separator": "\n\n",
+        }))
+        .unwrap();
+        assert_eq!(
+            suffix.combine_prompt("Fix the bug."),
+            "Fix the bug.\n\nBe brief."
+        );
+
+        for empty in [serde_json::json!(null), serde_json::json!({ "text": "" })] {
+            let empty: AppendPrompt = serde_json::from_value(empty).unwrap();
+            assert_eq!(empty.combine_prompt("Fix the bug."), "Fix the bug.");
+        }
+        assert_eq!(
+            AppendPrompt::default().combine_prompt("Fix the bug."),
+            "Fix the bug."
+        );
+    }
+
+    #[test]
+    fn test_append_prompt_prefix_puts_text_first() {
+        let prefix: AppendPrompt = serde_json::from_value(serde_json::json!({
+            "text": "Follow CONTRIBUTING.md.",
+            "mode": "prefix",
+            "separator": "\n\n",
+        }))
+        .unwrap();
+        assert_eq!(
+            prefix.combine_prompt("Fix the bug."),
+            "Follow CONTRIBUTING.md.\n\nFix the bug."
+        );
+
+        let empty = AppendPrompt::Configured {
+            text: None,
+            mode: AppendPromptMode::Prefix,
+            separator: Some("\n\n".to_string()),
+        };
+        assert_eq!(empty.combine_prompt("Fix the bug."), "Fix the bug.");
+    }
 }
@@ -19,7 +19,7 @@ use ts_rs::TS;
 use workspace_utils::{msg_store::MsgStore, path::make_path_relative};
 
 use crate::{
-    command::{CmdOverrides, CommandBuilder, apply_overrides},
+    command::{CmdOverrides, CommandBuilder, apply_env, apply_overrides},
     executors::{
         AppendPrompt, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
         opencode::share_bridge::Bridge as ShareBridge,
@@ -147,6 +147,7 @@ impl StandardCodingAgentExecutor for Opencode {
             .env("NODE_NO_WARNINGS", "1")
             .env("OPENCODE_AUTO_SHARE", "1")
             .env("OPENCODE_API", bridge.base_url.clone());
+        apply_env(&mut command, current_dir, &self.cmd);
 
         let mut child = match command.group_spawn() {
             Ok(c) => c,
@@ -213,6 +214,7 @@ impl StandardCodingAgentExecutor for Opencode {
             .env("NODE_NO_WARNINGS", "1")
             .env("OPENCODE_AUTO_SHARE", "1")
             .env("OPENCODE_API", bridge.base_url.clone());
+        apply_env(&mut command, current_dir, &self.cmd);
 
         let mut child = match command.group_spawn() {
             Ok(c) => c,
@@ -861,6 +863,7 @@ impl Opencode {
                 let path = input.file_path.as_deref().unwrap_or("");
                 Some(ActionType::FileRead {
                     path: make_path_relative(path, &worktree_path.to_string_lossy()),
+                    result: None,
                 })
             }
             ActionTool::Write { input } => {
@@ -883,16 +886,14 @@ impl Opencode {
                 }
                 Some(ActionType::FileEdit {
                     path: make_path_relative(path, &worktree_path.to_string_lossy()),
-                    changes: vec![FileChange::Edit {
-                        unified_diff: diff.to_string(),
-                        has_line_numbers: false,
-                    }],
+                    changes: vec![FileChange::edit(diff.to_string(), false)],
                 })
             }
             ActionTool::Bash { input } => {
                 let command = input.command.unwrap_or_default();
                 Some(ActionType::CommandRun {
                     command,
+                    cwd: None,
                     result: None,
                 })
             }
@@ -906,7 +907,7 @@ impl Opencode {
             }
             ActionTool::Webfetch { input } => {
                 let url = input.url.unwrap_or_default();
-                Some(ActionType::WebFetch { url })
+                Some(ActionType::WebFetch { url, result: None })
             }
             ActionTool::Todowrite { input } => {
                 let todos = input
@@ -1,8 +1,10 @@
 pub mod actions;
 pub mod approvals;
 pub mod command;
+pub mod concurrency;
 pub mod executors;
 pub mod logs;
 pub mod mcp_config;
+pub mod process_registry;
 pub mod profile;
 pub mod stdout_dup;
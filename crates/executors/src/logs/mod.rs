@@ -1,10 +1,13 @@
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
-use workspace_utils::approvals::ApprovalStatus;
+use workspace_utils::{approvals::ApprovalStatus, diff::DiffStats};
 
 pub mod plain_text_processor;
+pub mod query;
 pub mod stderr_processor;
+pub mod summary;
 pub mod utils;
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -29,6 +32,8 @@ pub struct ToolResult {
 pub enum CommandExitStatus {
     ExitCode { code: i32 },
     Success { success: bool },
+    /// The command was terminated by a signal, e.g. `9` for SIGKILL.
+    Signal { signal: i32 },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -54,6 +59,14 @@ pub enum NormalizedEntryError {
     Other,
 }
 
+/// Severity of an out-of-band notification entry
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationLevel {
+    Info,
+    Warn,
+}
+
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -72,6 +85,10 @@ pub enum NormalizedEntryType {
     ErrorMessage {
         error_type: NormalizedEntryError,
     },
+    /// Non-conversational notice (rate limits, background events) the UI can style separately
+    Notification {
+        level: NotificationLevel,
+    },
     Thinking,
     Loading,
     NextAction {
@@ -91,6 +108,19 @@ pub struct NormalizedEntry {
 }
 
 impl NormalizedEntry {
+    /// System message noting that an output line over `limit` bytes was skipped.
+    pub fn oversized_line(limit: usize) -> Self {
+        Self {
+            timestamp: None,
+            entry_type: NormalizedEntryType::SystemMessage,
+            content: format!(
+                "Skipped an output line longer than {limit} bytes (set {} to raise the limit)",
+                workspace_utils::stream_lines::MAX_LINE_BYTES_ENV
+            ),
+            metadata: None,
+        }
+    }
+
     pub fn with_tool_status(&self, status: ToolStatus) -> Option<Self> {
         if let NormalizedEntryType::ToolUse {
             tool_name,
@@ -129,6 +159,10 @@ pub enum ToolStatus {
         timeout_at: DateTime<Utc>,
     },
     TimedOut,
+    /// The agent asked for human approval before running the tool.
+    AwaitingApproval,
+    /// The session ended before the tool reported a result, so its outcome is unknown.
+    Unknown,
 }
 
 impl ToolStatus {
@@ -160,6 +194,9 @@ pub struct TodoItem {
 pub enum ActionType {
     FileRead {
         path: String,
+        /// Content that was read, once the agent reports it
+        #[serde(default)]
+        result: Option<ToolResult>,
     },
     FileEdit {
         path: String,
@@ -167,6 +204,9 @@ pub enum ActionType {
     },
     CommandRun {
         command: String,
+        /// Working directory the command ran in, when the agent reports it
+        #[serde(default)]
+        cwd: Option<String>,
         #[serde(default)]
         result: Option<CommandRunResult>,
     },
@@ -175,6 +215,9 @@ pub enum ActionType {
     },
     WebFetch {
         url: String,
+        /// Fetched content, once the agent reports it
+        #[serde(default)]
+        result: Option<ToolResult>,
     },
     /// Generic tool with optional arguments and result for rich rendering
     Tool {
@@ -199,6 +242,41 @@ pub enum ActionType {
     },
 }
 
+impl ActionType {
+    pub fn kind(&self) -> ActionKind {
+        match self {
+            ActionType::FileRead { .. } => ActionKind::FileRead,
+            ActionType::FileEdit { .. } => ActionKind::FileEdit,
+            ActionType::CommandRun { .. } => ActionKind::CommandRun,
+            ActionType::Search { .. } => ActionKind::Search,
+            ActionType::WebFetch { .. } => ActionKind::WebFetch,
+            ActionType::Tool { .. } => ActionKind::Tool,
+            ActionType::TaskCreate { .. } => ActionKind::TaskCreate,
+            ActionType::PlanPresentation { .. } => ActionKind::PlanPresentation,
+            ActionType::TodoManagement { .. } => ActionKind::TodoManagement,
+            ActionType::Other { .. } => ActionKind::Other,
+        }
+    }
+}
+
+/// The variant of an [`ActionType`] without its data, e.g. to choose which tool uses to show
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, JsonSchema)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum ActionKind {
+    FileRead,
+    FileEdit,
+    CommandRun,
+    Search,
+    WebFetch,
+    Tool,
+    TaskCreate,
+    PlanPresentation,
+    TodoManagement,
+    Other,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(tag = "action", rename_all = "snake_case")]
 pub enum FileChange {
@@ -214,5 +292,47 @@ pub enum FileChange {
         unified_diff: String,
         /// Whether line number in the hunks are reliable.
         has_line_numbers: bool,
+        /// Added/removed line counts of `unified_diff`; zero for entries stored before they
+        /// were recorded.
+        #[serde(default)]
+        stats: DiffStats,
     },
 }
+
+impl FileChange {
+    /// Builds an [`FileChange::Edit`], counting the diff's added and removed lines.
+    pub fn edit(unified_diff: String, has_line_numbers: bool) -> Self {
+        let stats = DiffStats::from_unified_diff(&unified_diff);
+        Self::Edit {
+            unified_diff,
+            has_line_numbers,
+            stats,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_stored_without_stats_still_deserializes() {
+        let change: FileChange = serde_json::from_value(serde_json::json!({
+            "action": "edit",
+            "unified_diff": "@@ -1 +1 @@\n-a\n+b\n",
+            "has_line_numbers": true,
+        }))
+        .unwrap();
+
+        let FileChange::Edit {
+            unified_diff,
+            stats,
+            ..
+        } = change
+        else {
+            panic!("expected an edit");
+        };
+        assert_eq!(unified_diff, "@@ -1 +1 @@\n-a\n+b\n");
+        assert_eq!(stats, DiffStats::default());
+    }
+}
@@ -18,6 +18,7 @@ use std::{
 };
 
 use bon::bon;
+use chrono::{DateTime, Utc};
 use json_patch::Patch;
 
 use super::{
@@ -149,6 +150,43 @@ impl PlainTextBuffer {
         None
     }
 
+    /// Resolve carriage returns and erase/clear sequences in complete lines, the way a terminal
+    /// would render them. Only the text after the last `\r` or erase-line sequence of a line is
+    /// kept, so they only rewrite the line they appear in; a line that starts with a cursor-up
+    /// redraws the previous line, and a clear-screen sequence drops everything buffered before it.
+    /// The partial line is left untouched until it completes.
+    pub fn apply_in_place_updates(&mut self) {
+        let partial = self.lines.pop_if(|line| !line.ends_with('\n')).into_iter();
+        let mut rendered: Vec<String> = Vec::with_capacity(self.lines.len());
+
+        for line in self.lines.drain(..) {
+            let body = line.strip_suffix('\n').unwrap_or(&line);
+            let body = body.strip_suffix('\r').unwrap_or(body);
+            let body = match rfind_any(body, CLEAR_SCREEN_SEQUENCES) {
+                Some((_, end)) => {
+                    rendered.clear();
+                    &body[end..]
+                }
+                None => body,
+            };
+            // After a `\n` the cursor is already on a fresh line, so a leading `\r` or erase-line
+            // has nothing to rewrite; only moving up reaches the completed line above.
+            let body = match strip_cursor_up(body) {
+                Some(rest) => {
+                    rendered.pop();
+                    rest
+                }
+                None => body,
+            };
+            let visible = rfind_any(body, REDRAW_SEQUENCES).map_or(body, |(_, end)| &body[end..]);
+            rendered.push(format!("{visible}\n"));
+        }
+
+        rendered.extend(partial);
+        self.lines = rendered;
+        self.recompute_len();
+    }
+
     /// Check if the buffer is empty.
     pub fn is_empty(&self) -> bool {
         debug_assert!(self.lines.len() == 0 || self.total_len > 0);
@@ -156,6 +194,30 @@ impl PlainTextBuffer {
     }
 }
 
+/// Sequences that return the cursor to the start of the line: carriage return and erase-line.
+const REDRAW_SEQUENCES: &[&str] = &["\r", "\x1b[2K", "\x1b[1K"];
+/// Sequences that clear the whole screen.
+const CLEAR_SCREEN_SEQUENCES: &[&str] = &["\x1b[2J", "\x1b[3J", "\x1bc"];
+
+/// Byte range of the last occurrence of any of `needles` in `haystack`.
+fn rfind_any(haystack: &str, needles: &[&str]) -> Option<(usize, usize)> {
+    needles
+        .iter()
+        .filter_map(|needle| {
+            haystack
+                .rfind(needle)
+                .map(|start| (start, start + needle.len()))
+        })
+        .max_by_key(|&(start, _)| start)
+}
+
+/// Strip a leading cursor-up sequence (`ESC [ n A`).
+fn strip_cursor_up(line: &str) -> Option<&str> {
+    let rest = line.strip_prefix("\x1b[")?;
+    let rest = rest.trim_start_matches(|c: char| c.is_ascii_digit());
+    rest.strip_prefix('A')
+}
+
 impl Default for PlainTextBuffer {
     fn default() -> Self {
         Self::new()
@@ -185,11 +247,25 @@ pub struct PlainTextLogProcessor {
     transform_lines: Option<LinesTransformFn>,
     message_boundary_predicate: Option<MessageBoundaryPredicateFn>,
     normalized_entry_producer: NormalizedEntryProducerFn,
+    in_place_updates: bool,
     last_chunk_arrival_time: Instant, // time since last chunk arrived
     current_entry_index: Option<usize>,
+    chunk_received_at: Option<DateTime<Utc>>,
+    entry_received_at: Option<DateTime<Utc>>,
 }
 
 impl PlainTextLogProcessor {
+    /// Like [`Self::process`], but entries without a timestamp of their own are stamped with
+    /// `received_at` of the chunk that started them.
+    pub fn process_received_at(
+        &mut self,
+        text_chunk: String,
+        received_at: DateTime<Utc>,
+    ) -> Vec<Patch> {
+        self.chunk_received_at = Some(received_at);
+        self.process(text_chunk)
+    }
+
     /// Process incoming text and return JSON patches for any complete entries
     pub fn process(&mut self, text_chunk: String) -> Vec<Patch> {
         if text_chunk.is_empty() {
@@ -226,6 +302,10 @@ impl PlainTextLogProcessor {
         // Let the buffer handle text buffering
         self.buffer.ingest(formatted_chunk);
 
+        if self.in_place_updates {
+            self.buffer.apply_in_place_updates();
+        }
+
         if let Some(transform_lines) = self.transform_lines.as_mut() {
             transform_lines(self.buffer.lines_mut());
             self.buffer.recompute_len();
@@ -292,7 +372,7 @@ impl PlainTextLogProcessor {
     /// Create patch
     fn create_patch(&mut self, lines: Vec<String>) -> Patch {
         let content = lines.concat();
-        let entry = (self.normalized_entry_producer)(content);
+        let mut entry = (self.normalized_entry_producer)(content);
 
         let added = self.current_entry_index.is_some();
         let index = if let Some(idx) = self.current_entry_index {
@@ -301,9 +381,14 @@ impl PlainTextLogProcessor {
             // If no current index, get next from provider
             let idx = self.index_provider.next();
             self.current_entry_index = Some(idx);
+            self.entry_received_at = self.chunk_received_at;
             idx
         };
 
+        if entry.timestamp.is_none() {
+            entry.timestamp = self.entry_received_at.map(|at| at.to_rfc3339());
+        }
+
         if !added {
             ConversationPatch::add_normalized_entry(index, entry)
         } else {
@@ -322,6 +407,7 @@ impl PlainTextLogProcessor {
     /// * `time_gap` - Optional time gap between individual entries. When new content arrives after this duration, it is considered a new entry.
     /// * `format_chunk` - Optional function to fix raw output before creating normalized entries.
     /// * `message_boundary_predicate` - Optional function to determine custom message boundaries. Useful when content is heterogeneous (e.g., tool calls interleaved with assistant messages).
+    /// * `in_place_updates` - Optional flag to render carriage returns and erase/clear-screen sequences as updates of the current entry instead of new lines. Useful for spinners and progress bars.
     /// * `index_provider` - Required sharable atomic counter for tracking entry indices.
     ///
     /// When both `size_threshold` and `time_gap` are `None`, a default size threshold of 8 KiB is used.
@@ -333,6 +419,7 @@ impl PlainTextLogProcessor {
         format_chunk: Option<FormatChunkFn>,
         transform_lines: Option<LinesTransformFn>,
         message_boundary_predicate: Option<MessageBoundaryPredicateFn>,
+        in_place_updates: Option<bool>,
         index_provider: EntryIndexProvider,
     ) -> Self {
         Self {
@@ -353,8 +440,11 @@ impl PlainTextLogProcessor {
                 Box::new(p) as Box<dyn Fn(&[String]) -> Option<MessageBoundary> + Send + 'static>
             }),
             normalized_entry_producer: Box::new(normalized_entry_producer),
+            in_place_updates: in_place_updates.unwrap_or(false),
             last_chunk_arrival_time: Instant::now(),
             current_entry_index: None,
+            chunk_received_at: None,
+            entry_received_at: None,
         }
     }
 }
@@ -488,4 +578,91 @@ mod tests {
         let patches = processor.process("real content\n".to_string());
         assert_eq!(patches.len(), 1);
     }
+    #[test]
+    fn test_processor_in_place_updates_replace_progress_lines() {
+        let producer = |content: String| -> NormalizedEntry {
+            NormalizedEntry {
+                timestamp: None,
+                entry_type: NormalizedEntryType::AssistantMessage,
+                content,
+                metadata: None,
+            }
+        };
+
+        let mut processor = PlainTextLogProcessor::builder()
+            .normalized_entry_producer(producer)
+            .in_place_updates(true)
+            .index_provider(EntryIndexProvider::test_new())
+            .build();
+
+        let chunks = [
+            "Working 1/3\n",
+            "\x1b[1A\rWorking 2/3\n",
+            "\x1b[1A\x1b[2KWorking 3/3\n",
+            "10%\r50%\r100%\n",
+            "Done",
+        ];
+        let patches: Vec<_> = chunks
+            .into_iter()
+            .flat_map(|chunk| processor.process(chunk.to_string()))
+            .map(|patch| serde_json::to_value(patch).unwrap())
+            .collect();
+
+        assert_eq!(patches.len(), chunks.len());
+        assert_eq!(patches[0][0]["op"], "add");
+        assert!(patches[1..].iter().all(|patch| patch[0]["op"] == "replace"));
+        assert_eq!(
+            patches[2][0]["value"]["content"]["content"],
+            "Working 3/3\n"
+        );
+        assert_eq!(
+            patches.last().unwrap()[0]["value"]["content"]["content"],
+            "Working 3/3\n100%\nDone"
+        );
+
+        let mut buffer = PlainTextBuffer::new();
+        buffer.ingest("old screen\n\x1b[H\x1b[2Jnew screen\n".to_string());
+        buffer.apply_in_place_updates();
+        assert_eq!(buffer.lines(), ["new screen\n"]);
+    }
+
+    #[test]
+    fn test_in_place_updates_keep_completed_lines() {
+        let mut buffer = PlainTextBuffer::new();
+        buffer.ingest("a\n\rb".to_string());
+        buffer.apply_in_place_updates();
+        assert_eq!(buffer.lines(), ["a\n", "\rb"]);
+
+        buffer.ingest("\n\x1b[2Kc\n".to_string());
+        buffer.apply_in_place_updates();
+        assert_eq!(buffer.lines(), ["a\n", "b\n", "c\n"]);
+    }
+
+    #[test]
+    fn test_processor_stamps_entries_with_first_chunk_time() {
+        let producer = |content: String| -> NormalizedEntry {
+            NormalizedEntry {
+                timestamp: None,
+                entry_type: NormalizedEntryType::SystemMessage,
+                content,
+                metadata: None,
+            }
+        };
+
+        let mut processor = PlainTextLogProcessor::builder()
+            .normalized_entry_producer(producer)
+            .index_provider(EntryIndexProvider::test_new())
+            .build();
+
+        let first = "2025-01-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let second = "2025-01-01T00:00:05Z".parse::<DateTime<Utc>>().unwrap();
+        processor.process_received_at("partial ".to_string(), first);
+        let patches = processor.process_received_at("line\n".to_string(), second);
+
+        let patch = serde_json::to_value(patches.last().unwrap()).unwrap();
+        assert_eq!(
+            patch[0]["value"]["content"]["timestamp"],
+            first.to_rfc3339()
+        );
+    }
 }
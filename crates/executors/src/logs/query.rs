@@ -0,0 +1,186 @@
+//! Lookups over a normalized conversation, e.g. the agent's final answer or the files it edited.
+//!
+//! These are pure functions over [`NormalizedEntry`] slices, so callers can run them on a live
+//! conversation snapshot or on entries rebuilt from stored logs alike.
+
+use workspace_utils::diff::DiffStats;
+
+use super::{ActionType, FileChange, NormalizedEntry, NormalizedEntryType, ToolStatus};
+
+/// The most recent assistant message, typically the agent's final answer.
+pub fn last_assistant_message(entries: &[NormalizedEntry]) -> Option<&NormalizedEntry> {
+    entries
+        .iter()
+        .rev()
+        .find(|entry| matches!(entry.entry_type, NormalizedEntryType::AssistantMessage))
+}
+
+/// Every file the agent edited, in the order first touched, with the line counts of all its
+/// edits to that file combined. Edits that were denied, timed out or failed are left out since
+/// they never reached the file.
+pub fn file_edits(entries: &[NormalizedEntry]) -> Vec<(String, DiffStats)> {
+    let mut edits: Vec<(String, DiffStats)> = Vec::new();
+    for entry in entries {
+        let NormalizedEntryType::ToolUse {
+            action_type: ActionType::FileEdit { path, changes },
+            status,
+            ..
+        } = &entry.entry_type
+        else {
+            continue;
+        };
+        if matches!(
+            status,
+            ToolStatus::Denied { .. } | ToolStatus::TimedOut | ToolStatus::Failed
+        ) {
+            continue;
+        }
+
+        let stats = change_stats(changes);
+        match edits.iter_mut().find(|(edited, _)| edited == path) {
+            Some((_, total)) => {
+                total.added += stats.added;
+                total.removed += stats.removed;
+            }
+            None => edits.push((path.clone(), stats)),
+        }
+    }
+    edits
+}
+
+/// Error entries, oldest first.
+pub fn errors(entries: &[NormalizedEntry]) -> Vec<&NormalizedEntry> {
+    entries
+        .iter()
+        .filter(|entry| matches!(entry.entry_type, NormalizedEntryType::ErrorMessage { .. }))
+        .collect()
+}
+
+/// Combined line counts of the changes a single edit made to one file. Writes count every line
+/// of the new content as added.
+pub fn change_stats(changes: &[FileChange]) -> DiffStats {
+    let mut stats = DiffStats {
+        files_changed: 1,
+        ..DiffStats::default()
+    };
+    for change in changes {
+        match change {
+            FileChange::Write { content } => stats.added += content.lines().count(),
+            FileChange::Edit {
+                stats: edit_stats, ..
+            } => {
+                stats.added += edit_stats.added;
+                stats.removed += edit_stats.removed;
+            }
+            FileChange::Delete | FileChange::Rename { .. } => {}
+        }
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logs::NormalizedEntryError;
+
+    fn entry(entry_type: NormalizedEntryType, content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    fn edit(path: &str, changes: Vec<FileChange>, status: ToolStatus) -> NormalizedEntry {
+        entry(
+            NormalizedEntryType::ToolUse {
+                tool_name: "Edit".to_string(),
+                action_type: ActionType::FileEdit {
+                    path: path.to_string(),
+                    changes,
+                },
+                status,
+            },
+            path,
+        )
+    }
+
+    #[test]
+    fn test_last_assistant_message_skips_later_entries() {
+        let entries = vec![
+            entry(NormalizedEntryType::AssistantMessage, "first"),
+            entry(NormalizedEntryType::AssistantMessage, "second"),
+            entry(NormalizedEntryType::SystemMessage, "done"),
+        ];
+        assert_eq!(
+            last_assistant_message(&entries).map(|entry| entry.content.as_str()),
+            Some("second")
+        );
+        assert!(last_assistant_message(&entries[2..]).is_none());
+    }
+
+    #[test]
+    fn test_file_edits_are_combined_per_path() {
+        let entries = vec![
+            edit(
+                "src/lib.rs",
+                vec![FileChange::edit("@@\n-a\n+b\n+c\n".to_string(), false)],
+                ToolStatus::Success,
+            ),
+            edit(
+                "README.md",
+                vec![FileChange::Write {
+                    content: "one\ntwo\n".to_string(),
+                }],
+                ToolStatus::Success,
+            ),
+            edit(
+                "src/lib.rs",
+                vec![FileChange::edit("@@\n-c\n".to_string(), false)],
+                ToolStatus::Created,
+            ),
+            edit(
+                "src/denied.rs",
+                vec![FileChange::edit("@@\n+x\n".to_string(), false)],
+                ToolStatus::Denied { reason: None },
+            ),
+        ];
+
+        let edits: Vec<_> = file_edits(&entries)
+            .into_iter()
+            .map(|(path, stats)| (path, stats.added, stats.removed, stats.files_changed))
+            .collect();
+        assert_eq!(
+            edits,
+            vec![
+                ("src/lib.rs".to_string(), 2, 2, 1),
+                ("README.md".to_string(), 2, 0, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_errors_keeps_only_error_entries() {
+        let entries = vec![
+            entry(NormalizedEntryType::AssistantMessage, "working"),
+            entry(
+                NormalizedEntryType::ErrorMessage {
+                    error_type: NormalizedEntryError::Other,
+                },
+                "boom",
+            ),
+            entry(
+                NormalizedEntryType::ErrorMessage {
+                    error_type: NormalizedEntryError::SetupRequired,
+                },
+                "login",
+            ),
+        ];
+        let contents: Vec<_> = errors(&entries)
+            .into_iter()
+            .map(|entry| entry.content.as_str())
+            .collect();
+        assert_eq!(contents, vec!["boom", "login"]);
+    }
+}
@@ -10,7 +10,7 @@
 //!
 use std::{sync::Arc, time::Duration};
 
-use futures::StreamExt;
+use futures::{StreamExt, TryStreamExt};
 use workspace_utils::msg_store::MsgStore;
 
 use super::{
@@ -36,9 +36,22 @@ use crate::logs::utils::EntryIndexProvider;
 /// # Arguments
 /// * `msg_store` - the message store providing a stream of stderr chunks and accepting patches.
 /// * `entry_index_provider` - provider of incremental entry indices for patch ordering.
+///
+/// When the store has timestamps enabled, each entry is stamped with the time its first chunk
+/// reached the store, so it can be placed correctly among stdout entries.
 pub fn normalize_stderr_logs(msg_store: Arc<MsgStore>, entry_index_provider: EntryIndexProvider) {
     tokio::spawn(async move {
-        let mut stderr = msg_store.stderr_chunked_stream();
+        let mut stderr = if msg_store.timestamps_enabled() {
+            msg_store
+                .stderr_timestamped_chunked_stream()
+                .map_ok(|(received_at, chunk)| (Some(received_at), chunk))
+                .boxed()
+        } else {
+            msg_store
+                .stderr_chunked_stream()
+                .map_ok(|chunk| (None, chunk))
+                .boxed()
+        };
 
         // Create a processor with time-based emission for stderr
         let mut processor = PlainTextLogProcessor::builder()
@@ -51,11 +64,18 @@ pub fn normalize_stderr_logs(msg_store: Arc<MsgStore>, entry_index_provider: Ent
                 metadata: None,
             }))
             .time_gap(Duration::from_secs(2)) // Break messages if they are 2 seconds apart
-            .index_provider(entry_index_provider)
+            .index_provider(entry_index_provider.clone())
             .build();
 
-        while let Some(Ok(chunk)) = stderr.next().await {
-            for patch in processor.process(chunk) {
+        while let Some(Ok((received_at, chunk))) = stderr.next().await {
+            let patches = match received_at {
+                Some(received_at) => processor.process_received_at(chunk, received_at),
+                None => processor.process(chunk),
+            };
+            for patch in patches
+                .into_iter()
+                .filter_map(|patch| entry_index_provider.skip_persisted(patch))
+            {
                 msg_store.push_patch(patch);
             }
         }
@@ -0,0 +1,191 @@
+//! One-line digests of normalized entries, e.g. `🔧 Edit src/main.rs (+12/-3)`.
+//!
+//! Summaries are derived only from the entry's own fields, so the same entry always produces
+//! the same line.
+
+use super::{
+    ActionType, CommandExitStatus, FileChange, NormalizedEntry, NormalizedEntryType, ToolStatus,
+    query::change_stats,
+};
+
+/// Characters of free text kept in a summary line
+const SUMMARY_TEXT_CHARS: usize = 80;
+
+impl NormalizedEntry {
+    /// Single-line digest of the entry, prefixed with an icon for its type.
+    pub fn summary(&self) -> String {
+        let text = || one_line(&self.content);
+        match &self.entry_type {
+            NormalizedEntryType::UserMessage => format!("👤 user: {}", text()),
+            NormalizedEntryType::UserFeedback { denied_tool } => {
+                format!("🚫 feedback on {denied_tool}: {}", text())
+            }
+            NormalizedEntryType::AssistantMessage => format!("💬 assistant: {}", text()),
+            NormalizedEntryType::ToolUse {
+                action_type,
+                status,
+                ..
+            } => format!(
+                "🔧 {}{}",
+                action_summary(action_type),
+                status_suffix(status)
+            ),
+            NormalizedEntryType::SystemMessage => format!("⚙️ system: {}", text()),
+            NormalizedEntryType::ErrorMessage { .. } => format!("❌ error: {}", text()),
+            NormalizedEntryType::Notification { .. } => format!("🔔 {}", text()),
+            NormalizedEntryType::Thinking => format!("🤔 thinking: {}", text()),
+            NormalizedEntryType::Loading => "⏳ loading".to_string(),
+            NormalizedEntryType::NextAction { failed, .. } => {
+                if *failed {
+                    "⏭ next action (after failure)".to_string()
+                } else {
+                    "⏭ next action".to_string()
+                }
+            }
+        }
+    }
+}
+
+fn action_summary(action_type: &ActionType) -> String {
+    match action_type {
+        ActionType::FileRead { path, .. } => format!("Read {path}"),
+        ActionType::FileEdit { path, changes } => file_edit_summary(path, changes),
+        ActionType::CommandRun {
+            command, result, ..
+        } => {
+            let exit = match result.as_ref().and_then(|r| r.exit_status.as_ref()) {
+                Some(CommandExitStatus::ExitCode { code }) => format!(" (exit {code})"),
+                Some(CommandExitStatus::Success { success: false }) => " (failed)".to_string(),
+                Some(CommandExitStatus::Signal { signal }) => format!(" (signal {signal})"),
+                Some(CommandExitStatus::Success { success: true }) | None => String::new(),
+            };
+            format!("Run `{}`{exit}", one_line(command))
+        }
+        ActionType::Search { query } => format!("Search {}", one_line(query)),
+        ActionType::WebFetch { url, .. } => format!("Fetch {url}"),
+        ActionType::Tool { tool_name, .. } => tool_name.clone(),
+        ActionType::TaskCreate { description } => format!("Task: {}", one_line(description)),
+        ActionType::PlanPresentation { plan } => format!("Plan: {}", one_line(plan)),
+        ActionType::TodoManagement { todos, operation } => {
+            format!("Todos {operation} ({} items)", todos.len())
+        }
+        ActionType::Other { description } => one_line(description),
+    }
+}
+
+fn file_edit_summary(path: &str, changes: &[FileChange]) -> String {
+    let mut verb = "Edit";
+    let mut renamed_to = None;
+    for change in changes {
+        match change {
+            FileChange::Write { .. } => verb = "Write",
+            FileChange::Delete => verb = "Delete",
+            FileChange::Rename { new_path } => renamed_to = Some(new_path.as_str()),
+            FileChange::Edit { .. } => {}
+        }
+    }
+
+    let mut summary = match renamed_to {
+        Some(new_path) => format!("Rename {path} → {new_path}"),
+        None => format!("{verb} {path}"),
+    };
+    let stats = change_stats(changes);
+    if stats.added > 0 || stats.removed > 0 {
+        summary.push_str(&format!(" (+{}/-{})", stats.added, stats.removed));
+    }
+    summary
+}
+
+fn status_suffix(status: &ToolStatus) -> &'static str {
+    match status {
+        ToolStatus::Created | ToolStatus::Success => "",
+        ToolStatus::Failed => " [failed]",
+        ToolStatus::Denied { .. } => " [denied]",
+        ToolStatus::PendingApproval { .. } | ToolStatus::AwaitingApproval => " [awaiting approval]",
+        ToolStatus::TimedOut => " [timed out]",
+        ToolStatus::Unknown => " [no result]",
+    }
+}
+
+/// First non-blank line with whitespace collapsed, cut to [`SUMMARY_TEXT_CHARS`].
+fn one_line(text: &str) -> String {
+    let line = text
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or_default()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if line.chars().count() > SUMMARY_TEXT_CHARS {
+        let cut: String = line.chars().take(SUMMARY_TEXT_CHARS).collect();
+        format!("{}…", cut.trim_end())
+    } else {
+        line
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logs::CommandRunResult;
+
+    fn entry(entry_type: NormalizedEntryType, content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    fn tool(action_type: ActionType, status: ToolStatus) -> NormalizedEntry {
+        entry(
+            NormalizedEntryType::ToolUse {
+                tool_name: "tool".to_string(),
+                action_type,
+                status,
+            },
+            "",
+        )
+    }
+
+    #[test]
+    fn test_summaries_are_single_lines() {
+        let edit = tool(
+            ActionType::FileEdit {
+                path: "src/main.rs".to_string(),
+                changes: vec![FileChange::edit(
+                    "--- a/src/main.rs\n+++ b/src/main.rs\n@@ -1,2 +1,2 @@\n-old\n+new\n+more\n context\n".to_string(),
+                    true,
+                )],
+            },
+            ToolStatus::Success,
+        );
+        assert_eq!(edit.summary(), "🔧 Edit src/main.rs (+2/-1)");
+
+        let command = tool(
+            ActionType::CommandRun {
+                command: "cargo test".to_string(),
+                cwd: None,
+                result: Some(CommandRunResult {
+                    exit_status: Some(CommandExitStatus::ExitCode { code: 101 }),
+                    output: None,
+                }),
+            },
+            ToolStatus::Failed,
+        );
+        assert_eq!(command.summary(), "🔧 Run `cargo test` (exit 101) [failed]");
+
+        let message = entry(
+            NormalizedEntryType::AssistantMessage,
+            "\n  I updated   the parser.\nDetails follow.",
+        );
+        assert_eq!(message.summary(), "💬 assistant: I updated the parser.");
+
+        let long = entry(NormalizedEntryType::UserMessage, &"word ".repeat(40));
+        let summary = long.summary();
+        assert!(summary.ends_with('…'));
+        assert_eq!(summary, long.summary());
+        assert!(!summary.contains('\n'));
+    }
+}
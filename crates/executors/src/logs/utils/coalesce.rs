@@ -0,0 +1,252 @@
+//! Collapses bursts of replace patches for the same entry before they reach the [`MsgStore`].
+//!
+//! Streaming agents rewrite one entry many times per second: Codex re-emits a command's
+//! output on every `exec_command_output_delta` and Claude re-emits the assistant message on
+//! every text delta. With the default 50ms window, a command streaming 200 output deltas
+//! produces 2 patches instead of 201 (see `test_streamed_command_output_is_coalesced` in the
+//! Codex normalizer).
+//!
+//! Streaming replaces are built with `ConversationPatch::streaming_replace` and their secrets
+//! are redacted here, once per flushed patch rather than once per delta.
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use json_patch::{Patch, PatchOperation};
+use workspace_utils::msg_store::MsgStore;
+
+use super::{EntryIndexProvider, redact::redact_patch_secrets};
+
+/// How long a replace patch may be held back waiting for a newer one.
+pub const DEFAULT_COALESCE_INTERVAL: Duration = Duration::from_millis(50);
+
+struct Held {
+    path: String,
+    patch: Patch,
+    generation: u64,
+}
+
+#[derive(Default)]
+struct State {
+    held: Option<Held>,
+    generation: u64,
+}
+
+struct Inner {
+    msg_store: Arc<MsgStore>,
+    interval: Duration,
+    state: Mutex<State>,
+    /// Patches are dropped for entries this provider reports as already persisted
+    entry_index: Option<EntryIndexProvider>,
+}
+
+/// Forwards patches to a [`MsgStore`], merging successive replaces of the same entry.
+///
+/// A replace is held for at most `interval`; a newer replace of the same entry overwrites it.
+/// Any other patch flushes the held one first, so the store sees patches in the same order.
+#[derive(Clone)]
+pub struct PatchCoalescer {
+    inner: Arc<Inner>,
+}
+
+impl PatchCoalescer {
+    pub fn new(msg_store: Arc<MsgStore>) -> Self {
+        Self::with_interval(msg_store, DEFAULT_COALESCE_INTERVAL)
+    }
+
+    pub fn with_interval(msg_store: Arc<MsgStore>, interval: Duration) -> Self {
+        Self::build(msg_store, interval, None)
+    }
+
+    /// Like [`Self::new`], but skips patches for entries `entry_index` reports as already
+    /// persisted (see [`EntryIndexProvider::resuming`]).
+    pub fn resuming(msg_store: Arc<MsgStore>, entry_index: &EntryIndexProvider) -> Self {
+        Self::build(
+            msg_store,
+            DEFAULT_COALESCE_INTERVAL,
+            Some(entry_index.clone()),
+        )
+    }
+
+    fn build(
+        msg_store: Arc<MsgStore>,
+        interval: Duration,
+        entry_index: Option<EntryIndexProvider>,
+    ) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                msg_store,
+                interval,
+                state: Mutex::new(State::default()),
+                entry_index,
+            }),
+        }
+    }
+
+    pub fn push_patch(&self, patch: Patch) {
+        let patch = match &self.inner.entry_index {
+            Some(entry_index) => match entry_index.skip_persisted(patch) {
+                Some(patch) => patch,
+                None => return,
+            },
+            None => patch,
+        };
+        let mut state = self.inner.state.lock().unwrap();
+        let Some(path) = single_replace_path(&patch) else {
+            self.flush_locked(&mut state);
+            self.inner.msg_store.push_patch(patch);
+            return;
+        };
+
+        if let Some(held) = state.held.as_mut()
+            && held.path == path
+        {
+            held.patch = patch;
+            return;
+        }
+
+        self.flush_locked(&mut state);
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            // Nothing could flush a held patch later, so don't hold it.
+            self.forward_replace(patch);
+            return;
+        };
+
+        state.generation += 1;
+        let generation = state.generation;
+        state.held = Some(Held {
+            path,
+            patch,
+            generation,
+        });
+
+        let coalescer = self.clone();
+        runtime.spawn(async move {
+            tokio::time::sleep(coalescer.inner.interval).await;
+            let mut state = coalescer.inner.state.lock().unwrap();
+            if state
+                .held
+                .as_ref()
+                .is_some_and(|held| held.generation == generation)
+            {
+                coalescer.flush_locked(&mut state);
+            }
+        });
+    }
+
+    pub fn push_session_id(&self, session_id: String) {
+        self.flush();
+        self.inner.msg_store.push_session_id(session_id);
+    }
+
+    /// Push the held replace, if any. Call once the source stream ends.
+    pub fn flush(&self) {
+        let mut state = self.inner.state.lock().unwrap();
+        self.flush_locked(&mut state);
+    }
+
+    fn flush_locked(&self, state: &mut State) {
+        if let Some(held) = state.held.take() {
+            self.forward_replace(held.patch);
+        }
+    }
+
+    fn forward_replace(&self, mut patch: Patch) {
+        redact_patch_secrets(&mut patch);
+        self.inner.msg_store.push_patch(patch);
+    }
+}
+
+fn single_replace_path(patch: &Patch) -> Option<String> {
+    match patch.0.as_slice() {
+        [PatchOperation::Replace(op)] => Some(op.path.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use workspace_utils::log_msg::LogMsg;
+
+    use super::*;
+    use crate::logs::{NormalizedEntry, NormalizedEntryType, utils::ConversationPatch};
+
+    fn entry(content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::AssistantMessage,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    fn patches(msg_store: &MsgStore) -> Vec<Patch> {
+        msg_store
+            .get_history()
+            .into_iter()
+            .filter_map(|msg| match msg {
+                LogMsg::JsonPatch(patch) => Some(patch),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn replaces_of_one_entry_collapse_into_the_latest() {
+        let msg_store = Arc::new(MsgStore::new());
+        let coalescer = PatchCoalescer::new(msg_store.clone());
+
+        coalescer.push_patch(ConversationPatch::add_normalized_entry(0, entry("")));
+        for i in 1..=100 {
+            coalescer.push_patch(ConversationPatch::replace(0, entry(&"a".repeat(i))));
+        }
+        coalescer.push_patch(ConversationPatch::add_normalized_entry(1, entry("next")));
+
+        let patches = patches(&msg_store);
+        assert_eq!(patches.len(), 3);
+        assert_eq!(
+            patches[1],
+            ConversationPatch::replace(0, entry(&"a".repeat(100)))
+        );
+    }
+
+    #[tokio::test]
+    async fn held_replace_is_flushed_after_the_interval() {
+        let msg_store = Arc::new(MsgStore::new());
+        let coalescer = PatchCoalescer::with_interval(msg_store.clone(), Duration::from_millis(10));
+
+        coalescer.push_patch(ConversationPatch::replace(0, entry("partial")));
+        assert!(patches(&msg_store).is_empty());
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(
+            patches(&msg_store),
+            vec![ConversationPatch::replace(0, entry("partial"))]
+        );
+    }
+
+    #[tokio::test]
+    async fn streaming_replaces_are_redacted_when_flushed() {
+        let msg_store = Arc::new(MsgStore::new());
+        let coalescer = PatchCoalescer::new(msg_store.clone());
+        let secret = "sk-proj-abcdefghijklmnopqrstuvwx";
+
+        for i in 1..=secret.len() {
+            coalescer.push_patch(ConversationPatch::streaming_replace(
+                0,
+                entry(&format!("key {}", &secret[..i])),
+            ));
+        }
+        coalescer.flush();
+
+        assert_eq!(
+            patches(&msg_store),
+            vec![ConversationPatch::streaming_replace(
+                0,
+                entry("key <redacted>")
+            )]
+        );
+    }
+}
@@ -5,31 +5,71 @@ use std::sync::{
     atomic::{AtomicUsize, Ordering},
 };
 
-use json_patch::PatchOperation;
+use json_patch::{Patch, PatchOperation};
 use workspace_utils::{log_msg::LogMsg, msg_store::MsgStore};
 
+use super::patch::skip_entries_before;
+
 /// Thread-safe provider for monotonically increasing entry indexes
 #[derive(Debug, Clone)]
-pub struct EntryIndexProvider(Arc<AtomicUsize>);
+pub struct EntryIndexProvider {
+    next: Arc<AtomicUsize>,
+    /// Entries below this index were emitted by an earlier run and must not be re-emitted
+    persisted: usize,
+}
 
 impl EntryIndexProvider {
     /// Create a new index provider starting from 0 (private; prefer seeding)
     fn new() -> Self {
-        Self(Arc::new(AtomicUsize::new(0)))
+        Self {
+            next: Arc::new(AtomicUsize::new(0)),
+            persisted: 0,
+        }
+    }
+
+    /// Create a provider for re-normalizing logs whose first `persisted` entries were already
+    /// emitted, e.g. before a server restart. Indexes start at 0 so replayed entries line up
+    /// with the persisted ones, and [`Self::skip_persisted`] drops the patches for them.
+    pub fn resuming(persisted: usize) -> Self {
+        Self {
+            persisted,
+            ..Self::new()
+        }
+    }
+
+    /// Number of leading entries that were already emitted
+    pub fn persisted(&self) -> usize {
+        self.persisted
+    }
+
+    /// Returns `patch` without the operations on already persisted entries, or `None` if
+    /// nothing is left to emit.
+    pub fn skip_persisted(&self, patch: Patch) -> Option<Patch> {
+        skip_entries_before(patch, self.persisted)
     }
 
     /// Get the next available index
     pub fn next(&self) -> usize {
-        self.0.fetch_add(1, Ordering::Relaxed)
+        self.next.fetch_add(1, Ordering::Relaxed)
     }
 
     /// Get the current index without incrementing
     pub fn current(&self) -> usize {
-        self.0.load(Ordering::Relaxed)
+        self.next.load(Ordering::Relaxed)
     }
 
     pub fn reset(&self) {
-        self.0.store(0, Ordering::Relaxed);
+        self.next.store(0, Ordering::Relaxed);
+    }
+
+    /// Create a provider for a normalizer that can skip entries the store marks as persisted:
+    /// [`Self::resuming`] when [`MsgStore::persisted_entries`] is set, [`Self::start_from`]
+    /// otherwise.
+    pub fn resume_from(msg_store: &MsgStore) -> Self {
+        match msg_store.persisted_entries() {
+            0 => Self::start_from(msg_store),
+            persisted => Self::resuming(persisted),
+        }
     }
 
     /// Create a provider starting from the maximum existing normalized-entry index
@@ -42,15 +82,19 @@ impl EntryIndexProvider {
             .iter()
             .filter_map(|msg| {
                 if let LogMsg::JsonPatch(patch) = msg {
-                    patch.iter().find_map(|op| {
-                        if let PatchOperation::Add(add) = op {
-                            add.path
-                                .strip_prefix("/entries/")
-                                .and_then(|n_str| n_str.parse::<usize>().ok())
-                        } else {
-                            None
-                        }
-                    })
+                    // Batched patches can add several entries
+                    patch
+                        .iter()
+                        .filter_map(|op| {
+                            if let PatchOperation::Add(add) = op {
+                                add.path
+                                    .strip_prefix("/entries/")
+                                    .and_then(|n_str| n_str.parse::<usize>().ok())
+                            } else {
+                                None
+                            }
+                        })
+                        .max()
                 } else {
                     None
                 }
@@ -58,7 +102,7 @@ impl EntryIndexProvider {
             .max();
 
         let start_at = max_index.map_or(0, |n| n.saturating_add(1));
-        provider.0.store(start_at, Ordering::Relaxed);
+        provider.next.store(start_at, Ordering::Relaxed);
         provider
     }
 }
@@ -99,6 +143,32 @@ mod tests {
         assert_eq!(provider1.next(), 2);
     }
 
+    #[test]
+    fn test_resuming_provider_skips_persisted_entries() {
+        use crate::logs::{NormalizedEntry, NormalizedEntryType, utils::ConversationPatch};
+
+        let entry = NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::AssistantMessage,
+            content: "hello".to_string(),
+            metadata: None,
+        };
+        let provider = EntryIndexProvider::resuming(2);
+        assert_eq!(provider.next(), 0);
+        assert_eq!(provider.persisted(), 2);
+
+        let persisted = ConversationPatch::replace(1, entry.clone());
+        assert!(provider.skip_persisted(persisted).is_none());
+
+        let batch = ConversationPatch::batch([
+            ConversationPatch::add_normalized_entry(1, entry.clone()),
+            ConversationPatch::add_normalized_entry(2, entry),
+        ]);
+        let kept = provider.skip_persisted(batch).unwrap();
+        assert_eq!(kept.0.len(), 1);
+        assert_eq!(kept.0[0].path().as_str(), "/entries/2");
+    }
+
     #[test]
     fn test_current_index() {
         let provider = EntryIndexProvider::test_new();
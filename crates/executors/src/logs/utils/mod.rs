@@ -1,7 +1,14 @@
 //! Utility modules for executor framework
 
+pub mod coalesce;
 pub mod entry_index;
 pub mod patch;
+pub mod rate_limit;
+pub mod redact;
+pub mod render_filter;
+pub mod tool_result;
 
+pub use coalesce::PatchCoalescer;
 pub use entry_index::EntryIndexProvider;
 pub use patch::ConversationPatch;
+pub use render_filter::ToolRenderFilter;
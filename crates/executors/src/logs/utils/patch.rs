@@ -4,7 +4,7 @@ use serde_json::{from_value, json, to_value};
 use ts_rs::TS;
 use workspace_utils::diff::Diff;
 
-use crate::logs::NormalizedEntry;
+use crate::logs::{NormalizedEntry, utils::redact::redact_entry_secrets};
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, TS)]
 #[serde(rename_all = "lowercase")]
@@ -39,8 +39,9 @@ pub fn escape_json_pointer_segment(s: &str) -> String {
 pub struct ConversationPatch;
 
 impl ConversationPatch {
-    /// Create an ADD patch for a new conversation entry at the given index
-    pub fn add_normalized_entry(entry_index: usize, entry: NormalizedEntry) -> Patch {
+    /// Create an ADD patch for a new conversation entry at the given index, with secrets redacted
+    pub fn add_normalized_entry(entry_index: usize, mut entry: NormalizedEntry) -> Patch {
+        redact_entry_secrets(&mut entry);
         let patch_entry = PatchEntry {
             op: PatchOperation::Add,
             path: format!("/entries/{entry_index}"),
@@ -103,8 +104,10 @@ impl ConversationPatch {
         .unwrap()
     }
 
-    /// Create a REPLACE patch for updating an existing conversation entry at the given index
-    pub fn replace(entry_index: usize, entry: NormalizedEntry) -> Patch {
+    /// Create a REPLACE patch for updating an existing conversation entry at the given index,
+    /// with secrets redacted
+    pub fn replace(entry_index: usize, mut entry: NormalizedEntry) -> Patch {
+        redact_entry_secrets(&mut entry);
         let patch_entry = PatchEntry {
             op: PatchOperation::Replace,
             path: format!("/entries/{entry_index}"),
@@ -114,6 +117,35 @@ impl ConversationPatch {
         from_value(json!([patch_entry])).unwrap()
     }
 
+    /// Like [`Self::replace`], but leaves secrets in place. Only for streaming updates pushed
+    /// through a [`PatchCoalescer`](super::PatchCoalescer), which redacts the surviving patch
+    /// once when it flushes instead of on every delta.
+    pub fn streaming_replace(entry_index: usize, entry: NormalizedEntry) -> Patch {
+        let patch_entry = PatchEntry {
+            op: PatchOperation::Replace,
+            path: format!("/entries/{entry_index}"),
+            value: PatchType::NormalizedEntry(entry),
+        };
+
+        from_value(json!([patch_entry])).unwrap()
+    }
+
+    /// Create a single REPLACE patch that empties the conversation, so a processor can rebuild
+    /// it from scratch without issuing one REMOVE per existing entry
+    pub fn clear_entries() -> Patch {
+        from_value(json!([{
+            "op": PatchOperation::Replace,
+            "path": "/entries",
+            "value": [],
+        }]))
+        .unwrap()
+    }
+
+    /// Combine several patches into one, applied in order as a single message
+    pub fn batch(patches: impl IntoIterator<Item = Patch>) -> Patch {
+        Patch(patches.into_iter().flat_map(|patch| patch.0).collect())
+    }
+
     pub fn remove(entry_index: usize) -> Patch {
         from_value(json!([{
             "op": PatchOperation::Remove,
@@ -123,19 +155,47 @@ impl ConversationPatch {
     }
 }
 
+/// Drop the operations of `patch` that target entries below `index`, returning `None` if none
+/// remain. Operations on the whole `/entries` list are kept.
+pub fn skip_entries_before(mut patch: Patch, index: usize) -> Option<Patch> {
+    if index == 0 {
+        return Some(patch);
+    }
+    patch.0.retain(|op| {
+        op.path()
+            .as_str()
+            .strip_prefix("/entries/")
+            .and_then(|rest| rest.split('/').next())
+            .and_then(|n| n.parse::<usize>().ok())
+            .is_none_or(|entry_index| entry_index >= index)
+    });
+    (!patch.0.is_empty()).then_some(patch)
+}
+
 /// Extract the entry index and `NormalizedEntry` from a JsonPatch if it contains one
 pub fn extract_normalized_entry_from_patch(patch: &Patch) -> Option<(usize, NormalizedEntry)> {
-    let value = to_value(patch).ok()?;
-    let ops = value.as_array()?;
-    ops.iter().rev().find_map(|op| {
-        let path = op.get("path")?.as_str()?;
-        let entry_index = path.strip_prefix("/entries/")?.parse::<usize>().ok()?;
-
-        let value = op.get("value")?;
-        (value.get("type")?.as_str()? == "NORMALIZED_ENTRY")
-            .then(|| value.get("content"))
-            .flatten()
-            .and_then(|c| from_value::<NormalizedEntry>(c.clone()).ok())
-            .map(|entry| (entry_index, entry))
-    })
+    extract_normalized_entries_from_patch(patch).pop()
+}
+
+/// Extract every entry index and `NormalizedEntry` a (possibly batched) JsonPatch writes, in order
+pub fn extract_normalized_entries_from_patch(patch: &Patch) -> Vec<(usize, NormalizedEntry)> {
+    let Ok(value) = to_value(patch) else {
+        return Vec::new();
+    };
+    let Some(ops) = value.as_array() else {
+        return Vec::new();
+    };
+    ops.iter()
+        .filter_map(|op| {
+            let path = op.get("path")?.as_str()?;
+            let entry_index = path.strip_prefix("/entries/")?.parse::<usize>().ok()?;
+
+            let value = op.get("value")?;
+            (value.get("type")?.as_str()? == "NORMALIZED_ENTRY")
+                .then(|| value.get("content"))
+                .flatten()
+                .and_then(|c| from_value::<NormalizedEntry>(c.clone()).ok())
+                .map(|entry| (entry_index, entry))
+        })
+        .collect()
 }
@@ -0,0 +1,84 @@
+//! Recognizes rate-limit errors from agent output.
+//!
+//! Providers throttle long sessions routinely and the agents usually back off and retry on their
+//! own, so a rate limit is surfaced as a warning notice (with the suggested wait when the
+//! message carries one) rather than as a failed run.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::logs::{NormalizedEntry, NormalizedEntryType, NotificationLevel};
+
+lazy_static! {
+    static ref RATE_LIMIT_RE: Regex =
+        Regex::new(r"(?i)rate[ _-]?limit|usage limit|too many requests|\b429\b").unwrap();
+    static ref RETRY_AFTER_RE: Regex = Regex::new(
+        r"(?i)(?:retry[ _-]after|try again in|retrying(?: \d+/\d+)? in)\D{0,3}(\d+(?:\.\d+)?)\s*(ms|milliseconds?|s|secs?|seconds?|m|mins?|minutes?|h|hrs?|hours?)?\b"
+    )
+    .unwrap();
+}
+
+/// True if `message` reads like a provider rate-limit error.
+pub fn is_rate_limit_message(message: &str) -> bool {
+    RATE_LIMIT_RE.is_match(message)
+}
+
+/// Suggested wait in whole seconds (rounded up), e.g. from `Retry-After: 30` or
+/// `try again in 1.5 minutes`. A bare number is taken as seconds.
+pub fn retry_after_secs(message: &str) -> Option<u64> {
+    let caps = RETRY_AFTER_RE.captures(message)?;
+    let value: f64 = caps[1].parse().ok()?;
+    let unit = caps.get(2).map(|m| m.as_str().to_ascii_lowercase());
+    let secs = match unit.as_deref() {
+        Some(unit) if unit.starts_with("ms") || unit.starts_with("milli") => value / 1000.0,
+        Some(unit) if unit.starts_with('m') => value * 60.0,
+        Some(unit) if unit.starts_with('h') => value * 3600.0,
+        _ => value,
+    };
+    Some(secs.ceil() as u64)
+}
+
+/// Warning notice for a rate-limit error. `metadata.retry_after_secs` carries the suggested wait
+/// when known.
+pub fn rate_limit_entry(message: &str) -> NormalizedEntry {
+    let retry_after = retry_after_secs(message);
+    let content = match retry_after {
+        Some(secs) => format!("Rate limited, retry after {secs}s: {}", message.trim()),
+        None => format!("Rate limited: {}", message.trim()),
+    };
+    NormalizedEntry {
+        timestamp: None,
+        entry_type: NormalizedEntryType::Notification {
+            level: NotificationLevel::Warn,
+        },
+        content,
+        metadata: Some(serde_json::json!({
+            "rate_limited": true,
+            "retry_after_secs": retry_after,
+        })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limit_signatures_and_retry_after() {
+        assert!(is_rate_limit_message(
+            "exceeded retry limit, last status: 429 Too Many Requests"
+        ));
+        assert!(is_rate_limit_message("Error: rate_limit_error"));
+        assert!(!is_rate_limit_message("error: expected 4290 bytes"));
+        assert!(!is_rate_limit_message("cargo test failed"));
+
+        assert_eq!(retry_after_secs("Retry-After: 30"), Some(30));
+        assert_eq!(
+            retry_after_secs("Please try again in 1.5 minutes"),
+            Some(90)
+        );
+        assert_eq!(retry_after_secs("retrying 2/5 in 250ms…"), Some(1));
+        assert_eq!(retry_after_secs("Try again in 2 hours"), Some(7200));
+        assert_eq!(retry_after_secs("rate limited"), None);
+    }
+}
@@ -0,0 +1,32 @@
+//! Hides tool uses of chosen kinds from the normalized conversation.
+//!
+//! Normalizers consult the filter before allocating an entry index, so hidden tool uses leave
+//! no gap in the entry list. Raw logs are untouched and still contain every tool call.
+
+use crate::logs::{ActionKind, ActionType, NormalizedEntry, NormalizedEntryType};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ToolRenderFilter {
+    hidden: Vec<ActionKind>,
+}
+
+impl ToolRenderFilter {
+    pub fn hiding(kinds: impl IntoIterator<Item = ActionKind>) -> Self {
+        Self {
+            hidden: kinds.into_iter().collect(),
+        }
+    }
+
+    /// Whether tool uses performing `action_type` are left out of the conversation
+    pub fn hides(&self, action_type: &ActionType) -> bool {
+        self.hidden.contains(&action_type.kind())
+    }
+
+    /// Whether `entry` is a tool use this filter leaves out; other entries are always shown
+    pub fn hides_entry(&self, entry: &NormalizedEntry) -> bool {
+        match &entry.entry_type {
+            NormalizedEntryType::ToolUse { action_type, .. } => self.hides(action_type),
+            _ => false,
+        }
+    }
+}
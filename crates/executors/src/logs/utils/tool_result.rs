@@ -0,0 +1,130 @@
+//! Caps the size of tool results kept in normalized entries.
+//!
+//! MCP servers and built-in tools can return megabytes of output (file dumps, search results,
+//! API payloads). Storing that verbatim bloats the conversation patches that get streamed and
+//! persisted, so normalizers pass results through [`ToolResult::capped`], which keeps the first
+//! part of the result and notes how much was dropped.
+
+use std::sync::{
+    LazyLock,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use serde_json::Value;
+
+use crate::logs::{ToolResult, ToolResultValueType};
+
+/// Environment variable overriding [`DEFAULT_MAX_TOOL_RESULT_BYTES`].
+pub const MAX_TOOL_RESULT_BYTES_ENV: &str = "FORGE_MAX_TOOL_RESULT_BYTES";
+
+/// Default upper bound for a tool result stored in a normalized entry.
+pub const DEFAULT_MAX_TOOL_RESULT_BYTES: usize = 64 * 1024;
+
+static MAX_TOOL_RESULT_BYTES: LazyLock<AtomicUsize> = LazyLock::new(|| {
+    let limit = std::env::var(MAX_TOOL_RESULT_BYTES_ENV)
+        .ok()
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .filter(|value| *value > 0)
+        .unwrap_or(DEFAULT_MAX_TOOL_RESULT_BYTES);
+    AtomicUsize::new(limit)
+});
+
+/// Maximum tool result size in bytes, honouring the `FORGE_MAX_TOOL_RESULT_BYTES` override.
+pub fn max_tool_result_bytes() -> usize {
+    MAX_TOOL_RESULT_BYTES.load(Ordering::Relaxed)
+}
+
+pub fn set_max_tool_result_bytes(limit: usize) {
+    MAX_TOOL_RESULT_BYTES.store(limit.max(1), Ordering::Relaxed);
+}
+
+impl ToolResult {
+    pub fn markdown(text: impl Into<String>) -> Self {
+        Self {
+            r#type: ToolResultValueType::Markdown,
+            value: Value::String(text.into()),
+        }
+    }
+
+    pub fn json(value: Value) -> Self {
+        Self {
+            r#type: ToolResultValueType::Json,
+            value,
+        }
+    }
+
+    /// Truncates the result to the process-wide [`max_tool_result_bytes`] limit.
+    pub fn capped(self) -> Self {
+        self.truncated_to(max_tool_result_bytes())
+    }
+
+    /// Keeps at most `limit` bytes of the result. Oversized Markdown keeps its head followed by a
+    /// truncation notice; oversized JSON becomes Markdown holding the head of the serialized value,
+    /// since a cut-off JSON document can't be rendered structurally.
+    pub fn truncated_to(self, limit: usize) -> Self {
+        match (&self.r#type, &self.value) {
+            (ToolResultValueType::Markdown, Value::String(text)) => {
+                if text.len() <= limit {
+                    return self;
+                }
+                Self::markdown(format!(
+                    "{}\n\n… (truncated, showing {limit} of {} bytes)",
+                    truncate_at_char_boundary(text, limit),
+                    text.len()
+                ))
+            }
+            _ => {
+                let serialized = serde_json::to_string_pretty(&self.value).unwrap_or_default();
+                if serialized.len() <= limit {
+                    return self;
+                }
+                Self::markdown(format!(
+                    "(large JSON result, {} bytes, truncated)\n\n```json\n{}\n…\n```",
+                    serialized.len(),
+                    truncate_at_char_boundary(&serialized, limit)
+                ))
+            }
+        }
+    }
+}
+
+fn truncate_at_char_boundary(text: &str, limit: usize) -> &str {
+    let mut end = limit.min(text.len());
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_results_under_the_limit_are_kept() {
+        let text = ToolResult::markdown("héllo").truncated_to(6);
+        assert_eq!(text.value, Value::String("héllo".to_string()));
+
+        let json = ToolResult::json(serde_json::json!({ "ok": true })).truncated_to(1024);
+        assert!(matches!(json.r#type, ToolResultValueType::Json));
+        assert_eq!(json.value, serde_json::json!({ "ok": true }));
+    }
+
+    #[test]
+    fn test_results_over_the_limit_are_truncated_with_a_notice() {
+        // The cut lands inside the two-byte "é", so it backs off to the preceding boundary
+        let text = ToolResult::markdown("héllo world").truncated_to(2);
+        assert_eq!(
+            text.value.as_str().unwrap(),
+            "h\n\n… (truncated, showing 2 of 12 bytes)"
+        );
+
+        let items: Vec<u32> = (0..1000).collect();
+        let json = ToolResult::json(serde_json::json!({ "items": items })).truncated_to(64);
+        assert!(matches!(json.r#type, ToolResultValueType::Markdown));
+        let rendered = json.value.as_str().unwrap();
+        assert!(rendered.starts_with("(large JSON result, "));
+        assert!(rendered.contains("bytes, truncated)"));
+        assert!(rendered.len() < 200);
+    }
+}
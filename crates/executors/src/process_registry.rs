@@ -0,0 +1,240 @@
+//! Keeps track of the execution processes that are currently running.
+//!
+//! The container owns a [`ProcessRegistry`], registers every spawned child together with its
+//! execution process and task attempt, and holds the returned [`ProcessRegistration`] until the
+//! child exits. Listing the registry samples memory and CPU usage of each child's process group
+//! from the OS where available, which helps operators spot runaway agents.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A running execution process, with resource usage sampled when it was listed.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct RunningProcess {
+    pub execution_process_id: Uuid,
+    pub task_attempt_id: Uuid,
+    /// The coding agent that was spawned, `None` for scripts
+    pub executor: Option<String>,
+    /// OS process id of the process group leader, if it was still known at spawn time
+    pub pid: Option<u32>,
+    pub started_at: DateTime<Utc>,
+    /// Resident memory of the whole process group; `None` when it can't be sampled on this OS
+    pub rss_bytes: Option<u64>,
+    /// Average CPU use of the process group since it started, where 100 is one full core
+    pub cpu_percent: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ProcessRegistry {
+    processes: Arc<Mutex<HashMap<Uuid, RunningProcess>>>,
+}
+
+impl ProcessRegistry {
+    /// Records a spawned child. It stays listed until the returned registration is dropped.
+    pub fn register(
+        &self,
+        execution_process_id: Uuid,
+        task_attempt_id: Uuid,
+        executor: Option<String>,
+        pid: Option<u32>,
+    ) -> ProcessRegistration {
+        let process = RunningProcess {
+            execution_process_id,
+            task_attempt_id,
+            executor,
+            pid,
+            started_at: Utc::now(),
+            rss_bytes: None,
+            cpu_percent: None,
+        };
+        self.processes
+            .lock()
+            .unwrap()
+            .insert(execution_process_id, process);
+        ProcessRegistration {
+            registry: self.clone(),
+            execution_process_id,
+        }
+    }
+
+    /// The running processes, oldest first, with their current resource usage.
+    pub fn list(&self) -> Vec<RunningProcess> {
+        let mut processes: Vec<RunningProcess> =
+            self.processes.lock().unwrap().values().cloned().collect();
+        processes.sort_by_key(|process| process.started_at);
+        let now = Utc::now();
+        for process in &mut processes {
+            if let Some(pid) = process.pid {
+                let usage = sample_usage(pid);
+                process.rss_bytes = usage.rss_bytes;
+                process.cpu_percent = usage.cpu_seconds.and_then(|cpu_seconds| {
+                    let elapsed = (now - process.started_at).num_milliseconds() as f64 / 1000.0;
+                    (elapsed > 0.0).then(|| cpu_seconds / elapsed * 100.0)
+                });
+            }
+        }
+        processes
+    }
+
+    fn remove(&self, execution_process_id: &Uuid) {
+        self.processes.lock().unwrap().remove(execution_process_id);
+    }
+}
+
+/// Held for as long as a registered process is running; dropping it unlists the process.
+#[derive(Debug)]
+pub struct ProcessRegistration {
+    registry: ProcessRegistry,
+    execution_process_id: Uuid,
+}
+
+impl Drop for ProcessRegistration {
+    fn drop(&mut self) {
+        self.registry.remove(&self.execution_process_id);
+    }
+}
+
+#[derive(Debug, Default)]
+struct ProcessUsage {
+    rss_bytes: Option<u64>,
+    cpu_seconds: Option<f64>,
+}
+
+/// Sums memory and CPU time over every process in the group led by `pgid`. Spawned children
+/// lead their own group, so this covers the agent and the tools it runs unless they detached.
+#[cfg(target_os = "linux")]
+fn sample_usage(pgid: u32) -> ProcessUsage {
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return ProcessUsage::default();
+    };
+    // SAFETY: sysconf only reads a system configuration value
+    let clock_ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+    let clock_ticks = (clock_ticks > 0).then_some(clock_ticks as f64);
+
+    let mut usage = ProcessUsage::default();
+    for pid in entries
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+    {
+        let Some(stat) = read_stat(pid).filter(|stat| stat.pgrp == pgid) else {
+            continue;
+        };
+        if let Some(rss) = read_rss_bytes(pid) {
+            usage.rss_bytes = Some(usage.rss_bytes.unwrap_or(0) + rss);
+        }
+        if let Some(clock_ticks) = clock_ticks {
+            usage.cpu_seconds =
+                Some(usage.cpu_seconds.unwrap_or(0.0) + stat.cpu_ticks as f64 / clock_ticks);
+        }
+    }
+    usage
+}
+
+#[cfg(target_os = "linux")]
+struct ProcStat {
+    pgrp: u32,
+    /// User and system time of the process and of its children that already exited and were
+    /// waited for, so the group total doesn't drop when a short-lived tool finishes
+    cpu_ticks: u64,
+}
+
+#[cfg(target_os = "linux")]
+fn read_stat(pid: u32) -> Option<ProcStat> {
+    let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    // The command name may contain spaces, so fields are counted from the closing parenthesis:
+    // pgrp is the 5th field of the whole line and utime, stime, cutime and cstime the 14th to 17th.
+    let (_, fields) = stat.rsplit_once(')')?;
+    let fields: Vec<&str> = fields.split_whitespace().collect();
+    let pgrp = fields.get(2)?.parse().ok()?;
+    let cpu_ticks = fields
+        .get(11..15)?
+        .iter()
+        .map(|field| field.parse::<i64>().ok())
+        .sum::<Option<i64>>()?;
+    Some(ProcStat {
+        pgrp,
+        cpu_ticks: cpu_ticks.max(0) as u64,
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn read_rss_bytes(pid: u32) -> Option<u64> {
+    let status = std::fs::read_to_string(format!("/proc/{pid}/status")).ok()?;
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))
+        .and_then(|value| value.trim_end_matches("kB").trim().parse::<u64>().ok())
+        .map(|kib| kib * 1024)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn sample_usage(_pid: u32) -> ProcessUsage {
+    ProcessUsage::default()
+}
+
+#[cfg(test)]
+mod tests {
+    use command_group::AsyncCommandGroup;
+    use tokio::process::Command;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_spawned_child_is_listed_until_it_exits() {
+        let registry = ProcessRegistry::default();
+        let mut child = Command::new("sleep").arg("5").group_spawn().unwrap();
+        let execution_process_id = Uuid::new_v4();
+        let task_attempt_id = Uuid::new_v4();
+
+        let registration = registry.register(
+            execution_process_id,
+            task_attempt_id,
+            Some("CLAUDE_CODE".to_string()),
+            child.id(),
+        );
+        let listed = registry.list();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].execution_process_id, execution_process_id);
+        assert_eq!(listed[0].task_attempt_id, task_attempt_id);
+        assert_eq!(listed[0].pid, child.id());
+        #[cfg(target_os = "linux")]
+        assert!(listed[0].rss_bytes.is_some_and(|rss| rss > 0));
+
+        child.kill().await.unwrap();
+        child.wait().await.unwrap();
+        drop(registration);
+        assert!(registry.list().is_empty());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_usage_covers_the_whole_process_group() {
+        let mut child = Command::new("sh")
+            .args(["-c", "sleep 5 & sleep 5 & wait"])
+            .group_spawn()
+            .unwrap();
+        let pid = child.id().unwrap();
+
+        // The shell starts its children asynchronously, so wait for them to show up
+        let mut covers_group = false;
+        for _ in 0..50 {
+            let group_rss = sample_usage(pid).rss_bytes.unwrap_or(0);
+            if group_rss > read_rss_bytes(pid).unwrap_or(u64::MAX) {
+                covers_group = true;
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+
+        child.kill().await.unwrap();
+        child.wait().await.unwrap();
+        assert!(covers_group);
+    }
+}
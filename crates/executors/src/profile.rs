@@ -2,6 +2,7 @@ use std::{collections::HashMap, str::FromStr, sync::RwLock};
 
 use convert_case::{Case, Casing};
 use lazy_static::lazy_static;
+use schemars::JsonSchema;
 use serde::{Deserialize, Deserializer, Serialize, de::Error as DeError};
 use thiserror::Error;
 use ts_rs::TS;
@@ -43,6 +44,16 @@ pub enum ProfileError {
 
     #[error("No available executor profile")]
     NoAvailableExecutorProfile,
+
+    #[error("Executor '{executor}' has no configured profiles")]
+    UnknownExecutor { executor: BaseCodingAgent },
+
+    #[error("Unknown variant '{variant}' for executor '{executor}'. Valid variants: {valid}")]
+    UnknownVariant {
+        executor: BaseCodingAgent,
+        variant: String,
+        valid: String,
+    },
 }
 
 lazy_static! {
@@ -54,10 +65,11 @@ lazy_static! {
 const DEFAULT_PROFILES_JSON: &str = include_str!("../default_profiles.json");
 
 // Executor-centric profile identifier
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, Hash, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema, Hash, Eq)]
 pub struct ExecutorProfileId {
     /// The executor type (e.g., "CLAUDE_CODE", "AMP")
     #[serde(alias = "profile", deserialize_with = "de_base_coding_agent_kebab")]
+    #[schemars(with = "BaseCodingAgent")]
     // Backwards compatability with ProfileVariantIds, esp stored in DB under ExecutorAction
     pub executor: BaseCodingAgent,
     /// Optional variant name (e.g., "PLAN", "ROUTER")
@@ -101,6 +113,38 @@ impl ExecutorProfileId {
             None => self.executor.clone().to_string(),
         }
     }
+
+    /// Check the variant against the variants configured for this executor, returning the id
+    /// with its variant in canonical form. The error lists the valid variants.
+    pub fn resolve(&self, configs: &ExecutorConfigs) -> Result<Self, ProfileError> {
+        let config =
+            configs
+                .executors
+                .get(&self.executor)
+                .ok_or(ProfileError::UnknownExecutor {
+                    executor: self.executor,
+                })?;
+        let Some(variant) = &self.variant else {
+            return Ok(self.clone());
+        };
+
+        let key = canonical_variant_key(variant);
+        if config.get_variant(&key).is_some() {
+            return Ok(Self::with_variant(self.executor, key));
+        }
+
+        let mut valid: Vec<&String> = config.configurations.keys().collect();
+        valid.sort_by_key(|name| (*name != "DEFAULT", *name));
+        Err(ProfileError::UnknownVariant {
+            executor: self.executor,
+            variant: variant.clone(),
+            valid: valid
+                .iter()
+                .map(|name| name.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+        })
+    }
 }
 
 impl std::fmt::Display for ExecutorProfileId {
@@ -169,6 +213,9 @@ impl ExecutorConfig {
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
 pub struct ExecutorConfigs {
     pub executors: HashMap<BaseCodingAgent, ExecutorConfig>,
+    /// Model used by an executor's configurations that leave `model` unset
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub default_models: HashMap<BaseCodingAgent, String>,
 }
 
 impl ExecutorConfigs {
@@ -202,6 +249,19 @@ impl ExecutorConfigs {
         *cache = Self::load();
     }
 
+    /// Default model configured for `executor` in these profiles
+    pub fn default_model(&self, executor: BaseCodingAgent) -> Option<String> {
+        self.default_models.get(&executor).cloned()
+    }
+
+    /// Default model configured for `executor` in the cached profiles
+    pub fn cached_default_model(executor: BaseCodingAgent) -> Option<String> {
+        EXECUTOR_PROFILES_CACHE
+            .read()
+            .unwrap()
+            .default_model(executor)
+    }
+
     /// Set cached executor profiles (used by Forge to inject per-workspace profiles)
     pub fn set_cached(configs: ExecutorConfigs) {
         let mut cache = EXECUTOR_PROFILES_CACHE.write().unwrap();
@@ -254,6 +314,7 @@ impl ExecutorConfigs {
                 }
             }
         }
+        defaults.default_models.extend(overrides.default_models);
         defaults
     }
 
@@ -261,6 +322,12 @@ impl ExecutorConfigs {
     fn compute_overrides(defaults: &Self, current: &Self) -> Result<Self, ProfileError> {
         let mut overrides = Self {
             executors: HashMap::new(),
+            default_models: current
+                .default_models
+                .iter()
+                .filter(|(executor, model)| defaults.default_models.get(executor) != Some(model))
+                .map(|(executor, model)| (*executor, model.clone()))
+                .collect(),
         };
 
         // Fast scan for any illegal deletions BEFORE allocating/cloning
@@ -340,13 +407,35 @@ impl ExecutorConfigs {
                 )));
             }
 
-            // Ensure configuration names don't conflict with reserved words
-            for config_name in profile.configurations.keys() {
+            if merged
+                .default_models
+                .get(executor_key)
+                .is_some_and(|model| model.trim().is_empty())
+            {
+                return Err(ProfileError::Validation(format!(
+                    "Default model for executor '{executor_key}' must not be empty"
+                )));
+            }
+
+            for (config_name, config) in &profile.configurations {
+                // Ensure configuration names don't conflict with reserved words
                 if config_name.starts_with("__") {
                     return Err(ProfileError::Validation(format!(
                         "Configuration name '{config_name}' is reserved (starts with '__')"
                     )));
                 }
+                if config.max_output_tokens() == Some(0) {
+                    return Err(ProfileError::Validation(format!(
+                        "max_output_tokens for '{executor_key}:{config_name}' must be positive"
+                    )));
+                }
+                if let CodingAgent::Codex(codex) = config
+                    && let Err(e) = codex.validate_mcp_servers()
+                {
+                    return Err(ProfileError::Validation(format!(
+                        "'{executor_key}:{config_name}': {e}"
+                    )));
+                }
             }
         }
         Ok(())
@@ -409,3 +498,78 @@ pub fn to_default_variant(id: &ExecutorProfileId) -> ExecutorProfileId {
         variant: None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_canonicalises_known_variants() {
+        let configs = ExecutorConfigs::from_defaults();
+
+        let id = ExecutorProfileId::with_variant(BaseCodingAgent::ClaudeCode, "plan".to_string());
+        assert_eq!(
+            id.resolve(&configs).unwrap(),
+            ExecutorProfileId::with_variant(BaseCodingAgent::ClaudeCode, "PLAN".to_string())
+        );
+
+        let default = ExecutorProfileId::new(BaseCodingAgent::ClaudeCode);
+        assert_eq!(default.resolve(&configs).unwrap(), default);
+    }
+
+    #[test]
+    fn default_models_survive_override_round_trip() {
+        let defaults = ExecutorConfigs::from_defaults();
+        let mut current = defaults.clone();
+        current
+            .default_models
+            .insert(BaseCodingAgent::Codex, "gpt-5-codex".to_string());
+
+        let overrides = ExecutorConfigs::compute_overrides(&defaults, &current).unwrap();
+        assert!(overrides.executors.is_empty());
+        let merged = ExecutorConfigs::merge_with_defaults(defaults, overrides);
+        assert_eq!(
+            merged
+                .default_models
+                .get(&BaseCodingAgent::Codex)
+                .map(String::as_str),
+            Some("gpt-5-codex")
+        );
+    }
+
+    #[test]
+    fn resolve_rejects_unknown_variant_and_lists_valid_ones() {
+        let configs = ExecutorConfigs::from_defaults();
+        let id = ExecutorProfileId::with_variant(BaseCodingAgent::ClaudeCode, "PLANN".to_string());
+
+        let err = id.resolve(&configs).unwrap_err();
+        assert!(matches!(err, ProfileError::UnknownVariant { .. }));
+        assert_eq!(
+            err.to_string(),
+            "Unknown variant 'PLANN' for executor 'CLAUDE_CODE'. Valid variants: DEFAULT, APPROVALS, PLAN"
+        );
+    }
+
+    #[test]
+    fn validation_rejects_zero_max_output_tokens() {
+        fn with_max_output_tokens(max_output_tokens: u32) -> ExecutorConfigs {
+            let mut configs = ExecutorConfigs::from_defaults();
+            let Some(CodingAgent::ClaudeCode(claude)) = configs
+                .executors
+                .get_mut(&BaseCodingAgent::ClaudeCode)
+                .and_then(|profile| profile.configurations.get_mut("DEFAULT"))
+            else {
+                panic!("missing default Claude Code configuration");
+            };
+            claude.max_output_tokens = Some(max_output_tokens);
+            configs
+        }
+
+        assert!(ExecutorConfigs::validate_merged(&with_max_output_tokens(4096)).is_ok());
+        let err = ExecutorConfigs::validate_merged(&with_max_output_tokens(0)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Validation error: max_output_tokens for 'CLAUDE_CODE:DEFAULT' must be positive"
+        );
+    }
+}
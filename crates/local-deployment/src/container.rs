@@ -28,7 +28,8 @@ use deployment::DeploymentError;
 use executors::{
     actions::{Executable, ExecutorAction},
     approvals::{ExecutorApprovalService, NoopExecutorApprovalService},
-    executors::BaseCodingAgent,
+    concurrency::{ExecutorPermit, acquire_executor_permit},
+    executors::{BaseCodingAgent, ReplacementChildren},
     logs::{
         NormalizedEntryType,
         utils::{
@@ -36,6 +37,7 @@ use executors::{
             patch::{escape_json_pointer_segment, extract_normalized_entry_from_patch},
         },
     },
+    process_registry::{ProcessRegistration, ProcessRegistry},
 };
 use futures::{FutureExt, StreamExt, TryStreamExt, stream::select};
 use serde_json::json;
@@ -48,12 +50,13 @@ use services::services::{
     git::{Commit, DiffTarget, GitService},
     image::ImageService,
     notification::NotificationService,
+    webhook::{AttemptEvent, AttemptEventPayload, WebhookService},
     worktree_manager::WorktreeManager,
 };
 use tokio::{sync::RwLock, task::JoinHandle};
 use tokio_util::io::ReaderStream;
 use utils::{
-    log_msg::LogMsg,
+    log_msg::{LogMsg, TerminalStatus},
     msg_store::MsgStore,
     text::{git_branch_id, short_uuid},
 };
@@ -71,6 +74,7 @@ pub struct LocalContainerService {
     image_service: ImageService,
     analytics: Option<AnalyticsContext>,
     approvals: Approvals,
+    process_registry: ProcessRegistry,
 }
 
 impl LocalContainerService {
@@ -94,6 +98,7 @@ impl LocalContainerService {
             image_service,
             analytics,
             approvals,
+            process_registry: ProcessRegistry::default(),
         }
     }
 
@@ -132,7 +137,22 @@ impl LocalContainerService {
         if let Err(e) = Task::update_status(&db.pool, ctx.task.id, TaskStatus::InReview).await {
             tracing::error!("Failed to update task status to InReview: {e}");
         }
-        let notify_cfg = config.read().await.notifications.clone();
+        let (notify_cfg, webhook_cfg) = {
+            let config = config.read().await;
+            (config.notifications.clone(), config.webhook.clone())
+        };
+        // Attempts stopped by the user are neither completed nor failed
+        let event = match ctx.execution_process.status {
+            ExecutionProcessStatus::Completed => Some(AttemptEvent::Completed),
+            ExecutionProcessStatus::Failed => Some(AttemptEvent::Failed),
+            _ => None,
+        };
+        if let Some(event) = event {
+            WebhookService::dispatch(
+                &webhook_cfg,
+                AttemptEventPayload::new(event, &ctx.task, &ctx.task_attempt),
+            );
+        }
         NotificationService::notify_execution_halted(notify_cfg, ctx).await;
     }
 
@@ -295,6 +315,9 @@ impl LocalContainerService {
         &self,
         exec_id: &Uuid,
         exit_signal: Option<tokio::sync::oneshot::Receiver<()>>,
+        permit: Option<ExecutorPermit>,
+        registration: ProcessRegistration,
+        replacements: Option<ReplacementChildren>,
     ) -> JoinHandle<()> {
         let exec_id = *exec_id;
         let child_store = self.child_store.clone();
@@ -311,28 +334,66 @@ impl LocalContainerService {
                 .map(|rx| rx.map(|_| ()).boxed()) // wait for signal
                 .unwrap_or_else(|| std::future::pending::<()>().boxed()); // no signal, stall forever
 
+            // While an executor can still replace its child, the child exiting may just be the
+            // start of a reconnect. Once it drops the sender without signalling completion (e.g.
+            // the app server died and reconnecting gave up), the child's exit ends the run.
+            let restartable = replacements.is_some();
+            let mut replacements = replacements;
             let status_result: std::io::Result<std::process::ExitStatus>;
 
             // Wait for process to exit, or exit signal from executor
-            tokio::select! {
-                // Exit signal.
-                // Some coding agent processes do not automatically exit after processing the user request; instead the executor
-                // signals when processing has finished to gracefully kill the process.
-                _ = &mut exit_signal_future => {
-                    // Executor signaled completion: kill group and remember to force Completed(0)
-                    if let Some(child_lock) = child_store.read().await.get(&exec_id).cloned() {
-                        let mut child = child_lock.write().await ;
-                        if let Err(err) = command::kill_process_group(&mut child).await {
-                            tracing::error!("Failed to kill process group after exit signal: {} {}", exec_id, err);
+            loop {
+                tokio::select! {
+                    // An executor signals completion before dropping its replacement sender
+                    biased;
+                    // Exit signal.
+                    // Some coding agent processes do not automatically exit after processing the user request; instead the executor
+                    // signals when processing has finished to gracefully kill the process.
+                    _ = &mut exit_signal_future => {
+                        // Executor signaled completion: kill group and remember to force Completed(0)
+                        if let Some(child_lock) = child_store.read().await.get(&exec_id).cloned() {
+                            let mut child = child_lock.write().await ;
+                            if let Err(err) = command::kill_process_group(&mut child).await {
+                                tracing::error!("Failed to kill process group after exit signal: {} {}", exec_id, err);
+                            }
                         }
+                        status_result = Ok(success_exit_status());
+                        break;
                     }
-                    status_result = Ok(success_exit_status());
-                }
-                // Process exit
-                exit_status_result = &mut process_exit_rx => {
-                    status_result = exit_status_result.unwrap_or_else(|e| Err(std::io::Error::other(e)));
+                    // Process exit
+                    exit_status_result = &mut process_exit_rx, if replacements.is_none() => {
+                        if restartable
+                            && let Some(child_lock) = child_store.read().await.get(&exec_id).cloned()
+                        {
+                            // The exit seen may be an earlier child's, so stop the latest one too
+                            let mut child = child_lock.write().await;
+                            if let Err(err) = command::kill_process_group(&mut child).await {
+                                tracing::error!("Failed to kill process group after reconnect ended: {} {}", exec_id, err);
+                            }
+                        }
+                        status_result = exit_status_result.unwrap_or_else(|e| Err(std::io::Error::other(e)));
+                        break;
+                    }
+                    // The executor started a replacement child
+                    replacement = async {
+                        match replacements.as_mut() {
+                            Some(rx) => rx.recv().await,
+                            None => std::future::pending().await,
+                        }
+                    } => match replacement {
+                        Some(child) => {
+                            if !container.adopt_replacement_child(exec_id, child).await {
+                                // Stopped meanwhile: refuse further replacements
+                                replacements = None;
+                            }
+                        }
+                        None => replacements = None,
+                    },
                 }
             }
+            // Free the executor slot before any follow-up action is started below
+            drop(permit);
+            drop(registration);
 
             let (exit_code, status) = match status_result {
                 Ok(exit_status) => {
@@ -347,7 +408,8 @@ impl LocalContainerService {
                 Err(_) => (None, ExecutionProcessStatus::Failed),
             };
 
-            if !ExecutionProcess::was_stopped(&db.pool, exec_id).await
+            let stopped = ExecutionProcess::was_stopped(&db.pool, exec_id).await;
+            if !stopped
                 && let Err(e) =
                     ExecutionProcess::update_completion(&db.pool, exec_id, status.clone(), exit_code).await
             {
@@ -483,7 +545,14 @@ impl LocalContainerService {
 
             // Cleanup msg store
             if let Some(msg_arc) = msg_stores.write().await.remove(&exec_id) {
-                msg_arc.push_finished();
+                let terminal_status = if stopped {
+                    TerminalStatus::Cancelled
+                } else if matches!(status, ExecutionProcessStatus::Completed) {
+                    TerminalStatus::Success
+                } else {
+                    TerminalStatus::Failed
+                };
+                msg_arc.push_finished_with_status(terminal_status);
                 tokio::time::sleep(Duration::from_millis(50)).await; // Wait for the finish message to propogate
                 match Arc::try_unwrap(msg_arc) {
                     Ok(inner) => drop(inner),
@@ -567,6 +636,30 @@ impl LocalContainerService {
         map.insert(id, store);
     }
 
+    /// Puts a child the executor started mid-run in place of the execution's current one and
+    /// forwards its stderr to the execution logs. Kills it instead and returns `false` when the
+    /// execution has been stopped.
+    async fn adopt_replacement_child(&self, id: Uuid, mut child: AsyncGroupChild) -> bool {
+        if ExecutionProcess::was_stopped(&self.db.pool, id).await
+            || !self.child_store.read().await.contains_key(&id)
+        {
+            if let Err(e) = command::kill_process_group(&mut child).await {
+                tracing::error!("Failed to kill replacement process for {}: {}", id, e);
+            }
+            return false;
+        }
+
+        if let Some(err) = child.inner().stderr.take()
+            && let Some(store) = self.get_msg_store_by_id(&id).await
+        {
+            let err = ReaderStream::new(err)
+                .map_ok(|chunk| LogMsg::Stderr(String::from_utf8_lossy(&chunk).into_owned()));
+            store.spawn_forwarder(utils::stream_ext::debounce_logs(err));
+        }
+        self.add_child_to_store(id, child).await;
+        true
+    }
+
     /// Get the worktree path for a task attempt
     #[allow(dead_code)]
     async fn get_worktree_path(
@@ -632,7 +725,7 @@ impl LocalContainerService {
             Ok::<_, std::io::Error>(LogMsg::JsonPatch(patch))
         }))
         .chain(futures::stream::once(async {
-            Ok::<_, std::io::Error>(LogMsg::Finished)
+            Ok::<_, std::io::Error>(LogMsg::Finished(None))
         }))
         .boxed();
 
@@ -685,6 +778,10 @@ impl ContainerService for LocalContainerService {
         &self.git
     }
 
+    fn process_registry(&self) -> &ProcessRegistry {
+        &self.process_registry
+    }
+
     async fn git_branch_prefix(&self) -> String {
         self.config.read().await.git_branch_prefix.clone()
     }
@@ -857,6 +954,7 @@ impl ContainerService for LocalContainerService {
         task_attempt: &TaskAttempt,
         execution_process: &ExecutionProcess,
         executor_action: &ExecutorAction,
+        permit: Option<ExecutorPermit>,
     ) -> Result<(), ContainerError> {
         // Get the worktree path
         let container_ref = task_attempt
@@ -883,30 +981,87 @@ impl ContainerService for LocalContainerService {
         let mut spawned = executor_action
             .spawn(&current_dir, approvals_service)
             .await?;
+        spawned.permit = permit;
 
         self.track_child_msgs_in_store(execution_process.id, &mut spawned.child)
             .await;
 
+        let registration = self.process_registry.register(
+            execution_process.id,
+            task_attempt.id,
+            executor_action
+                .base_executor()
+                .map(|agent| agent.to_string()),
+            spawned.child.id(),
+        );
+
         self.add_child_to_store(execution_process.id, spawned.child)
             .await;
 
         // Spawn unified exit monitor: watches OS exit and optional executor signal
-        let _hn = self.spawn_exit_monitor(&execution_process.id, spawned.exit_signal);
+        let _hn = self.spawn_exit_monitor(
+            &execution_process.id,
+            spawned.exit_signal,
+            spawned.permit,
+            registration,
+            spawned.replacements,
+        );
 
         Ok(())
     }
 
+    fn queue_execution(
+        &self,
+        task_attempt: &TaskAttempt,
+        execution_process: &ExecutionProcess,
+        executor_action: &ExecutorAction,
+    ) {
+        let container = self.clone();
+        let task_attempt = task_attempt.clone();
+        let execution_process = execution_process.clone();
+        let executor_action = executor_action.clone();
+        tokio::spawn(async move {
+            let permit = acquire_executor_permit().await;
+            // A process stopped while queued is no longer `queued`; give the slot back
+            match ExecutionProcess::start_queued(&container.db.pool, execution_process.id).await {
+                Ok(true) => {}
+                Ok(false) => return,
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to start queued execution process {}: {}",
+                        execution_process.id,
+                        e
+                    );
+                    return;
+                }
+            }
+            if let Err(e) = container
+                .launch_execution(&task_attempt, &execution_process, &executor_action, permit)
+                .await
+            {
+                tracing::error!(
+                    "Failed to launch queued execution process {}: {}",
+                    execution_process.id,
+                    e
+                );
+            }
+        });
+    }
+
     async fn stop_execution(
         &self,
         execution_process: &ExecutionProcess,
         status: ExecutionProcessStatus,
     ) -> Result<(), ContainerError> {
-        let child = self
-            .get_child_from_store(&execution_process.id)
-            .await
-            .ok_or_else(|| {
-                ContainerError::Other(anyhow!("Child process not found for execution"))
-            })?;
+        let Some(child) = self.get_child_from_store(&execution_process.id).await else {
+            // Still waiting for an executor slot: there is nothing to kill yet
+            if ExecutionProcess::cancel_queued(&self.db.pool, execution_process.id, status).await? {
+                return Ok(());
+            }
+            return Err(ContainerError::Other(anyhow!(
+                "Child process not found for execution"
+            )));
+        };
         let exit_code = if status == ExecutionProcessStatus::Completed {
             Some(0)
         } else {
@@ -916,6 +1071,11 @@ impl ContainerService for LocalContainerService {
         ExecutionProcess::update_completion(&self.db.pool, execution_process.id, status, exit_code)
             .await?;
 
+        // Deny approvals still waiting on a human so the executor isn't left blocked on them
+        self.approvals
+            .cancel_for_execution_process(execution_process.id)
+            .await;
+
         // Kill the child process and remove from the store
         {
             let mut child_guard = child.write().await;
@@ -932,7 +1092,7 @@ impl ContainerService for LocalContainerService {
 
         // Mark the process finished in the MsgStore
         if let Some(msg) = self.msg_stores.write().await.remove(&execution_process.id) {
-            msg.push_finished();
+            msg.push_finished_with_status(TerminalStatus::Cancelled);
         }
 
         // Update task status to InReview when execution is stopped
@@ -1203,10 +1363,7 @@ impl LocalContainerService {
         let procs =
             ExecutionProcess::find_by_task_attempt_id(&self.db.pool, ctx.task_attempt.id, false)
                 .await?;
-        if procs
-            .iter()
-            .any(|p| matches!(p.status, ExecutionProcessStatus::Running))
-        {
+        if procs.iter().any(|p| p.status.is_active()) {
             return Ok(());
         }
 
@@ -1237,12 +1394,7 @@ impl LocalContainerService {
         let container_ref = self.ensure_container_exists(&ctx.task_attempt).await?;
 
         // Get session id
-        let Some(session_id) = ExecutionProcess::find_latest_session_id_by_task_attempt(
-            &self.db.pool,
-            ctx.task_attempt.id,
-        )
-        .await?
-        else {
+        let Some(session_id) = self.latest_session_id(&ctx.task_attempt).await? else {
             tracing::warn!(
                 "No session id found for attempt {}. Cannot start queued follow-up.",
                 ctx.task_attempt.id
@@ -1314,6 +1466,7 @@ impl LocalContainerService {
                 prompt,
                 session_id,
                 executor_profile_id,
+                resume_overrides: None,
             };
 
         let follow_up_action = executors::actions::ExecutorAction::new(
@@ -1357,6 +1510,56 @@ fn truncate_to_char_boundary(content: &str, max_len: usize) -> &str {
 
 #[cfg(test)]
 mod tests {
+    use command_group::AsyncCommandGroup;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_exit_monitor_ends_when_app_server_dies_before_reconnect() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let msg_stores = Arc::new(RwLock::new(HashMap::new()));
+        let container = LocalContainerService::new(
+            db.clone(),
+            msg_stores.clone(),
+            Arc::new(RwLock::new(Config::default())),
+            GitService::new(),
+            ImageService::new(db.pool.clone()).unwrap(),
+            None,
+            Approvals::new(msg_stores),
+        );
+
+        let exec_id = Uuid::new_v4();
+        let child = tokio::process::Command::new("sleep")
+            .arg("30")
+            .group_spawn()
+            .unwrap();
+        container.add_child_to_store(exec_id, child).await;
+        let registration = container
+            .process_registry
+            .register(exec_id, Uuid::new_v4(), None, None);
+
+        // The executor never signals completion, and gives up on reconnecting once its server died
+        let (_exit_tx, exit_rx) = tokio::sync::oneshot::channel();
+        let (replacement_tx, replacement_rx) = tokio::sync::mpsc::unbounded_channel();
+        let monitor = container.spawn_exit_monitor(
+            &exec_id,
+            Some(exit_rx),
+            None,
+            registration,
+            Some(replacement_rx),
+        );
+
+        let child_lock = container.get_child_from_store(&exec_id).await.unwrap();
+        child_lock.write().await.kill().await.unwrap();
+        drop(replacement_tx);
+
+        tokio::time::timeout(Duration::from_secs(10), monitor)
+            .await
+            .expect("the run should end once the child died and reconnecting ended")
+            .unwrap();
+        assert!(container.get_child_from_store(&exec_id).await.is_none());
+        assert!(container.process_registry.list().is_empty());
+    }
 
     #[test]
     fn test_truncate_to_char_boundary() {
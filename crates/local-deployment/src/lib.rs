@@ -15,6 +15,7 @@ use services::services::{
     file_search_cache::FileSearchCache,
     filesystem::FilesystemService,
     git::GitService,
+    idempotency::IdempotencyService,
     image::ImageService,
 };
 use tokio::sync::RwLock;
@@ -41,12 +42,14 @@ pub struct LocalDeployment {
     file_search_cache: Arc<FileSearchCache>,
     approvals: Approvals,
     drafts: DraftsService,
+    idempotency: IdempotencyService,
 }
 
 #[async_trait]
 impl Deployment for LocalDeployment {
     async fn new() -> Result<Self, DeploymentError> {
-        let mut raw_config = load_config_from_file(&config_path()).await;
+        let config_path = config_path()?;
+        let mut raw_config = load_config_from_file(&config_path).await;
 
         let profiles = ExecutorConfigs::get_cached();
         if !raw_config.onboarding_acknowledged
@@ -68,7 +71,7 @@ impl Deployment for LocalDeployment {
         }
 
         // Always save config (may have been migrated or version updated)
-        save_config_to_file(&raw_config, &config_path()).await?;
+        save_config_to_file(&raw_config, &config_path).await?;
 
         let config = Arc::new(RwLock::new(raw_config));
         let user_id = generate_user_id();
@@ -141,6 +144,7 @@ impl Deployment for LocalDeployment {
             file_search_cache,
             approvals,
             drafts,
+            idempotency: IdempotencyService::new(),
         })
     }
 
@@ -202,4 +206,56 @@ impl Deployment for LocalDeployment {
     fn drafts(&self) -> &DraftsService {
         &self.drafts
     }
+
+    fn idempotency(&self) -> &IdempotencyService {
+        &self.idempotency
+    }
+}
+
+impl LocalDeployment {
+    /// A deployment backed by a fresh in-memory database and default config, intended for tests.
+    ///
+    /// Nothing is read from or written to the user's config and asset directories, analytics
+    /// are off, and no background cleanup is started.
+    pub async fn new_in_memory() -> Result<Self, DeploymentError> {
+        let config = Arc::new(RwLock::new(Config::default()));
+        let db = DBService::new_in_memory().await?;
+        let git = GitService::new();
+        let msg_stores = Arc::new(RwLock::new(HashMap::new()));
+        let image = ImageService::new(db.pool.clone())?;
+        let approvals = Approvals::new(msg_stores.clone());
+        let container = LocalContainerService::new(
+            db.clone(),
+            msg_stores.clone(),
+            config.clone(),
+            git.clone(),
+            image.clone(),
+            None,
+            approvals.clone(),
+        );
+        let events = EventService::new(
+            db.clone(),
+            Arc::new(MsgStore::new()),
+            Arc::new(RwLock::new(0)),
+        );
+        let drafts = DraftsService::new(db.clone(), image.clone());
+
+        Ok(Self {
+            config,
+            user_id: generate_user_id(),
+            db,
+            analytics: None,
+            msg_stores,
+            container,
+            git,
+            auth: AuthService::new(),
+            image,
+            filesystem: FilesystemService::new(),
+            events,
+            file_search_cache: Arc::new(FileSearchCache::new()),
+            approvals,
+            drafts,
+            idempotency: IdempotencyService::new(),
+        })
+    }
 }
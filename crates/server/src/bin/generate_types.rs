@@ -15,8 +15,11 @@ fn generate_types_content() -> String {
         db::models::project::Project::decl(),
         db::models::project::CreateProject::decl(),
         db::models::project::UpdateProject::decl(),
+        db::models::project::RenameProject::decl(),
         db::models::project::SearchResult::decl(),
         db::models::project::SearchMatchType::decl(),
+        db::models::project::ProjectGitConfig::decl(),
+        db::models::project::UpdateProjectGitConfig::decl(),
         executors::actions::ExecutorAction::decl(),
         executors::mcp_config::McpConfig::decl(),
         executors::actions::ExecutorActionType::decl(),
@@ -29,12 +32,14 @@ fn generate_types_content() -> String {
         db::models::tag::CreateTag::decl(),
         db::models::tag::UpdateTag::decl(),
         server::routes::tags::TagSearchParams::decl(),
+        server::routes::projects::ProjectGitConfigResponse::decl(),
         db::models::task::TaskStatus::decl(),
         db::models::task::Task::decl(),
         db::models::task::TaskWithAttemptStatus::decl(),
         db::models::task::TaskRelationships::decl(),
         db::models::task::CreateTask::decl(),
         db::models::task::UpdateTask::decl(),
+        db::models::task_assignee::SetTaskAssignee::decl(),
         db::models::image::Image::decl(),
         db::models::image::CreateImage::decl(),
         utils::response::ApiResponse::<()>::decl(),
@@ -50,6 +55,8 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::ChangeTargetBranchRequest::decl(),
         server::routes::task_attempts::ChangeTargetBranchResponse::decl(),
         server::routes::tasks::CreateAndStartTaskRequest::decl(),
+        server::routes::tasks::CreateTaskFromGitHubIssue::decl(),
+        server::routes::tasks::MoveTask::decl(),
         server::routes::task_attempts::CreateGitHubPrRequest::decl(),
         server::routes::images::ImageResponse::decl(),
         services::services::github_service::GitHubServiceError::decl(),
@@ -62,21 +69,26 @@ fn generate_types_content() -> String {
         services::services::config::SoundFile::decl(),
         services::services::config::UiLanguage::decl(),
         services::services::config::ShowcaseState::decl(),
+        services::services::config::WebhookConfig::decl(),
         services::services::auth::DeviceFlowStartResponse::decl(),
         server::routes::auth::DevicePollStatus::decl(),
         server::routes::auth::CheckTokenResponse::decl(),
         services::services::git::GitBranch::decl(),
         utils::diff::Diff::decl(),
         utils::diff::DiffChangeKind::decl(),
+        utils::diff::DiffAlgorithm::decl(),
+        utils::diff::DiffStats::decl(),
         services::services::github_service::RepositoryInfo::decl(),
         executors::command::CommandBuilder::decl(),
         executors::profile::ExecutorProfileId::decl(),
         executors::profile::ExecutorConfig::decl(),
         executors::executors::BaseAgentCapability::decl(),
         executors::executors::claude::ClaudeCode::decl(),
+        executors::executors::claude::ClaudeResumeMode::decl(),
         executors::executors::gemini::Gemini::decl(),
         executors::executors::gemini::GeminiModel::decl(),
         executors::executors::amp::Amp::decl(),
+        executors::executors::codex::CodexMcpServer::decl(),
         executors::executors::codex::Codex::decl(),
         executors::executors::codex::SandboxMode::decl(),
         executors::executors::codex::AskForApproval::decl(),
@@ -88,7 +100,10 @@ fn generate_types_content() -> String {
         executors::executors::opencode::Opencode::decl(),
         executors::executors::qwen::QwenCode::decl(),
         executors::executors::AppendPrompt::decl(),
+        executors::executors::AppendPromptMode::decl(),
+        executors::executors::ExecutorLogLevel::decl(),
         executors::actions::coding_agent_initial::CodingAgentInitialRequest::decl(),
+        executors::actions::coding_agent_follow_up::ResumeOverrides::decl(),
         executors::actions::coding_agent_follow_up::CodingAgentFollowUpRequest::decl(),
         server::routes::task_attempts::CreateTaskAttemptBody::decl(),
         server::routes::task_attempts::RunAgentSetupRequest::decl(),
@@ -97,12 +112,19 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::GitOperationError::decl(),
         server::routes::task_attempts::ReplaceProcessRequest::decl(),
         server::routes::task_attempts::CommitInfo::decl(),
+        server::routes::task_attempts::AttemptFileDiff::decl(),
+        server::routes::task_attempts::AttemptDiff::decl(),
+        server::routes::task_attempts::AttemptFile::decl(),
         server::routes::task_attempts::BranchStatus::decl(),
+        server::routes::execution_processes::RawLogStream::decl(),
+        server::routes::execution_processes::RawLogChunk::decl(),
+        server::routes::execution_processes::RawLogsPage::decl(),
         services::services::git::ConflictOp::decl(),
         db::models::task_attempt::TaskAttempt::decl(),
         db::models::execution_process::ExecutionProcess::decl(),
         db::models::execution_process::ExecutionProcessStatus::decl(),
         db::models::execution_process::ExecutionProcessRunReason::decl(),
+        executors::process_registry::RunningProcess::decl(),
         db::models::merge::Merge::decl(),
         db::models::merge::DirectMerge::decl(),
         db::models::merge::PrMerge::decl(),
@@ -116,8 +138,10 @@ fn generate_types_content() -> String {
         executors::logs::NormalizedEntryType::decl(),
         executors::logs::FileChange::decl(),
         executors::logs::ActionType::decl(),
+        executors::logs::ActionKind::decl(),
         executors::logs::TodoItem::decl(),
         executors::logs::NormalizedEntryError::decl(),
+        executors::logs::NotificationLevel::decl(),
         executors::logs::ToolResult::decl(),
         executors::logs::ToolResultValueType::decl(),
         executors::logs::ToolStatus::decl(),
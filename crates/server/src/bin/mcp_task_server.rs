@@ -5,6 +5,10 @@ use utils::{
     port_file::read_port_file,
     sentry::{self as sentry_utils, SentrySource, sentry_layer},
 };
+use uuid::Uuid;
+
+/// Comma-separated project ids the server is scoped to; unset allows every project.
+const ALLOWED_PROJECTS_ENV: &str = "FORGE_MCP_ALLOWED_PROJECTS";
 
 fn main() -> anyhow::Result<()> {
     sentry_utils::init_once(SentrySource::Mcp);
@@ -52,13 +56,36 @@ fn main() -> anyhow::Result<()> {
                 url
             };
 
-            let service = TaskServer::new(&base_url)
-                .serve(stdio())
-                .await
-                .map_err(|e| {
-                    tracing::error!("serving error: {:?}", e);
-                    e
-                })?;
+            let mut server = TaskServer::new(&base_url);
+
+            // Optionally scope the server to a comma-separated list of project ids
+            if let Ok(raw) = std::env::var(ALLOWED_PROJECTS_ENV) {
+                let project_ids = raw
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|id| !id.is_empty())
+                    .map(|id| {
+                        Uuid::parse_str(id).map_err(|e| {
+                            anyhow::anyhow!(
+                                "Invalid project id '{}' in {}: {}",
+                                id,
+                                ALLOWED_PROJECTS_ENV,
+                                e
+                            )
+                        })
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                tracing::info!(
+                    "[MCP] Restricting to {} allowed project(s)",
+                    project_ids.len()
+                );
+                server = server.with_allowed_projects(project_ids);
+            }
+
+            let service = server.serve(stdio()).await.map_err(|e| {
+                tracing::error!("serving error: {:?}", e);
+                e
+            })?;
 
             service.waiting().await?;
             Ok(())
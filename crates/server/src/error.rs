@@ -4,11 +4,15 @@ use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
 };
-use db::models::{
-    execution_process::ExecutionProcessError, project::ProjectError, task_attempt::TaskAttemptError,
+use db::{
+    DBError,
+    models::{
+        execution_process::ExecutionProcessError, project::ProjectError, task::TaskValidationError,
+        task_attempt::TaskAttemptError,
+    },
 };
 use deployment::DeploymentError;
-use executors::executors::ExecutorError;
+use executors::{executors::ExecutorError, profile::ProfileError};
 use git2::Error as Git2Error;
 use services::services::{
     auth::AuthError, config::ConfigError, container::ContainerError, drafts::DraftsServiceError,
@@ -40,8 +44,12 @@ pub enum ApiError {
     #[error(transparent)]
     Executor(#[from] ExecutorError),
     #[error(transparent)]
+    Profile(#[from] ProfileError),
+    #[error(transparent)]
     Database(#[from] sqlx::Error),
     #[error(transparent)]
+    DatabaseService(#[from] DBError),
+    #[error(transparent)]
     Worktree(#[from] WorktreeError),
     #[error(transparent)]
     Config(#[from] ConfigError),
@@ -55,6 +63,8 @@ pub enum ApiError {
     Io(#[from] std::io::Error),
     #[error("Conflict: {0}")]
     Conflict(String),
+    #[error("Bad request: {0}")]
+    BadRequest(String),
 }
 
 impl From<Git2Error> for ApiError {
@@ -63,6 +73,12 @@ impl From<Git2Error> for ApiError {
     }
 }
 
+impl From<TaskValidationError> for ApiError {
+    fn from(err: TaskValidationError) -> Self {
+        ApiError::BadRequest(err.to_string())
+    }
+}
+
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
         let (status_code, error_type) = match &self {
@@ -89,7 +105,21 @@ impl IntoResponse for ApiError {
             ApiError::Deployment(_) => (StatusCode::INTERNAL_SERVER_ERROR, "DeploymentError"),
             ApiError::Container(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ContainerError"),
             ApiError::Executor(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ExecutorError"),
+            ApiError::Profile(profile_err) => match profile_err {
+                ProfileError::UnknownExecutor { .. } | ProfileError::UnknownVariant { .. } => {
+                    (StatusCode::BAD_REQUEST, "ProfileError")
+                }
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "ProfileError"),
+            },
+            // An exhausted pool is transient, so let clients retry instead of reporting a failure
+            ApiError::Database(sqlx::Error::PoolTimedOut) => {
+                (StatusCode::SERVICE_UNAVAILABLE, "DatabaseBusy")
+            }
             ApiError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, "DatabaseError"),
+            ApiError::DatabaseService(DBError::PoolTimeout) => {
+                (StatusCode::SERVICE_UNAVAILABLE, "DatabaseBusy")
+            }
+            ApiError::DatabaseService(_) => (StatusCode::INTERNAL_SERVER_ERROR, "DatabaseError"),
             ApiError::Worktree(_) => (StatusCode::INTERNAL_SERVER_ERROR, "WorktreeError"),
             ApiError::Config(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ConfigError"),
             ApiError::Image(img_err) => match img_err {
@@ -114,6 +144,7 @@ impl IntoResponse for ApiError {
             ApiError::Io(_) => (StatusCode::INTERNAL_SERVER_ERROR, "IoError"),
             ApiError::Multipart(_) => (StatusCode::BAD_REQUEST, "MultipartError"),
             ApiError::Conflict(_) => (StatusCode::CONFLICT, "ConflictError"),
+            ApiError::BadRequest(_) => (StatusCode::BAD_REQUEST, "BadRequest"),
         };
 
         let error_message = match &self {
@@ -138,6 +169,7 @@ impl IntoResponse for ApiError {
             },
             ApiError::Multipart(_) => "Failed to upload file. Please ensure the file is valid and try again.".to_string(),
             ApiError::Conflict(msg) => msg.clone(),
+            ApiError::BadRequest(msg) => msg.clone(),
             ApiError::Drafts(drafts_err) => match drafts_err {
                 DraftsServiceError::Conflict(msg) => msg.clone(),
                 DraftsServiceError::Database(_) => format!("{}: {}", error_type, drafts_err),
@@ -8,6 +8,7 @@ use tracing_subscriber::{EnvFilter, prelude::*};
 use utils::{
     assets::asset_dir,
     browser::open_browser,
+    path::ensure_automagik_forge_temp_dir,
     port_file::write_port_file,
     sentry::{self as sentry_utils, SentrySource, sentry_layer},
 };
@@ -43,9 +44,9 @@ async fn main() -> Result<(), AutomagikForgeError> {
         .init();
 
     // Create asset directory if it doesn't exist
-    if !asset_dir().exists() {
-        std::fs::create_dir_all(asset_dir())?;
-    }
+    asset_dir()?;
+    // Fail early if worktrees and agent logs have nowhere to go
+    ensure_automagik_forge_temp_dir()?;
 
     let deployment = DeploymentImpl::new().await?;
     deployment.update_sentry_scope().await?;
@@ -463,4 +463,8 @@ pub struct CreateTaskAndStartRequest {
     pub base_branch: String,
     #[schemars(description = "Optional parent task attempt UUID")]
     pub parent_task_attempt: Option<Uuid>,
+    #[schemars(
+        description = "Optional client-chosen key. Retrying with the same key returns the task created by the first call instead of creating a duplicate"
+    )]
+    pub idempotency_key: Option<String>,
 }
@@ -1,17 +1,25 @@
 use std::{
     cmp::Ordering,
+    collections::{HashMap, HashSet},
     future::Future,
     path::PathBuf,
     str::FromStr,
     sync::{Arc, RwLock},
+    time::Instant,
 };
 
 use db::models::{
-    project::Project,
+    execution_process::ExecutionProcess,
+    project::{Project, RenameProject, UpdateProjectGitConfig},
     task::{CreateTask, Task, TaskStatus, TaskWithAttemptStatus, UpdateTask},
+    task_assignee::SetTaskAssignee,
     task_attempt::TaskAttempt,
 };
-use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
+use executors::{
+    executors::BaseCodingAgent,
+    process_registry::RunningProcess,
+    profile::{ExecutorConfigs, ExecutorProfileId},
+};
 use rmcp::{
     ErrorData, RoleServer, ServerHandler,
     handler::server::tool::{Parameters, ToolRouter},
@@ -19,20 +27,33 @@ use rmcp::{
         CallToolResult, Content, Implementation, InitializeRequestParam, ProtocolVersion,
         ServerCapabilities, ServerInfo,
     },
-    schemars, tool, tool_handler, tool_router,
+    schemars,
     service::RequestContext,
+    tool, tool_handler, tool_router,
 };
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use serde_json;
+use services::services::github_service::GitHubIssueRef;
 use tracing::info;
+use utils::path::is_contained_relative_path;
 use uuid::Uuid;
 
-use crate::routes::task_attempts::CreateTaskAttemptBody;
+use crate::{
+    mcp::advanced_tools::CreateTaskAndStartRequest,
+    routes::{
+        execution_processes::{RawLogStream, RawLogsPage},
+        health::VERSION_HEADER,
+        projects::ProjectGitConfigResponse,
+        task_attempts::{
+            AttemptDiff, AttemptFile, BranchStatus, CreateFollowUpAttempt, CreateTaskAttemptBody,
+            GitOperationError, RebaseTaskAttemptRequest,
+        },
+        tasks::{CreateAndStartTaskRequest, CreateTaskFromGitHubIssue, MoveTask},
+    },
+};
 
-const SUPPORTED_PROTOCOL_VERSIONS: [ProtocolVersion; 2] = [
-    ProtocolVersion::V_2025_03_26,
-    ProtocolVersion::V_2024_11_05,
-];
+const SUPPORTED_PROTOCOL_VERSIONS: [ProtocolVersion; 2] =
+    [ProtocolVersion::V_2025_03_26, ProtocolVersion::V_2024_11_05];
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct CreateTaskRequest {
@@ -42,6 +63,14 @@ pub struct CreateTaskRequest {
     pub title: String,
     #[schemars(description = "Optional description of the task")]
     pub description: Option<String>,
+    #[schemars(
+        description = "Optional client-chosen key. Retrying with the same key returns the task created by the first call instead of creating a duplicate"
+    )]
+    pub idempotency_key: Option<String>,
+    #[schemars(
+        description = "Optional IDs of tasks in the same project that have to be done before this one is ready"
+    )]
+    pub depends_on: Option<Vec<Uuid>>,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
@@ -49,6 +78,23 @@ pub struct CreateTaskResponse {
     pub task_id: String,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct CreateTaskFromGitHubIssueRequest {
+    #[schemars(description = "The ID of the project to create the task in. This is required!")]
+    pub project_id: Uuid,
+    #[schemars(
+        description = "URL of the GitHub issue, e.g. https://github.com/owner/repo/issues/42"
+    )]
+    pub issue_url: String,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct CreateTaskFromGitHubIssueResponse {
+    pub task_id: String,
+    #[schemars(description = "Title of the created task, taken from the issue")]
+    pub title: String,
+}
+
 #[derive(Debug, Serialize, schemars::JsonSchema)]
 pub struct ProjectSummary {
     #[schemars(description = "The unique identifier of the project")]
@@ -90,6 +136,62 @@ pub struct ListProjectsResponse {
     pub count: usize,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetProjectGitConfigRequest {
+    #[schemars(description = "The ID of the project whose git settings to return")]
+    pub project_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct UpdateProjectGitConfigRequest {
+    #[schemars(description = "The ID of the project to configure")]
+    pub project_id: Uuid,
+    #[schemars(
+        description = "Branch that PRs target when an attempt has no target branch of its own. Pass an empty string to clear it"
+    )]
+    pub default_base_branch: Option<String>,
+    #[schemars(
+        description = "Remote that branches are pushed to and PRs are opened from. Must be one of the repository's remotes; pass an empty string to go back to the default"
+    )]
+    pub remote_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct RenameProjectRequest {
+    #[schemars(description = "The ID of the project to rename")]
+    pub project_id: Uuid,
+    #[schemars(description = "The new project name. Must not be empty")]
+    pub new_name: String,
+    #[schemars(
+        description = "Optional new default PR base branch, set in the same transaction as the name"
+    )]
+    pub default_base_branch: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ProjectGitConfigSummary {
+    pub project_id: String,
+    #[schemars(description = "Configured default PR base branch, if any")]
+    pub default_base_branch: Option<String>,
+    #[schemars(
+        description = "Configured remote, if any. When unset, the repository's first remote is used"
+    )]
+    pub remote_name: Option<String>,
+    #[schemars(description = "Remotes defined in the project's repository")]
+    pub remotes: Vec<String>,
+}
+
+impl ProjectGitConfigSummary {
+    fn from_response(project_id: Uuid, response: ProjectGitConfigResponse) -> Self {
+        Self {
+            project_id: project_id.to_string(),
+            default_base_branch: response.default_base_branch,
+            remote_name: response.remote_name,
+            remotes: response.remotes,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct ListTasksRequest {
     #[schemars(description = "The ID of the project to list tasks from")]
@@ -100,6 +202,14 @@ pub struct ListTasksRequest {
     pub status: Option<String>,
     #[schemars(description = "Maximum number of tasks to return (default: 50)")]
     pub limit: Option<i32>,
+    #[schemars(
+        description = "Optional readiness filter: true returns only tasks whose dependencies are all done, false only tasks still blocked by one"
+    )]
+    pub ready: Option<bool>,
+    #[schemars(
+        description = "Optional assignee filter: returns only tasks assigned to this name, or only unassigned tasks when empty"
+    )]
+    pub assignee: Option<String>,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
@@ -120,10 +230,16 @@ pub struct TaskSummary {
     pub has_merged_attempt: Option<bool>,
     #[schemars(description = "Whether the last execution attempt failed")]
     pub last_attempt_failed: Option<bool>,
+    #[schemars(description = "IDs of the tasks that have to be done before this one")]
+    pub depends_on: Vec<String>,
+    #[schemars(description = "Whether every task this one depends on is done")]
+    pub ready: bool,
+    #[schemars(description = "Who is working on the task, if anyone claimed it")]
+    pub assignee: Option<String>,
 }
 
 impl TaskSummary {
-    fn from_task_with_status(task: TaskWithAttemptStatus) -> Self {
+    fn from_task_with_status(task: TaskWithAttemptStatus, ready: bool) -> Self {
         Self {
             id: task.id.to_string(),
             title: task.title.to_string(),
@@ -133,6 +249,9 @@ impl TaskSummary {
             has_in_progress_attempt: Some(task.has_in_progress_attempt),
             has_merged_attempt: Some(task.has_merged_attempt),
             last_attempt_failed: Some(task.last_attempt_failed),
+            depends_on: task.depends_on.iter().map(Uuid::to_string).collect(),
+            ready,
+            assignee: task.assignee,
         }
     }
 }
@@ -157,10 +276,14 @@ pub struct TaskDetails {
     pub has_merged_attempt: Option<bool>,
     #[schemars(description = "Whether the last execution attempt failed")]
     pub last_attempt_failed: Option<bool>,
+    #[schemars(description = "IDs of the tasks that have to be done before this one")]
+    pub depends_on: Vec<String>,
+    #[schemars(description = "Who is working on the task, if anyone claimed it")]
+    pub assignee: Option<String>,
 }
 
 impl TaskDetails {
-    fn from_task(task: Task) -> Self {
+    fn from_task(task: Task, depends_on: Vec<Uuid>, assignee: Option<String>) -> Self {
         Self {
             id: task.id.to_string(),
             title: task.title,
@@ -171,6 +294,8 @@ impl TaskDetails {
             has_in_progress_attempt: None,
             has_merged_attempt: None,
             last_attempt_failed: None,
+            depends_on: depends_on.iter().map(Uuid::to_string).collect(),
+            assignee,
         }
     }
 }
@@ -187,6 +312,8 @@ pub struct ListTasksResponse {
 pub struct ListTasksFilters {
     pub status: Option<String>,
     pub limit: i32,
+    pub ready: Option<bool>,
+    pub assignee: Option<String>,
 }
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
@@ -199,6 +326,10 @@ pub struct UpdateTaskRequest {
     pub description: Option<String>,
     #[schemars(description = "New status: 'todo', 'inprogress', 'inreview', 'done', 'cancelled'")]
     pub status: Option<String>,
+    #[schemars(
+        description = "Replaces the IDs of tasks in the same project that have to be done first. Pass an empty list to remove all dependencies"
+    )]
+    pub depends_on: Option<Vec<Uuid>>,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
@@ -206,6 +337,36 @@ pub struct UpdateTaskResponse {
     pub task: TaskDetails,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct AssignTaskRequest {
+    #[schemars(description = "The ID of the task to assign")]
+    pub task_id: Uuid,
+    #[schemars(
+        description = "Who takes the task, e.g. an agent name. Omit or pass an empty string to unassign it"
+    )]
+    pub assignee: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct AssignTaskResponse {
+    pub task: TaskDetails,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct MoveTaskRequest {
+    #[schemars(description = "The ID of the task to move")]
+    pub task_id: Uuid,
+    #[schemars(description = "The ID of the project to move the task to")]
+    pub target_project_id: Uuid,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct MoveTaskResponse {
+    pub task: TaskDetails,
+    #[schemars(description = "The project the task was moved from")]
+    pub previous_project_id: String,
+}
+
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct DeleteTaskRequest {
     #[schemars(description = "The ID of the task to delete")]
@@ -224,6 +385,18 @@ pub struct StartTaskAttemptRequest {
     pub variant: Option<String>,
     #[schemars(description = "The base branch to use for the attempt")]
     pub base_branch: String,
+    #[schemars(
+        description = "Optional exact name for the attempt branch. Must be a valid git branch name"
+    )]
+    pub branch_name: Option<String>,
+    #[schemars(
+        description = "Optional template for the attempt branch name, e.g. 'feature/{task_id}-{slug}'. Placeholders: {prefix}, {task_id}, {attempt_id}, {slug}. Cannot be combined with `branch_name`"
+    )]
+    pub branch_template: Option<String>,
+    #[schemars(
+        description = "Run the attempt in its own git worktree (default: true). When false the agent works directly in the project's repository checkout, with no isolation from local changes or other attempts"
+    )]
+    pub use_worktree: Option<bool>,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
@@ -232,11 +405,44 @@ pub struct StartTaskAttemptResponse {
     pub attempt_id: String,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct FollowUpRequest {
+    #[schemars(description = "The ID of the task attempt to continue")]
+    pub attempt_id: Uuid,
+    #[schemars(description = "The follow-up message for the coding agent")]
+    pub prompt: String,
+    #[schemars(description = "Optional executor variant, if needed")]
+    pub variant: Option<String>,
+    #[schemars(
+        description = "Optional IDs of previously uploaded images to attach to the follow-up. Each must be a UUID"
+    )]
+    pub image_ids: Option<Vec<Uuid>>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct FollowUpResponse {
+    pub attempt_id: String,
+    pub process_id: String,
+}
+
 #[derive(Debug, Serialize, schemars::JsonSchema)]
 pub struct DeleteTaskResponse {
     pub deleted_task_id: Option<String>,
 }
 
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct PingResponse {
+    #[schemars(description = "Whether the backend answered the health check")]
+    pub ok: bool,
+    #[schemars(description = "Version reported by the backend, if available")]
+    pub backend_version: Option<String>,
+    #[schemars(description = "Round-trip time of the health check in milliseconds")]
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(description = "Why the backend could not be reached")]
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct GetTaskRequest {
     #[schemars(description = "The ID of the task to retrieve")]
@@ -248,12 +454,397 @@ pub struct GetTaskResponse {
     pub task: TaskDetails,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetAttemptDiffRequest {
+    #[schemars(description = "The ID of the task attempt whose changes to return")]
+    pub attempt_id: Uuid,
+    #[schemars(description = "Lines of context around each change (default: 3)")]
+    pub context_lines: Option<usize>,
+    #[schemars(
+        description = "Return one diff per file instead of a single combined diff (default: false)"
+    )]
+    pub per_file: Option<bool>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct AttemptFileDiffSummary {
+    #[schemars(description = "Path of the changed file")]
+    pub path: String,
+    #[schemars(
+        description = "Kind of change: added, deleted, modified, renamed, copied or permissionChange"
+    )]
+    pub change: String,
+    #[schemars(description = "Unified diff of the file; null when its contents were omitted")]
+    pub unified_diff: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct GetAttemptDiffResponse {
+    pub attempt_id: String,
+    #[schemars(description = "Number of changed files")]
+    pub files_changed: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        description = "Combined unified diff of all files, unless `per_file` was requested"
+    )]
+    pub diff: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(description = "Per-file diffs, when `per_file` was requested")]
+    pub files: Option<Vec<AttemptFileDiffSummary>>,
+}
+
+impl GetAttemptDiffResponse {
+    fn from_attempt_diff(attempt_id: Uuid, diff: AttemptDiff, per_file: bool) -> Self {
+        let files_changed = diff.files.len();
+        let files: Vec<AttemptFileDiffSummary> = diff
+            .files
+            .into_iter()
+            .map(|file| AttemptFileDiffSummary {
+                change: serde_json::to_value(&file.change)
+                    .ok()
+                    .and_then(|v| v.as_str().map(str::to_string))
+                    .unwrap_or_default(),
+                unified_diff: file.unified_diff,
+                path: file.path,
+            })
+            .collect();
+
+        let (diff, files) = if per_file {
+            (None, Some(files))
+        } else {
+            let combined = files
+                .iter()
+                .map(|file| match &file.unified_diff {
+                    Some(d) => d.clone(),
+                    None => format!("# {} ({}): contents omitted\n", file.path, file.change),
+                })
+                .collect::<String>();
+            (Some(combined), None)
+        };
+
+        Self {
+            attempt_id: attempt_id.to_string(),
+            files_changed,
+            diff,
+            files,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetProcessLogsRequest {
+    #[schemars(description = "The ID of the execution process whose logs to return")]
+    pub process_id: Uuid,
+    #[schemars(
+        description = "Only return chunks from this offset on. Pass the `next_offset` of the previous call to receive only new output (default: 0)"
+    )]
+    pub after_offset: Option<usize>,
+    #[schemars(description = "Maximum number of chunks to return (default: 500)")]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ProcessLogChunkSummary {
+    pub offset: usize,
+    #[schemars(description = "`stdout` or `stderr`")]
+    pub stream: String,
+    pub content: String,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct GetProcessLogsResponse {
+    pub process_id: String,
+    pub chunks: Vec<ProcessLogChunkSummary>,
+    #[schemars(
+        description = "Pass as `after_offset` on the next call to continue where this one ended"
+    )]
+    pub next_offset: usize,
+    #[schemars(
+        description = "True once the process has exited and all of its output was returned"
+    )]
+    pub finished: bool,
+}
+
+impl GetProcessLogsResponse {
+    fn from_page(process_id: Uuid, page: RawLogsPage) -> Self {
+        Self {
+            process_id: process_id.to_string(),
+            chunks: page
+                .chunks
+                .into_iter()
+                .map(|chunk| ProcessLogChunkSummary {
+                    offset: chunk.offset,
+                    stream: match chunk.stream {
+                        RawLogStream::Stdout => "stdout",
+                        RawLogStream::Stderr => "stderr",
+                    }
+                    .to_string(),
+                    content: chunk.content,
+                })
+                .collect(),
+            next_offset: page.next_offset,
+            finished: page.finished,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetAttemptStatusRequest {
+    #[schemars(description = "The ID of the task attempt to check")]
+    pub attempt_id: Uuid,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct GetAttemptStatusResponse {
+    pub attempt_id: String,
+    #[schemars(description = "The attempt's most recent execution process, if any was created")]
+    pub latest_process: Option<AttemptProcessStatus>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct AttemptProcessStatus {
+    pub process_id: String,
+    #[schemars(
+        description = "Why the process ran: `setupscript`, `cleanupscript`, `codingagent` or `devserver`"
+    )]
+    pub run_reason: String,
+    #[schemars(
+        description = "`queued` while waiting for a free executor slot, then `running`, `completed`, `failed` or `killed`"
+    )]
+    pub status: String,
+    pub started_at: String,
+    pub completed_at: Option<String>,
+    pub exit_code: Option<i64>,
+}
+
+impl AttemptProcessStatus {
+    fn from_process(process: ExecutionProcess) -> Self {
+        Self {
+            process_id: process.id.to_string(),
+            run_reason: process.run_reason.to_string(),
+            status: process.status.to_string(),
+            started_at: process.started_at.to_rfc3339(),
+            completed_at: process.completed_at.map(|t| t.to_rfc3339()),
+            exit_code: process.exit_code,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ListProcessesResponse {
+    pub processes: Vec<RunningProcessSummary>,
+    pub count: usize,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct RunningProcessSummary {
+    pub process_id: String,
+    pub attempt_id: String,
+    #[schemars(description = "The coding agent running in the process, or null for scripts")]
+    pub executor: Option<String>,
+    #[schemars(description = "OS process id of the process group leader")]
+    pub pid: Option<u32>,
+    pub started_at: String,
+    #[schemars(description = "Resident memory in bytes, if the OS could be sampled")]
+    pub rss_bytes: Option<u64>,
+    #[schemars(
+        description = "Average CPU use since the process started, where 100 is one full core"
+    )]
+    pub cpu_percent: Option<f64>,
+}
+
+impl RunningProcessSummary {
+    fn from_process(process: RunningProcess) -> Self {
+        Self {
+            process_id: process.execution_process_id.to_string(),
+            attempt_id: process.task_attempt_id.to_string(),
+            executor: process.executor,
+            pid: process.pid,
+            started_at: process.started_at.to_rfc3339(),
+            rss_bytes: process.rss_bytes,
+            cpu_percent: process.cpu_percent,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct RetargetAndRebaseRequest {
+    #[schemars(description = "The ID of the task attempt to retarget")]
+    pub attempt_id: Uuid,
+    #[schemars(description = "The branch the attempt should be based on from now on")]
+    pub target_branch: String,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct RetargetAndRebaseResponse {
+    pub attempt_id: String,
+    pub previous_target_branch: String,
+    pub target_branch: String,
+    #[schemars(description = "True if the attempt branch now sits on top of `target_branch`")]
+    pub rebased: bool,
+    #[schemars(
+        description = "True if the rebase stopped on conflicts. Resolve them in the worktree or call `abort_conflicts`"
+    )]
+    pub has_conflicts: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub conflicted_files: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetBranchStatusRequest {
+    #[schemars(description = "The ID of the task attempt whose branch to inspect")]
+    pub attempt_id: Uuid,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct BranchStatusSummary {
+    pub attempt_id: String,
+    pub target_branch: String,
+    #[schemars(description = "Commits on the attempt branch that are not on `target_branch`")]
+    pub ahead: usize,
+    #[schemars(description = "Commits on `target_branch` that the attempt branch does not have")]
+    pub behind: usize,
+    #[schemars(
+        description = "True if the attempt branch already contains everything on `target_branch`"
+    )]
+    pub up_to_date: bool,
+    #[schemars(
+        description = "True if a rebase or merge stopped on conflicts. Resolve them in the worktree or call `abort_conflicts`"
+    )]
+    pub has_conflicts: bool,
+    #[schemars(
+        description = "Operation that stopped on conflicts: rebase, merge, cherry_pick or revert"
+    )]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conflict_op: Option<String>,
+    pub conflicted_files: Vec<String>,
+    #[schemars(description = "Full branch status as returned by the backend")]
+    pub raw: serde_json::Value,
+}
+
+impl BranchStatusSummary {
+    /// Reads the fields agents act on out of the backend's branch status, keeping the payload
+    /// itself under `raw` so fields added later stay reachable.
+    fn from_raw(attempt_id: Uuid, raw: serde_json::Value) -> Result<Self, serde_json::Error> {
+        let status: BranchStatus = serde_json::from_value(raw.clone())?;
+        let behind = status.commits_behind.unwrap_or(0);
+        let conflict_op = status
+            .conflict_op
+            .and_then(|op| serde_json::to_value(op).ok())
+            .and_then(|op| op.as_str().map(str::to_string));
+        Ok(Self {
+            attempt_id: attempt_id.to_string(),
+            target_branch: status.target_branch_name,
+            ahead: status.commits_ahead.unwrap_or(0),
+            behind,
+            up_to_date: behind == 0,
+            has_conflicts: conflict_op.is_some() || !status.conflicted_files.is_empty(),
+            conflict_op,
+            conflicted_files: status.conflicted_files,
+            raw,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct AbortConflictsRequest {
+    #[schemars(description = "The ID of the task attempt whose rebase or merge to abort")]
+    pub attempt_id: Uuid,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct AbortConflictsResponse {
+    pub attempt_id: String,
+    pub aborted: bool,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetCommitInfoRequest {
+    #[schemars(description = "The ID of the task attempt whose worktree contains the commit")]
+    pub attempt_id: Uuid,
+    #[schemars(description = "Full SHA of the commit to describe")]
+    pub sha: String,
+}
+
+/// Commit metadata as returned by the backend's `CommitInfo`.
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct CommitInfoSummary {
+    pub sha: String,
+    pub subject: String,
+    pub author: String,
+    pub email: String,
+    #[schemars(description = "Commit time (RFC 3339)")]
+    pub timestamp: String,
+    #[schemars(description = "Full commit message, subject included")]
+    pub message: String,
+    pub files_changed: usize,
+    #[serde(flatten)]
+    #[schemars(description = "Any further fields the backend returns, passed through as is")]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetAttemptFileRequest {
+    #[schemars(description = "The ID of the task attempt whose worktree to read from")]
+    pub attempt_id: Uuid,
+    #[schemars(description = "Path of the file relative to the attempt's worktree")]
+    pub file_path: String,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct GetAttemptFileResponse {
+    pub attempt_id: String,
+    pub file_path: String,
+    pub size: u64,
+    #[schemars(description = "True when the file is not UTF-8 text; `content` is then null")]
+    pub is_binary: bool,
+    pub content: Option<String>,
+}
+
+/// Machine-readable error category returned in the `code` field of tool errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolErrorCode {
+    NotFound,
+    InvalidArg,
+    BackendUnavailable,
+    Timeout,
+    Conflict,
+    Internal,
+}
+
+impl ToolErrorCode {
+    fn from_status(status: reqwest::StatusCode) -> Self {
+        use reqwest::StatusCode;
+        match status {
+            StatusCode::NOT_FOUND => Self::NotFound,
+            StatusCode::CONFLICT => Self::Conflict,
+            StatusCode::REQUEST_TIMEOUT | StatusCode::GATEWAY_TIMEOUT => Self::Timeout,
+            StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE => Self::BackendUnavailable,
+            s if s.is_client_error() => Self::InvalidArg,
+            _ => Self::Internal,
+        }
+    }
+
+    fn from_reqwest_error(error: &reqwest::Error) -> Self {
+        if error.is_timeout() {
+            Self::Timeout
+        } else {
+            Self::BackendUnavailable
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct TaskServer {
     client: reqwest::Client,
     base_url: String,
     tool_router: ToolRouter<TaskServer>,
     negotiated_protocol_version: Arc<RwLock<ProtocolVersion>>,
+    /// When set, only these projects are visible to and mutable by MCP clients.
+    allowed_projects: Option<Arc<HashSet<Uuid>>>,
 }
 
 impl TaskServer {
@@ -263,8 +854,16 @@ impl TaskServer {
             base_url: base_url.to_string(),
             tool_router: Self::tool_router(),
             negotiated_protocol_version: Arc::new(RwLock::new(Self::latest_supported_protocol())),
+            allowed_projects: None,
         }
     }
+
+    /// Restrict the server to the given projects. Other projects are hidden from
+    /// `list_projects` and every operation targeting them is rejected.
+    pub fn with_allowed_projects(mut self, project_ids: impl IntoIterator<Item = Uuid>) -> Self {
+        self.allowed_projects = Some(Arc::new(project_ids.into_iter().collect()));
+        self
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -274,6 +873,14 @@ struct ApiResponseEnvelope<T> {
     message: Option<String>,
 }
 
+/// Envelope of git operations that report conflicts through `error_data`.
+#[derive(Debug, Deserialize)]
+struct GitOperationEnvelope {
+    success: bool,
+    error_data: Option<GitOperationError>,
+    message: Option<String>,
+}
+
 impl TaskServer {
     fn success<T: Serialize>(data: &T) -> Result<CallToolResult, ErrorData> {
         Ok(CallToolResult::success(vec![Content::text(
@@ -289,42 +896,229 @@ impl TaskServer {
         )]))
     }
 
-    fn err<S: Into<String>>(msg: S, details: Option<S>) -> Result<CallToolResult, ErrorData> {
-        let mut v = serde_json::json!({"success": false, "error": msg.into()});
+    fn err<S: Into<String>>(
+        code: ToolErrorCode,
+        msg: S,
+        details: Option<S>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let mut v = serde_json::json!({"success": false, "code": code, "error": msg.into()});
         if let Some(d) = details {
             v["details"] = serde_json::json!(d.into());
         };
         Self::err_value(v)
     }
 
+    /// Parses a tool's executor name (case and dash insensitive) and optional variant into a
+    /// profile id known to the current executor configs.
+    fn resolve_executor_profile(
+        executor: &str,
+        variant: Option<String>,
+    ) -> Result<ExecutorProfileId, String> {
+        let executor = executor.trim();
+        if executor.is_empty() {
+            return Err("Executor must not be empty.".to_string());
+        }
+
+        let normalized_executor = executor.replace('-', "_").to_ascii_uppercase();
+        let base_executor = BaseCodingAgent::from_str(&normalized_executor)
+            .map_err(|_| format!("Unknown executor '{executor}'."))?;
+
+        let variant = variant.and_then(|v| {
+            let trimmed = v.trim();
+            if trimmed.is_empty() {
+                None
+            } else {
+                Some(trimmed.to_string())
+            }
+        });
+
+        ExecutorProfileId {
+            executor: base_executor,
+            variant,
+        }
+        .resolve(&ExecutorConfigs::get_cached())
+        .map_err(|e| e.to_string())
+    }
+
     async fn send_json<T: DeserializeOwned>(
         &self,
         rb: reqwest::RequestBuilder,
     ) -> Result<T, CallToolResult> {
-        let resp = rb
-            .send()
-            .await
-            .map_err(|e| Self::err("Failed to connect to AF API", Some(&e.to_string())).unwrap())?;
+        let resp = rb.send().await.map_err(|e| {
+            Self::err(
+                ToolErrorCode::from_reqwest_error(&e),
+                "Failed to connect to AF API",
+                Some(&e.to_string()),
+            )
+            .unwrap()
+        })?;
 
-        if !resp.status().is_success() {
-            let status = resp.status();
-            return Err(
-                Self::err(format!("AF API returned error status: {}", status), None).unwrap(),
-            );
+        let status = resp.status();
+        if !status.is_success() {
+            // Error bodies usually carry the backend's message in the standard envelope
+            let message = resp
+                .json::<ApiResponseEnvelope<serde_json::Value>>()
+                .await
+                .ok()
+                .and_then(|r| r.message);
+            return Err(Self::err(
+                ToolErrorCode::from_status(status),
+                format!("AF API returned error status: {}", status),
+                message,
+            )
+            .unwrap());
         }
 
         let api_response = resp.json::<ApiResponseEnvelope<T>>().await.map_err(|e| {
-            Self::err("Failed to parse AF API response", Some(&e.to_string())).unwrap()
+            Self::err(
+                ToolErrorCode::Internal,
+                "Failed to parse AF API response",
+                Some(&e.to_string()),
+            )
+            .unwrap()
         })?;
 
         if !api_response.success {
             let msg = api_response.message.as_deref().unwrap_or("Unknown error");
-            return Err(Self::err("AF API returned error", Some(msg)).unwrap());
+            return Err(
+                Self::err(ToolErrorCode::Internal, "AF API returned error", Some(msg)).unwrap(),
+            );
+        }
+
+        api_response.data.ok_or_else(|| {
+            Self::err(
+                ToolErrorCode::Internal,
+                "AF API response missing data field",
+                None,
+            )
+            .unwrap()
+        })
+    }
+
+    /// Like [`Self::send_json`] for endpoints without a payload, passing a reported
+    /// [`GitOperationError`] back to the caller instead of turning it into a tool error.
+    async fn send_git_operation(
+        &self,
+        rb: reqwest::RequestBuilder,
+    ) -> Result<Result<(), GitOperationError>, CallToolResult> {
+        let resp = rb.send().await.map_err(|e| {
+            Self::err(
+                ToolErrorCode::from_reqwest_error(&e),
+                "Failed to connect to AF API",
+                Some(&e.to_string()),
+            )
+            .unwrap()
+        })?;
+
+        let status = resp.status();
+        let envelope = resp.json::<GitOperationEnvelope>().await.ok();
+        if !status.is_success() {
+            return Err(Self::err(
+                ToolErrorCode::from_status(status),
+                format!("AF API returned error status: {}", status),
+                envelope.and_then(|e| e.message),
+            )
+            .unwrap());
+        }
+
+        match envelope {
+            Some(envelope) if envelope.success => Ok(Ok(())),
+            Some(GitOperationEnvelope {
+                error_data: Some(error),
+                ..
+            }) => Ok(Err(error)),
+            Some(GitOperationEnvelope { message, .. }) => Err(Self::err(
+                ToolErrorCode::InvalidArg,
+                "AF API returned error".to_string(),
+                Some(message.unwrap_or_else(|| "Unknown error".to_string())),
+            )
+            .unwrap()),
+            None => Err(Self::err(
+                ToolErrorCode::Internal,
+                "Failed to parse AF API response",
+                None,
+            )
+            .unwrap()),
+        }
+    }
+
+    fn is_project_allowed(&self, project_id: &Uuid) -> bool {
+        self.allowed_projects
+            .as_ref()
+            .is_none_or(|allowed| allowed.contains(project_id))
+    }
+
+    fn ensure_project_allowed(&self, project_id: Uuid) -> Result<(), CallToolResult> {
+        if self.is_project_allowed(&project_id) {
+            return Ok(());
+        }
+        Err(Self::err(
+            ToolErrorCode::NotFound,
+            format!("Project {project_id} is not accessible through this MCP server"),
+            None,
+        )
+        .unwrap())
+    }
+
+    async fn task_dependencies(&self, task_id: Uuid) -> Result<Vec<Uuid>, CallToolResult> {
+        let url = self.url(&format!("/api/tasks/{}/dependencies", task_id));
+        self.send_json(self.client.get(&url)).await
+    }
+
+    async fn task_assignee(&self, task_id: Uuid) -> Result<Option<String>, CallToolResult> {
+        let url = self.url(&format!("/api/tasks/{}/assignee", task_id));
+        self.send_json(self.client.get(&url)).await
+    }
+
+    /// Looks up the task's project when an allowlist is configured; a no-op otherwise.
+    async fn ensure_task_allowed(&self, task_id: Uuid) -> Result<(), CallToolResult> {
+        if self.allowed_projects.is_none() {
+            return Ok(());
+        }
+        let url = self.url(&format!("/api/tasks/{}", task_id));
+        let task: Task = self.send_json(self.client.get(&url)).await?;
+        self.ensure_project_allowed(task.project_id)
+    }
+
+    /// Looks up the attempt's task when an allowlist is configured; a no-op otherwise.
+    async fn ensure_attempt_allowed(&self, attempt_id: Uuid) -> Result<(), CallToolResult> {
+        if self.allowed_projects.is_none() {
+            return Ok(());
+        }
+        let url = self.url(&format!("/api/task-attempts/{}", attempt_id));
+        let attempt: TaskAttempt = self.send_json(self.client.get(&url)).await?;
+        self.ensure_task_allowed(attempt.task_id).await
+    }
+
+    /// Looks up the process' attempt when an allowlist is configured; a no-op otherwise.
+    async fn ensure_process_allowed(&self, process_id: Uuid) -> Result<(), CallToolResult> {
+        if self.allowed_projects.is_none() {
+            return Ok(());
         }
+        let url = self.url(&format!("/api/execution-processes/{}", process_id));
+        let process: ExecutionProcess = self.send_json(self.client.get(&url)).await?;
+        self.ensure_attempt_allowed(process.task_attempt_id).await
+    }
 
-        api_response
-            .data
-            .ok_or_else(|| Self::err("AF API response missing data field", None).unwrap())
+    /// Rejects paths that could leave the directory they are resolved against before anything
+    /// is sent, so a bad path fails with a clear message whatever the backend would make of it.
+    fn ensure_relative_path(field: &str, path: &str) -> Result<(), CallToolResult> {
+        if is_contained_relative_path(path) {
+            return Ok(());
+        }
+        let reason = if std::path::Path::new(path).has_root() {
+            "must be relative, not absolute"
+        } else if path.trim().is_empty() {
+            "must not be empty"
+        } else {
+            "must not contain '..'"
+        };
+        Err(Self::err(
+            ToolErrorCode::InvalidArg,
+            format!("`{field}` {reason}: {path}"),
+            None,
+        )
+        .unwrap())
     }
 
     fn url(&self, path: &str) -> String {
@@ -376,14 +1170,11 @@ impl TaskServer {
                 name: "automagik-forge".to_string(),
                 version: "1.0.0".to_string(),
             },
-            instructions: Some("A task and project management server. If you need to create or update tickets or tasks then use these tools. Most of them absolutely require that you pass the `project_id` of the project that you are currently working on. This should be provided to you. Call `list_tasks` to fetch the `task_ids` of all the tasks in a project`. TOOLS: 'list_projects', 'list_tasks', 'create_task', 'start_task_attempt', 'get_task', 'update_task', 'delete_task'. Make sure to pass `project_id` or `task_id` where required. You can use list tools to get the available ids.".to_string()),
+            instructions: Some("A task and project management server. If you need to create or update tickets or tasks then use these tools. Most of them absolutely require that you pass the `project_id` of the project that you are currently working on. This should be provided to you. Call `list_tasks` to fetch the `task_ids` of all the tasks in a project`. TOOLS: 'list_projects', 'get_project_git_config', 'update_project_git_config', 'rename_project', 'list_tasks', 'create_task', 'create_task_from_github_issue', 'create_task_and_start', 'start_task_attempt', 'follow_up', 'get_task', 'update_task', 'assign_task', 'move_task', 'delete_task', 'get_attempt_diff', 'get_attempt_file', 'get_commit_info', 'get_attempt_status', 'list_processes', 'get_process_logs', 'get_branch_status', 'retarget_and_rebase', 'abort_conflicts', 'get_config_schema', 'ping'. Make sure to pass `project_id` or `task_id` where required. You can use list tools to get the available ids.".to_string()),
         }
     }
 
-    fn log_downgrade_if_needed(
-        requested: &ProtocolVersion,
-        negotiated: &ProtocolVersion,
-    ) {
+    fn log_downgrade_if_needed(requested: &ProtocolVersion, negotiated: &ProtocolVersion) {
         let latest = Self::latest_supported_protocol();
         if negotiated != &latest {
             info!(
@@ -416,7 +1207,7 @@ impl TaskServer {
                                 .map(|v| v.to_string())
                                 .collect::<Vec<_>>(),
                         })),
-                    ))
+                    ));
                 }
             }
         }
@@ -453,21 +1244,23 @@ impl TaskServer {
             project_id,
             title,
             description,
+            idempotency_key,
+            depends_on,
         }): Parameters<CreateTaskRequest>,
     ) -> Result<CallToolResult, ErrorData> {
+        let mut payload =
+            match CreateTask::try_from_title_description(project_id, title, description) {
+                Ok(payload) => payload,
+                Err(e) => return Self::err(ToolErrorCode::InvalidArg, e.to_string(), None),
+            };
+        payload.depends_on = depends_on;
+        payload.idempotency_key = idempotency_key;
+        if let Err(e) = self.ensure_project_allowed(project_id) {
+            return Ok(e);
+        }
+
         let url = self.url("/api/tasks");
-        let task: Task = match self
-            .send_json(
-                self.client
-                    .post(&url)
-                    .json(&CreateTask::from_title_description(
-                        project_id,
-                        title,
-                        description,
-                    )),
-            )
-            .await
-        {
+        let task: Task = match self.send_json(self.client.post(&url).json(&payload)).await {
             Ok(t) => t,
             Err(e) => return Ok(e),
         };
@@ -477,6 +1270,95 @@ impl TaskServer {
         })
     }
 
+    #[tool(
+        description = "Create a task and immediately start an attempt on it with the given executor. `project_id`, `title`, `executor` and `base_branch` are required!"
+    )]
+    async fn create_task_and_start(
+        &self,
+        Parameters(CreateTaskAndStartRequest {
+            project_id,
+            title,
+            description,
+            executor,
+            variant,
+            base_branch,
+            parent_task_attempt,
+            idempotency_key,
+        }): Parameters<CreateTaskAndStartRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let mut task = match CreateTask::try_from_title_description(project_id, title, description)
+        {
+            Ok(task) => task,
+            Err(e) => return Self::err(ToolErrorCode::InvalidArg, e.to_string(), None),
+        };
+        task.parent_task_attempt = parent_task_attempt;
+        task.idempotency_key = idempotency_key;
+        let base_branch = base_branch.trim().to_string();
+        if base_branch.is_empty() {
+            return Self::err(
+                ToolErrorCode::InvalidArg,
+                "Base branch must not be empty.".to_string(),
+                None::<String>,
+            );
+        }
+        let executor_profile_id = match Self::resolve_executor_profile(&executor, variant) {
+            Ok(id) => id,
+            Err(e) => return Self::err(ToolErrorCode::InvalidArg, e, None::<String>),
+        };
+        if let Err(e) = self.ensure_project_allowed(project_id) {
+            return Ok(e);
+        }
+
+        let url = self.url("/api/tasks/create-and-start");
+        let payload = CreateAndStartTaskRequest {
+            task,
+            executor_profile_id,
+            base_branch,
+        };
+        let task: TaskWithAttemptStatus =
+            match self.send_json(self.client.post(&url).json(&payload)).await {
+                Ok(t) => t,
+                Err(e) => return Ok(e),
+            };
+
+        TaskServer::success(&CreateTaskResponse {
+            task_id: task.task.id.to_string(),
+        })
+    }
+
+    #[tool(
+        description = "Create a task from a GitHub issue. The task gets the issue's title, and its description holds the issue body plus a link back to the issue. Requires GitHub to be connected in the backend. `project_id` and `issue_url` are required!"
+    )]
+    async fn create_task_from_github_issue(
+        &self,
+        Parameters(CreateTaskFromGitHubIssueRequest {
+            project_id,
+            issue_url,
+        }): Parameters<CreateTaskFromGitHubIssueRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        if let Err(e) = self.ensure_project_allowed(project_id) {
+            return Ok(e);
+        }
+        if let Err(e) = GitHubIssueRef::from_url(&issue_url) {
+            return Self::err(ToolErrorCode::InvalidArg, e.to_string(), None);
+        }
+
+        let url = self.url("/api/tasks/from-github-issue");
+        let payload = CreateTaskFromGitHubIssue {
+            project_id,
+            issue_url,
+        };
+        let task: Task = match self.send_json(self.client.post(&url).json(&payload)).await {
+            Ok(t) => t,
+            Err(e) => return Ok(e),
+        };
+
+        TaskServer::success(&CreateTaskFromGitHubIssueResponse {
+            task_id: task.id.to_string(),
+            title: task.title,
+        })
+    }
+
     #[tool(description = "List all the available projects")]
     async fn list_projects(&self) -> Result<CallToolResult, ErrorData> {
         let url = self.url("/api/projects");
@@ -487,6 +1369,7 @@ impl TaskServer {
 
         let project_summaries: Vec<ProjectSummary> = projects
             .into_iter()
+            .filter(|p| self.is_project_allowed(&p.id))
             .map(ProjectSummary::from_project)
             .collect();
 
@@ -499,7 +1382,95 @@ impl TaskServer {
     }
 
     #[tool(
-        description = "List all the task/tickets in a project with optional filtering and execution status. `project_id` is required!"
+        description = "Get a project's git settings: the default PR base branch, the remote used for pushes and PRs, and the repository's remotes. `project_id` is required!"
+    )]
+    async fn get_project_git_config(
+        &self,
+        Parameters(GetProjectGitConfigRequest { project_id }): Parameters<
+            GetProjectGitConfigRequest,
+        >,
+    ) -> Result<CallToolResult, ErrorData> {
+        if let Err(e) = self.ensure_project_allowed(project_id) {
+            return Ok(e);
+        }
+
+        let url = self.url(&format!("/api/projects/{}/git-config", project_id));
+        let response: ProjectGitConfigResponse = match self.send_json(self.client.get(&url)).await {
+            Ok(response) => response,
+            Err(e) => return Ok(e),
+        };
+        TaskServer::success(&ProjectGitConfigSummary::from_response(
+            project_id, response,
+        ))
+    }
+
+    #[tool(
+        description = "Set a project's default PR base branch and/or the remote used for pushes and PRs, e.g. before creating PRs. Omitted fields are left unchanged. `project_id` is required!"
+    )]
+    async fn update_project_git_config(
+        &self,
+        Parameters(UpdateProjectGitConfigRequest {
+            project_id,
+            default_base_branch,
+            remote_name,
+        }): Parameters<UpdateProjectGitConfigRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        if let Err(e) = self.ensure_project_allowed(project_id) {
+            return Ok(e);
+        }
+
+        let url = self.url(&format!("/api/projects/{}/git-config", project_id));
+        let payload = UpdateProjectGitConfig {
+            default_base_branch,
+            remote_name,
+        };
+        let response: ProjectGitConfigResponse =
+            match self.send_json(self.client.put(&url).json(&payload)).await {
+                Ok(response) => response,
+                Err(e) => return Ok(e),
+            };
+        TaskServer::success(&ProjectGitConfigSummary::from_response(
+            project_id, response,
+        ))
+    }
+
+    #[tool(
+        description = "Rename a project, optionally setting its default PR base branch in the same update so neither change is applied alone. Returns the updated project. `project_id` and `new_name` are required!"
+    )]
+    async fn rename_project(
+        &self,
+        Parameters(RenameProjectRequest {
+            project_id,
+            new_name,
+            default_base_branch,
+        }): Parameters<RenameProjectRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let name = new_name.trim();
+        if name.is_empty() {
+            return Self::err(
+                ToolErrorCode::InvalidArg,
+                "new_name must not be empty".to_string(),
+                None,
+            );
+        }
+        if let Err(e) = self.ensure_project_allowed(project_id) {
+            return Ok(e);
+        }
+
+        let url = self.url(&format!("/api/projects/{}/rename", project_id));
+        let payload = RenameProject {
+            name: name.to_string(),
+            default_base_branch,
+        };
+        let project: Project = match self.send_json(self.client.put(&url).json(&payload)).await {
+            Ok(project) => project,
+            Err(e) => return Ok(e),
+        };
+        TaskServer::success(&ProjectSummary::from_project(project))
+    }
+
+    #[tool(
+        description = "List all the task/tickets in a project with optional filtering and execution status. Each task lists the tasks it `depends_on`; pass `ready: true` to only get tasks whose dependencies are all done. `project_id` is required!"
     )]
     async fn list_tasks(
         &self,
@@ -507,13 +1478,20 @@ impl TaskServer {
             project_id,
             status,
             limit,
+            ready,
+            assignee,
         }): Parameters<ListTasksRequest>,
     ) -> Result<CallToolResult, ErrorData> {
+        if let Err(e) = self.ensure_project_allowed(project_id) {
+            return Ok(e);
+        }
+
         let status_filter = if let Some(ref status_str) = status {
             match TaskStatus::from_str(status_str) {
                 Ok(s) => Some(s),
                 Err(_) => {
                     return Self::err(
+                        ToolErrorCode::InvalidArg,
                         "Invalid status filter. Valid values: 'todo', 'in-progress', 'in-review', 'done', 'cancelled'".to_string(),
                         Some(status_str.to_string()),
                     );
@@ -531,18 +1509,34 @@ impl TaskServer {
             };
 
         let task_limit = limit.unwrap_or(50).max(0) as usize;
-        let filtered = all_tasks.into_iter().filter(|t| {
-            if let Some(ref want) = status_filter {
-                &t.status == want
-            } else {
-                true
-            }
-        });
-        let limited: Vec<TaskWithAttemptStatus> = filtered.take(task_limit).collect();
-
-        let task_summaries: Vec<TaskSummary> = limited
+        let statuses: HashMap<Uuid, TaskStatus> =
+            all_tasks.iter().map(|t| (t.id, t.status)).collect();
+        let is_ready = |task: &TaskWithAttemptStatus| {
+            task.depends_on
+                .iter()
+                .all(|id| statuses.get(id) == Some(&TaskStatus::Done))
+        };
+        let task_summaries: Vec<TaskSummary> = all_tasks
             .into_iter()
-            .map(TaskSummary::from_task_with_status)
+            .filter(|t| {
+                if let Some(ref want) = status_filter {
+                    &t.status == want
+                } else {
+                    true
+                }
+            })
+            .map(|t| {
+                let task_ready = is_ready(&t);
+                (t, task_ready)
+            })
+            .filter(|(_, task_ready)| ready.is_none_or(|want| *task_ready == want))
+            .filter(|(t, _)| match assignee.as_deref().map(str::trim) {
+                None => true,
+                Some("") => t.assignee.is_none(),
+                Some(want) => t.assignee.as_deref() == Some(want),
+            })
+            .take(task_limit)
+            .map(|(t, task_ready)| TaskSummary::from_task_with_status(t, task_ready))
             .collect();
 
         let response = ListTasksResponse {
@@ -552,6 +1546,8 @@ impl TaskServer {
             applied_filters: ListTasksFilters {
                 status: status.clone(),
                 limit: task_limit as i32,
+                ready,
+                assignee,
             },
         };
 
@@ -566,47 +1562,36 @@ impl TaskServer {
             executor,
             variant,
             base_branch,
+            branch_name,
+            branch_template,
+            use_worktree,
         }): Parameters<StartTaskAttemptRequest>,
     ) -> Result<CallToolResult, ErrorData> {
         let base_branch = base_branch.trim().to_string();
         if base_branch.is_empty() {
-            return Self::err("Base branch must not be empty.".to_string(), None::<String>);
-        }
-
-        let executor_trimmed = executor.trim();
-        if executor_trimmed.is_empty() {
-            return Self::err("Executor must not be empty.".to_string(), None::<String>);
+            return Self::err(
+                ToolErrorCode::InvalidArg,
+                "Base branch must not be empty.".to_string(),
+                None::<String>,
+            );
         }
 
-        let normalized_executor = executor_trimmed.replace('-', "_").to_ascii_uppercase();
-        let base_executor = match BaseCodingAgent::from_str(&normalized_executor) {
-            Ok(exec) => exec,
-            Err(_) => {
-                return Self::err(
-                    format!("Unknown executor '{executor_trimmed}'."),
-                    None::<String>,
-                );
-            }
+        let executor_profile_id = match Self::resolve_executor_profile(&executor, variant) {
+            Ok(id) => id,
+            Err(e) => return Self::err(ToolErrorCode::InvalidArg, e, None::<String>),
         };
 
-        let variant = variant.and_then(|v| {
-            let trimmed = v.trim();
-            if trimmed.is_empty() {
-                None
-            } else {
-                Some(trimmed.to_string())
-            }
-        });
-
-        let executor_profile_id = ExecutorProfileId {
-            executor: base_executor,
-            variant,
-        };
+        if let Err(e) = self.ensure_task_allowed(task_id).await {
+            return Ok(e);
+        }
 
         let payload = CreateTaskAttemptBody {
             task_id,
             executor_profile_id,
             base_branch,
+            branch_name,
+            branch_template,
+            use_worktree,
         };
 
         let url = self.url("/api/task-attempts");
@@ -624,6 +1609,54 @@ impl TaskServer {
         TaskServer::success(&response)
     }
 
+    #[tool(
+        description = "Send a follow-up message to the coding agent of an existing task attempt, optionally attaching previously uploaded images by ID. `attempt_id` and `prompt` are required!"
+    )]
+    async fn follow_up(
+        &self,
+        Parameters(FollowUpRequest {
+            attempt_id,
+            prompt,
+            variant,
+            image_ids,
+        }): Parameters<FollowUpRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        if prompt.trim().is_empty() {
+            return Self::err(
+                ToolErrorCode::InvalidArg,
+                "Prompt must not be empty.".to_string(),
+                None::<String>,
+            );
+        }
+
+        if let Err(e) = self.ensure_attempt_allowed(attempt_id).await {
+            return Ok(e);
+        }
+
+        let payload = CreateFollowUpAttempt {
+            prompt,
+            variant: variant
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty()),
+            image_ids: image_ids.filter(|ids| !ids.is_empty()),
+            retry_process_id: None,
+            force_when_dirty: None,
+            perform_git_reset: None,
+            resume_overrides: None,
+        };
+        let url = self.url(&format!("/api/task-attempts/{}/follow-up", attempt_id));
+        let process: ExecutionProcess =
+            match self.send_json(self.client.post(&url).json(&payload)).await {
+                Ok(process) => process,
+                Err(e) => return Ok(e),
+            };
+
+        TaskServer::success(&FollowUpResponse {
+            attempt_id: attempt_id.to_string(),
+            process_id: process.id.to_string(),
+        })
+    }
+
     #[tool(
         description = "Update an existing task/ticket's title, description, or status. `project_id` and `task_id` are required! `title`, `description`, and `status` are optional."
     )]
@@ -634,6 +1667,7 @@ impl TaskServer {
             title,
             description,
             status,
+            depends_on,
         }): Parameters<UpdateTaskRequest>,
     ) -> Result<CallToolResult, ErrorData> {
         let status = if let Some(ref status_str) = status {
@@ -641,6 +1675,7 @@ impl TaskServer {
                 Ok(s) => Some(s),
                 Err(_) => {
                     return Self::err(
+                        ToolErrorCode::InvalidArg,
                         "Invalid status filter. Valid values: 'todo', 'in-progress', 'in-review', 'done', 'cancelled'".to_string(),
                         Some(status_str.to_string()),
                     );
@@ -651,19 +1686,36 @@ impl TaskServer {
         };
 
         let payload = UpdateTask {
-            title,
+            title: title.map(|title| title.trim().to_string()),
             description,
             status,
             parent_task_attempt: None,
             image_ids: None,
+            depends_on,
         };
+        if let Err(e) = payload.validate() {
+            return Self::err(ToolErrorCode::InvalidArg, e.to_string(), None);
+        }
+
+        if let Err(e) = self.ensure_task_allowed(task_id).await {
+            return Ok(e);
+        }
+
         let url = self.url(&format!("/api/tasks/{}", task_id));
         let updated_task: Task = match self.send_json(self.client.put(&url).json(&payload)).await {
             Ok(t) => t,
             Err(e) => return Ok(e),
         };
+        let depends_on = match self.task_dependencies(task_id).await {
+            Ok(depends_on) => depends_on,
+            Err(e) => return Ok(e),
+        };
+        let assignee = match self.task_assignee(task_id).await {
+            Ok(assignee) => assignee,
+            Err(e) => return Ok(e),
+        };
 
-        let details = TaskDetails::from_task(updated_task);
+        let details = TaskDetails::from_task(updated_task, depends_on, assignee);
         let repsonse = UpdateTaskResponse { task: details };
         TaskServer::success(&repsonse)
     }
@@ -675,6 +1727,10 @@ impl TaskServer {
         &self,
         Parameters(DeleteTaskRequest { task_id }): Parameters<DeleteTaskRequest>,
     ) -> Result<CallToolResult, ErrorData> {
+        if let Err(e) = self.ensure_task_allowed(task_id).await {
+            return Ok(e);
+        }
+
         let url = self.url(&format!("/api/tasks/{}", task_id));
         if let Err(e) = self
             .send_json::<serde_json::Value>(self.client.delete(&url))
@@ -691,52 +1747,499 @@ impl TaskServer {
     }
 
     #[tool(
-        description = "Get detailed information (like task description) about a specific task/ticket. You can use `list_tasks` to find the `task_ids` of all tasks in a project. `project_id` and `task_id` are required!"
+        description = "Set or clear who is working on a task, so cooperating agents can claim work without picking up the same task. Check `assignee` in `get_task` or filter `list_tasks` by it first. `task_id` is required!"
     )]
-    async fn get_task(
+    async fn assign_task(
         &self,
-        Parameters(GetTaskRequest { task_id }): Parameters<GetTaskRequest>,
+        Parameters(AssignTaskRequest { task_id, assignee }): Parameters<AssignTaskRequest>,
     ) -> Result<CallToolResult, ErrorData> {
         let url = self.url(&format!("/api/tasks/{}", task_id));
         let task: Task = match self.send_json(self.client.get(&url)).await {
             Ok(t) => t,
             Err(e) => return Ok(e),
         };
+        if let Err(e) = self.ensure_project_allowed(task.project_id) {
+            return Ok(e);
+        }
 
-        let details = TaskDetails::from_task(task);
-        let response = GetTaskResponse { task: details };
+        let url = self.url(&format!("/api/tasks/{}/assignee", task_id));
+        let payload = SetTaskAssignee { assignee };
+        let assignee: Option<String> =
+            match self.send_json(self.client.put(&url).json(&payload)).await {
+                Ok(assignee) => assignee,
+                Err(e) => return Ok(e),
+            };
+        let depends_on = match self.task_dependencies(task_id).await {
+            Ok(depends_on) => depends_on,
+            Err(e) => return Ok(e),
+        };
 
-        TaskServer::success(&response)
+        TaskServer::success(&AssignTaskResponse {
+            task: TaskDetails::from_task(task, depends_on, assignee),
+        })
     }
-}
 
-#[tool_handler]
-impl ServerHandler for TaskServer {
-    fn initialize(
+    #[tool(
+        description = "Move a task/ticket to another project, keeping its history. Refused while the task has running processes or attempt worktrees in its current project. Its dependencies are cleared. `task_id` and `target_project_id` are required!"
+    )]
+    async fn move_task(
         &self,
-        request: InitializeRequestParam,
-        context: RequestContext<RoleServer>,
-    ) -> impl Future<Output = Result<ServerInfo, ErrorData>> + Send + '_ {
-        async move {
-            if context.peer.peer_info().is_none() {
-                context.peer.set_peer_info(request.clone());
-            }
-
-            let requested_version = request.protocol_version.clone();
-            let negotiated_version = match Self::negotiate_protocol_version(&requested_version) {
-                Ok(version) => version,
-                Err(error) => return Err(error),
-            };
-
-            Self::log_downgrade_if_needed(&requested_version, &negotiated_version);
-            self.set_negotiated_protocol_version(negotiated_version.clone());
-
-            Ok(self.server_info_for_version(negotiated_version))
+        Parameters(MoveTaskRequest {
+            task_id,
+            target_project_id,
+        }): Parameters<MoveTaskRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        if let Err(e) = self.ensure_project_allowed(target_project_id) {
+            return Ok(e);
+        }
+        let url = self.url(&format!("/api/tasks/{}", task_id));
+        let task: Task = match self.send_json(self.client.get(&url)).await {
+            Ok(t) => t,
+            Err(e) => return Ok(e),
+        };
+        if let Err(e) = self.ensure_project_allowed(task.project_id) {
+            return Ok(e);
         }
-    }
 
-    /// Returns server info that reflects the currently negotiated protocol version so
-    /// any follow-up responses stay aligned with the handshake.
+        let url = self.url(&format!("/api/tasks/{}/move", task_id));
+        let payload = MoveTask {
+            project_id: target_project_id,
+        };
+        let moved: Task = match self.send_json(self.client.post(&url).json(&payload)).await {
+            Ok(t) => t,
+            Err(e) => return Ok(e),
+        };
+
+        // Dependencies only link tasks of one project, so a moved task has none left
+        let depends_on = if moved.project_id == task.project_id {
+            match self.task_dependencies(task_id).await {
+                Ok(depends_on) => depends_on,
+                Err(e) => return Ok(e),
+            }
+        } else {
+            Vec::new()
+        };
+        let assignee = match self.task_assignee(task_id).await {
+            Ok(assignee) => assignee,
+            Err(e) => return Ok(e),
+        };
+        TaskServer::success(&MoveTaskResponse {
+            task: TaskDetails::from_task(moved, depends_on, assignee),
+            previous_project_id: task.project_id.to_string(),
+        })
+    }
+
+    #[tool(
+        description = "Get the working changes of a task attempt as a unified diff against its base branch, e.g. to review an attempt before merging it. `attempt_id` is required!"
+    )]
+    async fn get_attempt_diff(
+        &self,
+        Parameters(GetAttemptDiffRequest {
+            attempt_id,
+            context_lines,
+            per_file,
+        }): Parameters<GetAttemptDiffRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        if let Err(e) = self.ensure_attempt_allowed(attempt_id).await {
+            return Ok(e);
+        }
+
+        let mut url = self.url(&format!("/api/task-attempts/{}/diff", attempt_id));
+        if let Some(context_lines) = context_lines {
+            url.push_str(&format!("?context_lines={context_lines}"));
+        }
+        let diff: AttemptDiff = match self.send_json(self.client.get(&url)).await {
+            Ok(d) => d,
+            Err(e) => return Ok(e),
+        };
+
+        let response =
+            GetAttemptDiffResponse::from_attempt_diff(attempt_id, diff, per_file.unwrap_or(false));
+        TaskServer::success(&response)
+    }
+
+    #[tool(
+        description = "Get the run state of a task attempt's latest execution process (queued, running, completed, failed or killed), with its start time and exit code. Cheaper than fetching logs when polling for completion. `attempt_id` is required!"
+    )]
+    async fn get_attempt_status(
+        &self,
+        Parameters(GetAttemptStatusRequest { attempt_id }): Parameters<GetAttemptStatusRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        if let Err(e) = self.ensure_attempt_allowed(attempt_id).await {
+            return Ok(e);
+        }
+
+        let url = self.url(&format!(
+            "/api/execution-processes?task_attempt_id={}",
+            attempt_id
+        ));
+        let processes: Vec<ExecutionProcess> = match self.send_json(self.client.get(&url)).await {
+            Ok(processes) => processes,
+            Err(e) => return Ok(e),
+        };
+
+        // Processes are returned oldest first
+        let response = GetAttemptStatusResponse {
+            attempt_id: attempt_id.to_string(),
+            latest_process: processes
+                .into_iter()
+                .last()
+                .map(AttemptProcessStatus::from_process),
+        };
+        TaskServer::success(&response)
+    }
+
+    #[tool(
+        description = "List the execution processes running right now across all attempts, oldest first, with their OS pid, start time, memory (`rss_bytes`) and average CPU use. Useful to find runaway agents."
+    )]
+    async fn list_processes(&self) -> Result<CallToolResult, ErrorData> {
+        let url = self.url("/api/execution-processes/running");
+        let processes: Vec<RunningProcess> = match self.send_json(self.client.get(&url)).await {
+            Ok(processes) => processes,
+            Err(e) => return Ok(e),
+        };
+
+        // Several processes usually share an attempt, so look each attempt up only once
+        let mut allowed_attempts: HashMap<Uuid, bool> = HashMap::new();
+        let mut summaries = Vec::with_capacity(processes.len());
+        for process in processes {
+            let allowed = match allowed_attempts.get(&process.task_attempt_id) {
+                Some(allowed) => *allowed,
+                None => {
+                    let allowed = self
+                        .ensure_attempt_allowed(process.task_attempt_id)
+                        .await
+                        .is_ok();
+                    allowed_attempts.insert(process.task_attempt_id, allowed);
+                    allowed
+                }
+            };
+            if allowed {
+                summaries.push(RunningProcessSummary::from_process(process));
+            }
+        }
+
+        TaskServer::success(&ListProcessesResponse {
+            count: summaries.len(),
+            processes: summaries,
+        })
+    }
+
+    #[tool(
+        description = "Get new output of an execution process, e.g. to watch a build while it runs. Returns the log chunks after `after_offset` and a `next_offset` cursor; call again with it to poll for more until `finished` is true. `process_id` is required!"
+    )]
+    async fn get_process_logs(
+        &self,
+        Parameters(GetProcessLogsRequest {
+            process_id,
+            after_offset,
+            limit,
+        }): Parameters<GetProcessLogsRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        if let Err(e) = self.ensure_process_allowed(process_id).await {
+            return Ok(e);
+        }
+
+        let mut url = self.url(&format!(
+            "/api/execution-processes/{}/raw-logs?after_offset={}",
+            process_id,
+            after_offset.unwrap_or(0)
+        ));
+        if let Some(limit) = limit {
+            url.push_str(&format!("&limit={limit}"));
+        }
+        let page: RawLogsPage = match self.send_json(self.client.get(&url)).await {
+            Ok(page) => page,
+            Err(e) => return Ok(e),
+        };
+
+        TaskServer::success(&GetProcessLogsResponse::from_page(process_id, page))
+    }
+
+    #[tool(
+        description = "Change the target branch of a task attempt and immediately rebase the attempt branch onto it. If the rebase conflicts, `has_conflicts` is true and `conflicted_files` lists the files to resolve; call `abort_conflicts` to give up instead. `attempt_id` and `target_branch` are required!"
+    )]
+    async fn retarget_and_rebase(
+        &self,
+        Parameters(RetargetAndRebaseRequest {
+            attempt_id,
+            target_branch,
+        }): Parameters<RetargetAndRebaseRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let attempt_url = self.url(&format!("/api/task-attempts/{}", attempt_id));
+        let attempt: TaskAttempt = match self.send_json(self.client.get(&attempt_url)).await {
+            Ok(attempt) => attempt,
+            Err(e) => return Ok(e),
+        };
+        if let Err(e) = self.ensure_task_allowed(attempt.task_id).await {
+            return Ok(e);
+        }
+
+        // The rebase endpoint stores the new target branch before rebasing onto it
+        let rebase_url = self.url(&format!("/api/task-attempts/{}/rebase", attempt_id));
+        let payload = RebaseTaskAttemptRequest {
+            old_base_branch: Some(attempt.target_branch.clone()),
+            new_base_branch: Some(target_branch.clone()),
+        };
+        let outcome = match self
+            .send_git_operation(self.client.post(&rebase_url).json(&payload))
+            .await
+        {
+            Ok(outcome) => outcome,
+            Err(e) => return Ok(e),
+        };
+
+        let mut response = RetargetAndRebaseResponse {
+            attempt_id: attempt_id.to_string(),
+            previous_target_branch: attempt.target_branch,
+            target_branch,
+            rebased: outcome.is_ok(),
+            has_conflicts: false,
+            conflicted_files: Vec::new(),
+            message: None,
+        };
+        match outcome {
+            Ok(()) => {}
+            Err(GitOperationError::MergeConflicts { message, .. }) => {
+                response.has_conflicts = true;
+                response.message = Some(message);
+                let status_url =
+                    self.url(&format!("/api/task-attempts/{}/branch-status", attempt_id));
+                if let Ok(status) = self
+                    .send_json::<BranchStatus>(self.client.get(&status_url))
+                    .await
+                {
+                    response.conflicted_files = status.conflicted_files;
+                }
+            }
+            Err(GitOperationError::RebaseInProgress) => {
+                response.has_conflicts = true;
+                response.message = Some(
+                    "A rebase is already in progress in this worktree; resolve it or call `abort_conflicts` first"
+                        .to_string(),
+                );
+            }
+        }
+
+        TaskServer::success(&response)
+    }
+
+    #[tool(
+        description = "Get how a task attempt's branch compares to its target branch: commits ahead and behind, and whether a rebase or merge is stopped on conflicts (with the conflicted files). `attempt_id` is required!"
+    )]
+    async fn get_branch_status(
+        &self,
+        Parameters(GetBranchStatusRequest { attempt_id }): Parameters<GetBranchStatusRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        if let Err(e) = self.ensure_attempt_allowed(attempt_id).await {
+            return Ok(e);
+        }
+
+        let url = self.url(&format!("/api/task-attempts/{}/branch-status", attempt_id));
+        let raw: serde_json::Value = match self.send_json(self.client.get(&url)).await {
+            Ok(raw) => raw,
+            Err(e) => return Ok(e),
+        };
+        match BranchStatusSummary::from_raw(attempt_id, raw) {
+            Ok(summary) => TaskServer::success(&summary),
+            Err(e) => TaskServer::err(
+                ToolErrorCode::Internal,
+                "Failed to parse branch status".to_string(),
+                Some(e.to_string()),
+            ),
+        }
+    }
+
+    #[tool(
+        description = "Abort an in-progress rebase or merge of a task attempt that stopped on conflicts, restoring the attempt branch. `attempt_id` is required!"
+    )]
+    async fn abort_conflicts(
+        &self,
+        Parameters(AbortConflictsRequest { attempt_id }): Parameters<AbortConflictsRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        if let Err(e) = self.ensure_attempt_allowed(attempt_id).await {
+            return Ok(e);
+        }
+
+        let url = self.url(&format!(
+            "/api/task-attempts/{}/conflicts/abort",
+            attempt_id
+        ));
+        match self.send_git_operation(self.client.post(&url)).await {
+            Ok(_) => TaskServer::success(&AbortConflictsResponse {
+                attempt_id: attempt_id.to_string(),
+                aborted: true,
+            }),
+            Err(e) => Ok(e),
+        }
+    }
+
+    #[tool(
+        description = "Describe a commit in a task attempt's worktree: subject, full message, author, email, timestamp and number of files changed. `attempt_id` and `sha` are required!"
+    )]
+    async fn get_commit_info(
+        &self,
+        Parameters(GetCommitInfoRequest { attempt_id, sha }): Parameters<GetCommitInfoRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        if let Err(e) = self.ensure_attempt_allowed(attempt_id).await {
+            return Ok(e);
+        }
+
+        let url = self.url(&format!("/api/task-attempts/{}/commit-info", attempt_id));
+        let info: CommitInfoSummary = match self
+            .send_json(self.client.get(&url).query(&[("sha", &sha)]))
+            .await
+        {
+            Ok(info) => info,
+            Err(e) => return Ok(e),
+        };
+
+        TaskServer::success(&info)
+    }
+
+    #[tool(
+        description = "Read a file from a task attempt's worktree, i.e. exactly what that attempt produced, which may differ from the project's own checkout. Binary files are reported with `is_binary` and no content. `attempt_id` and `file_path` are required!"
+    )]
+    async fn get_attempt_file(
+        &self,
+        Parameters(GetAttemptFileRequest {
+            attempt_id,
+            file_path,
+        }): Parameters<GetAttemptFileRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        if let Err(e) = Self::ensure_relative_path("file_path", &file_path) {
+            return Ok(e);
+        }
+        if let Err(e) = self.ensure_attempt_allowed(attempt_id).await {
+            return Ok(e);
+        }
+
+        let url = self.url(&format!("/api/task-attempts/{}/file", attempt_id));
+        let file: AttemptFile = match self
+            .send_json(self.client.get(&url).query(&[("file_path", &file_path)]))
+            .await
+        {
+            Ok(file) => file,
+            Err(e) => return Ok(e),
+        };
+
+        TaskServer::success(&GetAttemptFileResponse {
+            attempt_id: attempt_id.to_string(),
+            file_path: file.path,
+            size: file.size,
+            is_binary: file.is_binary,
+            content: file.content,
+        })
+    }
+
+    #[tool(
+        description = "Get the JSON Schema (draft-07) of the application config, i.e. the body accepted by `PUT /api/config`. Use it to validate config edits before submitting them."
+    )]
+    async fn get_config_schema(&self) -> Result<CallToolResult, ErrorData> {
+        let url = self.url("/api/config/schema");
+        let schema: serde_json::Value = match self.send_json(self.client.get(&url)).await {
+            Ok(schema) => schema,
+            Err(e) => return Ok(e),
+        };
+
+        TaskServer::success(&schema)
+    }
+
+    #[tool(
+        description = "Check that the task server can reach the backend. Returns `ok`, the backend version and the round-trip latency."
+    )]
+    async fn ping(&self) -> Result<CallToolResult, ErrorData> {
+        let url = self.url("/api/health");
+        let started = Instant::now();
+        let result = self.client.get(&url).send().await;
+        let latency_ms = started.elapsed().as_millis() as u64;
+
+        let response = match result {
+            Ok(resp) if resp.status().is_success() => PingResponse {
+                ok: true,
+                backend_version: resp
+                    .headers()
+                    .get(VERSION_HEADER)
+                    .and_then(|v| v.to_str().ok())
+                    .map(str::to_string),
+                latency_ms,
+                error: None,
+            },
+            Ok(resp) => PingResponse {
+                ok: false,
+                backend_version: None,
+                latency_ms,
+                error: Some(format!("AF API returned error status: {}", resp.status())),
+            },
+            Err(e) => PingResponse {
+                ok: false,
+                backend_version: None,
+                latency_ms,
+                error: Some(e.to_string()),
+            },
+        };
+
+        TaskServer::success(&response)
+    }
+
+    #[tool(
+        description = "Get detailed information (like task description) about a specific task/ticket. You can use `list_tasks` to find the `task_ids` of all tasks in a project. `project_id` and `task_id` are required!"
+    )]
+    async fn get_task(
+        &self,
+        Parameters(GetTaskRequest { task_id }): Parameters<GetTaskRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!("/api/tasks/{}", task_id));
+        let task: Task = match self.send_json(self.client.get(&url)).await {
+            Ok(t) => t,
+            Err(e) => return Ok(e),
+        };
+        if let Err(e) = self.ensure_project_allowed(task.project_id) {
+            return Ok(e);
+        }
+        let depends_on = match self.task_dependencies(task_id).await {
+            Ok(depends_on) => depends_on,
+            Err(e) => return Ok(e),
+        };
+        let assignee = match self.task_assignee(task_id).await {
+            Ok(assignee) => assignee,
+            Err(e) => return Ok(e),
+        };
+
+        let details = TaskDetails::from_task(task, depends_on, assignee);
+        let response = GetTaskResponse { task: details };
+
+        TaskServer::success(&response)
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for TaskServer {
+    fn initialize(
+        &self,
+        request: InitializeRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> impl Future<Output = Result<ServerInfo, ErrorData>> + Send + '_ {
+        async move {
+            if context.peer.peer_info().is_none() {
+                context.peer.set_peer_info(request.clone());
+            }
+
+            let requested_version = request.protocol_version.clone();
+            let negotiated_version = match Self::negotiate_protocol_version(&requested_version) {
+                Ok(version) => version,
+                Err(error) => return Err(error),
+            };
+
+            Self::log_downgrade_if_needed(&requested_version, &negotiated_version);
+            self.set_negotiated_protocol_version(negotiated_version.clone());
+
+            Ok(self.server_info_for_version(negotiated_version))
+        }
+    }
+
+    /// Returns server info that reflects the currently negotiated protocol version so
+    /// any follow-up responses stay aligned with the handshake.
     fn get_info(&self) -> ServerInfo {
         let protocol_version = self.current_protocol_version();
         self.server_info_for_version(protocol_version)
@@ -752,6 +2255,97 @@ mod tests {
         serde_json::from_str::<ProtocolVersion>(&format!("\"{version}\"")).unwrap()
     }
 
+    /// Serves `app` on a free local port and returns its base URL.
+    async fn spawn_backend(app: axum::Router) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        base_url
+    }
+
+    /// The JSON body of a tool result.
+    fn result_json(result: &CallToolResult) -> serde_json::Value {
+        let content = result.content.as_ref().unwrap();
+        serde_json::from_str(&content[0].as_text().unwrap().text).unwrap()
+    }
+
+    /// A task as the backend serializes it.
+    fn task_json(id: Uuid, project_id: Uuid, title: &str, status: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": id,
+            "project_id": project_id,
+            "title": title,
+            "description": null,
+            "status": status,
+            "parent_task_attempt": null,
+            "dev_server_id": null,
+            "created_at": "2025-01-01T00:00:00Z",
+            "updated_at": "2025-01-01T00:00:00Z",
+        })
+    }
+
+    /// A task as the backend lists it, with its attempt status, dependencies and assignee.
+    fn listed_task_json(
+        id: Uuid,
+        project_id: Uuid,
+        status: &str,
+        depends_on: &[Uuid],
+        assignee: Option<&str>,
+    ) -> serde_json::Value {
+        let mut task = task_json(id, project_id, &format!("task {id}"), status);
+        let listing = serde_json::json!({
+            "has_in_progress_attempt": false,
+            "has_merged_attempt": false,
+            "last_attempt_failed": false,
+            "executor": "CLAUDE_CODE",
+            "depends_on": depends_on,
+            "assignee": assignee,
+        });
+        task.as_object_mut()
+            .unwrap()
+            .extend(listing.as_object().unwrap().clone());
+        task
+    }
+
+    /// A task attempt as the backend serializes it.
+    fn attempt_json(id: Uuid, target_branch: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": id,
+            "task_id": Uuid::new_v4(),
+            "container_ref": null,
+            "branch": "forge/feature",
+            "target_branch": target_branch,
+            "executor": "CLAUDE_CODE",
+            "worktree_deleted": false,
+            "setup_completed_at": null,
+            "input_tokens": null,
+            "output_tokens": null,
+            "cache_creation_tokens": null,
+            "cache_read_tokens": null,
+            "created_at": "2025-01-01T00:00:00Z",
+            "updated_at": "2025-01-01T00:00:00Z",
+        })
+    }
+
+    /// A coding agent execution process of `attempt_id` as the backend serializes it.
+    fn process_json(attempt_id: Uuid, status: &str, exit_code: Option<i64>) -> serde_json::Value {
+        serde_json::json!({
+            "id": Uuid::new_v4(),
+            "task_attempt_id": attempt_id,
+            "run_reason": "codingagent",
+            "executor_action": {},
+            "before_head_commit": null,
+            "after_head_commit": null,
+            "status": status,
+            "exit_code": exit_code,
+            "dropped": false,
+            "started_at": "2025-01-01T00:00:00Z",
+            "completed_at": null,
+            "created_at": "2025-01-01T00:00:00Z",
+            "updated_at": "2025-01-01T00:00:00Z",
+        })
+    }
+
     #[test]
     fn client_requesting_latest_version_receives_latest() {
         let negotiated =
@@ -787,4 +2381,786 @@ mod tests {
         let info = server.get_info();
         assert_eq!(info.protocol_version, ProtocolVersion::V_2024_11_05);
     }
+
+    #[tokio::test]
+    async fn operations_on_disallowed_projects_are_rejected() {
+        let allowed = Uuid::new_v4();
+        let disallowed = Uuid::new_v4();
+        let server = TaskServer::new("http://127.0.0.1:9").with_allowed_projects([allowed]);
+
+        assert!(server.ensure_project_allowed(allowed).is_ok());
+        assert!(server.ensure_project_allowed(disallowed).is_err());
+
+        let result = server
+            .list_tasks(Parameters(ListTasksRequest {
+                project_id: disallowed,
+                status: None,
+                limit: None,
+                ready: None,
+                assignee: None,
+            }))
+            .await
+            .unwrap();
+        assert_eq!(result.is_error, Some(true));
+
+        let result = server
+            .create_task(Parameters(CreateTaskRequest {
+                project_id: disallowed,
+                title: "Sneaky".to_string(),
+                description: None,
+                idempotency_key: None,
+                depends_on: None,
+            }))
+            .await
+            .unwrap();
+        assert_eq!(result.is_error, Some(true));
+    }
+
+    #[tokio::test]
+    async fn ping_reports_unreachable_backend() {
+        let server = TaskServer::new("http://127.0.0.1:9");
+        let result = server.ping().await.unwrap();
+        assert_ne!(result.is_error, Some(true));
+
+        let body = result_json(&result);
+        assert_eq!(body["ok"], false);
+        assert!(body["error"].is_string());
+    }
+
+    #[test]
+    fn http_statuses_map_to_error_codes() {
+        use reqwest::StatusCode;
+
+        assert_eq!(
+            ToolErrorCode::from_status(StatusCode::NOT_FOUND),
+            ToolErrorCode::NotFound
+        );
+        assert_eq!(
+            ToolErrorCode::from_status(StatusCode::CONFLICT),
+            ToolErrorCode::Conflict
+        );
+        assert_eq!(
+            ToolErrorCode::from_status(StatusCode::BAD_REQUEST),
+            ToolErrorCode::InvalidArg
+        );
+        assert_eq!(
+            ToolErrorCode::from_status(StatusCode::GATEWAY_TIMEOUT),
+            ToolErrorCode::Timeout
+        );
+        assert_eq!(
+            ToolErrorCode::from_status(StatusCode::SERVICE_UNAVAILABLE),
+            ToolErrorCode::BackendUnavailable
+        );
+        assert_eq!(
+            ToolErrorCode::from_status(StatusCode::INTERNAL_SERVER_ERROR),
+            ToolErrorCode::Internal
+        );
+    }
+
+    #[tokio::test]
+    async fn errors_carry_a_code_field() {
+        let server = TaskServer::new("http://127.0.0.1:9");
+        let result = server
+            .list_tasks(Parameters(ListTasksRequest {
+                project_id: Uuid::new_v4(),
+                status: Some("bogus".to_string()),
+                limit: None,
+                ready: None,
+                assignee: None,
+            }))
+            .await
+            .unwrap();
+        let body = result_json(&result);
+        assert_eq!(body["code"], "invalid_arg");
+    }
+
+    #[tokio::test]
+    async fn blank_task_title_is_rejected_before_any_request() {
+        // Nothing listens on port 9, so reaching the backend would be a backend error
+        let server = TaskServer::new("http://127.0.0.1:9");
+        let result = server
+            .create_task(Parameters(CreateTaskRequest {
+                project_id: Uuid::new_v4(),
+                title: "   ".to_string(),
+                description: None,
+                idempotency_key: None,
+                depends_on: None,
+            }))
+            .await
+            .unwrap();
+        let body = result_json(&result);
+        assert_eq!(body["code"], "invalid_arg");
+    }
+
+    #[tokio::test]
+    async fn retarget_and_rebase_reports_conflicts() {
+        use axum::{
+            Json, Router,
+            routing::{get, post},
+        };
+
+        let attempt_id = Uuid::new_v4();
+        let attempt = attempt_json(attempt_id, "main");
+        let app = Router::new()
+            .route(
+                "/api/task-attempts/{id}",
+                get(move || async move {
+                    Json(serde_json::json!({ "success": true, "data": attempt }))
+                }),
+            )
+            .route(
+                "/api/task-attempts/{id}/rebase",
+                post(|Json(body): Json<serde_json::Value>| async move {
+                    assert_eq!(body["old_base_branch"], "main");
+                    assert_eq!(body["new_base_branch"], "release");
+                    Json(serde_json::json!({
+                        "success": false,
+                        "error_data": {
+                            "type": "merge_conflicts",
+                            "message": "conflict in src/lib.rs",
+                            "op": "rebase",
+                        },
+                    }))
+                }),
+            )
+            .route(
+                "/api/task-attempts/{id}/branch-status",
+                get(|| async {
+                    Json(serde_json::json!({
+                        "success": true,
+                        "data": {
+                            "target_branch_name": "release",
+                            "merges": [],
+                            "is_rebase_in_progress": true,
+                            "conflicted_files": ["src/lib.rs"],
+                        },
+                    }))
+                }),
+            );
+        let base_url = spawn_backend(app).await;
+
+        let result = TaskServer::new(&base_url)
+            .retarget_and_rebase(Parameters(RetargetAndRebaseRequest {
+                attempt_id,
+                target_branch: "release".to_string(),
+            }))
+            .await
+            .unwrap();
+        assert_ne!(result.is_error, Some(true));
+        let body = result_json(&result);
+        assert_eq!(body["previous_target_branch"], "main");
+        assert_eq!(body["target_branch"], "release");
+        assert_eq!(body["rebased"], false);
+        assert_eq!(body["has_conflicts"], true);
+        assert_eq!(body["conflicted_files"], serde_json::json!(["src/lib.rs"]));
+    }
+
+    #[test]
+    fn follow_up_image_ids_must_be_uuids() {
+        let image_id = Uuid::new_v4();
+        let request: FollowUpRequest = serde_json::from_value(serde_json::json!({
+            "attempt_id": Uuid::new_v4(),
+            "prompt": "Match the attached mockup",
+            "image_ids": [image_id],
+        }))
+        .unwrap();
+        assert_eq!(request.image_ids, Some(vec![image_id]));
+
+        let malformed = serde_json::from_value::<FollowUpRequest>(serde_json::json!({
+            "attempt_id": Uuid::new_v4(),
+            "prompt": "Match the attached mockup",
+            "image_ids": ["mockup.png"],
+        }));
+        assert!(malformed.is_err());
+    }
+
+    #[tokio::test]
+    async fn attempt_status_reports_latest_process() {
+        use axum::{Json, Router, routing::get};
+
+        let attempt_id = Uuid::new_v4();
+        let processes = serde_json::json!([
+            process_json(attempt_id, "failed", Some(1)),
+            process_json(attempt_id, "running", None),
+        ]);
+        let latest_id = processes[1]["id"].clone();
+        let app =
+            Router::new().route(
+                "/api/execution-processes",
+                get(move || async move {
+                    Json(serde_json::json!({ "success": true, "data": processes }))
+                }),
+            );
+        let base_url = spawn_backend(app).await;
+
+        let result = TaskServer::new(&base_url)
+            .get_attempt_status(Parameters(GetAttemptStatusRequest { attempt_id }))
+            .await
+            .unwrap();
+        let body = result_json(&result);
+        assert_eq!(body["latest_process"]["process_id"], latest_id);
+        assert_eq!(body["latest_process"]["status"], "running");
+        assert_eq!(body["latest_process"]["run_reason"], "codingagent");
+        assert!(body["latest_process"]["exit_code"].is_null());
+    }
+
+    #[tokio::test]
+    async fn task_from_github_issue_validates_url_and_creates_task() {
+        use axum::{Json, Router, routing::post};
+
+        let project_id = Uuid::new_v4();
+        let app = Router::new().route(
+            "/api/tasks/from-github-issue",
+            post(move |Json(body): Json<serde_json::Value>| async move {
+                assert_eq!(body["issue_url"], "https://github.com/owner/repo/issues/42");
+                let mut task = task_json(Uuid::new_v4(), project_id, "Crash on startup", "todo");
+                task["description"] = serde_json::json!(
+                    "Steps...\n\n---\nCreated from GitHub issue: https://github.com/owner/repo/issues/42"
+                );
+                Json(serde_json::json!({ "success": true, "data": task }))
+            }),
+        );
+        let base_url = spawn_backend(app).await;
+        let server = TaskServer::new(&base_url);
+        let invalid = server
+            .create_task_from_github_issue(Parameters(CreateTaskFromGitHubIssueRequest {
+                project_id,
+                issue_url: "https://github.com/owner/repo/pull/42".to_string(),
+            }))
+            .await
+            .unwrap();
+        assert_eq!(invalid.is_error, Some(true));
+        assert_eq!(result_json(&invalid)["code"], "invalid_arg");
+
+        let created = result_json(
+            &server
+                .create_task_from_github_issue(Parameters(CreateTaskFromGitHubIssueRequest {
+                    project_id,
+                    issue_url: "https://github.com/owner/repo/issues/42".to_string(),
+                }))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(created["title"], "Crash on startup");
+        assert!(created["task_id"].is_string());
+    }
+
+    #[test]
+    fn branch_status_is_summarized_from_backend_payload() {
+        let attempt_id = Uuid::new_v4();
+        let raw = serde_json::json!({
+            "commits_behind": 2,
+            "commits_ahead": 3,
+            "has_uncommitted_changes": false,
+            "head_oid": "abc123",
+            "uncommitted_count": 0,
+            "untracked_count": 0,
+            "target_branch_name": "main",
+            "remote_commits_behind": null,
+            "remote_commits_ahead": null,
+            "merges": [],
+            "is_rebase_in_progress": true,
+            "conflict_op": "rebase",
+            "conflicted_files": ["src/lib.rs"],
+            "future_field": 1,
+        });
+
+        let summary = BranchStatusSummary::from_raw(attempt_id, raw).unwrap();
+        assert_eq!(summary.target_branch, "main");
+        assert_eq!((summary.ahead, summary.behind), (3, 2));
+        assert!(!summary.up_to_date);
+        assert!(summary.has_conflicts);
+        assert_eq!(summary.conflict_op.as_deref(), Some("rebase"));
+        assert_eq!(summary.conflicted_files, vec!["src/lib.rs".to_string()]);
+        assert_eq!(summary.raw["future_field"], 1);
+
+        let clean = serde_json::json!({
+            "commits_behind": 0,
+            "commits_ahead": 1,
+            "has_uncommitted_changes": null,
+            "head_oid": null,
+            "uncommitted_count": null,
+            "untracked_count": null,
+            "target_branch_name": "main",
+            "remote_commits_behind": null,
+            "remote_commits_ahead": null,
+            "merges": [],
+            "is_rebase_in_progress": false,
+            "conflict_op": null,
+            "conflicted_files": [],
+        });
+        let summary = BranchStatusSummary::from_raw(attempt_id, clean).unwrap();
+        assert!(summary.up_to_date);
+        assert!(!summary.has_conflicts);
+        assert!(summary.conflict_op.is_none());
+    }
+
+    #[tokio::test]
+    async fn project_git_config_is_read_and_updated() {
+        use axum::{Json, Router, routing::get};
+
+        let project_id = Uuid::new_v4();
+        let app = Router::new().route(
+            "/api/projects/{id}/git-config",
+            get(|| async {
+                Json(serde_json::json!({
+                    "success": true,
+                    "data": {
+                        "default_base_branch": null,
+                        "remote_name": null,
+                        "remotes": ["origin", "upstream"],
+                    },
+                }))
+            })
+            .put(|Json(body): Json<serde_json::Value>| async move {
+                Json(serde_json::json!({
+                    "success": true,
+                    "data": {
+                        "default_base_branch": body["default_base_branch"],
+                        "remote_name": body["remote_name"],
+                        "remotes": ["origin", "upstream"],
+                    },
+                }))
+            }),
+        );
+        let base_url = spawn_backend(app).await;
+        let server = TaskServer::new(&base_url);
+        let current = result_json(
+            &server
+                .get_project_git_config(Parameters(GetProjectGitConfigRequest { project_id }))
+                .await
+                .unwrap(),
+        );
+        assert!(current["remote_name"].is_null());
+        assert_eq!(
+            current["remotes"],
+            serde_json::json!(["origin", "upstream"])
+        );
+
+        let updated = result_json(
+            &server
+                .update_project_git_config(Parameters(UpdateProjectGitConfigRequest {
+                    project_id,
+                    default_base_branch: Some("develop".to_string()),
+                    remote_name: Some("upstream".to_string()),
+                }))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(updated["project_id"], project_id.to_string());
+        assert_eq!(updated["default_base_branch"], "develop");
+        assert_eq!(updated["remote_name"], "upstream");
+    }
+
+    #[tokio::test]
+    async fn rename_project_sends_trimmed_name_and_branch() {
+        use axum::{Json, Router, routing::put};
+
+        let project_id = Uuid::new_v4();
+        let app = Router::new().route(
+            "/api/projects/{id}/rename",
+            put(move |Json(body): Json<serde_json::Value>| async move {
+                assert_eq!(body["default_base_branch"], "develop");
+                Json(serde_json::json!({
+                    "success": true,
+                    "data": {
+                        "id": project_id,
+                        "name": body["name"],
+                        "git_repo_path": "/tmp/repo",
+                        "setup_script": null,
+                        "dev_script": null,
+                        "cleanup_script": null,
+                        "copy_files": null,
+                        "created_at": "2025-01-01T00:00:00Z",
+                        "updated_at": "2025-01-02T00:00:00Z",
+                    },
+                }))
+            }),
+        );
+        let base_url = spawn_backend(app).await;
+        let server = TaskServer::new(&base_url);
+
+        let result = server
+            .rename_project(Parameters(RenameProjectRequest {
+                project_id,
+                new_name: "  Renamed  ".to_string(),
+                default_base_branch: Some("develop".to_string()),
+            }))
+            .await
+            .unwrap();
+        let summary = result_json(&result);
+        assert_eq!(summary["id"], project_id.to_string());
+        assert_eq!(summary["name"], "Renamed");
+
+        let result = server
+            .rename_project(Parameters(RenameProjectRequest {
+                project_id,
+                new_name: "   ".to_string(),
+                default_base_branch: None,
+            }))
+            .await
+            .unwrap();
+        assert_eq!(result.is_error, Some(true));
+        let error = result_json(&result);
+        assert_eq!(error["code"], "invalid_arg");
+    }
+
+    #[tokio::test]
+    async fn config_schema_is_returned_as_is() {
+        use axum::{Json, Router, routing::get};
+
+        let app = Router::new().route(
+            "/api/config/schema",
+            get(|| async {
+                Json(serde_json::json!({
+                    "success": true,
+                    "data": {
+                        "$schema": "http://json-schema.org/draft-07/schema#",
+                        "type": "object",
+                        "required": ["git_branch_prefix"],
+                    },
+                }))
+            }),
+        );
+        let base_url = spawn_backend(app).await;
+        let server = TaskServer::new(&base_url);
+
+        let result = server.get_config_schema().await.unwrap();
+        assert_ne!(result.is_error, Some(true));
+        let schema = result_json(&result);
+        assert_eq!(schema["type"], "object");
+        assert_eq!(schema["required"], serde_json::json!(["git_branch_prefix"]));
+    }
+
+    #[test]
+    fn commit_info_deserializes_backend_payload() {
+        let payload = serde_json::json!({
+            "sha": "4f2a9c1e8b7d6a5f4e3d2c1b0a9f8e7d6c5b4a39",
+            "subject": "Fix flaky test",
+            "author": "Test User",
+            "email": "test@example.com",
+            "timestamp": "2025-11-07T10:15:00Z",
+            "message": "Fix flaky test\n\nWait for the server before connecting.\n",
+            "files_changed": 2,
+            "signed": true,
+        });
+
+        let info: CommitInfoSummary = serde_json::from_value(payload).unwrap();
+        assert_eq!(info.subject, "Fix flaky test");
+        assert_eq!(info.author, "Test User");
+        assert_eq!(info.timestamp, "2025-11-07T10:15:00Z");
+        assert_eq!(info.files_changed, 2);
+        // Unknown fields survive the round trip instead of being dropped
+        assert_eq!(info.extra["signed"], true);
+        assert_eq!(serde_json::to_value(&info).unwrap()["signed"], true);
+    }
+
+    #[test]
+    fn relative_path_guard_rejects_escapes() {
+        assert!(TaskServer::ensure_relative_path("file_path", "src/lib.rs").is_ok());
+
+        for path in ["../etc/passwd", "docs/../../etc/passwd", "/etc/passwd"] {
+            let error = TaskServer::ensure_relative_path("file_path", path).unwrap_err();
+            assert_eq!(error.is_error, Some(true));
+            let error = result_json(&error);
+            assert_eq!(error["code"], "invalid_arg");
+            assert!(error["error"].as_str().unwrap().contains(path));
+        }
+    }
+
+    #[tokio::test]
+    async fn attempt_file_is_read_from_the_worktree() {
+        use axum::{Json, Router, extract::Query, routing::get};
+
+        let attempt_id = Uuid::new_v4();
+        let app = Router::new().route(
+            &format!("/api/task-attempts/{attempt_id}/file"),
+            get(|Query(query): Query<HashMap<String, String>>| async move {
+                Json(serde_json::json!({
+                    "success": true,
+                    "data": {
+                        "path": query["file_path"],
+                        "size": 12,
+                        "is_binary": false,
+                        "content": "fn main() {}",
+                    },
+                }))
+            }),
+        );
+        let base_url = spawn_backend(app).await;
+        let server = TaskServer::new(&base_url);
+
+        let result = server
+            .get_attempt_file(Parameters(GetAttemptFileRequest {
+                attempt_id,
+                file_path: "src/main.rs".to_string(),
+            }))
+            .await
+            .unwrap();
+        assert_ne!(result.is_error, Some(true));
+        let file = result_json(&result);
+        assert_eq!(file["file_path"], "src/main.rs");
+        assert_eq!(file["content"], "fn main() {}");
+
+        let result = server
+            .get_attempt_file(Parameters(GetAttemptFileRequest {
+                attempt_id,
+                file_path: "../etc/passwd".to_string(),
+            }))
+            .await
+            .unwrap();
+        assert_eq!(result.is_error, Some(true));
+        let error = result_json(&result);
+        assert_eq!(error["code"], "invalid_arg");
+    }
+
+    #[tokio::test]
+    async fn list_tasks_filters_on_dependency_readiness() {
+        use axum::{Json, Router, routing::get};
+
+        let project_id = Uuid::new_v4();
+        let (done, blocker, ready, blocked) = (
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        );
+        let task = |id: Uuid, status: &str, depends_on: &[Uuid]| {
+            listed_task_json(id, project_id, status, depends_on, None)
+        };
+        let tasks = serde_json::json!([
+            task(done, "done", &[]),
+            task(blocker, "inprogress", &[]),
+            task(ready, "todo", &[done]),
+            task(blocked, "todo", &[done, blocker]),
+        ]);
+        let app = Router::new().route(
+            "/api/tasks",
+            get(move || {
+                let tasks = tasks.clone();
+                async move { Json(serde_json::json!({ "success": true, "data": tasks })) }
+            }),
+        );
+        let base_url = spawn_backend(app).await;
+        let server = TaskServer::new(&base_url);
+        let ids = |ready_filter: Option<bool>| {
+            let server = &server;
+            async move {
+                let result = server
+                    .list_tasks(Parameters(ListTasksRequest {
+                        project_id,
+                        status: Some("todo".to_string()),
+                        limit: None,
+                        ready: ready_filter,
+                        assignee: None,
+                    }))
+                    .await
+                    .unwrap();
+                let body = result_json(&result);
+                body["tasks"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|task| task["id"].as_str().unwrap().to_string())
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        assert_eq!(ids(Some(true)).await, [ready.to_string()]);
+        assert_eq!(ids(Some(false)).await, [blocked.to_string()]);
+        assert_eq!(ids(None).await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn move_task_returns_the_task_in_its_new_project() {
+        use axum::{
+            Json, Router,
+            extract::Path,
+            routing::{get, post},
+        };
+
+        let (task_id, old_project, new_project) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let task = move |project_id: Uuid| task_json(task_id, project_id, "Misfiled", "todo");
+        let app = Router::new()
+            .route(
+                "/api/tasks/{id}",
+                get(move || async move {
+                    Json(serde_json::json!({ "success": true, "data": task(old_project) }))
+                }),
+            )
+            .route(
+                "/api/tasks/{id}/assignee",
+                get(|| async { Json(serde_json::json!({ "success": true, "data": null })) }),
+            )
+            .route(
+                "/api/tasks/{id}/move",
+                post(
+                    move |Path(_id): Path<Uuid>, Json(body): Json<serde_json::Value>| async move {
+                        let project_id: Uuid =
+                            serde_json::from_value(body["project_id"].clone()).unwrap();
+                        Json(serde_json::json!({ "success": true, "data": task(project_id) }))
+                    },
+                ),
+            );
+        let base_url = spawn_backend(app).await;
+        let server = TaskServer::new(&base_url);
+
+        let result = server
+            .move_task(Parameters(MoveTaskRequest {
+                task_id,
+                target_project_id: new_project,
+            }))
+            .await
+            .unwrap();
+        assert_ne!(result.is_error, Some(true));
+        let body = result_json(&result);
+        assert_eq!(body["task"]["id"], task_id.to_string());
+        assert_eq!(body["previous_project_id"], old_project.to_string());
+        assert_eq!(body["task"]["depends_on"], serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn assign_task_returns_details_with_the_assignee() {
+        use axum::{
+            Json, Router,
+            routing::{get, put},
+        };
+
+        let task_id = Uuid::new_v4();
+        let task = task_json(task_id, Uuid::new_v4(), "Claim me", "todo");
+        let app = Router::new()
+            .route(
+                "/api/tasks/{id}",
+                get(move || {
+                    let task = task.clone();
+                    async move { Json(serde_json::json!({ "success": true, "data": task })) }
+                }),
+            )
+            .route(
+                "/api/tasks/{id}/assignee",
+                put(|Json(body): Json<serde_json::Value>| async move {
+                    Json(serde_json::json!({ "success": true, "data": body["assignee"] }))
+                }),
+            )
+            .route(
+                "/api/tasks/{id}/dependencies",
+                get(|| async { Json(serde_json::json!({ "success": true, "data": [] })) }),
+            );
+        let base_url = spawn_backend(app).await;
+
+        let result = TaskServer::new(&base_url)
+            .assign_task(Parameters(AssignTaskRequest {
+                task_id,
+                assignee: Some("agent-1".to_string()),
+            }))
+            .await
+            .unwrap();
+        assert_ne!(result.is_error, Some(true));
+        let body = result_json(&result);
+        assert_eq!(body["task"]["id"], task_id.to_string());
+        assert_eq!(body["task"]["assignee"], "agent-1");
+    }
+
+    #[tokio::test]
+    async fn list_tasks_filters_on_assignee() {
+        use axum::{Json, Router, routing::get};
+
+        let project_id = Uuid::new_v4();
+        let (mine, theirs, open) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let task = |id: Uuid, assignee: Option<&str>| {
+            listed_task_json(id, project_id, "todo", &[], assignee)
+        };
+        let tasks = serde_json::json!([
+            task(mine, Some("agent-1")),
+            task(theirs, Some("agent-2")),
+            task(open, None),
+        ]);
+        let app = Router::new().route(
+            "/api/tasks",
+            get(move || {
+                let tasks = tasks.clone();
+                async move { Json(serde_json::json!({ "success": true, "data": tasks })) }
+            }),
+        );
+        let base_url = spawn_backend(app).await;
+        let server = TaskServer::new(&base_url);
+        let ids = |assignee: Option<&str>| {
+            let server = &server;
+            let assignee = assignee.map(str::to_string);
+            async move {
+                let result = server
+                    .list_tasks(Parameters(ListTasksRequest {
+                        project_id,
+                        status: None,
+                        limit: None,
+                        ready: None,
+                        assignee,
+                    }))
+                    .await
+                    .unwrap();
+                let body = result_json(&result);
+                body["tasks"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|task| task["id"].as_str().unwrap().to_string())
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        assert_eq!(ids(Some("agent-1")).await, [mine.to_string()]);
+        assert_eq!(ids(Some("")).await, [open.to_string()]);
+        assert_eq!(ids(None).await.len(), 3);
+    }
+
+    fn sample_attempt_diff() -> AttemptDiff {
+        use crate::routes::task_attempts::AttemptFileDiff;
+        use utils::diff::DiffChangeKind;
+
+        AttemptDiff {
+            files: vec![
+                AttemptFileDiff {
+                    path: "src/lib.rs".to_string(),
+                    change: DiffChangeKind::Modified,
+                    unified_diff: Some("--- a/src/lib.rs\n+++ b/src/lib.rs\n".to_string()),
+                },
+                AttemptFileDiff {
+                    path: "assets/big.bin".to_string(),
+                    change: DiffChangeKind::Added,
+                    unified_diff: None,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn attempt_diff_is_combined_or_split_per_file() {
+        let attempt_id = Uuid::new_v4();
+
+        let combined =
+            GetAttemptDiffResponse::from_attempt_diff(attempt_id, sample_attempt_diff(), false);
+        assert_eq!(combined.files_changed, 2);
+        assert!(combined.files.is_none());
+        assert_eq!(
+            combined.diff.as_deref(),
+            Some(
+                "--- a/src/lib.rs\n+++ b/src/lib.rs\n# assets/big.bin (added): contents omitted\n"
+            )
+        );
+
+        let split =
+            GetAttemptDiffResponse::from_attempt_diff(attempt_id, sample_attempt_diff(), true);
+        assert!(split.diff.is_none());
+        let files = split.files.unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, "src/lib.rs");
+        assert_eq!(files[0].change, "modified");
+        assert!(files[1].unified_diff.is_none());
+    }
+
+    #[test]
+    fn unrestricted_server_allows_any_project() {
+        let server = TaskServer::new("http://example.com");
+        assert!(server.ensure_project_allowed(Uuid::new_v4()).is_ok());
+    }
 }
@@ -74,7 +74,7 @@ async fn device_poll(
     };
     // Save to config
     {
-        let config_path = utils::assets::config_path();
+        let config_path = utils::assets::config_path()?;
         let mut config = deployment.config().write().await;
         config.github.username = Some(user_info.username.clone());
         config.github.primary_email = user_info.primary_email.clone();
@@ -14,6 +14,7 @@ use executors::{
     mcp_config::{McpConfig, read_agent_config, write_agent_config},
     profile::{ExecutorConfigs, ExecutorProfileId},
 };
+use schemars::generate::SchemaSettings;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use services::services::config::{Config, ConfigError, SoundFile, save_config_to_file};
@@ -27,6 +28,7 @@ pub fn router() -> Router<DeploymentImpl> {
     Router::new()
         .route("/info", get(get_user_system_info))
         .route("/config", put(update_config))
+        .route("/config/schema", get(get_config_schema))
         .route("/sounds/{sound}", get(get_sound))
         .route("/mcp-config", get(get_mcp_servers).post(update_mcp_servers))
         .route("/profiles", get(get_profiles).put(update_profiles))
@@ -100,7 +102,12 @@ async fn update_config(
     State(deployment): State<DeploymentImpl>,
     Json(new_config): Json<Config>,
 ) -> ResponseJson<ApiResponse<Config>> {
-    let config_path = config_path();
+    let config_path = match config_path() {
+        Ok(path) => path,
+        Err(e) => {
+            return ResponseJson(ApiResponse::error(&format!("Failed to save config: {}", e)));
+        }
+    };
 
     // Validate git branch prefix
     if !utils::git::is_valid_branch_prefix(&new_config.git_branch_prefix) {
@@ -127,6 +134,14 @@ async fn update_config(
     }
 }
 
+/// JSON Schema (draft-07, fully inlined) for the body accepted by `PUT /config`
+async fn get_config_schema() -> ResponseJson<ApiResponse<Value>> {
+    let mut settings = SchemaSettings::draft07();
+    settings.inline_subschemas = true;
+    let schema = settings.into_generator().into_root_schema_for::<Config>();
+    ResponseJson(ApiResponse::success(schema.to_value()))
+}
+
 /// Track config events when fields transition from false → true
 async fn track_config_events(deployment: &DeploymentImpl, old: &Config, new: &Config) {
     let events = [
@@ -392,7 +407,15 @@ pub struct ProfilesContent {
 async fn get_profiles(
     State(_deployment): State<DeploymentImpl>,
 ) -> ResponseJson<ApiResponse<ProfilesContent>> {
-    let profiles_path = utils::assets::profiles_path();
+    let profiles_path = match utils::assets::profiles_path() {
+        Ok(path) => path,
+        Err(e) => {
+            return ResponseJson(ApiResponse::error(&format!(
+                "Failed to locate profiles: {}",
+                e
+            )));
+        }
+    };
 
     // Use cached data to ensure consistency with runtime and PUT updates
     let profiles = ExecutorConfigs::get_cached();
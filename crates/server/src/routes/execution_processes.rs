@@ -5,6 +5,7 @@ use axum::{
         Path, Query, State,
         ws::{WebSocket, WebSocketUpgrade},
     },
+    http::header,
     middleware::from_fn_with_state,
     response::{IntoResponse, Json as ResponseJson},
     routing::{get, post},
@@ -13,9 +14,11 @@ use db::models::execution_process::{
     ExecutionProcess, ExecutionProcessError, ExecutionProcessStatus,
 };
 use deployment::Deployment;
-use futures_util::{SinkExt, StreamExt, TryStreamExt};
-use serde::Deserialize;
+use executors::{logs::utils::redact::redact_text_secrets, process_registry::RunningProcess};
+use futures_util::{FutureExt, SinkExt, StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
 use services::services::container::ContainerService;
+use ts_rs::TS;
 use utils::{log_msg::LogMsg, response::ApiResponse};
 use uuid::Uuid;
 
@@ -44,6 +47,15 @@ pub async fn get_execution_processes(
     Ok(ResponseJson(ApiResponse::success(execution_processes)))
 }
 
+/// The processes running right now across all attempts, with their memory and CPU usage
+pub async fn get_running_processes(
+    State(deployment): State<DeploymentImpl>,
+) -> ResponseJson<ApiResponse<Vec<RunningProcess>>> {
+    ResponseJson(ApiResponse::success(
+        deployment.container().process_registry().list(),
+    ))
+}
+
 pub async fn get_execution_process_by_id(
     Extension(execution_process): Extension<ExecutionProcess>,
     State(_deployment): State<DeploymentImpl>,
@@ -106,7 +118,7 @@ async fn handle_raw_logs_ws(
                 let patch = ConversationPatch::add_stderr(index, content);
                 LogMsg::JsonPatch(patch).to_ws_message_unchecked()
             }
-            LogMsg::Finished => LogMsg::Finished.to_ws_message_unchecked(),
+            LogMsg::Finished(status) => LogMsg::Finished(status).to_ws_message_unchecked(),
             _ => unreachable!("Raw stream should only have Stdout/Stderr/Finished"),
         }
     });
@@ -134,6 +146,135 @@ async fn handle_raw_logs_ws(
     Ok(())
 }
 
+/// Chunks returned per call when the caller does not ask for a specific amount
+const DEFAULT_LOG_CHUNK_LIMIT: usize = 500;
+
+#[derive(Debug, Deserialize)]
+pub struct RawLogsQuery {
+    /// Skip chunks before this offset, i.e. the `next_offset` of the previous page
+    pub after_offset: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum RawLogStream {
+    Stdout,
+    Stderr,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct RawLogChunk {
+    /// Position among every chunk the process wrote; stays valid when older chunks are evicted
+    pub offset: usize,
+    pub stream: RawLogStream,
+    pub content: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct RawLogsPage {
+    pub chunks: Vec<RawLogChunk>,
+    /// Pass as `after_offset` to fetch the chunks that follow this page
+    pub next_offset: usize,
+    /// True once the process has exited and every chunk up to `next_offset` was returned
+    pub finished: bool,
+}
+
+/// The raw log chunks available right now, starting at `after_offset`. Poll with the returned
+/// `next_offset` to follow a running process without holding a WebSocket open.
+///
+/// Each chunk is redacted on its own, so a secret the process wrote across two chunks is not
+/// masked; the download below redacts the joined output instead.
+pub async fn get_raw_logs(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<RawLogsQuery>,
+) -> Result<ResponseJson<ApiResponse<RawLogsPage>>, ApiError> {
+    let snapshot = deployment
+        .container()
+        .raw_output_snapshot(&execution_process.id)
+        .await
+        .ok_or_else(|| {
+            ApiError::ExecutionProcess(ExecutionProcessError::ExecutionProcessNotFound)
+        })?;
+
+    let after_offset = query.after_offset.unwrap_or(0);
+    let limit = query.limit.unwrap_or(DEFAULT_LOG_CHUNK_LIMIT).max(1);
+    // Chunks before `first_seq` were evicted from memory; continue with the oldest one left
+    let start = after_offset.max(snapshot.first_seq);
+    let end = snapshot.first_seq + snapshot.chunks.len();
+
+    let chunks: Vec<RawLogChunk> = snapshot
+        .chunks
+        .into_iter()
+        .zip(snapshot.first_seq..)
+        .skip(start - snapshot.first_seq)
+        .take(limit)
+        .filter_map(|(msg, offset)| {
+            let (stream, content) = match msg {
+                LogMsg::Stdout(content) => (RawLogStream::Stdout, content),
+                LogMsg::Stderr(content) => (RawLogStream::Stderr, content),
+                _ => return None,
+            };
+            Some(RawLogChunk {
+                offset,
+                stream,
+                content: redact_text_secrets(&content).into_owned(),
+            })
+        })
+        .collect();
+    let next_offset = chunks.last().map_or(start, |chunk| chunk.offset + 1);
+
+    Ok(ResponseJson(ApiResponse::success(RawLogsPage {
+        chunks,
+        next_offset,
+        finished: snapshot.finished && next_offset >= end,
+    })))
+}
+
+/// Everything the process wrote to stdout so far, before normalization, as a file download. For
+/// coding agents this is the executor's JSONL stream, which is what a normalization bug report
+/// needs to be reproducible. Secrets are redacted, as in the normalized logs.
+pub async fn download_raw_stdout(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<impl IntoResponse, ApiError> {
+    let mut stream = deployment
+        .container()
+        .stream_raw_logs(&execution_process.id)
+        .await
+        .ok_or_else(|| {
+            ApiError::ExecutionProcess(ExecutionProcessError::ExecutionProcessNotFound)
+        })?;
+
+    let mut stdout = String::new();
+    // Like `get_raw_logs`, only take what is buffered so a running process can still be downloaded
+    while let Some(Some(msg)) = stream.next().now_or_never() {
+        match msg? {
+            LogMsg::Stdout(content) => stdout.push_str(&content),
+            LogMsg::Finished(_) => break,
+            _ => {}
+        }
+    }
+
+    let stdout = redact_text_secrets(&stdout).into_owned();
+
+    let disposition = format!(
+        "attachment; filename=\"execution-{}-stdout.log\"",
+        execution_process.id
+    );
+    Ok((
+        [
+            (
+                header::CONTENT_TYPE,
+                "text/plain; charset=utf-8".to_string(),
+            ),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        stdout,
+    ))
+}
+
 pub async fn stream_normalized_logs_ws(
     ws: WebSocketUpgrade,
     State(deployment): State<DeploymentImpl>,
@@ -251,7 +392,9 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let task_attempt_id_router = Router::new()
         .route("/", get(get_execution_process_by_id))
         .route("/stop", post(stop_execution_process))
+        .route("/raw-logs", get(get_raw_logs))
         .route("/raw-logs/ws", get(stream_raw_logs_ws))
+        .route("/raw-logs/download", get(download_raw_stdout))
         .route("/normalized-logs/ws", get(stream_normalized_logs_ws))
         .layer(from_fn_with_state(
             deployment.clone(),
@@ -260,6 +403,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
 
     let task_attempts_router = Router::new()
         .route("/", get(get_execution_processes))
+        .route("/running", get(get_running_processes))
         .route("/stream/ws", get(stream_execution_processes_ws))
         .nest("/{id}", task_attempt_id_router);
 
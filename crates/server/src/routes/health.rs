@@ -1,6 +1,10 @@
-use axum::response::Json;
-use utils::response::ApiResponse;
+use axum::response::{IntoResponse, Json};
+use utils::{response::ApiResponse, version::APP_VERSION};
 
-pub async fn health_check() -> Json<ApiResponse<String>> {
-    Json(ApiResponse::success("OK".to_string()))
+/// Header carrying the backend version, used by clients for readiness checks.
+pub const VERSION_HEADER: &str = "x-forge-version";
+
+pub async fn health_check() -> impl IntoResponse {
+    let body: Json<ApiResponse<String>> = Json(ApiResponse::success("OK".to_string()));
+    ([(VERSION_HEADER, APP_VERSION)], body)
 }
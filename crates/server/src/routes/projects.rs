@@ -6,10 +6,11 @@ use axum::{
     http::StatusCode,
     middleware::from_fn_with_state,
     response::Json as ResponseJson,
-    routing::{get, post},
+    routing::{get, post, put},
 };
 use db::models::project::{
-    CreateProject, Project, ProjectError, SearchMatchType, SearchResult, UpdateProject,
+    CreateProject, Project, ProjectError, ProjectGitConfig, RenameProject, SearchMatchType,
+    SearchResult, UpdateProject, UpdateProjectGitConfig,
 };
 use deployment::Deployment;
 use ignore::WalkBuilder;
@@ -44,6 +45,75 @@ pub async fn get_project_branches(
     Ok(ResponseJson(ApiResponse::success(branches)))
 }
 
+#[derive(Debug, serde::Serialize, serde::Deserialize, ts_rs::TS)]
+pub struct ProjectGitConfigResponse {
+    pub default_base_branch: Option<String>,
+    pub remote_name: Option<String>,
+    /// Remotes defined in the project's repository
+    pub remotes: Vec<String>,
+}
+
+impl ProjectGitConfigResponse {
+    fn new(config: ProjectGitConfig, remotes: Vec<String>) -> Self {
+        Self {
+            default_base_branch: config.default_base_branch,
+            remote_name: config.remote_name,
+            remotes,
+        }
+    }
+}
+
+pub async fn get_project_git_config(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectGitConfigResponse>>, ApiError> {
+    let config = ProjectGitConfig::find(&deployment.db().pool, project.id).await?;
+    let remotes = deployment.git().get_remote_names(&project.git_repo_path)?;
+    Ok(ResponseJson(ApiResponse::success(
+        ProjectGitConfigResponse::new(config, remotes),
+    )))
+}
+
+pub async fn update_project_git_config(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateProjectGitConfig>,
+) -> Result<ResponseJson<ApiResponse<ProjectGitConfigResponse>>, ApiError> {
+    let remotes = deployment.git().get_remote_names(&project.git_repo_path)?;
+    if let Some(remote_name) = payload.remote_name.as_deref().map(str::trim)
+        && !remote_name.is_empty()
+        && !remotes.iter().any(|remote| remote == remote_name)
+    {
+        return Ok(ResponseJson(ApiResponse::error(&format!(
+            "Remote '{remote_name}' not found; available remotes: {}",
+            remotes.join(", ")
+        ))));
+    }
+
+    let config = ProjectGitConfig::update(&deployment.db().pool, project.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(
+        ProjectGitConfigResponse::new(config, remotes),
+    )))
+}
+
+/// Rename a project, optionally setting its default PR base branch in the same transaction.
+pub async fn rename_project(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<RenameProject>,
+) -> Result<ResponseJson<ApiResponse<Project>>, ApiError> {
+    if payload.name.trim().is_empty() {
+        return Err(ApiError::BadRequest(
+            "Project name must not be empty".to_string(),
+        ));
+    }
+
+    let renamed = Project::rename(&deployment.db().pool, project.id, &payload)
+        .await?
+        .ok_or(ProjectError::ProjectNotFound)?;
+    Ok(ResponseJson(ApiResponse::success(renamed)))
+}
+
 pub async fn create_project(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<CreateProject>,
@@ -511,6 +581,11 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             get(get_project).put(update_project).delete(delete_project),
         )
         .route("/branches", get(get_project_branches))
+        .route(
+            "/git-config",
+            get(get_project_git_config).put(update_project_git_config),
+        )
+        .route("/rename", put(rename_project))
         .route("/search", get(search_project_files))
         .route("/open-editor", post(open_project_in_editor))
         .layer(from_fn_with_state(
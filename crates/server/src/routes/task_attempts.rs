@@ -13,11 +13,12 @@ use axum::{
     response::{IntoResponse, Json as ResponseJson},
     routing::{get, post},
 };
+use chrono::{DateTime, Utc};
 use db::models::{
     draft::{Draft, DraftType},
     execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
     merge::{Merge, MergeStatus, PrMerge, PullRequestInfo},
-    project::{Project, ProjectError},
+    project::{Project, ProjectError, ProjectGitConfig},
     task::{Task, TaskRelationships, TaskStatus},
     task_attempt::{CreateTaskAttempt, TaskAttempt, TaskAttemptError},
 };
@@ -25,7 +26,7 @@ use deployment::Deployment;
 use executors::{
     actions::{
         ExecutorAction, ExecutorActionType,
-        coding_agent_follow_up::CodingAgentFollowUpRequest,
+        coding_agent_follow_up::{CodingAgentFollowUpRequest, ResumeOverrides},
         script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
     },
     executors::{CodingAgent, ExecutorError},
@@ -35,12 +36,17 @@ use git2::BranchType;
 use serde::{Deserialize, Serialize};
 use services::services::{
     container::ContainerService,
-    git::{ConflictOp, WorktreeResetOptions},
+    git::{ConflictOp, DiffTarget, GitService, WorktreeResetOptions},
     github_service::{CreatePrRequest, GitHubService, GitHubServiceError},
+    webhook::AttemptEvent,
 };
 use sqlx::Error as SqlxError;
 use ts_rs::TS;
-use utils::response::ApiResponse;
+use utils::{
+    diff::{DiffChangeKind, create_unified_diff_with_context},
+    git,
+    response::ApiResponse,
+};
 use uuid::Uuid;
 
 use crate::{
@@ -134,6 +140,20 @@ pub struct CreateTaskAttemptBody {
     /// Executor profile specification
     pub executor_profile_id: ExecutorProfileId,
     pub base_branch: String,
+    /// Exact name for the attempt branch, instead of the generated one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub branch_name: Option<String>,
+    /// Template for the attempt branch name, e.g. `feature/{task_id}-{slug}`.
+    /// Supports `{prefix}`, `{task_id}`, `{attempt_id}` and `{slug}`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub branch_template: Option<String>,
+    /// Run the attempt in its own git worktree (default). When false the agent works directly
+    /// in the project repository, without isolation from local changes or other attempts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub use_worktree: Option<bool>,
 }
 
 impl CreateTaskAttemptBody {
@@ -141,6 +161,42 @@ impl CreateTaskAttemptBody {
     pub fn get_executor_profile_id(&self) -> ExecutorProfileId {
         self.executor_profile_id.clone()
     }
+
+    /// The requested branch name, the rendered template, or the generated default.
+    async fn git_branch_name(
+        &self,
+        deployment: &DeploymentImpl,
+        task: &Task,
+        attempt_id: &Uuid,
+    ) -> Result<String, ApiError> {
+        match (&self.branch_name, &self.branch_template) {
+            (Some(_), Some(_)) => Err(ApiError::BadRequest(
+                "Pass either branch_name or branch_template, not both".to_string(),
+            )),
+            (Some(name), None) => {
+                if !git::is_valid_branch_name(name) {
+                    return Err(ApiError::BadRequest(format!(
+                        "'{name}' is not a valid git branch name"
+                    )));
+                }
+                Ok(name.clone())
+            }
+            (None, Some(template)) => {
+                let prefix = deployment.container().git_branch_prefix().await;
+                let vars = git::BranchTemplateVars {
+                    prefix: &prefix,
+                    task_id: &task.id,
+                    attempt_id,
+                    task_title: &task.title,
+                };
+                git::render_branch_template(template, &vars).map_err(ApiError::BadRequest)
+            }
+            (None, None) => Ok(deployment
+                .container()
+                .git_branch_from_task_attempt(attempt_id, &task.title)
+                .await),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, TS)]
@@ -156,16 +212,17 @@ pub async fn create_task_attempt(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<CreateTaskAttemptBody>,
 ) -> Result<ResponseJson<ApiResponse<TaskAttempt>>, ApiError> {
-    let executor_profile_id = payload.get_executor_profile_id();
+    let executor_profile_id = payload
+        .get_executor_profile_id()
+        .resolve(&ExecutorConfigs::get_cached())?;
     let task = Task::find_by_id(&deployment.db().pool, payload.task_id)
         .await?
         .ok_or(SqlxError::RowNotFound)?;
 
     let attempt_id = Uuid::new_v4();
-    let git_branch_name = deployment
-        .container()
-        .git_branch_from_task_attempt(&attempt_id, &task.title)
-        .await;
+    let git_branch_name = payload
+        .git_branch_name(&deployment, &task, &attempt_id)
+        .await?;
 
     let task_attempt = TaskAttempt::create(
         &deployment.db().pool,
@@ -179,6 +236,10 @@ pub async fn create_task_attempt(
     )
     .await?;
 
+    if let Some(use_worktree) = payload.use_worktree {
+        TaskAttempt::set_use_worktree(&deployment.db().pool, task_attempt.id, use_worktree).await?;
+    }
+
     if let Err(err) = deployment
         .container()
         .start_attempt(&task_attempt, executor_profile_id.clone())
@@ -198,6 +259,9 @@ pub async fn create_task_attempt(
             }),
         )
         .await;
+    deployment
+        .send_attempt_webhook(AttemptEvent::Created, &task, &task_attempt)
+        .await;
 
     tracing::info!("Created attempt for task {}", task.id);
 
@@ -233,7 +297,7 @@ pub async fn run_agent_setup(
     Ok(ResponseJson(ApiResponse::success(RunAgentSetupResponse {})))
 }
 
-#[derive(Debug, Deserialize, TS)]
+#[derive(Debug, Serialize, Deserialize, TS)]
 pub struct CreateFollowUpAttempt {
     pub prompt: String,
     pub variant: Option<String>,
@@ -241,6 +305,8 @@ pub struct CreateFollowUpAttempt {
     pub retry_process_id: Option<Uuid>,
     pub force_when_dirty: Option<bool>,
     pub perform_git_reset: Option<bool>,
+    /// Model or reasoning effort to switch to when resuming the conversation
+    pub resume_overrides: Option<ResumeOverrides>,
 }
 
 pub async fn follow_up(
@@ -336,11 +402,10 @@ pub async fn follow_up(
         let _ = Draft::clear_after_send(pool, task_attempt.id, DraftType::Retry).await;
     }
 
-    let latest_session_id = ExecutionProcess::find_latest_session_id_by_task_attempt(
-        &deployment.db().pool,
-        task_attempt.id,
-    )
-    .await?;
+    let latest_session_id = deployment
+        .container()
+        .latest_session_id(&task_attempt)
+        .await?;
 
     let mut prompt = payload.prompt;
     if let Some(image_ids) = &payload.image_ids {
@@ -357,6 +422,7 @@ pub async fn follow_up(
             prompt: prompt.clone(),
             session_id,
             executor_profile_id: executor_profile_id.clone(),
+            resume_overrides: payload.resume_overrides,
         })
     } else {
         ExecutorActionType::CodingAgentInitialRequest(
@@ -475,14 +541,17 @@ pub async fn replace_process(
     };
 
     // Use latest session_id from remaining (earlier) processes; if none exists, start a fresh initial request
-    let latest_session_id =
-        ExecutionProcess::find_latest_session_id_by_task_attempt(pool, task_attempt.id).await?;
+    let latest_session_id = deployment
+        .container()
+        .latest_session_id(&task_attempt)
+        .await?;
 
     let action = if let Some(session_id) = latest_session_id {
         let follow_up_request = CodingAgentFollowUpRequest {
             prompt: payload.prompt.clone(),
             session_id,
             executor_profile_id,
+            resume_overrides: None,
         };
         ExecutorAction::new(
             ExecutorActionType::CodingAgentFollowUpRequest(follow_up_request),
@@ -582,10 +651,16 @@ async fn handle_task_attempt_diff_ws(
     Ok(())
 }
 
-#[derive(Debug, Serialize, TS)]
+#[derive(Debug, Serialize, Deserialize, TS)]
 pub struct CommitInfo {
     pub sha: String,
     pub subject: String,
+    pub author: String,
+    pub email: String,
+    pub timestamp: DateTime<Utc>,
+    /// Full commit message, subject included
+    pub message: String,
+    pub files_changed: usize,
 }
 
 pub async fn get_commit_info(
@@ -600,10 +675,15 @@ pub async fn get_commit_info(
     };
     let wt_buf = ensure_worktree_path(&deployment, &task_attempt).await?;
     let wt = wt_buf.as_path();
-    let subject = deployment.git().get_commit_subject(wt, &sha)?;
+    let details = deployment.git().get_commit_details(wt, &sha)?;
     Ok(ResponseJson(ApiResponse::success(CommitInfo {
         sha,
-        subject,
+        subject: details.subject,
+        author: details.author_name,
+        email: details.author_email,
+        timestamp: details.timestamp,
+        message: details.message,
+        files_changed: details.files_changed,
     })))
 }
 
@@ -643,6 +723,71 @@ pub async fn compare_commit_to_head(
     })))
 }
 
+/// Lines of context around each change when the caller does not ask for a specific amount
+const DEFAULT_DIFF_CONTEXT_LINES: usize = 3;
+
+#[derive(Debug, Deserialize)]
+pub struct AttemptDiffQuery {
+    pub context_lines: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct AttemptFileDiff {
+    pub path: String,
+    pub change: DiffChangeKind,
+    /// Unified diff of the file, `None` when its contents were omitted (e.g., too large)
+    pub unified_diff: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct AttemptDiff {
+    pub files: Vec<AttemptFileDiff>,
+}
+
+/// Snapshot of the attempt's working changes against its base commit, as unified diffs.
+pub async fn get_task_attempt_diff(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Query(params): Query<AttemptDiffQuery>,
+) -> Result<ResponseJson<ApiResponse<AttemptDiff>>, ApiError> {
+    let context_lines = params.context_lines.unwrap_or(DEFAULT_DIFF_CONTEXT_LINES);
+    let wt_buf = ensure_worktree_path(&deployment, &task_attempt).await?;
+    let wt = wt_buf.as_path();
+    let base_commit =
+        deployment
+            .git()
+            .get_base_commit(wt, &task_attempt.branch, &task_attempt.target_branch)?;
+    let diffs = deployment.git().get_diffs(
+        DiffTarget::Worktree {
+            worktree_path: wt,
+            base_commit: &base_commit,
+        },
+        None,
+    )?;
+
+    let files = diffs
+        .into_iter()
+        .map(|diff| {
+            let path = GitService::diff_path(&diff);
+            let unified_diff = (!diff.content_omitted).then(|| {
+                create_unified_diff_with_context(
+                    &path,
+                    diff.old_content.as_deref().unwrap_or_default(),
+                    diff.new_content.as_deref().unwrap_or_default(),
+                    context_lines,
+                )
+            });
+            AttemptFileDiff {
+                path,
+                change: diff.change,
+                unified_diff,
+            }
+        })
+        .collect();
+
+    Ok(ResponseJson(ApiResponse::success(AttemptDiff { files })))
+}
+
 #[axum::debug_handler]
 pub async fn merge_task_attempt(
     Extension(task_attempt): Extension<TaskAttempt>,
@@ -700,6 +845,9 @@ pub async fn merge_task_attempt(
             }),
         )
         .await;
+    deployment
+        .send_attempt_webhook(AttemptEvent::Merged, &ctx.task, &task_attempt)
+        .await;
 
     Ok(ResponseJson(ApiResponse::success(())))
 }
@@ -718,9 +866,19 @@ pub async fn push_task_attempt_branch(
 
     let ws_path = ensure_worktree_path(&deployment, &task_attempt).await?;
 
-    deployment
-        .git()
-        .push_to_github(&ws_path, &task_attempt.branch, &github_token)?;
+    let pool = &deployment.db().pool;
+    let task = task_attempt
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::TaskAttempt(TaskAttemptError::TaskNotFound))?;
+    let git_config = ProjectGitConfig::find(pool, task.project_id).await?;
+
+    deployment.git().push_to_github(
+        &ws_path,
+        &task_attempt.branch,
+        &github_token,
+        git_config.remote_name.as_deref(),
+    )?;
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
@@ -737,20 +895,6 @@ pub async fn create_github_pr(
     };
     // Create GitHub service instance
     let github_service = GitHubService::new(&github_token)?;
-    // Get the task attempt to access the stored target branch
-    let target_branch = request.target_branch.unwrap_or_else(|| {
-        // Use the stored target branch from the task attempt as the default
-        // Fall back to config default or "main" only if stored target branch is somehow invalid
-        if !task_attempt.target_branch.trim().is_empty() {
-            task_attempt.target_branch.clone()
-        } else {
-            github_config
-                .default_pr_base
-                .as_ref()
-                .map_or_else(|| "main".to_string(), |b| b.to_string())
-        }
-    });
-
     let pool = &deployment.db().pool;
     let task = task_attempt
         .parent_task(pool)
@@ -759,15 +903,33 @@ pub async fn create_github_pr(
     let project = Project::find_by_id(pool, task.project_id)
         .await?
         .ok_or(ApiError::Project(ProjectError::ProjectNotFound))?;
+    let git_config = ProjectGitConfig::find(pool, project.id).await?;
+
+    // Get the task attempt to access the stored target branch
+    let target_branch = request.target_branch.unwrap_or_else(|| {
+        // Use the stored target branch from the task attempt as the default
+        // Fall back to the project's, then the global, default base or "main" only if the
+        // stored target branch is somehow invalid
+        if !task_attempt.target_branch.trim().is_empty() {
+            task_attempt.target_branch.clone()
+        } else {
+            git_config
+                .default_base_branch
+                .clone()
+                .or_else(|| github_config.default_pr_base.clone())
+                .unwrap_or_else(|| "main".to_string())
+        }
+    });
 
     let workspace_path = ensure_worktree_path(&deployment, &task_attempt).await?;
 
     // Push the branch to GitHub first
-    if let Err(e) =
-        deployment
-            .git()
-            .push_to_github(&workspace_path, &task_attempt.branch, &github_token)
-    {
+    if let Err(e) = deployment.git().push_to_github(
+        &workspace_path,
+        &task_attempt.branch,
+        &github_token,
+        git_config.remote_name.as_deref(),
+    ) {
         tracing::error!("Failed to push branch to GitHub: {}", e);
         let gh_e = GitHubServiceError::from(e);
         if gh_e.is_api_data() {
@@ -808,7 +970,7 @@ pub async fn create_github_pr(
     // Use GitService to get the remote URL, then create GitHubRepoInfo
     let repo_info = deployment
         .git()
-        .get_github_repo_info(&project.git_repo_path)?;
+        .get_github_repo_info(&project.git_repo_path, git_config.remote_name.as_deref())?;
 
     match github_service.create_pr(&repo_info, &pr_request).await {
         Ok(pr_info) => {
@@ -1279,6 +1441,73 @@ pub async fn delete_task_attempt_file(
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+/// Files larger than this are refused rather than read into the response
+const MAX_ATTEMPT_FILE_BYTES: u64 = 1024 * 1024;
+
+#[derive(Debug, Deserialize)]
+pub struct AttemptFileQuery {
+    file_path: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct AttemptFile {
+    /// Path relative to the attempt's worktree, as requested
+    pub path: String,
+    pub size: u64,
+    /// True when the file is not UTF-8 text; `content` is then omitted
+    pub is_binary: bool,
+    pub content: Option<String>,
+}
+
+/// Read a file from the attempt's worktree, which may differ from the project's checkout.
+#[axum::debug_handler]
+pub async fn get_task_attempt_file(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    Query(query): Query<AttemptFileQuery>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<AttemptFile>>, ApiError> {
+    if !utils::path::is_contained_relative_path(&query.file_path) {
+        return Err(ApiError::BadRequest(format!(
+            "file_path must be relative to the worktree without '..': {}",
+            query.file_path
+        )));
+    }
+    let worktree_path = ensure_worktree_path(&deployment, &task_attempt).await?;
+
+    // Resolve symlinks so a link inside the worktree can't expose files outside of it
+    let not_found = || ApiError::BadRequest(format!("File not found: {}", query.file_path));
+    let file_path =
+        std::fs::canonicalize(worktree_path.join(&query.file_path)).map_err(|_| not_found())?;
+    if !file_path.starts_with(std::fs::canonicalize(&worktree_path)?) {
+        return Err(ApiError::BadRequest(format!(
+            "file_path resolves outside the worktree: {}",
+            query.file_path
+        )));
+    }
+    let metadata = tokio::fs::metadata(&file_path).await?;
+    if !metadata.is_file() {
+        return Err(not_found());
+    }
+    if metadata.len() > MAX_ATTEMPT_FILE_BYTES {
+        return Err(ApiError::BadRequest(format!(
+            "{} is {} bytes, larger than the {MAX_ATTEMPT_FILE_BYTES} byte limit",
+            query.file_path,
+            metadata.len()
+        )));
+    }
+
+    let bytes = tokio::fs::read(&file_path).await?;
+    let content = String::from_utf8(bytes)
+        .ok()
+        .filter(|text| !text.contains('\0'));
+    Ok(ResponseJson(ApiResponse::success(AttemptFile {
+        path: query.file_path,
+        size: metadata.len(),
+        is_binary: content.is_none(),
+        content,
+    })))
+}
+
 #[axum::debug_handler]
 pub async fn start_dev_server(
     Extension(task_attempt): Extension<TaskAttempt>,
@@ -1456,11 +1685,12 @@ pub async fn attach_existing_pr(
     let Some(project) = Project::find_by_id(pool, task.project_id).await? else {
         return Err(ApiError::Project(ProjectError::ProjectNotFound));
     };
+    let git_config = ProjectGitConfig::find(pool, project.id).await?;
 
     let github_service = GitHubService::new(&github_token)?;
     let repo_info = deployment
         .git()
-        .get_github_repo_info(&project.git_repo_path)?;
+        .get_github_repo_info(&project.git_repo_path, git_config.remote_name.as_deref())?;
 
     // List all PRs for branch (open, closed, and merged)
     let prs = github_service
@@ -1528,6 +1758,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/commit-compare", get(compare_commit_to_head))
         .route("/start-dev-server", post(start_dev_server))
         .route("/branch-status", get(get_task_attempt_branch_status))
+        .route("/diff", get(get_task_attempt_diff))
         .route("/diff/ws", get(stream_task_attempt_diff_ws))
         .route("/merge", post(merge_task_attempt))
         .route("/push", post(push_task_attempt_branch))
@@ -1537,6 +1768,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/pr/attach", post(attach_existing_pr))
         .route("/open-editor", post(open_task_attempt_in_editor))
         .route("/delete-file", post(delete_task_attempt_file))
+        .route("/file", get(get_task_attempt_file))
         .route("/children", get(get_task_attempt_children))
         .route("/stop", post(stop_task_attempt_execution))
         .route("/change-target-branch", post(change_target_branch))
@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::{future::Future, path::PathBuf};
 
 use anyhow;
 use axum::{
@@ -14,17 +14,23 @@ use axum::{
 };
 use db::models::{
     image::TaskImage,
+    project::Project,
     task::{CreateTask, Task, TaskStatus, TaskWithAttemptStatus, UpdateTask},
+    task_assignee::{SetTaskAssignee, TaskAssignee},
     task_attempt::{CreateTaskAttempt, TaskAttempt},
+    task_dependency::TaskDependency,
 };
 use deployment::Deployment;
-use executors::profile::ExecutorProfileId;
+use executors::profile::{ExecutorConfigs, ExecutorProfileId};
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
-use services::services::container::{
-    ContainerService, WorktreeCleanupData, cleanup_worktrees_direct,
+use services::services::{
+    container::{ContainerService, WorktreeCleanupData, cleanup_worktrees_direct},
+    github_service::{GitHubIssue, GitHubIssueRef, GitHubService},
+    idempotency::{IdempotencyService, Idempotent, KeyRecorder},
+    webhook::AttemptEvent,
 };
-use sqlx::Error as SqlxError;
+use sqlx::{Connection, Error as SqlxError};
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
@@ -101,10 +107,129 @@ pub async fn get_task(
     Ok(ResponseJson(ApiResponse::success(task)))
 }
 
+pub async fn get_task_dependencies(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<Uuid>>>, ApiError> {
+    let depends_on = TaskDependency::find_for_task(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(depends_on)))
+}
+
+pub async fn get_task_assignee(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<String>>>, ApiError> {
+    let assignee = TaskAssignee::find_for_task(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(assignee)))
+}
+
+/// Sets or clears who is working on the task; returns the stored assignee.
+pub async fn set_task_assignee(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SetTaskAssignee>,
+) -> Result<ResponseJson<ApiResponse<Option<String>>>, ApiError> {
+    let assignee =
+        TaskAssignee::set(&deployment.db().pool, task.id, payload.assignee.as_deref()).await?;
+    Ok(ResponseJson(ApiResponse::success(assignee)))
+}
+
+/// Checks that `depends_on` only names other tasks of the project and doesn't close a cycle.
+/// Returns the IDs with duplicates removed.
+async fn validate_dependencies(
+    deployment: &DeploymentImpl,
+    task_id: Uuid,
+    project_id: Uuid,
+    depends_on: &[Uuid],
+) -> Result<Vec<Uuid>, ApiError> {
+    let pool = &deployment.db().pool;
+    let mut unique = Vec::with_capacity(depends_on.len());
+    for &dependency in depends_on {
+        if dependency == task_id {
+            return Err(ApiError::BadRequest(
+                "A task cannot depend on itself".to_string(),
+            ));
+        }
+        if unique.contains(&dependency) {
+            continue;
+        }
+        if !Task::exists(pool, dependency, project_id).await? {
+            return Err(ApiError::BadRequest(format!(
+                "Dependency {dependency} is not a task of this project"
+            )));
+        }
+        unique.push(dependency);
+    }
+
+    let existing = TaskDependency::find_by_project_id(pool, project_id).await?;
+    if TaskDependency::would_create_cycle(&existing, task_id, &unique) {
+        return Err(ApiError::BadRequest(
+            "These dependencies would create a dependency cycle".to_string(),
+        ));
+    }
+    Ok(unique)
+}
+
+fn idempotency_key(payload: &CreateTask) -> Option<&str> {
+    payload
+        .idempotency_key
+        .as_deref()
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+}
+
+/// Runs `insert` unless the project's `key` already created a task. `insert` records its task
+/// as soon as the row exists, so a retry after a later failure gets it back instead of a
+/// duplicate.
+async fn create_once<T, Fut>(
+    idempotency: &IdempotencyService,
+    project_id: Uuid,
+    key: Option<&str>,
+    insert: impl FnOnce(Option<KeyRecorder>) -> Fut,
+) -> Result<Idempotent<T>, ApiError>
+where
+    Fut: Future<Output = Result<T, ApiError>>,
+{
+    match key {
+        Some(key) => {
+            idempotency
+                .run_once(project_id, key, |recorder| insert(Some(recorder)))
+                .await
+        }
+        None => insert(None).await.map(Idempotent::Created),
+    }
+}
+
 pub async fn create_task(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<CreateTask>,
 ) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+    let payload = payload.validated()?;
+    let outcome = create_once(
+        deployment.idempotency(),
+        payload.project_id,
+        idempotency_key(&payload),
+        |recorder| insert_task(&deployment, &payload, recorder),
+    )
+    .await?;
+    let task = match outcome {
+        Idempotent::Created(task) => task,
+        Idempotent::Existing(task_id) => {
+            tracing::debug!("Idempotency key already created task {}", task_id);
+            Task::find_by_id(&deployment.db().pool, task_id)
+                .await?
+                .ok_or(ApiError::Database(SqlxError::RowNotFound))?
+        }
+    };
+
+    Ok(ResponseJson(ApiResponse::success(task)))
+}
+
+async fn insert_task(
+    deployment: &DeploymentImpl,
+    payload: &CreateTask,
+    mut recorder: Option<KeyRecorder>,
+) -> Result<Task, ApiError> {
     let id = Uuid::new_v4();
 
     tracing::debug!(
@@ -113,11 +238,24 @@ pub async fn create_task(
         payload.project_id
     );
 
-    let task = Task::create(&deployment.db().pool, &payload, id).await?;
+    let depends_on = match &payload.depends_on {
+        Some(depends_on) => {
+            validate_dependencies(deployment, id, payload.project_id, depends_on).await?
+        }
+        None => Vec::new(),
+    };
+
+    let task = Task::create(&deployment.db().pool, payload, id).await?;
+    if let Some(recorder) = recorder.as_mut() {
+        recorder.record(task.id);
+    }
 
     if let Some(image_ids) = &payload.image_ids {
         TaskImage::associate_many_dedup(&deployment.db().pool, task.id, image_ids).await?;
     }
+    if !depends_on.is_empty() {
+        TaskDependency::replace(&deployment.db().pool, task.id, &depends_on).await?;
+    }
 
     deployment
         .track_if_analytics_allowed(
@@ -131,10 +269,56 @@ pub async fn create_task(
         )
         .await;
 
+    Ok(task)
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct CreateTaskFromGitHubIssue {
+    pub project_id: Uuid,
+    pub issue_url: String,
+}
+
+/// Create a task pre-filled with a GitHub issue's title and body, linking back to the issue
+pub async fn create_task_from_github_issue(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateTaskFromGitHubIssue>,
+) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+    let issue_ref = GitHubIssueRef::from_url(&payload.issue_url)
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+
+    let github_config = deployment.config().read().await.github.clone();
+    let Some(github_token) = github_config.token() else {
+        return Err(ApiError::BadRequest(
+            "GitHub integration is not configured. Sign in with GitHub in Settings first."
+                .to_string(),
+        ));
+    };
+    let issue = GitHubService::new(&github_token)?
+        .get_issue(&issue_ref)
+        .await?;
+
+    let payload = github_issue_task(payload.project_id, &issue)?;
+    let task = insert_task(&deployment, &payload, None).await?;
     Ok(ResponseJson(ApiResponse::success(task)))
 }
 
-#[derive(Debug, Deserialize, TS)]
+fn github_issue_task(project_id: Uuid, issue: &GitHubIssue) -> Result<CreateTask, ApiError> {
+    Ok(CreateTask::try_from_title_description(
+        project_id,
+        issue.title.clone(),
+        Some(github_issue_description(issue)),
+    )?)
+}
+
+fn github_issue_description(issue: &GitHubIssue) -> String {
+    let link = format!("Created from GitHub issue: {}", issue.html_url);
+    match issue.body.as_deref().map(str::trim) {
+        Some(body) if !body.is_empty() => format!("{body}\n\n---\n{link}"),
+        _ => link,
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
 pub struct CreateAndStartTaskRequest {
     pub task: CreateTask,
     pub executor_profile_id: ExecutorProfileId,
@@ -143,14 +327,59 @@ pub struct CreateAndStartTaskRequest {
 
 pub async fn create_task_and_start(
     State(deployment): State<DeploymentImpl>,
-    Json(payload): Json<CreateAndStartTaskRequest>,
+    Json(mut payload): Json<CreateAndStartTaskRequest>,
 ) -> Result<ResponseJson<ApiResponse<TaskWithAttemptStatus>>, ApiError> {
+    payload.task = payload.task.validated()?;
+    let project_id = payload.task.project_id;
+    let key = idempotency_key(&payload.task).map(str::to_string);
+    let outcome = create_once(
+        deployment.idempotency(),
+        project_id,
+        key.as_deref(),
+        |recorder| insert_and_start_task(&deployment, payload, recorder),
+    )
+    .await?;
+    let task = match outcome {
+        Idempotent::Created(task) => task,
+        Idempotent::Existing(task_id) => {
+            tracing::debug!("Idempotency key already created task {}", task_id);
+            Task::find_by_project_id_with_attempt_status(&deployment.db().pool, project_id)
+                .await?
+                .into_iter()
+                .find(|task| task.task.id == task_id)
+                .ok_or(ApiError::Database(SqlxError::RowNotFound))?
+        }
+    };
+
+    Ok(ResponseJson(ApiResponse::success(task)))
+}
+
+async fn insert_and_start_task(
+    deployment: &DeploymentImpl,
+    payload: CreateAndStartTaskRequest,
+    mut recorder: Option<KeyRecorder>,
+) -> Result<TaskWithAttemptStatus, ApiError> {
+    let executor_profile_id = payload
+        .executor_profile_id
+        .resolve(&ExecutorConfigs::get_cached())?;
     let task_id = Uuid::new_v4();
+    let depends_on = match &payload.task.depends_on {
+        Some(depends_on) => {
+            validate_dependencies(deployment, task_id, payload.task.project_id, depends_on).await?
+        }
+        None => Vec::new(),
+    };
     let task = Task::create(&deployment.db().pool, &payload.task, task_id).await?;
+    if let Some(recorder) = recorder.as_mut() {
+        recorder.record(task.id);
+    }
 
     if let Some(image_ids) = &payload.task.image_ids {
         TaskImage::associate_many(&deployment.db().pool, task.id, image_ids).await?;
     }
+    if !depends_on.is_empty() {
+        TaskDependency::replace(&deployment.db().pool, task.id, &depends_on).await?;
+    }
 
     deployment
         .track_if_analytics_allowed(
@@ -172,7 +401,7 @@ pub async fn create_task_and_start(
     let task_attempt = TaskAttempt::create(
         &deployment.db().pool,
         &CreateTaskAttempt {
-            executor: payload.executor_profile_id.executor,
+            executor: executor_profile_id.executor,
             base_branch: payload.base_branch,
             branch: git_branch_name,
         },
@@ -182,7 +411,7 @@ pub async fn create_task_and_start(
     .await?;
     let is_attempt_running = deployment
         .container()
-        .start_attempt(&task_attempt, payload.executor_profile_id.clone())
+        .start_attempt(&task_attempt, executor_profile_id.clone())
         .await
         .inspect_err(|err| tracing::error!("Failed to start task attempt: {}", err))
         .is_ok();
@@ -191,25 +420,30 @@ pub async fn create_task_and_start(
             "task_attempt_started",
             serde_json::json!({
                 "task_id": task.id.to_string(),
-                "executor": &payload.executor_profile_id.executor,
-                "variant": &payload.executor_profile_id.variant,
+                "executor": &executor_profile_id.executor,
+                "variant": &executor_profile_id.variant,
                 "attempt_id": task_attempt.id.to_string(),
             }),
         )
         .await;
+    deployment
+        .send_attempt_webhook(AttemptEvent::Created, &task, &task_attempt)
+        .await;
 
     let task = Task::find_by_id(&deployment.db().pool, task.id)
         .await?
         .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
 
     tracing::info!("Started attempt for task {}", task.id);
-    Ok(ResponseJson(ApiResponse::success(TaskWithAttemptStatus {
+    Ok(TaskWithAttemptStatus {
         task,
         has_in_progress_attempt: is_attempt_running,
         has_merged_attempt: false,
         last_attempt_failed: false,
         executor: task_attempt.executor,
-    })))
+        depends_on,
+        assignee: None,
+    })
 }
 
 pub async fn update_task(
@@ -217,8 +451,12 @@ pub async fn update_task(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<UpdateTask>,
 ) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+    payload.validate()?;
     // Use existing values if not provided in update
-    let title = payload.title.unwrap_or(existing_task.title);
+    let title = payload
+        .title
+        .map(|title| title.trim().to_string())
+        .unwrap_or(existing_task.title);
     let description = match payload.description {
         Some(s) if s.trim().is_empty() => None, // Empty string = clear description
         Some(s) => Some(s),                     // Non-empty string = update description
@@ -228,6 +466,18 @@ pub async fn update_task(
     let parent_task_attempt = payload
         .parent_task_attempt
         .or(existing_task.parent_task_attempt);
+    let depends_on = match &payload.depends_on {
+        Some(depends_on) => Some(
+            validate_dependencies(
+                &deployment,
+                existing_task.id,
+                existing_task.project_id,
+                depends_on,
+            )
+            .await?,
+        ),
+        None => None,
+    };
 
     let task = Task::update(
         &deployment.db().pool,
@@ -244,6 +494,9 @@ pub async fn update_task(
         TaskImage::delete_by_task_id(&deployment.db().pool, task.id).await?;
         TaskImage::associate_many_dedup(&deployment.db().pool, task.id, image_ids).await?;
     }
+    if let Some(depends_on) = &depends_on {
+        TaskDependency::replace(&deployment.db().pool, task.id, depends_on).await?;
+    }
 
     // Handle archive status transition
     if status == TaskStatus::Archived && existing_task.status != TaskStatus::Archived {
@@ -254,6 +507,59 @@ pub async fn update_task(
     Ok(ResponseJson(ApiResponse::success(task)))
 }
 
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct MoveTask {
+    pub project_id: Uuid,
+}
+
+/// Reassigns a task to another project. Refused while the task still has work tied to its
+/// current project's repository: running processes or attempt worktrees that weren't cleaned up.
+pub async fn move_task(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<MoveTask>,
+) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+    if payload.project_id == task.project_id {
+        return Ok(ResponseJson(ApiResponse::success(task)));
+    }
+    let pool = &deployment.db().pool;
+    if Project::find_by_id(pool, payload.project_id)
+        .await?
+        .is_none()
+    {
+        return Err(ApiError::BadRequest(format!(
+            "Target project {} not found",
+            payload.project_id
+        )));
+    }
+
+    if deployment
+        .container()
+        .has_running_processes(task.id)
+        .await?
+    {
+        return Err(ApiError::Conflict("Task has running execution processes. Please wait for them to complete or stop them first.".to_string()));
+    }
+    let attempts = TaskAttempt::fetch_all(pool, Some(task.id)).await?;
+    if attempts
+        .iter()
+        .any(|attempt| attempt.container_ref.is_some() && !attempt.worktree_deleted)
+    {
+        return Err(ApiError::Conflict(
+            "Task has attempt worktrees in its current project's repository. Archive the task to clean them up before moving it.".to_string(),
+        ));
+    }
+
+    let moved = Task::move_to_project(pool, task.id, payload.project_id).await?;
+    tracing::info!(
+        "Moved task {} from project {} to {}",
+        task.id,
+        task.project_id,
+        payload.project_id
+    );
+    Ok(ResponseJson(ApiResponse::success(moved)))
+}
+
 pub async fn delete_task(
     Extension(task): Extension<Task>,
     State(deployment): State<DeploymentImpl>,
@@ -296,7 +602,8 @@ pub async fn delete_task(
         .collect();
 
     // Use a transaction to ensure atomicity: either all operations succeed or all are rolled back
-    let mut tx = deployment.db().pool.begin().await?;
+    let mut conn = deployment.db().acquire().await?;
+    let mut tx = conn.begin().await?;
 
     // Nullify parent_task_attempt for all child tasks before deletion
     // This breaks parent-child relationships to avoid foreign key constraint violations
@@ -440,7 +747,11 @@ fn handle_task_archive(deployment: &DeploymentImpl, task_id: Uuid) {
                 tracing::info!("Completed worktree cleanup for archived task {}", task_id);
             }
             Err(e) => {
-                tracing::error!("Failed to cleanup worktrees for archived task {}: {}", task_id, e);
+                tracing::error!(
+                    "Failed to cleanup worktrees for archived task {}: {}",
+                    task_id,
+                    e
+                );
             }
         }
     });
@@ -449,14 +760,165 @@ fn handle_task_archive(deployment: &DeploymentImpl, task_id: Uuid) {
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let task_id_router = Router::new()
         .route("/", get(get_task).put(update_task).delete(delete_task))
+        .route("/dependencies", get(get_task_dependencies))
+        .route("/assignee", get(get_task_assignee).put(set_task_assignee))
+        .route("/move", post(move_task))
         .layer(from_fn_with_state(deployment.clone(), load_task_middleware));
 
     let inner = Router::new()
         .route("/", get(get_tasks).post(create_task))
         .route("/stream/ws", get(stream_tasks_ws))
         .route("/create-and-start", post(create_task_and_start))
+        .route("/from-github-issue", post(create_task_from_github_issue))
         .nest("/{task_id}", task_id_router);
 
     // mount under /projects/:project_id/tasks
     Router::new().nest("/tasks", inner)
 }
+
+#[cfg(test)]
+mod tests {
+    use db::{
+        DBService,
+        models::{project::CreateProject, task::MAX_TASK_TITLE_CHARS},
+    };
+
+    use super::*;
+
+    async fn setup() -> (DeploymentImpl, Uuid) {
+        let deployment = DeploymentImpl::new_in_memory().await.unwrap();
+        let data = CreateProject::from_existing_repo(
+            "idempotency".to_string(),
+            "/tmp/idempotency".to_string(),
+        );
+        let project = Project::create(&deployment.db().pool, &data, Uuid::new_v4())
+            .await
+            .unwrap();
+        (deployment, project.id)
+    }
+
+    fn keyed_task(project_id: Uuid, key: &str) -> CreateTask {
+        let mut data = CreateTask::from_title_description(project_id, "task".to_string(), None);
+        data.idempotency_key = Some(key.to_string());
+        data
+    }
+
+    async fn created(deployment: &DeploymentImpl, data: CreateTask) -> Task {
+        create_task(State(deployment.clone()), Json(data))
+            .await
+            .unwrap()
+            .0
+            .into_data()
+            .unwrap()
+    }
+
+    async fn task_count(db: &DBService, project_id: Uuid) -> usize {
+        Task::find_by_project_id_with_attempt_status(&db.pool, project_id)
+            .await
+            .unwrap()
+            .len()
+    }
+
+    #[tokio::test]
+    async fn replayed_key_returns_the_first_task() {
+        let (deployment, project_id) = setup().await;
+
+        let first = created(&deployment, keyed_task(project_id, "key")).await;
+        let replay = created(&deployment, keyed_task(project_id, "key")).await;
+
+        assert_eq!(replay.id, first.id);
+        assert_eq!(task_count(deployment.db(), project_id).await, 1);
+    }
+
+    #[tokio::test]
+    async fn failure_after_insert_keeps_the_key_bound_to_the_task() {
+        let (deployment, project_id) = setup().await;
+
+        // Linking an image that doesn't exist fails once the task row is already in
+        let mut data = keyed_task(project_id, "key");
+        data.image_ids = Some(vec![Uuid::new_v4()]);
+        let failed = create_task(State(deployment.clone()), Json(data)).await;
+        assert!(failed.is_err());
+        assert_eq!(task_count(deployment.db(), project_id).await, 1);
+
+        let retry = created(&deployment, keyed_task(project_id, "key")).await;
+        let tasks = Task::find_by_project_id_with_attempt_status(&deployment.db().pool, project_id)
+            .await
+            .unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].id, retry.id);
+    }
+
+    #[tokio::test]
+    async fn create_rejects_invalid_titles_and_trims_valid_ones() {
+        let (deployment, project_id) = setup().await;
+        let create = |title: &str| {
+            create_task(
+                State(deployment.clone()),
+                Json(CreateTask::from_title_description(
+                    project_id,
+                    title.to_string(),
+                    None,
+                )),
+            )
+        };
+
+        assert!(matches!(create(" \n").await, Err(ApiError::BadRequest(_))));
+        assert!(matches!(
+            create(&"x".repeat(MAX_TASK_TITLE_CHARS + 1)).await,
+            Err(ApiError::BadRequest(_))
+        ));
+        let task = create("  Fix login  ")
+            .await
+            .unwrap()
+            .0
+            .into_data()
+            .unwrap();
+        assert_eq!(task.title, "Fix login");
+        assert_eq!(task_count(deployment.db(), project_id).await, 1);
+    }
+
+    #[tokio::test]
+    async fn update_rejects_a_blank_title() {
+        let (deployment, project_id) = setup().await;
+        let data = CreateTask::from_title_description(project_id, "Fix login".to_string(), None);
+        let task = Task::create(&deployment.db().pool, &data, Uuid::new_v4())
+            .await
+            .unwrap();
+        let update = |title: &str| {
+            update_task(
+                Extension(task.clone()),
+                State(deployment.clone()),
+                Json(UpdateTask {
+                    title: Some(title.to_string()),
+                    description: None,
+                    status: None,
+                    parent_task_attempt: None,
+                    image_ids: None,
+                    depends_on: None,
+                }),
+            )
+        };
+
+        assert!(matches!(update("   ").await, Err(ApiError::BadRequest(_))));
+        let updated = update(" Fix logout ").await.unwrap().0.into_data().unwrap();
+        assert_eq!(updated.title, "Fix logout");
+    }
+
+    #[test]
+    fn github_issue_titles_are_validated() {
+        let issue = |title: &str| GitHubIssue {
+            title: title.to_string(),
+            body: None,
+            html_url: "https://github.com/acme/app/issues/1".to_string(),
+        };
+        let project_id = Uuid::new_v4();
+
+        assert!(matches!(
+            github_issue_task(project_id, &issue("  ")),
+            Err(ApiError::BadRequest(_))
+        ));
+        let task = github_issue_task(project_id, &issue(" Crash on start ")).unwrap();
+        assert_eq!(task.title, "Crash on start");
+    }
+}
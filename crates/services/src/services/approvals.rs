@@ -11,7 +11,7 @@ use executors::{
     approvals::ToolCallMetadata,
     logs::{
         NormalizedEntry, NormalizedEntryType, ToolStatus,
-        utils::patch::{ConversationPatch, extract_normalized_entry_from_patch},
+        utils::patch::{ConversationPatch, extract_normalized_entries_from_patch},
     },
 };
 use futures::future::{BoxFuture, FutureExt, Shared};
@@ -25,6 +25,8 @@ use utils::{
 };
 use uuid::Uuid;
 
+const APPROVAL_CANCELLED_REASON: &str = "Approval cancelled because the execution was stopped";
+
 #[derive(Debug)]
 struct PendingApproval {
     entry_index: usize,
@@ -189,6 +191,48 @@ impl Approvals {
         }
     }
 
+    /// Denies every pending approval of `execution_process_id`, e.g. because the process was
+    /// stopped. Returns how many approvals were cancelled.
+    pub async fn cancel_for_execution_process(&self, execution_process_id: Uuid) -> usize {
+        let ids: Vec<String> = self
+            .pending
+            .iter()
+            .filter(|entry| entry.execution_process_id == execution_process_id)
+            .map(|entry| entry.key().clone())
+            .collect();
+        let store = self.msg_store_by_id(&execution_process_id).await;
+        let status = ApprovalStatus::Denied {
+            reason: Some(APPROVAL_CANCELLED_REASON.to_string()),
+        };
+
+        let mut cancelled = 0;
+        for id in ids {
+            let Some((_, p)) = self.pending.remove(&id) else {
+                continue;
+            };
+            self.completed.insert(id.clone(), status.clone());
+            if p.response_tx.send(status.clone()).is_err() {
+                tracing::debug!("approval '{}' cancellation receiver dropped", id);
+            }
+            if let Some(store) = store.as_ref()
+                && let Some(updated_entry) = ToolStatus::from_approval_status(&status)
+                    .and_then(|tool_status| p.entry.with_tool_status(tool_status))
+            {
+                store.push_patch(ConversationPatch::replace(p.entry_index, updated_entry));
+            }
+            cancelled += 1;
+        }
+
+        if cancelled > 0 {
+            tracing::debug!(
+                "Cancelled {} pending approval(s) for execution process {}",
+                cancelled,
+                execution_process_id
+            );
+        }
+        cancelled
+    }
+
     #[tracing::instrument(skip(self, id, timeout_at, waiter))]
     fn spawn_timeout_watcher(
         &self,
@@ -278,14 +322,18 @@ fn find_matching_tool_use(
 ) -> Option<(usize, NormalizedEntry)> {
     let history = store.get_history();
 
-    // Single loop through history
-    for msg in history.iter().rev() {
-        if let LogMsg::JsonPatch(patch) = msg
-            && let Some((idx, entry)) = extract_normalized_entry_from_patch(patch)
-            && let NormalizedEntryType::ToolUse { status, .. } = &entry.entry_type
-        {
-            // Only match tools that are in Created state
-            if !matches!(status, ToolStatus::Created) {
+    // Single loop through history, latest entries first
+    let entries = history.iter().rev().flat_map(|msg| {
+        let entries = match msg {
+            LogMsg::JsonPatch(patch) => extract_normalized_entries_from_patch(patch),
+            _ => Vec::new(),
+        };
+        entries.into_iter().rev()
+    });
+    for (idx, entry) in entries {
+        if let NormalizedEntryType::ToolUse { status, .. } = &entry.entry_type {
+            // Only match tools that haven't been resolved or picked up by another approval
+            if !matches!(status, ToolStatus::Created | ToolStatus::AwaitingApproval) {
                 continue;
             }
 
@@ -329,6 +377,7 @@ mod tests {
                 tool_name: tool_name.to_string(),
                 action_type: ActionType::FileRead {
                     path: file_path.to_string(),
+                    result: None,
                 },
                 status,
             },
@@ -397,5 +446,73 @@ mod tests {
             find_matching_tool_use(store.clone(), "wrong-id").is_none(),
             "Should not match different tool ids"
         );
+
+        // Test 4: Tools the agent flagged as awaiting approval are matched
+        let read_awaiting =
+            create_tool_use_entry("Read", "qux.rs", "qux-id", ToolStatus::AwaitingApproval);
+        store.push_patch(
+            executors::logs::utils::patch::ConversationPatch::add_normalized_entry(
+                4,
+                read_awaiting,
+            ),
+        );
+        let (idx_qux, _) =
+            find_matching_tool_use(store.clone(), "qux-id").expect("Should match qux.rs");
+        assert_eq!(idx_qux, 4);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_resolves_pending_approval() {
+        let execution_process_id = Uuid::new_v4();
+        let store = Arc::new(MsgStore::new());
+        store.push_patch(ConversationPatch::add_normalized_entry(
+            0,
+            create_tool_use_entry("Bash", "ls", "bash-id", ToolStatus::Created),
+        ));
+        let msg_stores = Arc::new(RwLock::new(HashMap::from([(
+            execution_process_id,
+            store.clone(),
+        )])));
+        let approvals = Approvals::new(msg_stores);
+
+        let request = ApprovalRequest::from_create(
+            utils::approvals::CreateApprovalRequest {
+                tool_name: "Bash".to_string(),
+                tool_input: serde_json::json!({ "command": "ls" }),
+                tool_call_id: "bash-id".to_string(),
+            },
+            execution_process_id,
+        );
+        let (request, waiter) = approvals.create_with_waiter(request).await.unwrap();
+
+        assert_eq!(
+            approvals.cancel_for_execution_process(Uuid::new_v4()).await,
+            0
+        );
+        assert_eq!(
+            approvals
+                .cancel_for_execution_process(execution_process_id)
+                .await,
+            1
+        );
+
+        let status = tokio::time::timeout(StdDuration::from_secs(1), waiter)
+            .await
+            .expect("cancellation should resolve the waiter");
+        assert!(matches!(status, ApprovalStatus::Denied { reason: Some(_) }));
+        assert!(approvals.completed.contains_key(&request.id));
+        let Some(LogMsg::JsonPatch(patch)) = store.get_history().last().cloned() else {
+            panic!("expected the tool use entry to be updated");
+        };
+        let (_, entry) = extract_normalized_entries_from_patch(&patch)
+            .pop()
+            .expect("patch should carry the tool use entry");
+        assert!(matches!(
+            entry.entry_type,
+            NormalizedEntryType::ToolUse {
+                status: ToolStatus::Denied { .. },
+                ..
+            }
+        ));
     }
 }
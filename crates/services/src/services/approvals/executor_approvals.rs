@@ -60,4 +60,10 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
 
         Ok(status)
     }
+
+    async fn cancel_pending_approvals(&self) {
+        self.approvals
+            .cancel_for_execution_process(self.execution_process_id)
+            .await;
+    }
 }
@@ -1,5 +1,6 @@
 use anyhow::Error;
 use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use strum_macros::EnumString;
 use ts_rs::TS;
@@ -11,13 +12,21 @@ fn default_git_branch_prefix() -> String {
     "af".to_string()
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, TS, Default)]
+#[derive(Clone, Debug, Serialize, Deserialize, TS, JsonSchema, Default)]
 pub struct ShowcaseState {
     #[serde(default)]
     pub seen_features: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, TS, EnumString)]
+/// Where task attempt lifecycle events are POSTed, see `services::webhook`
+#[derive(Clone, Debug, Serialize, Deserialize, TS, JsonSchema, Default)]
+pub struct WebhookConfig {
+    pub url: Option<String>,
+    /// Signs each body with HMAC-SHA256, sent as `X-Forge-Signature: sha256=<hex>`
+    pub secret: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, JsonSchema, EnumString)]
 #[ts(use_ts_enum)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 #[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
@@ -27,7 +36,7 @@ pub enum ThemeMode {
     System,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+#[derive(Clone, Debug, Serialize, Deserialize, TS, JsonSchema)]
 pub struct Config {
     pub config_version: String,
     pub theme: ThemeMode,
@@ -51,6 +60,8 @@ pub struct Config {
     pub git_branch_prefix: String,
     #[serde(default)]
     pub showcases: ShowcaseState,
+    #[serde(default)]
+    pub webhook: WebhookConfig,
 }
 
 impl Config {
@@ -103,6 +114,7 @@ impl Config {
             language: old_config.language,
             git_branch_prefix: default_git_branch_prefix(),
             showcases: ShowcaseState::default(),
+            webhook: WebhookConfig::default(),
         })
     }
 }
@@ -150,6 +162,7 @@ impl Default for Config {
             language: UiLanguage::default(),
             git_branch_prefix: default_git_branch_prefix(),
             showcases: ShowcaseState::default(),
+            webhook: WebhookConfig::default(),
         }
     }
 }
@@ -26,17 +26,22 @@ use executors::{
         coding_agent_initial::CodingAgentInitialRequest,
         script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
     },
+    concurrency::{ExecutorLimitReached, ExecutorPermit, try_acquire_executor_permit},
     executors::{ExecutorError, StandardCodingAgentExecutor},
-    logs::{NormalizedEntry, NormalizedEntryError, NormalizedEntryType, utils::ConversationPatch},
+    logs::{
+        NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
+        utils::{ConversationPatch, EntryIndexProvider},
+    },
+    process_registry::ProcessRegistry,
     profile::{ExecutorConfigs, ExecutorProfileId, to_default_variant},
 };
-use futures::{StreamExt, future};
+use futures::{StreamExt, TryStreamExt, future};
 use sqlx::Error as SqlxError;
 use thiserror::Error;
 use tokio::{sync::RwLock, task::JoinHandle};
 use utils::{
     log_msg::LogMsg,
-    msg_store::MsgStore,
+    msg_store::{MsgStore, OutputSnapshot},
     text::{git_branch_id, short_uuid},
 };
 use uuid::Uuid;
@@ -110,6 +115,9 @@ pub trait ContainerService {
 
     fn git(&self) -> &GitService;
 
+    /// The processes this container has spawned and not yet seen exit.
+    fn process_registry(&self) -> &ProcessRegistry;
+
     fn task_attempt_to_current_dir(&self, task_attempt: &TaskAttempt) -> PathBuf;
 
     async fn create(&self, task_attempt: &TaskAttempt) -> Result<ContainerRef, ContainerError>;
@@ -128,7 +136,7 @@ pub trait ContainerService {
                 ExecutionProcess::find_by_task_attempt_id(&self.db().pool, attempt.id, false).await
             {
                 for process in processes {
-                    if process.status == ExecutionProcessStatus::Running {
+                    if process.status.is_active() {
                         return Ok(true);
                     }
                 }
@@ -138,6 +146,43 @@ pub trait ContainerService {
         Ok(false)
     }
 
+    /// Session id a follow-up on the attempt resumes: the latest one in the logs, or else the one
+    /// the executor of the latest coding agent run kept next to its worktree.
+    async fn latest_session_id(
+        &self,
+        task_attempt: &TaskAttempt,
+    ) -> Result<Option<String>, ContainerError> {
+        let pool = &self.db().pool;
+        if let Some(session_id) =
+            ExecutionProcess::find_latest_session_id_by_task_attempt(pool, task_attempt.id).await?
+        {
+            return Ok(Some(session_id));
+        }
+
+        let Some(container_ref) = &task_attempt.container_ref else {
+            return Ok(None);
+        };
+        let Some(latest) = ExecutionProcess::find_latest_by_task_attempt_and_run_reason(
+            pool,
+            task_attempt.id,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await?
+        else {
+            return Ok(None);
+        };
+        let executor_profile_id = match latest.executor_action()?.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => &request.executor_profile_id,
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => &request.executor_profile_id,
+            _ => return Ok(None),
+        };
+        let Some(executor) = ExecutorConfigs::get_cached().get_coding_agent(executor_profile_id)
+        else {
+            return Ok(None);
+        };
+        Ok(executor.recorded_session_id(Path::new(container_ref)).await)
+    }
+
     /// Stop execution processes for task attempts without cleanup
     async fn stop_task_processes(
         &self,
@@ -168,7 +213,7 @@ pub trait ContainerService {
             ExecutionProcess::find_by_task_attempt_id(&self.db().pool, task_attempt.id, false).await
         {
             for process in processes {
-                if process.status == ExecutionProcessStatus::Running {
+                if process.status.is_active() {
                     self.stop_execution(&process, ExecutionProcessStatus::Killed)
                         .await
                         .unwrap_or_else(|e| {
@@ -197,8 +242,18 @@ pub trait ContainerService {
         task_attempt: &TaskAttempt,
         execution_process: &ExecutionProcess,
         executor_action: &ExecutorAction,
+        permit: Option<ExecutorPermit>,
     ) -> Result<(), ContainerError>;
 
+    /// Starts a [`ExecutionProcessStatus::Queued`] process once an executor slot frees up, without
+    /// blocking the caller.
+    fn queue_execution(
+        &self,
+        task_attempt: &TaskAttempt,
+        execution_process: &ExecutionProcess,
+        executor_action: &ExecutorAction,
+    );
+
     async fn stop_execution(
         &self,
         execution_process: &ExecutionProcess,
@@ -252,7 +307,7 @@ pub trait ContainerService {
                     .filter(|msg| {
                         future::ready(matches!(
                             msg,
-                            Ok(LogMsg::Stdout(..) | LogMsg::Stderr(..) | LogMsg::Finished)
+                            Ok(LogMsg::Stdout(..) | LogMsg::Stderr(..) | LogMsg::Finished(_))
                         ))
                     })
                     .boxed(),
@@ -282,7 +337,7 @@ pub trait ContainerService {
                 messages
                     .into_iter()
                     .filter(|m| matches!(m, LogMsg::Stdout(_) | LogMsg::Stderr(_)))
-                    .chain(std::iter::once(LogMsg::Finished))
+                    .chain(std::iter::once(LogMsg::Finished(None)))
                     .map(Ok::<_, std::io::Error>),
             )
             .boxed();
@@ -291,6 +346,28 @@ pub trait ContainerService {
         }
     }
 
+    /// The stdout and stderr chunks available right now, numbered like
+    /// [`MsgStore::output_snapshot`]. Persisted logs hold every chunk, so their numbering starts
+    /// at 0 and matches the one the process had while it was running.
+    async fn raw_output_snapshot(&self, id: &Uuid) -> Option<OutputSnapshot> {
+        if let Some(store) = self.get_msg_store_by_id(id).await {
+            return Some(store.output_snapshot());
+        }
+
+        let chunks = self
+            .stream_raw_logs(id)
+            .await?
+            .try_filter(|msg| future::ready(!matches!(msg, LogMsg::Finished(_))))
+            .try_collect()
+            .await
+            .ok()?;
+        Some(OutputSnapshot {
+            first_seq: 0,
+            chunks,
+            finished: true,
+        })
+    }
+
     async fn stream_normalized_logs(
         &self,
         id: &Uuid,
@@ -302,7 +379,7 @@ pub trait ContainerService {
                     .history_plus_stream() // BoxStream<Result<LogMsg, io::Error>>
                     .filter(|msg| future::ready(matches!(msg, Ok(LogMsg::JsonPatch(..)))))
                     .chain(futures::stream::once(async {
-                        Ok::<_, std::io::Error>(LogMsg::Finished)
+                        Ok::<_, std::io::Error>(LogMsg::Finished(None))
                     }))
                     .boxed(),
             )
@@ -337,6 +414,10 @@ pub trait ContainerService {
                     temp_store.push(msg);
                 }
             }
+            // Entries restored as patches are already part of the conversation; normalizers
+            // that support it skip them instead of appending copies.
+            temp_store
+                .mark_persisted_entries(EntryIndexProvider::start_from(&temp_store).current());
             temp_store.push_finished();
 
             let process = match ExecutionProcess::find_by_id(&self.db().pool, *id).await {
@@ -413,7 +494,7 @@ pub trait ContainerService {
                     .history_plus_stream()
                     .filter(|msg| future::ready(matches!(msg, Ok(LogMsg::JsonPatch(..)))))
                     .chain(futures::stream::once(async {
-                        Ok::<_, std::io::Error>(LogMsg::Finished)
+                        Ok::<_, std::io::Error>(LogMsg::Finished(None))
                     }))
                     .boxed(),
             )
@@ -484,7 +565,7 @@ pub trait ContainerService {
                                 );
                             }
                         }
-                        LogMsg::Finished => {
+                        LogMsg::Finished(_) => {
                             break;
                         }
                         LogMsg::JsonPatch(_) => continue,
@@ -589,6 +670,17 @@ pub trait ContainerService {
         {
             Task::update_status(&self.db().pool, task.id, TaskStatus::InProgress).await?;
         }
+        // Coding agents need a free executor slot; take it before the record exists so a busy
+        // limiter queues the process instead of holding up the caller.
+        let slot = match executor_action.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(_)
+            | ExecutorActionType::CodingAgentFollowUpRequest(_) => try_acquire_executor_permit(),
+            _ => Ok(None),
+        };
+        let status = match slot {
+            Ok(_) => ExecutionProcessStatus::Running,
+            Err(ExecutorLimitReached) => ExecutionProcessStatus::Queued,
+        };
         // Create new execution process record
         // Capture current HEAD as the "before" commit for this execution
         let before_head_commit = {
@@ -610,6 +702,7 @@ pub trait ContainerService {
             &create_execution_process,
             Uuid::new_v4(),
             before_head_commit.as_deref(),
+            status,
         )
         .await?;
 
@@ -638,8 +731,29 @@ pub trait ContainerService {
             .await?;
         }
 
+        match slot {
+            Ok(permit) => {
+                self.launch_execution(task_attempt, &execution_process, executor_action, permit)
+                    .await?
+            }
+            Err(ExecutorLimitReached) => {
+                self.queue_execution(task_attempt, &execution_process, executor_action)
+            }
+        }
+        Ok(execution_process)
+    }
+
+    /// Spawns an execution process whose record already exists and starts processing its logs.
+    /// The permit is released on failure.
+    async fn launch_execution(
+        &self,
+        task_attempt: &TaskAttempt,
+        execution_process: &ExecutionProcess,
+        executor_action: &ExecutorAction,
+        permit: Option<ExecutorPermit>,
+    ) -> Result<(), ContainerError> {
         if let Err(start_error) = self
-            .start_execution_inner(task_attempt, &execution_process, executor_action)
+            .start_execution_inner(task_attempt, execution_process, executor_action, permit)
             .await
         {
             // Mark process as failed
@@ -657,7 +771,8 @@ pub trait ContainerService {
                     update_error
                 );
             }
-            Task::update_status(&self.db().pool, task.id, TaskStatus::InReview).await?;
+            Task::update_status(&self.db().pool, task_attempt.task_id, TaskStatus::InReview)
+                .await?;
 
             // Emit stderr error message
             let log_message = LogMsg::Stderr(format!("Failed to start execution: {start_error}"));
@@ -671,10 +786,14 @@ pub trait ContainerService {
             }
 
             // Emit NextAction with failure context for coding agent requests
-            if let ContainerError::ExecutorError(ExecutorError::ExecutableNotFound { program }) =
-                &start_error
+            if let ContainerError::ExecutorError(ExecutorError::MissingBinary {
+                command,
+                install_hint,
+            }) = &start_error
             {
-                let help_text = format!("The required executable `{program}` is not installed.");
+                let help_text = format!(
+                    "The required executable `{command}` is not installed. {install_hint}."
+                );
                 let error_message = NormalizedEntry {
                     timestamp: None,
                     entry_type: NormalizedEntryType::ErrorMessage {
@@ -721,7 +840,7 @@ pub trait ContainerService {
         }
 
         self.spawn_stream_raw_logs_to_db(&execution_process.id);
-        Ok(execution_process)
+        Ok(())
     }
 
     async fn try_start_next_action(&self, ctx: &ExecutionContext) -> Result<(), ContainerError> {
@@ -800,6 +919,7 @@ pub trait ContainerService {
             prompt: String::from("The plan has been approved, please execute it."),
             session_id: session_id.unwrap(),
             executor_profile_id: default_profile,
+            resume_overrides: None,
         };
         let action = ExecutorAction::new(
             ExecutorActionType::CodingAgentFollowUpRequest(follow_up),
@@ -4,10 +4,7 @@ use db::{
     DBService,
     models::{
         draft::{Draft, DraftType, UpsertDraft},
-        execution_process::{
-            ExecutionProcess, ExecutionProcessError, ExecutionProcessRunReason,
-            ExecutionProcessStatus,
-        },
+        execution_process::{ExecutionProcess, ExecutionProcessError, ExecutionProcessRunReason},
         image::TaskImage,
         task_attempt::TaskAttempt,
     },
@@ -158,8 +155,7 @@ impl DraftsService {
         let processes =
             ExecutionProcess::find_by_task_attempt_id(self.pool(), attempt_id, false).await?;
         Ok(processes.into_iter().any(|p| {
-            matches!(p.status, ExecutionProcessStatus::Running)
-                && !matches!(p.run_reason, ExecutionProcessRunReason::DevServer)
+            p.status.is_active() && !matches!(p.run_reason, ExecutionProcessRunReason::DevServer)
         }))
     }
 
@@ -244,15 +240,14 @@ impl DraftsService {
                 .await?;
         }
 
-        let latest_session_id =
-            ExecutionProcess::find_latest_session_id_by_task_attempt(self.pool(), task_attempt.id)
-                .await?;
+        let latest_session_id = container.latest_session_id(task_attempt).await?;
 
         let action_type = if let Some(session_id) = latest_session_id {
             ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
                 prompt: prompt.clone(),
                 session_id,
                 executor_profile_id,
+                resume_overrides: None,
             })
         } else {
             ExecutorActionType::CodingAgentInitialRequest(
@@ -65,6 +65,18 @@ pub struct GitBranch {
     pub last_commit_date: DateTime<Utc>,
 }
 
+/// Metadata of a single commit, as shown when inspecting an attempt's history.
+#[derive(Debug, Clone)]
+pub struct CommitDetails {
+    pub subject: String,
+    pub message: String,
+    pub author_name: String,
+    pub author_email: String,
+    pub timestamp: DateTime<Utc>,
+    /// Files changed relative to the first parent, or to the empty tree for a root commit
+    pub files_changed: usize,
+}
+
 #[derive(Debug, Clone)]
 pub struct HeadInfo {
     pub branch: String,
@@ -196,6 +208,20 @@ impl GitService {
         }
     }
 
+    /// `preferred` when given (e.g. a project's configured remote), otherwise the default remote.
+    fn resolve_remote_name(&self, repo: &Repository, preferred: Option<&str>) -> String {
+        match preferred {
+            Some(name) => name.to_string(),
+            None => self.default_remote_name(repo),
+        }
+    }
+
+    pub fn get_remote_names(&self, repo_path: &Path) -> Result<Vec<String>, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let remotes = repo.remotes()?;
+        Ok(remotes.iter().flatten().map(str::to_string).collect())
+    }
+
     /// Initialize a new git repository with a main branch and initial commit
     pub fn initialize_repo_with_main_branch(
         &self,
@@ -1065,6 +1091,36 @@ impl GitService {
         Ok(commit.summary().unwrap_or("(no subject)").to_string())
     }
 
+    /// Get the author, full message and changed-file count for a given commit OID
+    pub fn get_commit_details(
+        &self,
+        repo_path: &Path,
+        commit_sha: &str,
+    ) -> Result<CommitDetails, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let oid = git2::Oid::from_str(commit_sha)
+            .map_err(|_| GitServiceError::InvalidRepository("Invalid commit SHA".into()))?;
+        let commit = repo.find_commit(oid)?;
+
+        let parent_tree = if commit.parent_count() == 0 {
+            None
+        } else {
+            Some(commit.parent(0)?.tree()?)
+        };
+        let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+        let author = commit.author();
+
+        Ok(CommitDetails {
+            subject: commit.summary().unwrap_or("(no subject)").to_string(),
+            message: commit.message().unwrap_or_default().to_string(),
+            author_name: author.name().unwrap_or_default().to_string(),
+            author_email: author.email().unwrap_or_default().to_string(),
+            timestamp: DateTime::from_timestamp(commit.time().seconds(), 0)
+                .unwrap_or_else(Utc::now),
+            files_changed: diff.deltas().len(),
+        })
+    }
+
     /// Compare two OIDs and return (ahead, behind) counts: how many commits
     /// `from_oid` is ahead of and behind `to_oid`.
     pub fn ahead_behind_commits_by_oid(
@@ -1677,9 +1733,10 @@ impl GitService {
     pub fn get_github_repo_info(
         &self,
         repo_path: &Path,
+        remote_name: Option<&str>,
     ) -> Result<GitHubRepoInfo, GitServiceError> {
         let repo = self.open_repo(repo_path)?;
-        let remote_name = self.default_remote_name(&repo);
+        let remote_name = self.resolve_remote_name(&repo, remote_name);
         let remote = repo.find_remote(&remote_name).map_err(|_| {
             GitServiceError::InvalidRepository(format!("No '{remote_name}' remote found"))
         })?;
@@ -1734,12 +1791,13 @@ impl GitService {
         worktree_path: &Path,
         branch_name: &str,
         github_token: &str,
+        remote_name: Option<&str>,
     ) -> Result<(), GitServiceError> {
         let repo = Repository::open(worktree_path)?;
         self.check_worktree_clean(&repo)?;
 
         // Get the remote
-        let remote_name = self.default_remote_name(&repo);
+        let remote_name = self.resolve_remote_name(&repo, remote_name);
         let remote = repo.find_remote(&remote_name)?;
 
         let remote_url = remote
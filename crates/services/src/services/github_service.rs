@@ -139,6 +139,47 @@ impl GitHubRepoInfo {
     }
 }
 
+/// An issue addressed by its web URL, e.g. `https://github.com/owner/repo/issues/42`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitHubIssueRef {
+    pub owner: String,
+    pub repo_name: String,
+    pub number: u64,
+}
+
+impl GitHubIssueRef {
+    pub fn from_url(issue_url: &str) -> Result<Self, GitHubServiceError> {
+        let re = Regex::new(
+            r"^https?://(?:www\.)?github\.com/(?P<owner>[^/\s]+)/(?P<repo>[^/\s]+)/issues/(?P<number>\d+)/?(?:[?#]\S*)?$",
+        )
+        .map_err(|e| GitHubServiceError::Repository(format!("Failed to compile regex: {e}")))?;
+
+        let invalid = || {
+            GitHubServiceError::Repository(format!(
+                "Invalid GitHub issue URL: {issue_url} (expected https://github.com/<owner>/<repo>/issues/<number>)"
+            ))
+        };
+        let caps = re.captures(issue_url.trim()).ok_or_else(invalid)?;
+        Ok(Self {
+            owner: caps.name("owner").unwrap().as_str().to_string(),
+            repo_name: caps.name("repo").unwrap().as_str().to_string(),
+            number: caps
+                .name("number")
+                .unwrap()
+                .as_str()
+                .parse()
+                .map_err(|_| invalid())?,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GitHubIssue {
+    pub title: String,
+    pub body: Option<String>,
+    pub html_url: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct CreatePrRequest {
     pub title: String,
@@ -330,6 +371,50 @@ impl GitHubService {
         .await
     }
 
+    /// Fetch an issue's title and body
+    pub async fn get_issue(
+        &self,
+        issue: &GitHubIssueRef,
+    ) -> Result<GitHubIssue, GitHubServiceError> {
+        (|| async {
+            self.client
+                .issues(&issue.owner, &issue.repo_name)
+                .get(issue.number)
+                .await
+                .map(|found| GitHubIssue {
+                    title: found.title,
+                    body: found.body,
+                    html_url: found.html_url.to_string(),
+                })
+                .map_err(|err| match GitHubServiceError::from(err) {
+                    GitHubServiceError::Client(source) => GitHubServiceError::Repository(format!(
+                        "Failed to get issue {}/{}#{}: {source}",
+                        issue.owner,
+                        issue.repo_name,
+                        issue.number,
+                        source = format_octocrab_error(&source),
+                    )),
+                    other => other,
+                })
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|err| err.should_retry())
+        .notify(|err: &GitHubServiceError, dur: Duration| {
+            tracing::warn!(
+                "GitHub API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+    }
+
     fn map_pull_request(pr: octocrab::models::pulls::PullRequest) -> PullRequestInfo {
         let state = match pr.state {
             Some(IssueState::Open) => MergeStatus::Open,
@@ -0,0 +1,153 @@
+use std::{
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use dashmap::DashMap;
+use tokio::sync::{Mutex, OwnedMutexGuard};
+use uuid::Uuid;
+
+/// How long a key keeps resolving to the task it created.
+const KEY_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+struct Slot {
+    created_at: Instant,
+    task_id: Arc<Mutex<Option<Uuid>>>,
+}
+
+/// Outcome of [`IdempotencyService::run_once`].
+#[derive(Debug)]
+pub enum Idempotent<T> {
+    /// This call ran the creation.
+    Created(T),
+    /// An earlier call with the same key already created this task.
+    Existing(Uuid),
+}
+
+/// Handed to the creation run by [`IdempotencyService::run_once`]. Record the task as soon as
+/// its row exists: from then on the key resolves to it, even if a later step of the creation
+/// fails.
+pub struct KeyRecorder(OwnedMutexGuard<Option<Uuid>>);
+
+impl KeyRecorder {
+    pub fn record(&mut self, task_id: Uuid) {
+        *self.0 = Some(task_id);
+    }
+}
+
+/// Remembers which task a `(project_id, idempotency_key)` pair created, so retried create
+/// calls get the original task back instead of producing duplicates. Keys live in memory only.
+#[derive(Clone, Default)]
+pub struct IdempotencyService {
+    slots: Arc<DashMap<(Uuid, String), Slot>>,
+}
+
+impl IdempotencyService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs `create` unless the key already has a task recorded. Calls that arrive while
+    /// another one is in flight wait for it. A `create` that fails before recording a task
+    /// leaves the key unused so the next retry can try again.
+    pub async fn run_once<T, E, Fut>(
+        &self,
+        project_id: Uuid,
+        key: &str,
+        create: impl FnOnce(KeyRecorder) -> Fut,
+    ) -> Result<Idempotent<T>, E>
+    where
+        Fut: Future<Output = Result<T, E>>,
+    {
+        self.slots
+            .retain(|_, slot| slot.created_at.elapsed() < KEY_TTL);
+        let task_id = self
+            .slots
+            .entry((project_id, key.to_string()))
+            .or_insert_with(|| Slot {
+                created_at: Instant::now(),
+                task_id: Arc::new(Mutex::new(None)),
+            })
+            .task_id
+            .clone();
+
+        let recorded = task_id.lock_owned().await;
+        if let Some(task_id) = *recorded {
+            return Ok(Idempotent::Existing(task_id));
+        }
+        create(KeyRecorder(recorded)).await.map(Idempotent::Created)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn same_key_creates_once() {
+        let service = IdempotencyService::new();
+        let project_id = Uuid::new_v4();
+        let task_id = Uuid::new_v4();
+        let calls = &AtomicUsize::new(0);
+        let create = |mut recorder: KeyRecorder| async move {
+            calls.fetch_add(1, Ordering::SeqCst);
+            recorder.record(task_id);
+            Ok::<_, ()>("created")
+        };
+
+        let first = service.run_once(project_id, "key-1", create).await.unwrap();
+        assert!(matches!(first, Idempotent::Created("created")));
+
+        let retry = service.run_once(project_id, "key-1", create).await.unwrap();
+        assert!(matches!(retry, Idempotent::Existing(id) if id == task_id));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // Keys are scoped per project
+        let other = service
+            .run_once(Uuid::new_v4(), "key-1", create)
+            .await
+            .unwrap();
+        assert!(matches!(other, Idempotent::Created(_)));
+    }
+
+    #[tokio::test]
+    async fn failed_creation_frees_the_key() {
+        let service = IdempotencyService::new();
+        let project_id = Uuid::new_v4();
+
+        let failed = service
+            .run_once(project_id, "key", |_| async { Err::<(), _>("boom") })
+            .await;
+        assert!(failed.is_err());
+
+        let retried = service
+            .run_once(project_id, "key", |_| async { Ok::<_, &str>(()) })
+            .await
+            .unwrap();
+        assert!(matches!(retried, Idempotent::Created(())));
+    }
+
+    #[tokio::test]
+    async fn failure_after_recording_keeps_the_task() {
+        let service = IdempotencyService::new();
+        let project_id = Uuid::new_v4();
+        let task_id = Uuid::new_v4();
+
+        let failed = service
+            .run_once(project_id, "key", |mut recorder| async move {
+                recorder.record(task_id);
+                Err::<(), _>("starting the attempt failed")
+            })
+            .await;
+        assert!(failed.is_err());
+
+        let retried = service
+            .run_once(project_id, "key", |_| async { Ok::<_, &str>(()) })
+            .await
+            .unwrap();
+        assert!(matches!(retried, Idempotent::Existing(id) if id == task_id));
+    }
+}
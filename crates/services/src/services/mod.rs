@@ -13,7 +13,9 @@ pub mod filesystem_watcher;
 pub mod git;
 pub mod git_cli;
 pub mod github_service;
+pub mod idempotency;
 pub mod image;
 pub mod notification;
 pub mod pr_monitor;
+pub mod webhook;
 pub mod worktree_manager;
@@ -18,6 +18,7 @@ use crate::services::{
     analytics::AnalyticsContext,
     config::Config,
     github_service::{GitHubRepoInfo, GitHubService, GitHubServiceError},
+    webhook::{AttemptEvent, AttemptEventPayload, WebhookService},
 };
 
 #[derive(Debug, Error)]
@@ -141,6 +142,14 @@ impl PrMonitorService {
                 );
                 Task::update_status(&self.db.pool, task_attempt.task_id, TaskStatus::Done).await?;
 
+                if let Some(task) = Task::find_by_id(&self.db.pool, task_attempt.task_id).await? {
+                    let webhook = self.config.read().await.webhook.clone();
+                    WebhookService::dispatch(
+                        &webhook,
+                        AttemptEventPayload::new(AttemptEvent::Merged, &task, &task_attempt),
+                    );
+                }
+
                 // Track analytics event
                 if let Some(analytics) = &self.analytics
                     && let Ok(Some(task)) =
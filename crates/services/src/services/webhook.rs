@@ -0,0 +1,200 @@
+//! POSTs task attempt lifecycle events to a user-configured URL, so external systems (chat,
+//! CI) can react when attempts start, finish or get merged.
+//!
+//! Delivery is best effort: events are sent once in the background and failures are only logged.
+//! When a secret is configured the raw body is signed with HMAC-SHA256 and the hex digest is sent
+//! in [`SIGNATURE_HEADER`] as `sha256=<hex>`, which receivers recompute to verify the sender.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use db::models::{task::Task, task_attempt::TaskAttempt};
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use uuid::Uuid;
+
+use crate::services::config::WebhookConfig;
+
+pub const SIGNATURE_HEADER: &str = "X-Forge-Signature";
+
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttemptEvent {
+    Created,
+    Completed,
+    Failed,
+    Merged,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AttemptEventPayload {
+    pub event: AttemptEvent,
+    pub task_attempt_id: Uuid,
+    pub task_id: Uuid,
+    pub project_id: Uuid,
+    pub task_title: String,
+    pub branch: String,
+    pub executor: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl AttemptEventPayload {
+    pub fn new(event: AttemptEvent, task: &Task, task_attempt: &TaskAttempt) -> Self {
+        Self {
+            event,
+            task_attempt_id: task_attempt.id,
+            task_id: task.id,
+            project_id: task.project_id,
+            task_title: task.title.clone(),
+            branch: task_attempt.branch.clone(),
+            executor: task_attempt.executor.clone(),
+            timestamp: Utc::now(),
+        }
+    }
+}
+
+pub struct WebhookService;
+
+impl WebhookService {
+    /// Sends the event in the background if a webhook URL is configured.
+    pub fn dispatch(config: &WebhookConfig, payload: AttemptEventPayload) {
+        if target_url(config).is_none() {
+            return;
+        }
+        let config = config.clone();
+        tokio::spawn(async move {
+            if let Err(e) = Self::send(&config, &payload).await {
+                tracing::warn!(
+                    "Failed to deliver {:?} webhook for attempt {}: {}",
+                    payload.event,
+                    payload.task_attempt_id,
+                    e
+                );
+            }
+        });
+    }
+
+    pub async fn send(
+        config: &WebhookConfig,
+        payload: &AttemptEventPayload,
+    ) -> Result<(), reqwest::Error> {
+        let Some(url) = target_url(config) else {
+            return Ok(());
+        };
+        let body = serde_json::to_vec(payload).unwrap_or_default();
+
+        let mut request = reqwest::Client::new()
+            .post(url)
+            .timeout(DELIVERY_TIMEOUT)
+            .header("Content-Type", "application/json");
+        if let Some(secret) = config.secret.as_deref().filter(|s| !s.is_empty()) {
+            request = request.header(SIGNATURE_HEADER, signature(secret, &body));
+        }
+        request.body(body).send().await?.error_for_status()?;
+        Ok(())
+    }
+}
+
+fn target_url(config: &WebhookConfig) -> Option<&str> {
+    config
+        .url
+        .as_deref()
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+}
+
+/// `sha256=<hex>` HMAC of `body` keyed with `secret`.
+pub fn signature(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body);
+    format!("sha256={:x}", mac.finalize().into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use axum::{Router, body::Bytes, http::HeaderMap, routing::post};
+    use db::models::task::TaskStatus;
+
+    use super::*;
+
+    fn sample_task() -> Task {
+        Task {
+            id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            title: "Add dark mode".to_string(),
+            description: None,
+            status: TaskStatus::InReview,
+            parent_task_attempt: None,
+            dev_server_id: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn sample_attempt(task_id: Uuid) -> TaskAttempt {
+        TaskAttempt {
+            id: Uuid::new_v4(),
+            task_id,
+            container_ref: None,
+            branch: "af/dark-mode".to_string(),
+            target_branch: "main".to_string(),
+            executor: "CLAUDE_CODE".to_string(),
+            worktree_deleted: false,
+            setup_completed_at: None,
+            input_tokens: None,
+            output_tokens: None,
+            cache_creation_tokens: None,
+            cache_read_tokens: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn completion_event_is_posted_with_signature() {
+        let received: Arc<Mutex<Option<(HeaderMap, Bytes)>>> = Arc::new(Mutex::new(None));
+        let app = Router::new().route(
+            "/hook",
+            post({
+                let received = received.clone();
+                move |headers: HeaderMap, body: Bytes| async move {
+                    *received.lock().unwrap() = Some((headers, body));
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let task = sample_task();
+        let attempt = sample_attempt(task.id);
+        let config = WebhookConfig {
+            url: Some(format!("http://{addr}/hook")),
+            secret: Some("s3cret".to_string()),
+        };
+        WebhookService::send(
+            &config,
+            &AttemptEventPayload::new(AttemptEvent::Completed, &task, &attempt),
+        )
+        .await
+        .unwrap();
+
+        let (headers, body) = received.lock().unwrap().take().expect("webhook received");
+        assert_eq!(
+            headers[SIGNATURE_HEADER].to_str().unwrap(),
+            signature("s3cret", &body)
+        );
+        let payload: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(payload["event"], "completed");
+        assert_eq!(payload["task_attempt_id"], attempt.id.to_string());
+        assert_eq!(payload["task_id"], task.id.to_string());
+        assert_eq!(payload["task_title"], "Add dark mode");
+        assert_eq!(payload["branch"], "af/dark-mode");
+    }
+}
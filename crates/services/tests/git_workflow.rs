@@ -6,7 +6,7 @@ use std::{
 
 use services::services::{
     git::{DiffTarget, GitService},
-    github_service::{GitHubRepoInfo, GitHubServiceError},
+    github_service::{GitHubIssueRef, GitHubRepoInfo, GitHubServiceError},
 };
 use tempfile::TempDir;
 use utils::diff::DiffChangeKind;
@@ -243,6 +243,24 @@ fn commit_and_is_worktree_clean() {
     );
 }
 
+#[test]
+fn commit_details_include_author_message_and_files() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    write_file(&repo_path, "a.txt", "a\n");
+    write_file(&repo_path, "dir/b.txt", "b\n");
+
+    let s = GitService::new();
+    assert!(s.commit(&repo_path, "add files\n\nWith a body").unwrap());
+    let head = s.get_head_info(&repo_path).unwrap();
+    let details = s.get_commit_details(&repo_path, &head.oid).unwrap();
+    assert_eq!(details.subject, "add files");
+    assert!(details.message.contains("With a body"));
+    assert_eq!(details.author_name, "Test User");
+    assert_eq!(details.author_email, "test@example.com");
+    assert_eq!(details.files_changed, 2);
+}
+
 #[test]
 fn commit_in_detached_head_succeeds_via_service() {
     let td = TempDir::new().unwrap();
@@ -335,7 +353,7 @@ fn get_github_repo_info_parses_origin() {
     let s = GitService::new();
     s.set_remote(&repo_path, "origin", "https://github.com/foo/bar.git")
         .unwrap();
-    let info = s.get_github_repo_info(&repo_path).unwrap();
+    let info = s.get_github_repo_info(&repo_path, None).unwrap();
     assert_eq!(info.owner, "foo");
     assert_eq!(info.repo_name, "bar");
 }
@@ -587,6 +605,37 @@ fn github_repo_info_parses_https_and_ssh_urls() {
     }
 }
 
+#[test]
+fn github_issue_ref_parses_issue_urls() {
+    let issue = GitHubIssueRef::from_url("https://github.com/owner/repo/issues/42").unwrap();
+    assert_eq!(
+        issue,
+        GitHubIssueRef {
+            owner: "owner".to_string(),
+            repo_name: "repo".to_string(),
+            number: 42,
+        }
+    );
+
+    let issue =
+        GitHubIssueRef::from_url("https://github.com/owner/repo/issues/7#issuecomment-1").unwrap();
+    assert_eq!(issue.number, 7);
+
+    for invalid in [
+        "https://github.com/owner/repo/pull/42",
+        "https://github.com/owner/repo/issues/",
+        "https://example.com/owner/repo/issues/42",
+        "owner/repo#42",
+    ] {
+        match GitHubIssueRef::from_url(invalid).unwrap_err() {
+            GitHubServiceError::Repository(msg) => {
+                assert!(msg.contains("Invalid GitHub issue URL"))
+            }
+            other => panic!("unexpected error variant: {other:?}"),
+        }
+    }
+}
+
 #[test]
 fn squash_merge_libgit2_sets_author_without_user() {
     // Verify merge_changes (libgit2 path) uses fallback author when no config exists
@@ -1,35 +1,68 @@
+use std::{io, path::PathBuf};
+
 use directories::ProjectDirs;
 use rust_embed::RustEmbed;
 
 const PROJECT_ROOT: &str = env!("CARGO_MANIFEST_DIR");
 
-pub fn asset_dir() -> std::path::PathBuf {
-    let path = if cfg!(debug_assertions) {
-        std::path::PathBuf::from(PROJECT_ROOT).join("../../dev_assets")
-    } else {
-        ProjectDirs::from("ai", "namastex", "automagik-forge")
-            .expect("OS didn't give us a home directory")
+/// Environment variables that override the asset directory, checked in order.
+const ASSET_DIR_ENV_VARS: [&str; 2] = ["FORGE_ASSET_DIR", "FORGE_DATA_DIR"];
+
+/// Returns the directory holding the database, config and profiles, creating it if needed.
+///
+/// `FORGE_ASSET_DIR` or `FORGE_DATA_DIR` take precedence over the platform default, which
+/// helps when the default location isn't writable (e.g. in sandboxes).
+pub fn asset_dir() -> io::Result<PathBuf> {
+    let override_dir = ASSET_DIR_ENV_VARS
+        .iter()
+        .filter_map(std::env::var_os)
+        .find(|value| !value.is_empty())
+        .map(PathBuf::from);
+    resolve_asset_dir(override_dir)
+}
+
+fn resolve_asset_dir(override_dir: Option<PathBuf>) -> io::Result<PathBuf> {
+    let path = match override_dir {
+        Some(dir) => dir,
+        None if cfg!(debug_assertions) => PathBuf::from(PROJECT_ROOT).join("../../dev_assets"),
+        // ✔ macOS → ~/Library/Application Support/MyApp
+        // ✔ Linux → ~/.local/share/myapp   (respects XDG_DATA_HOME)
+        // ✔ Windows → %APPDATA%\Example\MyApp
+        None => ProjectDirs::from("ai", "namastex", "automagik-forge")
+            .ok_or_else(missing_home_dir_error)?
             .data_dir()
-            .to_path_buf()
+            .to_path_buf(),
     };
 
-    // Ensure the directory exists
-    if !path.exists() {
-        std::fs::create_dir_all(&path).expect("Failed to create asset directory");
-    }
+    std::fs::create_dir_all(&path).map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!("Failed to create asset directory {}: {e}", path.display()),
+        )
+    })?;
 
-    path
-    // ✔ macOS → ~/Library/Application Support/MyApp
-    // ✔ Linux → ~/.local/share/myapp   (respects XDG_DATA_HOME)
-    // ✔ Windows → %APPDATA%\Example\MyApp
+    Ok(path)
 }
 
-pub fn config_path() -> std::path::PathBuf {
-    asset_dir().join("config.json")
+/// Raised when the platform data directory can't be derived, typically because `HOME` is unset
+/// in a minimal container.
+fn missing_home_dir_error() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        format!(
+            "Could not determine a data directory: no home directory was found (is HOME set?). \
+             Set HOME, or point {} at a writable directory.",
+            ASSET_DIR_ENV_VARS.join(" or ")
+        ),
+    )
 }
 
-pub fn profiles_path() -> std::path::PathBuf {
-    asset_dir().join("profiles.json")
+pub fn config_path() -> io::Result<PathBuf> {
+    Ok(asset_dir()?.join("config.json"))
+}
+
+pub fn profiles_path() -> io::Result<PathBuf> {
+    Ok(asset_dir()?.join("profiles.json"))
 }
 
 #[derive(RustEmbed)]
@@ -39,3 +72,33 @@ pub struct SoundAssets;
 #[derive(RustEmbed)]
 #[folder = "../../assets/scripts"]
 pub struct ScriptAssets;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_asset_dir_override_is_created() {
+        let dir = std::env::temp_dir()
+            .join(format!("vk-assets-{}", uuid::Uuid::new_v4()))
+            .join("nested");
+        assert!(!dir.exists());
+
+        let resolved = resolve_asset_dir(Some(dir.clone())).unwrap();
+        assert_eq!(resolved, dir);
+        assert!(dir.is_dir());
+
+        std::fs::remove_dir_all(dir.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_asset_dir_reports_uncreatable_path() {
+        let file = std::env::temp_dir().join(format!("vk-assets-{}", uuid::Uuid::new_v4()));
+        std::fs::write(&file, "not a directory").unwrap();
+
+        let err = resolve_asset_dir(Some(file.join("child"))).unwrap_err();
+        assert!(err.to_string().contains("Failed to create asset directory"));
+
+        std::fs::remove_file(file).unwrap();
+    }
+}
@@ -1,7 +1,8 @@
 use std::borrow::Cow;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use similar::{ChangeTag, TextDiff};
+use similar::{Algorithm, ChangeTag, TextDiff};
 use ts_rs::TS;
 
 // Structs compatable with props: https://github.com/MrWangJustToDo/git-diff-view
@@ -41,13 +42,66 @@ pub enum DiffChangeKind {
     PermissionChange,
 }
 
+/// Line counts of a unified diff, so consumers don't have to parse it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct DiffStats {
+    pub added: usize,
+    pub removed: usize,
+    /// Files with a `---`/`+++` header; a headerless diff with changes counts as one file
+    pub files_changed: usize,
+}
+
+impl DiffStats {
+    pub fn from_unified_diff(unified_diff: &str) -> Self {
+        let mut stats = Self::default();
+        let mut lines = unified_diff.lines().peekable();
+        while let Some(line) = lines.next() {
+            if line.starts_with("--- ") && lines.peek().is_some_and(|next| next.starts_with("+++ "))
+            {
+                lines.next();
+                stats.files_changed += 1;
+            } else if line.starts_with('+') {
+                stats.added += 1;
+            } else if line.starts_with('-') {
+                stats.removed += 1;
+            }
+        }
+        if stats.files_changed == 0 && (stats.added > 0 || stats.removed > 0) {
+            stats.files_changed = 1;
+        }
+        stats
+    }
+}
+
+/// Line diff algorithm used to build unified diffs of agent edits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffAlgorithm {
+    #[default]
+    Myers,
+    /// Anchors on lines that occur once on each side; keeps moved blocks readable
+    Patience,
+    /// Longest common subsequence
+    Lcs,
+}
+
+impl From<DiffAlgorithm> for Algorithm {
+    fn from(algorithm: DiffAlgorithm) -> Self {
+        match algorithm {
+            DiffAlgorithm::Myers => Algorithm::Myers,
+            DiffAlgorithm::Patience => Algorithm::Patience,
+            DiffAlgorithm::Lcs => Algorithm::Lcs,
+        }
+    }
+}
+
 // ==============================
 // Unified diff utility functions
 // ==============================
 
 /// Converts a replace diff to a unified diff hunk without the hunk header.
 /// The hunk returned will have valid hunk, and diff lines.
-pub fn create_unified_diff_hunk(old: &str, new: &str) -> String {
+pub fn create_unified_diff_hunk(old: &str, new: &str, algorithm: DiffAlgorithm) -> String {
     // normalize ending line feed to optimize diff output
     let mut old = old.to_string();
     let mut new = new.to_string();
@@ -58,7 +112,9 @@ pub fn create_unified_diff_hunk(old: &str, new: &str) -> String {
         new.push('\n');
     }
 
-    let diff = TextDiff::from_lines(&old, &new);
+    let diff = TextDiff::configure()
+        .algorithm(algorithm.into())
+        .diff_lines(&old, &new);
 
     let mut out = String::new();
 
@@ -84,13 +140,32 @@ pub fn create_unified_diff_hunk(old: &str, new: &str) -> String {
 }
 
 /// Creates a full unified diff with the file path in the header.
-pub fn create_unified_diff(file_path: &str, old: &str, new: &str) -> String {
+pub fn create_unified_diff(
+    file_path: &str,
+    old: &str,
+    new: &str,
+    algorithm: DiffAlgorithm,
+) -> String {
     let mut out = String::new();
     out.push_str(format!("--- a/{file_path}\n+++ b/{file_path}\n").as_str());
-    out.push_str(&create_unified_diff_hunk(old, new));
+    out.push_str(&create_unified_diff_hunk(old, new, algorithm));
     out
 }
 
+/// Creates a unified diff with real hunk headers and `context_lines` of surrounding context.
+pub fn create_unified_diff_with_context(
+    file_path: &str,
+    old: &str,
+    new: &str,
+    context_lines: usize,
+) -> String {
+    TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(context_lines)
+        .header(&format!("a/{file_path}"), &format!("b/{file_path}"))
+        .to_string()
+}
+
 /// Compute addition/deletion counts between two text snapshots.
 pub fn compute_line_change_counts(old: &str, new: &str) -> (usize, usize) {
     let old = ensure_newline(old);
@@ -260,3 +335,71 @@ pub fn concatenate_diff_hunks(file_path: &str, hunks: &[String]) -> String {
 
     unified_diff
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sides(hunk: &str) -> (String, String) {
+        let (mut old, mut new) = (String::new(), String::new());
+        for line in hunk.lines().skip(1) {
+            let (sign, text) = line.split_at(1);
+            if sign != "+" {
+                old.push_str(text);
+                old.push('\n');
+            }
+            if sign != "-" {
+                new.push_str(text);
+                new.push('\n');
+            }
+        }
+        (old, new)
+    }
+
+    #[test]
+    fn test_diff_algorithms_are_stable_on_reordered_blocks() {
+        let old = "fn a() {\n    1\n}\n\nfn b() {\n    2\n}\n\nfn c() {\n    3\n}\n\nfn d() {\n    4\n}\n";
+        let new = "fn d() {\n    4\n}\n\nfn b() {\n    2\n}\n\nfn a() {\n    1\n}\n\nfn c() {\n    3\n}\n";
+
+        for algorithm in [
+            DiffAlgorithm::Myers,
+            DiffAlgorithm::Patience,
+            DiffAlgorithm::Lcs,
+        ] {
+            let hunk = create_unified_diff_hunk(old, new, algorithm);
+            assert_eq!(hunk, create_unified_diff_hunk(old, new, algorithm));
+            assert_eq!(sides(&hunk), (old.to_string(), new.to_string()));
+        }
+        assert_eq!(DiffAlgorithm::default(), DiffAlgorithm::Myers);
+
+        // Patience moves whole functions instead of interleaving their lines
+        let patience = create_unified_diff_hunk(old, new, DiffAlgorithm::Patience);
+        assert!(patience.contains("+fn d() {\n+    4\n+}\n"));
+        assert!(patience.contains("-fn d() {\n-    4\n-}\n"));
+    }
+
+    #[test]
+    fn test_diff_stats_count_lines_and_files() {
+        let diff = "--- a/src/main.rs\n+++ b/src/main.rs\n@@ -1,3 +1,4 @@\n-fn main() {}\n+fn main() {\n+    run();\n+}\n context\n--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1,2 +1,1 @@\n--- not a header\n-pub mod old;\n";
+        assert_eq!(
+            DiffStats::from_unified_diff(diff),
+            DiffStats {
+                added: 3,
+                removed: 3,
+                files_changed: 2,
+            }
+        );
+
+        let stats = DiffStats::from_unified_diff(&create_unified_diff(
+            "a.txt",
+            "one\ntwo\n",
+            "one\nthree\nfour\n",
+            DiffAlgorithm::Myers,
+        ));
+        assert_eq!((stats.added, stats.removed, stats.files_changed), (2, 1, 1));
+
+        let headerless = DiffStats::from_unified_diff("@@\n-old\n+new\n");
+        assert_eq!(headerless.files_changed, 1);
+        assert_eq!(DiffStats::from_unified_diff(""), DiffStats::default());
+    }
+}
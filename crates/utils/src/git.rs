@@ -1,3 +1,7 @@
+use uuid::Uuid;
+
+use crate::text::{git_branch_id, short_uuid};
+
 pub fn is_valid_branch_prefix(prefix: &str) -> bool {
     if prefix.is_empty() {
         return true;
@@ -10,6 +14,63 @@ pub fn is_valid_branch_prefix(prefix: &str) -> bool {
     git2::Branch::name_is_valid(&format!("{prefix}/x")).unwrap_or_default()
 }
 
+pub fn is_valid_branch_name(name: &str) -> bool {
+    git2::Branch::name_is_valid(name).unwrap_or_default()
+}
+
+/// Values substituted into a branch name template.
+pub struct BranchTemplateVars<'a> {
+    /// Configured `git_branch_prefix`, possibly empty
+    pub prefix: &'a str,
+    pub task_id: &'a Uuid,
+    pub attempt_id: &'a Uuid,
+    pub task_title: &'a str,
+}
+
+/// Renders a branch name such as `feature/{task_id}-{slug}`.
+///
+/// Supported placeholders are `{prefix}`, `{task_id}`, `{attempt_id}` (short form, as in the
+/// default branch names) and `{slug}` (derived from the task title). Empty path segments left by
+/// an empty prefix are dropped. The result must be a valid git branch name.
+pub fn render_branch_template(
+    template: &str,
+    vars: &BranchTemplateVars<'_>,
+) -> Result<String, String> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find('}') else {
+            return Err(format!(
+                "Unclosed placeholder in branch template '{template}'"
+            ));
+        };
+        match &rest[start + 1..start + len] {
+            "prefix" => rendered.push_str(vars.prefix),
+            "task_id" => rendered.push_str(&vars.task_id.to_string()),
+            "attempt_id" => rendered.push_str(&short_uuid(vars.attempt_id)),
+            "slug" => rendered.push_str(&git_branch_id(vars.task_title)),
+            other => {
+                return Err(format!(
+                    "Unknown placeholder '{{{other}}}' in branch template. Supported: {{prefix}}, {{task_id}}, {{attempt_id}}, {{slug}}"
+                ));
+            }
+        }
+        rest = &rest[start + len + 1..];
+    }
+    rendered.push_str(rest);
+
+    let name = rendered
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>()
+        .join("/");
+    if !is_valid_branch_name(&name) {
+        return Err(format!("'{name}' is not a valid git branch name"));
+    }
+    Ok(name)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -44,4 +105,28 @@ mod tests {
         assert!(!is_valid_branch_prefix("foo/"));
         assert!(!is_valid_branch_prefix(".foo"));
     }
+
+    #[test]
+    fn test_render_branch_template() {
+        let task_id = Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+        let attempt_id = Uuid::parse_str("a1b2c3d4-0000-0000-0000-000000000000").unwrap();
+        let vars = |prefix| BranchTemplateVars {
+            prefix,
+            task_id: &task_id,
+            attempt_id: &attempt_id,
+            task_title: "Fix login bug!",
+        };
+
+        assert_eq!(
+            render_branch_template("feature/{task_id}-{slug}", &vars("vk")).unwrap(),
+            "feature/67e55044-10b1-426f-9247-bb680e5fe0c8-fix-login-bug"
+        );
+        assert_eq!(
+            render_branch_template("{prefix}/{attempt_id}-{slug}", &vars("")).unwrap(),
+            "a1b2-fix-login-bug"
+        );
+        assert!(render_branch_template("feature/{ticket}", &vars("vk")).is_err());
+        assert!(render_branch_template("feature/{slug", &vars("vk")).is_err());
+        assert!(render_branch_template("feature..{slug}", &vars("vk")).is_err());
+    }
 }
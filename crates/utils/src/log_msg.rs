@@ -8,13 +8,33 @@ pub const EV_JSON_PATCH: &str = "json_patch";
 pub const EV_SESSION_ID: &str = "session_id";
 pub const EV_FINISHED: &str = "finished";
 
+/// How a process ended, so consumers can tell a crash from a normal completion.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TerminalStatus {
+    Success,
+    Failed,
+    Cancelled,
+}
+
+impl TerminalStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TerminalStatus::Success => "success",
+            TerminalStatus::Failed => "failed",
+            TerminalStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum LogMsg {
     Stdout(String),
     Stderr(String),
     JsonPatch(Patch),
     SessionId(String),
-    Finished,
+    /// End of the stream; the status is `None` when the producer doesn't know how the process ended
+    Finished(Option<TerminalStatus>),
 }
 
 impl LogMsg {
@@ -24,7 +44,7 @@ impl LogMsg {
             LogMsg::Stderr(_) => EV_STDERR,
             LogMsg::JsonPatch(_) => EV_JSON_PATCH,
             LogMsg::SessionId(_) => EV_SESSION_ID,
-            LogMsg::Finished => EV_FINISHED,
+            LogMsg::Finished(_) => EV_FINISHED,
         }
     }
 
@@ -37,7 +57,9 @@ impl LogMsg {
                 Event::default().event(EV_JSON_PATCH).data(data)
             }
             LogMsg::SessionId(s) => Event::default().event(EV_SESSION_ID).data(s.clone()),
-            LogMsg::Finished => Event::default().event(EV_FINISHED).data(""),
+            LogMsg::Finished(status) => Event::default()
+                .event(EV_FINISHED)
+                .data(status.map(|status| status.as_str()).unwrap_or_default()),
         }
     }
 
@@ -52,9 +74,12 @@ impl LogMsg {
     /// This method mirrors the behavior of the original logmsg_to_ws function
     /// but with better error handling than unwrap().
     pub fn to_ws_message_unchecked(&self) -> Message {
-        // Finished becomes JSON {finished: true}
+        // Finished becomes JSON {finished: true}, plus the terminal status when known
         let json = match self {
-            LogMsg::Finished => r#"{"finished":true}"#.to_string(),
+            LogMsg::Finished(None) => r#"{"finished":true}"#.to_string(),
+            LogMsg::Finished(Some(status)) => {
+                format!(r#"{{"finished":true,"status":"{}"}}"#, status.as_str())
+            }
             _ => serde_json::to_string(self)
                 .unwrap_or_else(|_| r#"{"error":"serialization_failed"}"#.to_string()),
         };
@@ -73,7 +98,7 @@ impl LogMsg {
                 EV_JSON_PATCH.len() + json_len + OVERHEAD
             }
             LogMsg::SessionId(s) => EV_SESSION_ID.len() + s.len() + OVERHEAD,
-            LogMsg::Finished => EV_FINISHED.len() + OVERHEAD,
+            LogMsg::Finished(_) => EV_FINISHED.len() + OVERHEAD,
         }
     }
 }
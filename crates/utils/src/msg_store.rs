@@ -4,29 +4,68 @@ use std::{
 };
 
 use axum::response::sse::Event;
+use chrono::{DateTime, Utc};
 use futures::{StreamExt, TryStreamExt, future};
 use tokio::{sync::broadcast, task::JoinHandle};
 use tokio_stream::wrappers::BroadcastStream;
 
-use crate::{log_msg::LogMsg, stream_lines::LinesStreamExt};
+use crate::{
+    log_msg::{LogMsg, TerminalStatus},
+    stream_lines::{BoundedLine, LinesStreamExt, max_line_bytes},
+};
 
 // 100 MB Limit
 const HISTORY_BYTES: usize = 100000 * 1024;
 
+/// Environment variable that makes normalizers stamp entries with the time their output was
+/// ingested, so stdout and stderr entries can be ordered against each other.
+pub const TIMESTAMP_ENTRIES_ENV: &str = "FORGE_TIMESTAMP_LOG_ENTRIES";
+
+/// Whether `FORGE_TIMESTAMP_LOG_ENTRIES` is set to `1` or `true`.
+pub fn entry_timestamps_enabled() -> bool {
+    std::env::var(TIMESTAMP_ENTRIES_ENV)
+        .map(|value| matches!(value.trim().to_ascii_lowercase().as_str(), "1" | "true"))
+        .unwrap_or(false)
+}
+
 #[derive(Clone)]
 struct StoredMsg {
     msg: LogMsg,
     bytes: usize,
+    received_at: Option<DateTime<Utc>>,
+}
+
+/// A message together with the time the store ingested it.
+#[derive(Debug, Clone)]
+pub struct TimestampedMsg {
+    pub received_at: DateTime<Utc>,
+    pub msg: LogMsg,
+}
+
+/// The stdout and stderr chunks still held in memory, see [`MsgStore::output_snapshot`].
+#[derive(Debug, Clone, Default)]
+pub struct OutputSnapshot {
+    /// Output sequence number of the first chunk in `chunks`
+    pub first_seq: usize,
+    pub chunks: Vec<LogMsg>,
+    /// Whether the process has finished, so no chunks follow
+    pub finished: bool,
 }
 
 struct Inner {
     history: VecDeque<StoredMsg>,
     total_bytes: usize,
+    next_seq: u64,
+    /// Stdout and stderr chunks pushed so far, including those evicted from `history`
+    output_chunks: usize,
+    persisted_entries: usize,
 }
 
 pub struct MsgStore {
     inner: RwLock<Inner>,
     sender: broadcast::Sender<LogMsg>,
+    timestamped_sender: broadcast::Sender<(u64, TimestampedMsg)>,
+    timestamps: bool,
 }
 
 impl Default for MsgStore {
@@ -37,21 +76,57 @@ impl Default for MsgStore {
 
 impl MsgStore {
     pub fn new() -> Self {
+        Self::with_timestamps(entry_timestamps_enabled())
+    }
+
+    /// A store that stamps messages with their ingestion time only if `timestamps` is set,
+    /// regardless of [`TIMESTAMP_ENTRIES_ENV`].
+    pub fn with_timestamps(timestamps: bool) -> Self {
         let (sender, _) = broadcast::channel(10000);
+        let (timestamped_sender, _) = broadcast::channel(10000);
         Self {
             inner: RwLock::new(Inner {
                 history: VecDeque::with_capacity(32),
                 total_bytes: 0,
+                next_seq: 0,
+                output_chunks: 0,
+                persisted_entries: 0,
             }),
             sender,
+            timestamped_sender,
+            timestamps,
         }
     }
 
+    /// Whether messages are stamped with their ingestion time, see [`Self::timestamped_history`].
+    pub fn timestamps_enabled(&self) -> bool {
+        self.timestamps
+    }
+
     pub fn push(&self, msg: LogMsg) {
         let _ = self.sender.send(msg.clone()); // live listeners
         let bytes = msg.approx_bytes();
 
         let mut inner = self.inner.write().unwrap();
+        // Stamped and sent under the lock, so stamps never go backwards in insertion order and the
+        // timestamped history and stream agree.
+        let received_at = self.timestamps.then(Utc::now);
+        let seq = inner.next_seq;
+        inner.next_seq += 1;
+        if matches!(msg, LogMsg::Stdout(_) | LogMsg::Stderr(_)) {
+            inner.output_chunks += 1;
+        }
+        if let Some(received_at) = received_at
+            && self.timestamped_sender.receiver_count() > 0
+        {
+            let _ = self.timestamped_sender.send((
+                seq,
+                TimestampedMsg {
+                    received_at,
+                    msg: msg.clone(),
+                },
+            ));
+        }
         while inner.total_bytes.saturating_add(bytes) > HISTORY_BYTES {
             if let Some(front) = inner.history.pop_front() {
                 inner.total_bytes = inner.total_bytes.saturating_sub(front.bytes);
@@ -59,7 +134,11 @@ impl MsgStore {
                 break;
             }
         }
-        inner.history.push_back(StoredMsg { msg, bytes });
+        inner.history.push_back(StoredMsg {
+            msg,
+            bytes,
+            received_at,
+        });
         inner.total_bytes = inner.total_bytes.saturating_add(bytes);
     }
 
@@ -80,7 +159,23 @@ impl MsgStore {
     }
 
     pub fn push_finished(&self) {
-        self.push(LogMsg::Finished);
+        self.push(LogMsg::Finished(None));
+    }
+
+    /// Mark the end of the stream, recording whether the process succeeded, failed or was stopped.
+    pub fn push_finished_with_status(&self, status: TerminalStatus) {
+        self.push(LogMsg::Finished(Some(status)));
+    }
+
+    /// Records that the first `count` conversation entries were normalized and stored before this
+    /// store was rebuilt, e.g. from the database, so normalizers replaying it don't emit them again.
+    pub fn mark_persisted_entries(&self, count: usize) {
+        self.inner.write().unwrap().persisted_entries = count;
+    }
+
+    /// Number of leading conversation entries already stored, see [`Self::mark_persisted_entries`].
+    pub fn persisted_entries(&self) -> usize {
+        self.inner.read().unwrap().persisted_entries
     }
 
     pub fn get_receiver(&self) -> broadcast::Receiver<LogMsg> {
@@ -97,6 +192,70 @@ impl MsgStore {
             .collect()
     }
 
+    /// The stdout and stderr chunks still in the history. Chunks are numbered by the order they
+    /// were pushed, counting evicted ones, so a chunk keeps its number when older output is
+    /// dropped to stay within the history limit.
+    pub fn output_snapshot(&self) -> OutputSnapshot {
+        let inner = self.inner.read().unwrap();
+        let chunks: Vec<LogMsg> = inner
+            .history
+            .iter()
+            .filter(|s| matches!(s.msg, LogMsg::Stdout(_) | LogMsg::Stderr(_)))
+            .map(|s| s.msg.clone())
+            .collect();
+        OutputSnapshot {
+            first_seq: inner.output_chunks - chunks.len(),
+            finished: inner
+                .history
+                .iter()
+                .any(|s| matches!(s.msg, LogMsg::Finished(_))),
+            chunks,
+        }
+    }
+
+    /// History in the order it was pushed, each message paired with when it was received. Empty
+    /// unless [`Self::timestamps_enabled`].
+    ///
+    /// stdout and stderr are forwarded by independent tasks, so this is the view to use when
+    /// rendering them as one interleaved transcript.
+    pub fn timestamped_history(&self) -> Vec<TimestampedMsg> {
+        self.timestamped_history_with_next_seq().0
+    }
+
+    fn timestamped_history_with_next_seq(&self) -> (Vec<TimestampedMsg>, u64) {
+        let inner = self.inner.read().unwrap();
+        let history = inner
+            .history
+            .iter()
+            .filter_map(|s| {
+                s.received_at.map(|received_at| TimestampedMsg {
+                    received_at,
+                    msg: s.msg.clone(),
+                })
+            })
+            .collect();
+        (history, inner.next_seq)
+    }
+
+    /// Timestamped history then live, as `TimestampedMsg`. Yields nothing unless
+    /// [`Self::timestamps_enabled`].
+    pub fn timestamped_history_plus_stream(
+        &self,
+    ) -> futures::stream::BoxStream<'static, Result<TimestampedMsg, std::io::Error>> {
+        // Subscribe before reading history so nothing pushed in between is lost.
+        let rx = self.timestamped_sender.subscribe();
+        let (history, next_seq) = self.timestamped_history_with_next_seq();
+
+        let hist = futures::stream::iter(history.into_iter().map(Ok::<_, std::io::Error>));
+        let live = BroadcastStream::new(rx).filter_map(move |res| async move {
+            res.ok()
+                .filter(|(seq, _)| *seq >= next_seq)
+                .map(|(_, m)| Ok::<_, std::io::Error>(m))
+        });
+
+        Box::pin(hist.chain(live))
+    }
+
     /// History then live, as `LogMsg`.
     pub fn history_plus_stream(
         &self,
@@ -114,7 +273,7 @@ impl MsgStore {
         &self,
     ) -> futures::stream::BoxStream<'static, Result<String, std::io::Error>> {
         self.history_plus_stream()
-            .take_while(|res| future::ready(!matches!(res, Ok(LogMsg::Finished))))
+            .take_while(|res| future::ready(!matches!(res, Ok(LogMsg::Finished(_)))))
             .filter_map(|res| async move {
                 match res {
                     Ok(LogMsg::Stdout(s)) => Some(Ok(s)),
@@ -130,11 +289,18 @@ impl MsgStore {
         self.stdout_chunked_stream().lines()
     }
 
+    /// Stdout lines, skipping any line longer than [`max_line_bytes`].
+    pub fn stdout_bounded_lines_stream(
+        &self,
+    ) -> futures::stream::BoxStream<'static, std::io::Result<BoundedLine>> {
+        self.stdout_chunked_stream().bounded_lines(max_line_bytes())
+    }
+
     pub fn stderr_chunked_stream(
         &self,
     ) -> futures::stream::BoxStream<'static, Result<String, std::io::Error>> {
         self.history_plus_stream()
-            .take_while(|res| future::ready(!matches!(res, Ok(LogMsg::Finished))))
+            .take_while(|res| future::ready(!matches!(res, Ok(LogMsg::Finished(_)))))
             .filter_map(|res| async move {
                 match res {
                     Ok(LogMsg::Stderr(s)) => Some(Ok(s)),
@@ -144,6 +310,26 @@ impl MsgStore {
             .boxed()
     }
 
+    /// Stderr chunks paired with the time each one was ingested.
+    pub fn stderr_timestamped_chunked_stream(
+        &self,
+    ) -> futures::stream::BoxStream<'static, Result<(DateTime<Utc>, String), std::io::Error>> {
+        self.timestamped_history_plus_stream()
+            .take_while(|res| {
+                future::ready(!matches!(res, Ok(m) if matches!(m.msg, LogMsg::Finished(_))))
+            })
+            .filter_map(|res| async move {
+                match res {
+                    Ok(TimestampedMsg {
+                        received_at,
+                        msg: LogMsg::Stderr(s),
+                    }) => Some(Ok((received_at, s))),
+                    _ => None,
+                }
+            })
+            .boxed()
+    }
+
     pub fn stderr_lines_stream(
         &self,
     ) -> futures::stream::BoxStream<'static, std::io::Result<String>> {
@@ -175,3 +361,120 @@ impl MsgStore {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::extract::ws::Message;
+
+    use super::*;
+
+    fn text(msg: &TimestampedMsg) -> &str {
+        match &msg.msg {
+            LogMsg::Stdout(s) | LogMsg::Stderr(s) => s,
+            _ => "",
+        }
+    }
+
+    #[tokio::test]
+    async fn timestamped_stream_interleaves_history_and_live_in_order() {
+        let store = MsgStore::with_timestamps(true);
+        store.push_stdout("out 1");
+        store.push_stderr("err 1");
+
+        let stream = store.timestamped_history_plus_stream();
+        store.push_stdout("out 2");
+        store.push_finished();
+
+        let msgs: Vec<TimestampedMsg> = stream
+            .take_while(|res| {
+                future::ready(!matches!(res, Ok(m) if matches!(m.msg, LogMsg::Finished(_))))
+            })
+            .map(|res| res.unwrap())
+            .collect()
+            .await;
+
+        assert_eq!(
+            msgs.iter().map(text).collect::<Vec<_>>(),
+            vec!["out 1", "err 1", "out 2"]
+        );
+        assert!(
+            msgs.windows(2)
+                .all(|w| w[0].received_at <= w[1].received_at)
+        );
+        assert_eq!(store.timestamped_history().len(), 4);
+    }
+
+    #[test]
+    fn messages_are_not_stamped_when_timestamps_are_off() {
+        let store = MsgStore::with_timestamps(false);
+        store.push_stdout("out");
+        store.push_stderr("err");
+
+        assert!(!store.timestamps_enabled());
+        assert!(store.timestamped_history().is_empty());
+        assert_eq!(store.get_history().len(), 2);
+    }
+
+    #[test]
+    fn output_numbers_survive_history_eviction() {
+        let store = MsgStore::with_timestamps(false);
+        store.push_stdout("a".repeat(HISTORY_BYTES / 2));
+        store.push_session_id("session".to_string());
+        store.push_stderr("b".repeat(HISTORY_BYTES / 2));
+        store.push_stdout("c");
+
+        let snapshot = store.output_snapshot();
+        assert_eq!(snapshot.first_seq, 1);
+        assert_eq!(snapshot.chunks.len(), 2);
+        assert!(matches!(&snapshot.chunks[1], LogMsg::Stdout(s) if s == "c"));
+        assert!(!snapshot.finished);
+
+        store.push_finished();
+        assert!(store.output_snapshot().finished);
+    }
+
+    async fn terminal_message(status: Option<TerminalStatus>) -> LogMsg {
+        let store = MsgStore::new();
+        store.push_stdout("out");
+        match status {
+            Some(status) => store.push_finished_with_status(status),
+            None => store.push_finished(),
+        }
+        store
+            .history_plus_stream()
+            .map(|res| res.unwrap())
+            .skip(1)
+            .next()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn finished_carries_each_terminal_status() {
+        for status in [
+            TerminalStatus::Success,
+            TerminalStatus::Failed,
+            TerminalStatus::Cancelled,
+        ] {
+            let msg = terminal_message(Some(status)).await;
+            assert!(matches!(msg, LogMsg::Finished(Some(s)) if s == status));
+            let Message::Text(json) = msg.to_ws_message_unchecked() else {
+                panic!("expected a text message");
+            };
+            assert_eq!(
+                json.as_str(),
+                format!(r#"{{"finished":true,"status":"{}"}}"#, status.as_str())
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn finished_without_status_keeps_the_plain_marker() {
+        let msg = terminal_message(None).await;
+        assert!(matches!(msg, LogMsg::Finished(None)));
+        let Message::Text(json) = msg.to_ws_message_unchecked() else {
+            panic!("expected a text message");
+        };
+        assert_eq!(json.as_str(), r#"{"finished":true}"#);
+    }
+}
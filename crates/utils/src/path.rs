@@ -1,8 +1,14 @@
-use std::path::{Path, PathBuf};
+use std::{
+    io,
+    path::{Component, Path, PathBuf},
+};
 
 /// Directory name for storing images in worktrees
 pub const FORGE_IMAGES_DIR: &str = ".forge-images";
 
+/// Environment variable that overrides where worktrees and agent logs are stored.
+pub const FORGE_TEMP_DIR_ENV: &str = "FORGE_TEMP_DIR";
+
 /// Convert absolute paths to relative paths based on worktree path
 /// This is a robust implementation that handles symlinks and edge cases
 pub fn make_path_relative(path: &str, worktree_path: &str) -> String {
@@ -112,7 +118,16 @@ fn normalize_macos_private_alias<P: AsRef<Path>>(p: P) -> PathBuf {
     p.to_path_buf()
 }
 
+/// Directory for worktrees and agent logs. `FORGE_TEMP_DIR` takes precedence over the platform
+/// default, for systems where that location is too small or mounted `noexec`.
 pub fn get_automagik_forge_temp_dir() -> std::path::PathBuf {
+    std::env::var_os(FORGE_TEMP_DIR_ENV)
+        .filter(|value| !value.is_empty())
+        .map(|value| expand_tilde(&value.to_string_lossy()))
+        .unwrap_or_else(default_automagik_forge_temp_dir)
+}
+
+fn default_automagik_forge_temp_dir() -> std::path::PathBuf {
     let dir_name = if cfg!(debug_assertions) {
         "automagik-forge-dev"
     } else {
@@ -131,15 +146,84 @@ pub fn get_automagik_forge_temp_dir() -> std::path::PathBuf {
     }
 }
 
+/// Creates the temp directory if needed and checks that it is writable, so that a bad location
+/// fails at startup rather than halfway through creating a worktree.
+pub fn ensure_automagik_forge_temp_dir() -> io::Result<PathBuf> {
+    let dir = get_automagik_forge_temp_dir();
+    ensure_writable_dir(&dir)?;
+    Ok(dir)
+}
+
+fn ensure_writable_dir(dir: &Path) -> io::Result<()> {
+    std::fs::create_dir_all(dir).map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!(
+                "Failed to create temp directory {}: {e}. Set {FORGE_TEMP_DIR_ENV} to a writable location",
+                dir.display()
+            ),
+        )
+    })?;
+
+    let probe = dir.join(format!(".write-test-{}", uuid::Uuid::new_v4()));
+    std::fs::write(&probe, b"").map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!(
+                "Temp directory {} is not writable: {e}. Set {FORGE_TEMP_DIR_ENV} to a writable location",
+                dir.display()
+            ),
+        )
+    })?;
+    let _ = std::fs::remove_file(probe);
+    Ok(())
+}
+
 /// Expand leading ~ to user's home directory.
 pub fn expand_tilde(path_str: &str) -> std::path::PathBuf {
     shellexpand::tilde(path_str).as_ref().into()
 }
 
+/// Whether `path` names something inside the directory it is joined onto: relative and without
+/// `..` components. Checked lexically, so the path doesn't need to exist; symlinks inside the
+/// directory can still point elsewhere.
+pub fn is_contained_relative_path(path: &str) -> bool {
+    !path.trim().is_empty()
+        && Path::new(path)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_temp_dir_is_created_and_checked_for_writability() {
+        let root = std::env::temp_dir().join(format!("vk-temp-{}", uuid::Uuid::new_v4()));
+        let dir = root.join("nested");
+        ensure_writable_dir(&dir).unwrap();
+        assert!(dir.is_dir());
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+
+        let file = root.join("file");
+        std::fs::write(&file, "not a directory").unwrap();
+        let err = ensure_writable_dir(&file.join("child")).unwrap_err();
+        assert!(err.to_string().contains(FORGE_TEMP_DIR_ENV));
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_is_contained_relative_path() {
+        assert!(is_contained_relative_path("src/main.rs"));
+        assert!(is_contained_relative_path("./README.md"));
+        assert!(!is_contained_relative_path("../etc/passwd"));
+        assert!(!is_contained_relative_path("src/../../secret"));
+        assert!(!is_contained_relative_path("/etc/passwd"));
+        assert!(!is_contained_relative_path(""));
+    }
+
     #[test]
     fn test_make_path_relative() {
         // Test with relative path (should remain unchanged)
@@ -1,10 +1,134 @@
-use bytes::Bytes;
+use bytes::{Buf, Bytes, BytesMut};
 use futures::{Stream, StreamExt, TryStreamExt};
 use tokio_util::{
-    codec::{FramedRead, LinesCodec},
+    codec::{Decoder, FramedRead, LinesCodec},
     io::StreamReader,
 };
 
+/// Environment variable overriding [`DEFAULT_MAX_LINE_BYTES`].
+pub const MAX_LINE_BYTES_ENV: &str = "FORGE_MAX_LOG_LINE_BYTES";
+
+/// Default upper bound for a single line read by [`LinesStreamExt::bounded_lines`].
+pub const DEFAULT_MAX_LINE_BYTES: usize = 8 * 1024 * 1024;
+
+/// Number of leading bytes kept from a line that exceeded the limit.
+const OVERSIZED_PREFIX_BYTES: usize = 1024;
+
+/// Maximum line length in bytes, honouring the `FORGE_MAX_LOG_LINE_BYTES` override.
+pub fn max_line_bytes() -> usize {
+    std::env::var(MAX_LINE_BYTES_ENV)
+        .ok()
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .filter(|value| *value > 0)
+        .unwrap_or(DEFAULT_MAX_LINE_BYTES)
+}
+
+/// A line produced by [`LinesStreamExt::bounded_lines`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BoundedLine {
+    Line(String),
+    /// A line longer than `limit` bytes was skipped. Only its first bytes are kept, so callers
+    /// can still sniff what the line was without the whole thing being buffered.
+    Oversized {
+        limit: usize,
+        prefix: String,
+    },
+}
+
+/// Line decoder that drops lines longer than `max_length` instead of buffering them.
+struct BoundedLinesCodec {
+    max_length: usize,
+    next_index: usize,
+    discarding: bool,
+}
+
+impl BoundedLinesCodec {
+    fn new(max_length: usize) -> Self {
+        Self {
+            max_length,
+            next_index: 0,
+            discarding: false,
+        }
+    }
+
+    fn oversized(&self, buf: &[u8]) -> BoundedLine {
+        let end = buf.len().min(OVERSIZED_PREFIX_BYTES);
+        BoundedLine::Oversized {
+            limit: self.max_length,
+            prefix: String::from_utf8_lossy(&buf[..end]).into_owned(),
+        }
+    }
+
+    fn to_line(bytes: &[u8]) -> std::io::Result<BoundedLine> {
+        let bytes = bytes.strip_suffix(b"\r").unwrap_or(bytes);
+        String::from_utf8(bytes.to_vec())
+            .map(BoundedLine::Line)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+}
+
+impl Decoder for BoundedLinesCodec {
+    type Item = BoundedLine;
+    type Error = std::io::Error;
+
+    fn decode(&mut self, buf: &mut BytesMut) -> std::io::Result<Option<BoundedLine>> {
+        loop {
+            let newline = buf[self.next_index..]
+                .iter()
+                .position(|b| *b == b'\n')
+                .map(|offset| self.next_index + offset);
+
+            match newline {
+                Some(idx) if self.discarding => {
+                    // Tail of a line that was already reported as oversized.
+                    buf.advance(idx + 1);
+                    self.next_index = 0;
+                    self.discarding = false;
+                }
+                Some(idx) => {
+                    let line = buf.split_to(idx + 1);
+                    self.next_index = 0;
+                    let line = &line[..idx];
+                    if line.len() > self.max_length {
+                        return Ok(Some(self.oversized(line)));
+                    }
+                    return Self::to_line(line).map(Some);
+                }
+                None if self.discarding => {
+                    buf.clear();
+                    self.next_index = 0;
+                    return Ok(None);
+                }
+                None if buf.len() > self.max_length => {
+                    let item = self.oversized(buf);
+                    buf.clear();
+                    self.next_index = 0;
+                    self.discarding = true;
+                    return Ok(Some(item));
+                }
+                None => {
+                    self.next_index = buf.len();
+                    return Ok(None);
+                }
+            }
+        }
+    }
+
+    fn decode_eof(&mut self, buf: &mut BytesMut) -> std::io::Result<Option<BoundedLine>> {
+        if let Some(line) = self.decode(buf)? {
+            return Ok(Some(line));
+        }
+        if self.discarding || buf.is_empty() {
+            buf.clear();
+            self.next_index = 0;
+            return Ok(None);
+        }
+        let line = buf.split_to(buf.len());
+        self.next_index = 0;
+        Self::to_line(&line).map(Some)
+    }
+}
+
 /// Extension trait for converting chunked string streams to line streams.
 pub trait LinesStreamExt: Stream<Item = Result<String, std::io::Error>> + Sized {
     /// Convert a chunked string stream to a line stream.
@@ -17,6 +141,59 @@ pub trait LinesStreamExt: Stream<Item = Result<String, std::io::Error>> + Sized
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
             .boxed()
     }
+
+    /// Convert a chunked string stream to a line stream, skipping lines longer than
+    /// `max_length` bytes rather than buffering them.
+    fn bounded_lines(
+        self,
+        max_length: usize,
+    ) -> futures::stream::BoxStream<'static, std::io::Result<BoundedLine>>
+    where
+        Self: Send + 'static,
+    {
+        let reader = StreamReader::new(self.map(|result| result.map(Bytes::from)));
+        FramedRead::new(reader, BoundedLinesCodec::new(max_length)).boxed()
+    }
 }
 
 impl<S> LinesStreamExt for S where S: Stream<Item = Result<String, std::io::Error>> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn collect(chunks: &[&str], max_length: usize) -> Vec<BoundedLine> {
+        let chunks: Vec<std::io::Result<String>> =
+            chunks.iter().map(|chunk| Ok(chunk.to_string())).collect();
+        futures::stream::iter(chunks)
+            .bounded_lines(max_length)
+            .map(|line| line.unwrap())
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn bounded_lines_skips_oversized_line_and_recovers() {
+        let oversized = "x".repeat(64);
+        let lines = collect(
+            &[
+                "first\n",
+                &oversized[..40],
+                &oversized[40..],
+                "\nlast\r\n",
+                "tail",
+            ],
+            16,
+        )
+        .await;
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], BoundedLine::Line("first".to_string()));
+        assert!(matches!(
+            &lines[1],
+            BoundedLine::Oversized { limit: 16, prefix } if prefix.starts_with("xxxx")
+        ));
+        assert_eq!(lines[2], BoundedLine::Line("last".to_string()));
+        assert_eq!(lines[3], BoundedLine::Line("tail".to_string()));
+    }
+}
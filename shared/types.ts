@@ -14,10 +14,16 @@ export type CreateProject = { name: string, git_repo_path: string, use_existing_
 
 export type UpdateProject = { name: string | null, git_repo_path: string | null, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, };
 
+export type RenameProject = { name: string, default_base_branch: string | null, };
+
 export type SearchResult = { path: string, is_file: boolean, match_type: SearchMatchType, };
 
 export type SearchMatchType = "FileName" | "DirectoryName" | "FullPath";
 
+export type ProjectGitConfig = { default_base_branch: string | null, remote_name: string | null, };
+
+export type UpdateProjectGitConfig = { default_base_branch: string | null, remote_name: string | null, };
+
 export type ExecutorAction = { typ: ExecutorActionType, next_action: ExecutorAction | null, };
 
 export type McpConfig = { servers: { [key in string]?: JsonValue }, servers_path: Array<string>, template: JsonValue, preconfigured: JsonValue, is_toml_config: boolean, };
@@ -42,17 +48,50 @@ export type UpdateTag = { tag_name: string | null, content: string | null, };
 
 export type TagSearchParams = { search: string | null, };
 
+export type ProjectGitConfigResponse = { default_base_branch: string | null, remote_name: string | null, 
+/**
+ * Remotes defined in the project's repository
+ */
+remotes: Array<string>, };
+
 export type TaskStatus = "todo" | "inprogress" | "inreview" | "done" | "cancelled";
 
 export type Task = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_task_attempt: string | null, created_at: string, updated_at: string, };
 
-export type TaskWithAttemptStatus = { has_in_progress_attempt: boolean, has_merged_attempt: boolean, last_attempt_failed: boolean, executor: string, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_task_attempt: string | null, created_at: string, updated_at: string, };
+export type TaskWithAttemptStatus = { has_in_progress_attempt: boolean, has_merged_attempt: boolean, last_attempt_failed: boolean, executor: string, 
+/**
+ * Tasks that have to be done before this one is ready
+ */
+depends_on: Array<string>, 
+/**
+ * Who is working on the task, if anyone claimed it
+ */
+assignee: string | null, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_task_attempt: string | null, created_at: string, updated_at: string, };
 
 export type TaskRelationships = { parent_task: Task | null, current_attempt: TaskAttempt, children: Array<Task>, };
 
-export type CreateTask = { project_id: string, title: string, description: string | null, parent_task_attempt: string | null, image_ids: Array<string> | null, };
+export type CreateTask = { project_id: string, title: string, description: string | null, parent_task_attempt: string | null, image_ids: Array<string> | null, 
+/**
+ * Tasks of the same project that have to be done first
+ */
+depends_on: Array<string> | null, 
+/**
+ * Client-chosen key; retrying a create with the same key returns the first task instead of
+ * creating a duplicate
+ */
+idempotency_key: string | null, };
+
+export type UpdateTask = { title: string | null, description: string | null, status: TaskStatus | null, parent_task_attempt: string | null, image_ids: Array<string> | null, 
+/**
+ * Replaces the task's dependencies; an empty list clears them
+ */
+depends_on: Array<string> | null, };
 
-export type UpdateTask = { title: string | null, description: string | null, status: TaskStatus | null, parent_task_attempt: string | null, image_ids: Array<string> | null, };
+export type SetTaskAssignee = { 
+/**
+ * `None` or a blank name clears the assignee
+ */
+assignee: string | null, };
 
 export type Image = { id: string, file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, created_at: string, updated_at: string, };
 
@@ -64,7 +103,11 @@ export type UserSystemInfo = { config: Config, analytics_user_id: string, enviro
 /**
  * Capabilities supported per executor (e.g., { "CLAUDE_CODE": ["SESSION_FORK"] })
  */
-capabilities: { [key in string]?: Array<BaseAgentCapability> }, executors: { [key in BaseCodingAgent]?: ExecutorConfig }, };
+capabilities: { [key in string]?: Array<BaseAgentCapability> }, executors: { [key in BaseCodingAgent]?: ExecutorConfig }, 
+/**
+ * Model used by an executor's configurations that leave `model` unset
+ */
+default_models?: { [key in BaseCodingAgent]?: string }, };
 
 export type Environment = { os_type: string, os_version: string, os_architecture: string, bitness: string, };
 
@@ -74,7 +117,11 @@ export type UpdateMcpServersBody = { servers: { [key in string]?: JsonValue }, }
 
 export type GetMcpServerResponse = { mcp_config: McpConfig, config_path: string, };
 
-export type CreateFollowUpAttempt = { prompt: string, variant: string | null, image_ids: Array<string> | null, retry_process_id: string | null, force_when_dirty: boolean | null, perform_git_reset: boolean | null, };
+export type CreateFollowUpAttempt = { prompt: string, variant: string | null, image_ids: Array<string> | null, retry_process_id: string | null, force_when_dirty: boolean | null, perform_git_reset: boolean | null, 
+/**
+ * Model or reasoning effort to switch to when resuming the conversation
+ */
+resume_overrides: ResumeOverrides | null, };
 
 export type DraftResponse = { task_attempt_id: string, draft_type: DraftType, retry_process_id: string | null, prompt: string, queued: boolean, variant: string | null, image_ids: Array<string> | null, version: bigint, };
 
@@ -88,13 +135,17 @@ export type ChangeTargetBranchResponse = { new_target_branch: string, status: [n
 
 export type CreateAndStartTaskRequest = { task: CreateTask, executor_profile_id: ExecutorProfileId, base_branch: string, };
 
+export type CreateTaskFromGitHubIssue = { project_id: string, issue_url: string, };
+
+export type MoveTask = { project_id: string, };
+
 export type CreateGitHubPrRequest = { title: string, body: string | null, target_branch: string | null, };
 
 export type ImageResponse = { id: string, file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, created_at: string, updated_at: string, };
 
 export enum GitHubServiceError { TOKEN_INVALID = "TOKEN_INVALID", INSUFFICIENT_PERMISSIONS = "INSUFFICIENT_PERMISSIONS", REPO_NOT_FOUND_OR_NO_ACCESS = "REPO_NOT_FOUND_OR_NO_ACCESS" }
 
-export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, github_login_acknowledged: boolean, telemetry_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean | null, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, };
+export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, github_login_acknowledged: boolean, telemetry_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean | null, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, webhook: WebhookConfig, };
 
 export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, };
 
@@ -112,6 +163,12 @@ export type UiLanguage = "BROWSER" | "EN" | "JA" | "ES" | "KO";
 
 export type ShowcaseState = { seen_features: Array<string>, };
 
+export type WebhookConfig = { url: string | null, 
+/**
+ * Signs each body with HMAC-SHA256, sent as `X-Forge-Signature: sha256=<hex>`
+ */
+secret: string | null, };
+
 export type DeviceFlowStartResponse = { user_code: string, verification_uri: string, expires_in: number, interval: number, };
 
 export enum DevicePollStatus { SLOW_DOWN = "SLOW_DOWN", AUTHORIZATION_PENDING = "AUTHORIZATION_PENDING", SUCCESS = "SUCCESS" }
@@ -132,6 +189,14 @@ additions: number | null, deletions: number | null, };
 
 export type DiffChangeKind = "added" | "deleted" | "modified" | "renamed" | "copied" | "permissionChange";
 
+export type DiffAlgorithm = "myers" | "patience" | "lcs";
+
+export type DiffStats = { added: number, removed: number, 
+/**
+ * Files with a `---`/`+++` header; a headerless diff with changes counts as one file
+ */
+files_changed: number, };
+
 export type RepositoryInfo = { id: bigint, name: string, full_name: string, owner: string, description: string | null, clone_url: string, ssh_url: string, default_branch: string, private: boolean, };
 
 export type CommandBuilder = { 
@@ -142,7 +207,11 @@ base: string,
 /**
  * Optional parameters to append to the base command
  */
-params: Array<string> | null, };
+params: Array<string> | null, 
+/**
+ * Optional shell (program, argument) the command is run through instead of being spawned directly
+ */
+shell?: [string, string] | null, };
 
 export type ExecutorProfileId = { 
 /**
@@ -158,15 +227,84 @@ export type ExecutorConfig = { [key in string]?: { "CLAUDE_CODE": ClaudeCode } |
 
 export enum BaseAgentCapability { SESSION_FORK = "SESSION_FORK", SETUP_HELPER = "SETUP_HELPER" }
 
-export type ClaudeCode = { append_prompt: AppendPrompt, claude_code_router?: boolean | null, plan?: boolean | null, approvals?: boolean | null, model?: string | null, dangerously_skip_permissions?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type ClaudeCode = { append_prompt: AppendPrompt, 
+/**
+ * Text added to Claude's system prompt for every session (`--append-system-prompt`), e.g.
+ * coding standards. Unlike `append_prompt`, the user message is left as is.
+ */
+system_prompt_append?: string | null, claude_code_router?: boolean | null, plan?: boolean | null, approvals?: boolean | null, model?: string | null, dangerously_skip_permissions?: boolean | null, 
+/**
+ * Emit the resolved configuration (secrets redacted) as a system message at spawn
+ */
+dump_config?: boolean | null, resume_mode?: ClaudeResumeMode | null, 
+/**
+ * Line diff algorithm used to render Edit and MultiEdit calls (default: myers)
+ */
+diff_algorithm?: DiffAlgorithm | null, 
+/**
+ * Don't add a warning to the conversation when ANTHROPIC_API_KEY is billed instead of the
+ * Claude subscription
+ */
+suppress_api_key_warning?: boolean | null, 
+/**
+ * Show tool results as plain text even when they are valid JSON
+ */
+prefer_raw_tool_results?: boolean | null, 
+/**
+ * Cap on tokens per model response. Claude Code has no CLI flag for this, so it is passed
+ * as `CLAUDE_CODE_MAX_OUTPUT_TOKENS`; must be positive.
+ */
+max_output_tokens?: number | null, 
+/**
+ * `debug` adds Claude Code's `--debug`. `--verbose` is always passed because stream-json
+ * output requires it, so lower levels change nothing yet.
+ */
+log_level?: ExecutorLogLevel | null, 
+/**
+ * Add a "Completed in 6.0s, 3 turns" system message when Claude Code reports its final result
+ */
+completion_summary?: boolean | null, 
+/**
+ * Tool use kinds left out of the conversation, e.g. `todo_management` or `search`. Raw logs
+ * still contain them.
+ */
+hidden_tool_kinds?: Array<ActionKind> | null, base_command_override?: string | null, additional_params?: Array<string> | null, shell_override?: [string, string] | null, env_file?: string | null, env?: { [key in string]?: string } | null, };
+
+export type ClaudeResumeMode = "fork" | "continue";
 
-export type Gemini = { append_prompt: AppendPrompt, model: GeminiModel, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type Gemini = { append_prompt: AppendPrompt, model: GeminiModel, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, shell_override?: [string, string] | null, env_file?: string | null, env?: { [key in string]?: string } | null, };
 
 export type GeminiModel = "default" | "flash";
 
-export type Amp = { append_prompt: AppendPrompt, dangerously_allow_all?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type Amp = { append_prompt: AppendPrompt, dangerously_allow_all?: boolean | null, diff_algorithm?: DiffAlgorithm | null, base_command_override?: string | null, additional_params?: Array<string> | null, shell_override?: [string, string] | null, env_file?: string | null, env?: { [key in string]?: string } | null, };
 
-export type Codex = { append_prompt: AppendPrompt, sandbox?: SandboxMode | null, ask_for_approval?: AskForApproval | null, oss?: boolean | null, model?: string | null, model_reasoning_effort?: ReasoningEffort | null, model_reasoning_summary?: ReasoningSummary | null, model_reasoning_summary_format?: ReasoningSummaryFormat | null, profile?: string | null, base_instructions?: string | null, include_plan_tool?: boolean | null, include_apply_patch_tool?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type CodexMcpServer = { command?: string | null, args?: Array<string> | null, env?: { [key in string]?: string } | null, url?: string | null, };
+
+export type Codex = { append_prompt: AppendPrompt, sandbox?: SandboxMode | null, ask_for_approval?: AskForApproval | null, oss?: boolean | null, model?: string | null, model_reasoning_effort?: ReasoningEffort | null, model_reasoning_summary?: ReasoningSummary | null, model_reasoning_summary_format?: ReasoningSummaryFormat | null, profile?: string | null, base_instructions?: string | null, 
+/**
+ * Text added after `base_instructions` for every session, e.g. coding standards. Codex can
+ * only replace its built-in prompt, so this is ignored unless `base_instructions` is set.
+ */
+system_prompt_append?: string | null, include_plan_tool?: boolean | null, include_apply_patch_tool?: boolean | null, 
+/**
+ * How many times to restart the app server and resume the conversation if it disconnects
+ * mid-task, backing off exponentially between attempts. Disabled when unset or 0.
+ */
+reconnect_attempts?: number | null, 
+/**
+ * Show tool results as plain text even when they are valid JSON
+ */
+prefer_raw_tool_results?: boolean | null, 
+/**
+ * Cap on tokens per model response, passed as Codex's `model_max_output_tokens` config;
+ * must be positive.
+ */
+max_output_tokens?: number | null, 
+/**
+ * MCP servers added to every conversation, keyed by name, on top of those in
+ * `~/.codex/config.toml`
+ */
+mcp_servers?: { [key in string]?: CodexMcpServer } | null, base_command_override?: string | null, additional_params?: Array<string> | null, shell_override?: [string, string] | null, env_file?: string | null, env?: { [key in string]?: string } | null, };
 
 export type SandboxMode = "auto" | "read-only" | "workspace-write" | "danger-full-access";
 
@@ -178,15 +316,32 @@ export type ReasoningSummary = "auto" | "concise" | "detailed" | "none";
 
 export type ReasoningSummaryFormat = "none" | "experimental";
 
-export type CursorAgent = { append_prompt: AppendPrompt, force?: boolean | null, model?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type CursorAgent = { append_prompt: AppendPrompt, force?: boolean | null, model?: string | null, diff_algorithm?: DiffAlgorithm | null, base_command_override?: string | null, additional_params?: Array<string> | null, shell_override?: [string, string] | null, env_file?: string | null, env?: { [key in string]?: string } | null, };
 
-export type Copilot = { append_prompt: AppendPrompt, model?: string | null, allow_all_tools?: boolean | null, allow_tool?: string | null, deny_tool?: string | null, add_dir?: Array<string> | null, disable_mcp_server?: Array<string> | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type Copilot = { append_prompt: AppendPrompt, model?: string | null, allow_all_tools?: boolean | null, allow_tool?: string | null, deny_tool?: string | null, add_dir?: Array<string> | null, disable_mcp_server?: Array<string> | null, 
+/**
+ * Seconds to wait for Copilot to create its session log before giving up on the session id
+ * (default: 600)
+ */
+session_watch_timeout_secs?: number | null, 
+/**
+ * Value of Copilot's `--log-level` (default: debug)
+ */
+log_level?: ExecutorLogLevel | null, base_command_override?: string | null, additional_params?: Array<string> | null, shell_override?: [string, string] | null, env_file?: string | null, env?: { [key in string]?: string } | null, };
 
-export type Opencode = { append_prompt: AppendPrompt, model?: string | null, agent?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type Opencode = { append_prompt: AppendPrompt, model?: string | null, agent?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, shell_override?: [string, string] | null, env_file?: string | null, env?: { [key in string]?: string } | null, };
 
-export type QwenCode = { append_prompt: AppendPrompt, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type QwenCode = { append_prompt: AppendPrompt, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, shell_override?: [string, string] | null, env_file?: string | null, env?: { [key in string]?: string } | null, };
 
-export type AppendPrompt = string | null;
+export type AppendPrompt = string | null | { text: string | null, mode: AppendPromptMode, 
+/**
+ * Placed between the prompt and the text; nothing by default
+ */
+separator?: string | null, };
+
+export type AppendPromptMode = "prefix" | "suffix";
+
+export type ExecutorLogLevel = "error" | "warning" | "info" | "debug";
 
 export type CodingAgentInitialRequest = { prompt: string, 
 /**
@@ -194,17 +349,33 @@ export type CodingAgentInitialRequest = { prompt: string,
  */
 executor_profile_id: ExecutorProfileId, };
 
+export type ResumeOverrides = { model?: string | null, reasoning_effort?: ReasoningEffort | null, };
+
 export type CodingAgentFollowUpRequest = { prompt: string, session_id: string, 
 /**
  * Executor profile specification
  */
-executor_profile_id: ExecutorProfileId, };
+executor_profile_id: ExecutorProfileId, resume_overrides?: ResumeOverrides | null, };
 
 export type CreateTaskAttemptBody = { task_id: string, 
 /**
  * Executor profile specification
  */
-executor_profile_id: ExecutorProfileId, base_branch: string, };
+executor_profile_id: ExecutorProfileId, base_branch: string, 
+/**
+ * Exact name for the attempt branch, instead of the generated one
+ */
+branch_name?: string, 
+/**
+ * Template for the attempt branch name, e.g. `feature/{task_id}-{slug}`.
+ * Supports `{prefix}`, `{task_id}`, `{attempt_id}` and `{slug}`.
+ */
+branch_template?: string, 
+/**
+ * Run the attempt in its own git worktree (default). When false the agent works directly
+ * in the project repository, without isolation from local changes or other attempts.
+ */
+use_worktree?: boolean, };
 
 export type RunAgentSetupRequest = { executor_profile_id: ExecutorProfileId, };
 
@@ -236,7 +407,29 @@ force_when_dirty: boolean | null,
  */
 perform_git_reset: boolean | null, };
 
-export type CommitInfo = { sha: string, subject: string, };
+export type CommitInfo = { sha: string, subject: string, author: string, email: string, timestamp: string, 
+/**
+ * Full commit message, subject included
+ */
+message: string, files_changed: number, };
+
+export type AttemptFileDiff = { path: string, change: DiffChangeKind, 
+/**
+ * Unified diff of the file, `None` when its contents were omitted (e.g., too large)
+ */
+unified_diff: string | null, };
+
+export type AttemptDiff = { files: Array<AttemptFileDiff>, };
+
+export type AttemptFile = { 
+/**
+ * Path relative to the attempt's worktree, as requested
+ */
+path: string, size: bigint, 
+/**
+ * True when the file is not UTF-8 text; `content` is then omitted
+ */
+is_binary: boolean, content: string | null, };
 
 export type BranchStatus = { commits_behind: number | null, commits_ahead: number | null, has_uncommitted_changes: boolean | null, head_oid: string | null, uncommitted_count: number | null, untracked_count: number | null, target_branch_name: string, remote_commits_behind: number | null, remote_commits_ahead: number | null, merges: Array<Merge>, 
 /**
@@ -252,6 +445,24 @@ conflict_op: ConflictOp | null,
  */
 conflicted_files: Array<string>, };
 
+export type RawLogStream = "stdout" | "stderr";
+
+export type RawLogChunk = { 
+/**
+ * Position among every chunk the process wrote; stays valid when older chunks are evicted
+ */
+offset: number, stream: RawLogStream, content: string, };
+
+export type RawLogsPage = { chunks: Array<RawLogChunk>, 
+/**
+ * Pass as `after_offset` to fetch the chunks that follow this page
+ */
+next_offset: number, 
+/**
+ * True once the process has exited and every chunk up to `next_offset` was returned
+ */
+finished: boolean, };
+
 export type ConflictOp = "rebase" | "merge" | "cherry_pick" | "revert";
 
 export type TaskAttempt = { id: string, task_id: string, container_ref: string | null, branch: string, target_branch: string, executor: string, worktree_deleted: boolean, setup_completed_at: string | null, created_at: string, updated_at: string, };
@@ -272,10 +483,28 @@ after_head_commit: string | null, status: ExecutionProcessStatus, exit_code: big
  */
 dropped: boolean, started_at: string, completed_at: string | null, created_at: string, updated_at: string, };
 
-export enum ExecutionProcessStatus { running = "running", completed = "completed", failed = "failed", killed = "killed" }
+export enum ExecutionProcessStatus { queued = "queued", running = "running", completed = "completed", failed = "failed", killed = "killed" }
 
 export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "codingagent" | "devserver";
 
+export type RunningProcess = { execution_process_id: string, task_attempt_id: string, 
+/**
+ * The coding agent that was spawned, `None` for scripts
+ */
+executor: string | null, 
+/**
+ * OS process id of the process group leader, if it was still known at spawn time
+ */
+pid: number | null, started_at: string, 
+/**
+ * Resident memory of the group leader; `None` when it can't be sampled on this OS
+ */
+rss_bytes: bigint | null, 
+/**
+ * Average CPU use of the group leader since it started, where 100 is one full core
+ */
+cpu_percent: number | null, };
+
 export type Merge = { "type": "direct" } & DirectMerge | { "type": "pr" } & PrMerge;
 
 export type DirectMerge = { id: string, task_attempt_id: string, merge_commit: string, target_branch_name: string, created_at: string, };
@@ -290,13 +519,13 @@ export type Draft = { id: string, task_attempt_id: string, draft_type: DraftType
 
 export type DraftType = "follow_up" | "retry";
 
-export type CommandExitStatus = { "type": "exit_code", code: number, } | { "type": "success", success: boolean, };
+export type CommandExitStatus = { "type": "exit_code", code: number, } | { "type": "success", success: boolean, } | { "type": "signal", signal: number, };
 
 export type CommandRunResult = { exit_status: CommandExitStatus | null, output: string | null, };
 
 export type NormalizedEntry = { timestamp: string | null, entry_type: NormalizedEntryType, content: string, };
 
-export type NormalizedEntryType = { "type": "user_message" } | { "type": "user_feedback", denied_tool: string, } | { "type": "assistant_message" } | { "type": "tool_use", tool_name: string, action_type: ActionType, status: ToolStatus, } | { "type": "system_message" } | { "type": "error_message", error_type: NormalizedEntryError, } | { "type": "thinking" } | { "type": "loading" } | { "type": "next_action", failed: boolean, execution_processes: number, needs_setup: boolean, };
+export type NormalizedEntryType = { "type": "user_message" } | { "type": "user_feedback", denied_tool: string, } | { "type": "assistant_message" } | { "type": "tool_use", tool_name: string, action_type: ActionType, status: ToolStatus, } | { "type": "system_message" } | { "type": "error_message", error_type: NormalizedEntryError, } | { "type": "notification", level: NotificationLevel, } | { "type": "thinking" } | { "type": "loading" } | { "type": "next_action", failed: boolean, execution_processes: number, needs_setup: boolean, };
 
 export type FileChange = { "action": "write", content: string, } | { "action": "delete" } | { "action": "rename", new_path: string, } | { "action": "edit", 
 /**
@@ -306,14 +535,35 @@ unified_diff: string,
 /**
  * Whether line number in the hunks are reliable.
  */
-has_line_numbers: boolean, };
+has_line_numbers: boolean, 
+/**
+ * Added/removed line counts of `unified_diff`; zero for entries stored before they
+ * were recorded.
+ */
+stats: DiffStats, };
+
+export type ActionType = { "action": "file_read", path: string, 
+/**
+ * Content that was read, once the agent reports it
+ */
+result: ToolResult | null, } | { "action": "file_edit", path: string, changes: Array<FileChange>, } | { "action": "command_run", command: string, 
+/**
+ * Working directory the command ran in, when the agent reports it
+ */
+cwd: string | null, result: CommandRunResult | null, } | { "action": "search", query: string, } | { "action": "web_fetch", url: string, 
+/**
+ * Fetched content, once the agent reports it
+ */
+result: ToolResult | null, } | { "action": "tool", tool_name: string, arguments: JsonValue | null, result: ToolResult | null, } | { "action": "task_create", description: string, } | { "action": "plan_presentation", plan: string, } | { "action": "todo_management", todos: Array<TodoItem>, operation: string, } | { "action": "other", description: string, };
 
-export type ActionType = { "action": "file_read", path: string, } | { "action": "file_edit", path: string, changes: Array<FileChange>, } | { "action": "command_run", command: string, result: CommandRunResult | null, } | { "action": "search", query: string, } | { "action": "web_fetch", url: string, } | { "action": "tool", tool_name: string, arguments: JsonValue | null, result: ToolResult | null, } | { "action": "task_create", description: string, } | { "action": "plan_presentation", plan: string, } | { "action": "todo_management", todos: Array<TodoItem>, operation: string, } | { "action": "other", description: string, };
+export type ActionKind = "file_read" | "file_edit" | "command_run" | "search" | "web_fetch" | "tool" | "task_create" | "plan_presentation" | "todo_management" | "other";
 
 export type TodoItem = { content: string, status: string, priority: string | null, };
 
 export type NormalizedEntryError = { "type": "setup_required" } | { "type": "other" };
 
+export type NotificationLevel = "info" | "warn";
+
 export type ToolResult = { type: ToolResultValueType, 
 /**
  * For Markdown, this will be a JSON string; for JSON, a structured value
@@ -322,7 +572,7 @@ value: JsonValue, };
 
 export type ToolResultValueType = { "type": "markdown" } | { "type": "json" };
 
-export type ToolStatus = { "status": "created" } | { "status": "success" } | { "status": "failed" } | { "status": "denied", reason: string | null, } | { "status": "pending_approval", approval_id: string, requested_at: string, timeout_at: string, } | { "status": "timed_out" };
+export type ToolStatus = { "status": "created" } | { "status": "success" } | { "status": "failed" } | { "status": "denied", reason: string | null, } | { "status": "pending_approval", approval_id: string, requested_at: string, timeout_at: string, } | { "status": "timed_out" } | { "status": "awaiting_approval" } | { "status": "unknown" };
 
 export type PatchType = { "type": "NORMALIZED_ENTRY", "content": NormalizedEntry } | { "type": "STDOUT", "content": string } | { "type": "STDERR", "content": string } | { "type": "DIFF", "content": Diff };
 